@@ -0,0 +1,16 @@
+fn main() {
+    // Node resolves the N-API symbols when it loads the addon, which some linkers need to be told.
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+
+    // The service is compiled without protoc so that building the feature needs nothing outside of cargo.
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/srs.proto");
+        let descriptors =
+            protox::compile(["proto/srs.proto"], ["proto"]).expect("proto/srs.proto is valid");
+        tonic_build::configure()
+            .compile_fds(descriptors)
+            .expect("the service code can be generated");
+    }
+}
@@ -0,0 +1,48 @@
+/*
+ * C declarations for the functions srs-interpolation exports when built with the `ffi` feature.
+ *
+ * Each point is its affine x then y coordinate, each a canonical big-endian field element, and the point at
+ * infinity is all zeros. A function returns SRS_OK on success and leaves its output untouched otherwise.
+ */
+#ifndef SRS_INTERPOLATION_H
+#define SRS_INTERPOLATION_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SRS_OK 0
+#define SRS_ERROR_NULL_POINTER 1
+#define SRS_ERROR_SIZE 2
+#define SRS_ERROR_INVALID_POINT 3
+#define SRS_ERROR_INVALID_PARAMETERS 4
+#define SRS_ERROR_INTERNAL 5
+#define SRS_ERROR_OUT_OF_MEMORY 6
+
+#define SRS_BN254_FIELD_SIZE 32
+#define SRS_BLS12_381_FIELD_SIZE 48
+
+/* Converts num_points BN254 G1 points, 2 * SRS_BN254_FIELD_SIZE bytes each, to the Lagrange basis. */
+int32_t srs_to_lagrange_bn254(const uint8_t *input, uint8_t *output, size_t num_points, bool bit_reversed);
+
+/* Converts num_points BLS12-381 G1 points, 2 * SRS_BLS12_381_FIELD_SIZE bytes each, to the Lagrange basis.
+ * Only exported when the library is also built with the `bls12-381` feature. */
+int32_t srs_to_lagrange_bls12_381(const uint8_t *input, uint8_t *output, size_t num_points, bool bit_reversed);
+
+/* Converts num_points BN254 G1 points held as Constantine's bn254_snarks_g1_aff to the Lagrange basis in place.
+ * Only exported when the library is also built with the `constantine` feature. */
+int32_t srs_to_lagrange_bn254_constantine(void *points, size_t num_points, bool bit_reversed);
+
+/* Converts num_points BLS12-381 G1 points held as Constantine's bls12_381_g1_aff to the Lagrange basis in place.
+ * Only exported when the library is also built with the `constantine` and `bls12-381` features. */
+int32_t srs_to_lagrange_bls12_381_constantine(void *points, size_t num_points, bool bit_reversed);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SRS_INTERPOLATION_H */
@@ -0,0 +1,535 @@
+//! This module contains async counterparts of the binary format readers and writers and of the streaming
+//! conversion, for services that fetch setups over the network and publish the converted output.
+use crate::{
+    buffer::{try_with_capacity, VecProvider},
+    curves::SrsCurve,
+    domain_size_inv,
+    error::InterpolationError,
+    formats::binary::{
+        decode_point, decode_prime_field, encode_header, encode_point, prime_field_size, Basis,
+        BinaryHeader, Endianness, PointEncoding, HEADER_SIZE,
+    },
+    group_fft_radix2_with,
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+    srs_to_lagrange_unscaled,
+    utils::{is_primitive_root, size_from_u64},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    sync::{mpsc, watch},
+};
+
+/// The number of points encoded at a time when the conversion hands its output to the writer.
+const CHUNK_SIZE: usize = 1 << 12;
+/// The number of encoded chunks that can be waiting to be written before the conversion pauses.
+const CHANNEL_CAPACITY: usize = 4;
+
+/// An async reader that reads points in this crate's binary format one at a time.
+pub struct AsyncSrsReader<R: AsyncRead + Unpin, E: SrsCurve> {
+    reader: R,
+    header: BinaryHeader,
+    generator: Option<E::ScalarField>,
+    remaining: u64,
+    validate: bool,
+    buffer: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin, E: SrsCurve> AsyncSrsReader<R, E> {
+    /// This function reads the header and returns a reader that checks every point is in the prime order subgroup.
+    pub async fn new(reader: R) -> Result<Self, InterpolationError> {
+        Self::new_with_validation(reader, true).await
+    }
+
+    /// This function is the same as [`AsyncSrsReader::new`] but the points are not checked.
+    pub async fn new_unchecked(reader: R) -> Result<Self, InterpolationError> {
+        Self::new_with_validation(reader, false).await
+    }
+
+    async fn new_with_validation(
+        mut reader: R,
+        validate: bool,
+    ) -> Result<Self, InterpolationError> {
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header_bytes).await?;
+        let header = BinaryHeader::read(&mut header_bytes.as_slice())?;
+        if header.curve != E::CURVE_ID {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "File is for {} but the points are expected on {}",
+                header.curve.name(),
+                E::CURVE_ID.name()
+            )));
+        }
+        // A file with more points than this target can index is rejected up front rather than partly read.
+        size_from_u64(header.size)?;
+        let generator = match header.basis {
+            Basis::Monomial => None,
+            Basis::Lagrange => {
+                let mut bytes = vec![0u8; prime_field_size::<E::ScalarField>()];
+                reader.read_exact(&mut bytes).await?;
+                Some(decode_prime_field(&bytes, header.endianness)?)
+            }
+        };
+        Ok(Self {
+            reader,
+            header,
+            generator,
+            remaining: header.size,
+            validate,
+            buffer: vec![0u8; header.point_size::<E>()],
+        })
+    }
+
+    /// The header of the file.
+    pub fn header(&self) -> &BinaryHeader {
+        &self.header
+    }
+
+    /// The generator of the domain of a Lagrange SRS.
+    pub fn generator(&self) -> Option<E::ScalarField> {
+        self.generator
+    }
+
+    /// Reads the next point, returning `None` once all the points in the header have been read.
+    pub async fn read_point(&mut self) -> Result<Option<Affine<E>>, InterpolationError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut self.buffer).await?;
+        self.remaining -= 1;
+        decode_point(&self.buffer, &self.header, self.validate).map(Some)
+    }
+
+    /// Reads all the remaining points, returning [`InterpolationError::OutOfMemory`] if the header declares more
+    /// than can be allocated.
+    pub async fn read_all(&mut self) -> Result<Vec<Affine<E>>, InterpolationError> {
+        let mut points = try_with_capacity(size_from_u64(self.remaining)?)?;
+        while let Some(point) = self.read_point().await? {
+            points.push(point);
+        }
+        Ok(points)
+    }
+}
+
+/// An async writer that writes points in this crate's binary format one at a time.
+pub struct AsyncSrsWriter<W: AsyncWrite + Unpin, E: SrsCurve> {
+    writer: W,
+    header: BinaryHeader,
+    remaining: u64,
+    buffer: Vec<u8>,
+    _curve: ark_std::marker::PhantomData<E>,
+}
+
+impl<W: AsyncWrite + Unpin, E: SrsCurve> AsyncSrsWriter<W, E> {
+    /// This function writes the header and returns a writer expecting `header.size` points.
+    /// A Lagrange SRS must provide the generator of its domain and a monomial SRS must not.
+    pub async fn new(
+        mut writer: W,
+        header: BinaryHeader,
+        generator: Option<E::ScalarField>,
+    ) -> Result<Self, InterpolationError> {
+        writer
+            .write_all(&encode_header::<E>(&header, generator)?)
+            .await?;
+        Ok(Self {
+            writer,
+            header,
+            remaining: header.size,
+            buffer: Vec::with_capacity(header.point_size::<E>()),
+            _curve: ark_std::marker::PhantomData,
+        })
+    }
+
+    /// Writes the next point, erroring if all the points in the header have already been written.
+    pub async fn write_point(&mut self, point: &Affine<E>) -> Result<(), InterpolationError> {
+        if self.remaining == 0 {
+            return Err(InterpolationError::InvalidParameters(
+                "More points written than the header declared".to_string(),
+            ));
+        }
+        self.buffer.clear();
+        encode_point(point, &self.header, &mut self.buffer);
+        self.writer.write_all(&self.buffer).await?;
+        self.remaining -= 1;
+        Ok(())
+    }
+
+    /// Finishes writing, erroring if fewer points were written than the header declared.
+    pub async fn finish(mut self) -> Result<W, InterpolationError> {
+        if self.remaining != 0 {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "{} points declared in the header were not written",
+                self.remaining
+            )));
+        }
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+/// This function reads a monomial SRS, checking every point.
+pub async fn read_monomial_async<R, E>(reader: R) -> Result<MonomialSrs<E>, InterpolationError>
+where
+    R: AsyncRead + Unpin,
+    E: SrsCurve,
+{
+    let mut reader = AsyncSrsReader::<R, E>::new(reader).await?;
+    if reader.header().basis != Basis::Monomial {
+        return Err(InterpolationError::SerializationError(
+            "Expected a monomial SRS".to_string(),
+        ));
+    }
+    Ok(MonomialSrs::new(reader.read_all().await?))
+}
+
+/// This function reads a Lagrange SRS, checking every point and that the generator matches the size of the domain.
+pub async fn read_lagrange_async<R, E>(reader: R) -> Result<LagrangeSrs<E>, InterpolationError>
+where
+    R: AsyncRead + Unpin,
+    E: SrsCurve,
+{
+    let mut reader = AsyncSrsReader::<R, E>::new(reader).await?;
+    let (ordering, generator) = match (reader.header().basis, reader.generator()) {
+        (Basis::Lagrange, Some(generator)) => (reader.header().ordering, generator),
+        _ => {
+            return Err(InterpolationError::SerializationError(
+                "Expected a Lagrange SRS".to_string(),
+            ))
+        }
+    };
+    let points = reader.read_all().await?;
+    if !is_primitive_root(generator, points.len()) {
+        return Err(InterpolationError::SerializationError(
+            "The generator does not match the size of the domain".to_string(),
+        ));
+    }
+    Ok(LagrangeSrs::from_parts(points, generator, ordering))
+}
+
+/// This function writes a monomial SRS.
+pub async fn write_monomial_async<W, E>(
+    writer: W,
+    srs: &MonomialSrs<E>,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError>
+where
+    W: AsyncWrite + Unpin,
+    E: SrsCurve,
+{
+    let header = BinaryHeader {
+        curve: E::CURVE_ID,
+        basis: Basis::Monomial,
+        ordering: PointOrdering::Natural,
+        encoding,
+        endianness,
+        size: srs.len() as u64,
+    };
+    let mut writer = AsyncSrsWriter::<W, E>::new(writer, header, None).await?;
+    for point in srs.points() {
+        writer.write_point(point).await?;
+    }
+    writer.finish().await
+}
+
+/// This function writes a Lagrange SRS.
+pub async fn write_lagrange_async<W, E>(
+    writer: W,
+    srs: &LagrangeSrs<E>,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError>
+where
+    W: AsyncWrite + Unpin,
+    E: SrsCurve,
+{
+    let header = BinaryHeader {
+        curve: E::CURVE_ID,
+        basis: Basis::Lagrange,
+        ordering: srs.ordering(),
+        encoding,
+        endianness,
+        size: srs.len() as u64,
+    };
+    let mut writer = AsyncSrsWriter::<W, E>::new(writer, header, Some(srs.generator())).await?;
+    for point in srs.points() {
+        writer.write_point(point).await?;
+    }
+    writer.finish().await
+}
+
+/// This function reads a monomial SRS in this crate's binary format from `reader` and writes the Lagrange SRS
+/// to `writer`, the async counterpart of [`crate::stream::convert_binary`].
+///
+/// Points are decoded as they arrive, the FFT style operation runs on tokio's blocking pool so it doesn't stall
+/// the executor, and the output is rescaled and encoded in chunks that are written while later chunks are still
+/// being produced.
+pub async fn convert_binary_async<R, W, E>(
+    reader: R,
+    mut writer: W,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    E: SrsCurve,
+{
+    let mut reader = AsyncSrsReader::<R, E>::new(reader).await?;
+    let input_header = *reader.header();
+    if input_header.basis != Basis::Monomial {
+        return Err(InterpolationError::InvalidParameters(
+            "Expected a monomial SRS".to_string(),
+        ));
+    }
+    let size = size_from_u64(input_header.size)?;
+    let domain =
+        Radix2EvaluationDomain::<E::ScalarField>::new(size).ok_or(InterpolationError::SizeError)?;
+    let header = BinaryHeader {
+        basis: Basis::Lagrange,
+        ordering: PointOrdering::Natural,
+        encoding,
+        endianness,
+        ..input_header
+    };
+    let header_bytes = encode_header::<E>(&header, Some(domain.group_gen()))?;
+    let mut points = reader.read_all().await?;
+
+    let (sender, mut receiver) = mpsc::channel::<Vec<u8>>(CHANNEL_CAPACITY);
+    let task = tokio::task::spawn_blocking(move || -> Result<(), InterpolationError> {
+        srs_to_lagrange_unscaled(&mut points, domain.group_gen())?;
+        let domain_size_inv = domain_size_inv::<E::ScalarField>(points.len())?;
+        for chunk in points.chunks_mut(CHUNK_SIZE) {
+            cfg_iter_mut!(chunk)
+                .for_each(|point| *point = (*point * domain_size_inv).into_affine());
+            let mut bytes = Vec::with_capacity(chunk.len() * header.point_size::<E>());
+            for point in chunk.iter() {
+                encode_point(point, &header, &mut bytes);
+            }
+            // If the receiver has gone the writer failed, and that error is reported instead.
+            if sender.blocking_send(bytes).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    });
+
+    writer.write_all(&header_bytes).await?;
+    while let Some(bytes) = receiver.recv().await {
+        writer.write_all(&bytes).await?;
+    }
+    task.await
+        .map_err(|e| InterpolationError::TaskError(e.to_string()))??;
+    writer.flush().await?;
+    Ok(writer)
+}
+
+/// How far a conversion started by [`convert_async`] has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConversionProgress {
+    /// The number of rounds of the FFT style operation that are done.
+    pub rounds_done: usize,
+    /// The number of rounds in the whole operation, the log of the number of points.
+    pub rounds: usize,
+}
+
+/// Sets the flag it holds when dropped, which tells a conversion on the blocking pool to stop.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// This function converts `points` to the Lagrange basis, the same as [`crate::srs_to_lagrange`], on tokio's
+/// blocking pool so the executor is free to run other tasks. It errors if the number of points is not a power of
+/// two.
+///
+/// If `progress` is given, it is sent a [`ConversionProgress`] after every round. Dropping the returned future
+/// cancels the conversion, which stops at the end of the round it is in. The rounds always run radix-2 over the
+/// whole domain so every round can report in.
+pub async fn convert_async<E, F>(
+    mut points: Vec<Affine<E>>,
+    progress: Option<watch::Sender<ConversionProgress>>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let _cancel_on_drop = CancelOnDrop(cancelled.clone());
+
+    let task = tokio::task::spawn_blocking(move || -> Result<_, InterpolationError> {
+        let gen_inv = domain.group_gen_inv();
+        group_fft_radix2_with(
+            &mut points,
+            gen_inv,
+            &VecProvider,
+            |_, rounds_done, rounds| {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(InterpolationError::TaskError(
+                        "The conversion was cancelled".to_string(),
+                    ));
+                }
+                if let Some(progress) = &progress {
+                    progress.send_replace(ConversionProgress {
+                        rounds_done,
+                        rounds,
+                    });
+                }
+                Ok(())
+            },
+        )?;
+        let domain_size_inv = domain_size_inv::<F>(points.len())?;
+        cfg_iter_mut!(points).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+        Ok(points)
+    });
+    task.await
+        .map_err(|e| InterpolationError::TaskError(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{write_lagrange, write_monomial},
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[tokio::test]
+    async fn test_convert_async() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        let (sender, receiver) = watch::channel(ConversionProgress::default());
+        let lagrange = convert_async(srs.clone(), Some(sender)).await.unwrap();
+        assert_eq!(lagrange, crate::srs_to_lagrange(&srs).unwrap());
+        assert_eq!(
+            *receiver.borrow(),
+            ConversionProgress {
+                rounds_done: 6,
+                rounds: 6
+            }
+        );
+        assert!(convert_async(srs[..3].to_vec(), None).await.is_err());
+
+        // A conversion whose future is dropped while it runs stops at the end of its round without reporting it.
+        let (sender, mut receiver) = watch::channel(ConversionProgress::default());
+        let large = gen_srs_for_testing::<BnConfig, _>(rng, (1 << 12) - 1).unwrap();
+        let conversion = tokio::spawn(convert_async(large, Some(sender)));
+        receiver.changed().await.unwrap();
+        assert!(receiver.borrow_and_update().rounds_done >= 1);
+        conversion.abort();
+        assert!(conversion.await.unwrap_err().is_cancelled());
+        let rounds_done = receiver.borrow_and_update().rounds_done;
+        // The sender is dropped once the blocking task has stopped.
+        while receiver.changed().await.is_ok() {}
+        assert_eq!(receiver.borrow().rounds_done, rounds_done);
+        assert!(rounds_done < 12);
+    }
+
+    #[tokio::test]
+    async fn test_async_conversion() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+
+        let input = write_monomial_async(
+            Vec::new(),
+            &srs,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            input,
+            write_monomial(
+                Vec::new(),
+                &srs,
+                PointEncoding::Compressed,
+                Endianness::Little
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            read_monomial_async::<_, BnConfig>(input.as_slice())
+                .await
+                .unwrap(),
+            srs
+        );
+
+        let output = convert_binary_async::<_, _, BnConfig>(
+            input.as_slice(),
+            Vec::new(),
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .await
+        .unwrap();
+        let expected = write_lagrange(
+            Vec::new(),
+            &lagrange_srs,
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(
+            read_lagrange_async::<_, BnConfig>(output.as_slice())
+                .await
+                .unwrap(),
+            lagrange_srs
+        );
+        let written = write_lagrange_async(
+            Vec::new(),
+            &lagrange_srs,
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .await
+        .unwrap();
+        assert_eq!(written, expected);
+
+        // Truncated input is reported as an error.
+        assert!(convert_binary_async::<_, _, BnConfig>(
+            &input[..input.len() - 1],
+            Vec::new(),
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .await
+        .is_err());
+
+        // A header declaring more points than can be allocated is an error rather than an abort.
+        let mut oversized = input.clone();
+        oversized[10..HEADER_SIZE].copy_from_slice(&(1u64 << 60).to_le_bytes());
+        assert!(matches!(
+            read_monomial_async::<_, BnConfig>(oversized.as_slice()).await,
+            Err(InterpolationError::OutOfMemory { .. } | InterpolationError::InvalidParameters(_))
+        ));
+        assert!(convert_binary_async::<_, _, BnConfig>(
+            oversized.as_slice(),
+            Vec::new(),
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .await
+        .is_err());
+    }
+}
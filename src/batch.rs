@@ -0,0 +1,235 @@
+//! This module contains batched affine point arithmetic. Adding two affine points takes one base field inversion,
+//! and Montgomery's trick shares a single inversion between every addition in a batch, which is the same trick
+//! [`crate::srs_to_lagrange`] uses for its butterflies.
+//!
+//! Unlike the butterflies, which only ever add points with distinct x-coordinates, these functions handle every
+//! special case: either point at infinity, equal points and opposite points, and [`batch_double`] handles points
+//! of order two.
+use crate::error::InterpolationError;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{batch_inversion, Field};
+use ark_std::{vec::Vec, Zero};
+
+/// This function sets `point` to the third point on the line of slope `lambda` through `point` and a point with
+/// x-coordinate `other_x`, reflected in the x-axis.
+fn apply_chord<E: SWCurveConfig>(
+    point: &mut Affine<E>,
+    other_x: E::BaseField,
+    lambda: E::BaseField,
+) {
+    let x = lambda.square() - point.x - other_x;
+    point.y = lambda * (point.x - x) - point.y;
+    point.x = x;
+}
+
+/// This function returns `3 x^2 + a`, the numerator of the slope of the tangent at `point`. Nearly every curve used
+/// with an SRS has `a = 0`, and since `a` is a constant of the curve the addition is compiled out for those.
+fn tangent_numerator<E: SWCurveConfig>(point: &Affine<E>) -> E::BaseField {
+    let x_squared = point.x.square();
+    let numerator = x_squared.double() + x_squared;
+    if E::COEFF_A.is_zero() {
+        numerator
+    } else {
+        numerator + E::COEFF_A
+    }
+}
+
+/// This function returns the numerator and denominator of the slope of the line through `p` and `q`, or sets `p`
+/// to `p + q` and returns `None` if the sum needs no slope.
+fn slope_or_sum<E: SWCurveConfig>(
+    p: &mut Affine<E>,
+    q: &Affine<E>,
+) -> Option<(E::BaseField, E::BaseField)> {
+    if q.infinity {
+        None
+    } else if p.infinity {
+        *p = *q;
+        None
+    } else if p.x != q.x {
+        Some((q.y - p.y, q.x - p.x))
+    } else if p.y == q.y && !p.y.is_zero() {
+        Some((tangent_numerator(p), p.y.double()))
+    } else {
+        *p = Affine::<E>::identity();
+        None
+    }
+}
+
+/// This function adds `points[i]` to `acc[i]` for every `i`, using one base field inversion for the whole batch.
+pub fn batch_add_assign<E: SWCurveConfig>(
+    acc: &mut [Affine<E>],
+    points: &[Affine<E>],
+) -> Result<(), InterpolationError> {
+    if acc.len() != points.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot add {} points to {} accumulators",
+            points.len(),
+            acc.len()
+        )));
+    }
+
+    // The slope of each addition that needs one is kept as a numerator and a denominator to be inverted later.
+    let mut numerators = Vec::with_capacity(acc.len());
+    let mut denominators = Vec::with_capacity(acc.len());
+    for (i, (p, q)) in acc.iter_mut().zip(points).enumerate() {
+        if let Some((numerator, denominator)) = slope_or_sum(p, q) {
+            numerators.push((i, numerator));
+            denominators.push(denominator);
+        }
+    }
+
+    batch_inversion(&mut denominators);
+    for ((i, numerator), inverse) in numerators.into_iter().zip(denominators) {
+        apply_chord(&mut acc[i], points[i].x, numerator * inverse);
+    }
+    Ok(())
+}
+
+/// This function is the same as [`batch_add_assign`] but adds each point of `additions` to the accumulator at its
+/// index. The indices must be distinct.
+pub(crate) fn batch_add_at<E: SWCurveConfig>(
+    acc: &mut [Affine<E>],
+    additions: &[(usize, Affine<E>)],
+) {
+    let mut numerators = Vec::with_capacity(additions.len());
+    let mut denominators = Vec::with_capacity(additions.len());
+    for (k, (i, q)) in additions.iter().enumerate() {
+        if let Some((numerator, denominator)) = slope_or_sum(&mut acc[*i], q) {
+            numerators.push((k, numerator));
+            denominators.push(denominator);
+        }
+    }
+
+    batch_inversion(&mut denominators);
+    for ((k, numerator), inverse) in numerators.into_iter().zip(denominators) {
+        let (i, q) = &additions[k];
+        apply_chord(&mut acc[*i], q.x, numerator * inverse);
+    }
+}
+
+/// This function adds each point in the second half of `points` to the matching point in the first half, so the
+/// first half holds the sums and the second half is left as it was. The number of points must be even, and calling
+/// this on the first half of the result repeatedly sums a power-of-two number of points.
+pub fn batch_add_pairs<E: SWCurveConfig>(
+    points: &mut [Affine<E>],
+) -> Result<(), InterpolationError> {
+    if !points.len().is_multiple_of(2) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot pair up {} points",
+            points.len()
+        )));
+    }
+    let (acc, rest) = points.split_at_mut(points.len() / 2);
+    batch_add_assign(acc, rest)
+}
+
+/// This function doubles every point in `points`, using one base field inversion for the whole batch.
+pub fn batch_double<E: SWCurveConfig>(points: &mut [Affine<E>]) {
+    // Points of order two, whose tangent is vertical, double to the point at infinity.
+    let mut numerators = Vec::with_capacity(points.len());
+    let mut denominators = Vec::with_capacity(points.len());
+    for (i, p) in points.iter_mut().enumerate() {
+        if p.infinity {
+            continue;
+        }
+        if p.y.is_zero() {
+            *p = Affine::<E>::identity();
+        } else {
+            numerators.push((i, tangent_numerator(p)));
+            denominators.push(p.y.double());
+        }
+    }
+
+    batch_inversion(&mut denominators);
+    for ((i, numerator), inverse) in numerators.into_iter().zip(denominators) {
+        let x = points[i].x;
+        apply_chord(&mut points[i], x, numerator * inverse);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{g1::Config as BnConfig, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_batch_add_assign() {
+        let rng = &mut ark_std::test_rng();
+        let random = (0..4)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let identity = Affine::<BnConfig>::identity();
+
+        // Random pairs with every special case mixed in: infinity on either side, equal and opposite points.
+        let mut acc = vec![
+            random[0], random[1], identity, random[2], random[3], random[3], identity,
+        ];
+        let points = vec![
+            random[1], random[2], random[0], identity, random[3], -random[3], identity,
+        ];
+        let expected = acc
+            .iter()
+            .zip(&points)
+            .map(|(p, q)| (*p + q).into_affine())
+            .collect::<Vec<_>>();
+        batch_add_assign(&mut acc, &points).unwrap();
+        assert_eq!(acc, expected);
+
+        assert!(batch_add_assign(&mut acc[..2], &points).is_err());
+    }
+
+    #[test]
+    fn test_batch_add_pairs() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = (0..8)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let sum = points.iter().map(|p| p.into_group()).sum::<G1Projective>();
+        let mut len = points.len();
+        while len > 1 {
+            batch_add_pairs(&mut points[..len]).unwrap();
+            len /= 2;
+        }
+        assert_eq!(points[0], sum.into_affine());
+
+        assert!(batch_add_pairs(&mut points[..3]).is_err());
+    }
+
+    #[test]
+    fn test_batch_double() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = (0..4)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        points.push(Affine::<BnConfig>::identity());
+        let expected = points
+            .iter()
+            .map(|p| (*p + p).into_affine())
+            .collect::<Vec<_>>();
+        batch_double(&mut points);
+        assert_eq!(points, expected);
+        batch_double::<BnConfig>(&mut []);
+    }
+
+    #[cfg(feature = "bandersnatch")]
+    #[test]
+    fn test_batch_double_nonzero_a() {
+        use ark_ec::short_weierstrass::Projective;
+        use ark_ed_on_bls12_381_bandersnatch::SWConfig;
+
+        // Bandersnatch in short Weierstrass form has a != 0, so the tangent needs the a term.
+        assert!(!SWConfig::COEFF_A.is_zero());
+        let rng = &mut ark_std::test_rng();
+        let mut points = (0..4)
+            .map(|_| Projective::<SWConfig>::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let expected = points
+            .iter()
+            .map(|p| (*p + p).into_affine())
+            .collect::<Vec<_>>();
+        batch_double(&mut points);
+        assert_eq!(points, expected);
+    }
+}
@@ -0,0 +1,1277 @@
+//! The `srs-interp` command line tool. It converts monomial SRSs to the Lagrange basis, checks converted SRSs
+//! against their source and prints what a file in this crate's binary format holds. `convert --dry-run` reports
+//! what a conversion would do without doing it, so the input of a long job can be checked before it starts, and
+//! `bench` times a conversion of a synthetic SRS so machines can be compared. `split` and `merge` cut a file in
+//! this crate's binary format into shards with a manifest and put them back together, and `distribute` hands the
+//! rounds of a conversion out to worker processes a shard each.
+//!
+//! `convert` takes `-` as its input and output to read from stdin and write to stdout, so it can sit in a pipeline
+//! behind a download or a decompressor, and detects the format of its input from the header. With `--layout
+//! rapidsnark` it writes the bare points in the layout native provers map into memory instead, and with `--layout abi`
+//! or `--layout solidity` it writes them for publishing on the EVM. `--layout json` writes them as hex strings for web
+//! tooling.
+//!
+//! `convert --signing-key` signs a manifest of the conversion with an ed25519 key, and `verify-manifest` checks one
+//! against the public key of its signer.
+//!
+//! With the `grpc` feature, `serve` runs the gRPC service of [`srs_interpolation::grpc`] on an address.
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::{pairing::Pairing, short_weierstrass::Affine, AffineRepr};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use clap::{Parser, Subcommand, ValueEnum};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use indicatif::{ProgressBar, ProgressStyle};
+use srs_interpolation::{
+    curves::{CurveId, SrsCurve},
+    distributed::DistributedPlan,
+    formats::{
+        binary::{
+            read_lagrange, read_monomial, write_lagrange, write_monomial, Basis, BinaryHeader,
+            Endianness, PointEncoding, SrsReader,
+        },
+        ckzg::CkzgTrustedSetup,
+        inspect::{inspect as inspect_format, SrsFormat, SrsInfo},
+        json::{write_json_lagrange, write_json_monomial},
+        ptau::read_tau_g1,
+        rapidsnark::write_rapidsnark,
+        shard::{merge_srs, split_srs, ShardManifest},
+        solidity::{write_abi, write_solidity_library},
+    },
+    manifest::PointsHasher,
+    memory::memory_requirements,
+    report::srs_to_lagrange_with_report,
+    setups::insecure::insecure_monomial_srs,
+    signed_manifest::{sign_manifest, verify_manifest, ManifestStatement, SignedManifest},
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+    verify::{check_lagrange_files_match, check_lagrange_matches_monomial, check_monomial_powers},
+    InterpolationError,
+};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "srs-interp",
+    version,
+    about = "Convert and check KZG structured reference strings"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Converts a monomial SRS to this crate's binary format, in the Lagrange basis unless asked otherwise.
+    Convert {
+        /// The file to read the monomial SRS from, or `-` for stdin.
+        #[arg(long)]
+        input: PathBuf,
+        /// The format of the input file, which is detected from its header if not given.
+        #[arg(long, value_enum)]
+        from: Option<InputFormat>,
+        /// The file to write the converted SRS to, or `-` for stdout, which is required unless this is a dry run.
+        #[arg(long, required_unless_present = "dry_run")]
+        output: Option<PathBuf>,
+        /// The number of points to convert, which is required for ptau files and defaults to the whole SRS.
+        #[arg(long)]
+        size: Option<usize>,
+        /// The basis of the output.
+        #[arg(long, value_enum, default_value_t = OutputBasis::Lagrange)]
+        basis: OutputBasis,
+        /// The order of the Lagrange points in the output.
+        #[arg(long, value_enum, default_value_t = Ordering::Natural)]
+        ordering: Ordering,
+        /// How the points of the output are encoded.
+        #[arg(long, value_enum, default_value_t = Encoding::Compressed)]
+        encoding: Encoding,
+        /// The byte order of the output.
+        #[arg(long, value_enum, default_value_t = ByteOrder::Little)]
+        endianness: ByteOrder,
+        /// The layout of the output. Only the binary layout has a header or uses the encoding and byte order.
+        #[arg(long, value_enum, default_value_t = Layout::Binary)]
+        layout: Layout,
+        /// The name of the library written with `--layout solidity`.
+        #[arg(long, default_value = "Srs")]
+        library_name: String,
+        /// A file holding the hex encoded 32 byte seed of the ed25519 key to sign the manifest of the conversion with.
+        #[arg(long, requires = "signed_manifest")]
+        signing_key: Option<PathBuf>,
+        /// The file to write the signed manifest to.
+        #[arg(long, requires = "signing_key")]
+        signed_manifest: Option<PathBuf>,
+        /// Reports the size, curve, memory estimate and algorithm of the conversion and exits without converting.
+        #[arg(long)]
+        dry_run: bool,
+        /// With `--dry-run`, reads and checks every input point rather than only the header.
+        #[arg(long, requires = "dry_run")]
+        validate: bool,
+    },
+    /// Checks a monomial SRS in this crate's binary format, and a Lagrange SRS against it if one is given.
+    Verify {
+        /// The monomial SRS.
+        #[arg(long)]
+        monomial: PathBuf,
+        /// The Lagrange SRS to check is the conversion of the monomial SRS.
+        #[arg(long)]
+        lagrange: Option<PathBuf>,
+        /// The hex encoded compressed G2 point `[tau]` to check the monomial points are powers of tau with.
+        #[arg(long)]
+        tau_g2: Option<String>,
+        /// Checks the Lagrange SRS by reading both files a chunk at a time rather than loading them, for setups
+        /// larger than memory.
+        #[arg(long, requires = "lagrange")]
+        streaming: bool,
+    },
+    /// Checks a signed manifest was signed by the holder of a public key.
+    VerifyManifest {
+        /// The signed manifest.
+        #[arg(long)]
+        manifest: PathBuf,
+        /// The hex encoded ed25519 public key of the signer.
+        #[arg(long)]
+        public_key: String,
+    },
+    /// Prints the header and point digests of a file in this crate's binary format.
+    Inspect {
+        /// The file to inspect.
+        file: PathBuf,
+    },
+    /// Splits a file in this crate's binary format into shards and a manifest in a directory.
+    Split {
+        /// The file to split.
+        #[arg(long)]
+        input: PathBuf,
+        /// The number of points in each shard, apart from the last which may have fewer.
+        #[arg(long)]
+        shard_size: u64,
+        /// The directory to write the shards and `manifest.txt` to, which is created if it does not exist.
+        #[arg(long)]
+        output_dir: PathBuf,
+    },
+    /// Merges the shards listed in a manifest, checking each against its digest.
+    Merge {
+        /// The manifest, which the shards are found next to.
+        #[arg(long)]
+        manifest: PathBuf,
+        /// The file to write the merged SRS to.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Converts a monomial SRS in this crate's binary format by handing shards of its rounds out to worker
+    /// processes, for domains too large to convert in one process.
+    Distribute {
+        /// The file to read the monomial SRS from.
+        #[arg(long)]
+        input: PathBuf,
+        /// The file to write the Lagrange SRS to.
+        #[arg(long)]
+        output: PathBuf,
+        /// The number of workers to start on this machine when no worker config is given.
+        #[arg(long, default_value_t = 4)]
+        workers: usize,
+        /// A file with the command that starts each worker on its own line, such as `ssh node1 srs-interp`. The
+        /// work directory must be at the same path for every worker.
+        #[arg(long)]
+        worker_config: Option<PathBuf>,
+        /// The directory the shards passed to and from the workers are written to, which is created if it does not
+        /// exist.
+        #[arg(long)]
+        work_dir: PathBuf,
+    },
+    /// Runs one job of a distributed conversion, which `distribute` starts.
+    #[command(hide = true)]
+    Worker {
+        /// The step of the conversion the job is part of.
+        #[arg(long, value_enum)]
+        stage: Stage,
+        /// The number of points in the whole conversion.
+        #[arg(long)]
+        size: usize,
+        /// The index of the first column in the shard.
+        #[arg(long, default_value_t = 0)]
+        first: usize,
+        /// The shard to run the job on.
+        #[arg(long)]
+        input: PathBuf,
+        /// The file to write the shard to once the job has run.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Converts a random insecure SRS and prints the throughput and the time each round took.
+    Bench {
+        /// The curve of the SRS.
+        #[arg(long, value_enum, default_value_t = BenchCurve::Bn254)]
+        curve: BenchCurve,
+        /// The base two logarithm of the number of points.
+        #[arg(long, default_value_t = 16)]
+        log_size: u32,
+    },
+    /// Serves conversions, checks and inspections over gRPC until stopped.
+    #[cfg(feature = "grpc")]
+    Serve {
+        /// The address to listen on.
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        addr: std::net::SocketAddr,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Stage {
+    Columns,
+    Rows,
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Columns => "columns",
+            Stage::Rows => "rows",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BenchCurve {
+    Bn254,
+    #[value(name = "bls12-381")]
+    Bls12_381,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// This crate's binary format.
+    Binary,
+    /// A snarkjs powers of tau file over BN254.
+    Ptau,
+    /// A c-kzg-4844 trusted setup file over BLS12-381.
+    Ckzg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputBasis {
+    Monomial,
+    Lagrange,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Ordering {
+    Natural,
+    BitReversed,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Encoding {
+    Compressed,
+    Uncompressed,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Layout {
+    /// This crate's binary format.
+    Binary,
+    /// The bare points as rapidsnark maps them into memory.
+    Rapidsnark,
+    /// The ABI encoding of the points as a `uint256[2][]`.
+    Abi,
+    /// A Solidity library holding the points.
+    Solidity,
+    /// A JSON document of the hex encoded compressed points with a header describing them.
+    Json,
+}
+
+/// How a converted SRS is written.
+struct OutputOptions {
+    basis: OutputBasis,
+    ordering: PointOrdering,
+    encoding: PointEncoding,
+    endianness: Endianness,
+    layout: Layout,
+    library_name: String,
+    /// The key to sign the manifest of the conversion with and the file to write the manifest to.
+    signing: Option<(SigningKey, PathBuf)>,
+}
+
+/// The curves the tool works with, which are the ones with a pairing to check monomial SRSs with.
+trait CliCurve: SrsCurve {
+    /// Checks the points of `srs` are powers of the tau committed to by the compressed G2 point `tau_g2`.
+    fn check_powers(srs: &MonomialSrs<Self>, tau_g2: &[u8]) -> Result<bool, InterpolationError>;
+}
+
+fn check_powers_with<P, E>(srs: &MonomialSrs<E>, tau_g2: &[u8]) -> Result<bool, InterpolationError>
+where
+    P: Pairing<
+        G1Affine = ark_ec::short_weierstrass::Affine<E>,
+        G1 = ark_ec::short_weierstrass::Projective<E>,
+        ScalarField = E::ScalarField,
+    >,
+    E: SrsCurve,
+{
+    let tau_g2 = P::G2Affine::deserialize_compressed(tau_g2)
+        .map_err(|e| InterpolationError::SerializationError(e.to_string()))?;
+    Ok(check_monomial_powers::<P, E, _>(
+        srs,
+        P::G2Affine::generator(),
+        tau_g2,
+        &mut rand::thread_rng(),
+    ))
+}
+
+impl CliCurve for ark_bn254::g1::Config {
+    fn check_powers(srs: &MonomialSrs<Self>, tau_g2: &[u8]) -> Result<bool, InterpolationError> {
+        check_powers_with::<Bn254, _>(srs, tau_g2)
+    }
+}
+
+impl CliCurve for ark_bls12_381::g1::Config {
+    fn check_powers(srs: &MonomialSrs<Self>, tau_g2: &[u8]) -> Result<bool, InterpolationError> {
+        check_powers_with::<Bls12_381, _>(srs, tau_g2)
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, InterpolationError> {
+    Ok(BufReader::new(File::open(path)?))
+}
+
+fn read_curve(path: &Path) -> Result<CurveId, InterpolationError> {
+    Ok(BinaryHeader::read(&mut open(path)?)?.curve)
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, InterpolationError> {
+    let hex = hex.trim_start_matches("0x");
+    if !hex.len().is_multiple_of(2) {
+        return Err(InterpolationError::InvalidParameters(
+            "Hex strings must have an even length".to_string(),
+        ));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|e| InterpolationError::InvalidParameters(e.to_string()))
+        })
+        .collect()
+}
+
+fn truncate<E: SrsCurve>(
+    srs: MonomialSrs<E>,
+    size: Option<usize>,
+) -> Result<MonomialSrs<E>, InterpolationError> {
+    match size {
+        Some(size) if size > srs.len() => Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} were requested",
+            srs.len(),
+            size
+        ))),
+        Some(size) => Ok(MonomialSrs::new(srs.points()[..size].to_vec())),
+        None => Ok(srs),
+    }
+}
+
+/// The path that stands for stdin as an input and stdout as an output.
+const STDIO: &str = "-";
+/// The number of points of each file `verify --streaming` holds at once.
+const STREAMING_CHUNK_SIZE: usize = 1 << 16;
+
+/// A reader that keeps a copy of everything read through it, so the header of a stream that cannot be reopened,
+/// such as stdin, can be looked at before the stream is read from the start.
+struct Recorded<R> {
+    inner: R,
+    bytes: Vec<u8>,
+}
+
+impl<R: Read> Read for Recorded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Opens the input to convert, detects its format and curve from its header and returns the input from its start.
+/// It errors if the format is not the one given or cannot be converted.
+fn open_input(
+    input: &Path,
+    from: Option<InputFormat>,
+) -> Result<(SrsInfo, InputFormat, Box<dyn Read>), InterpolationError> {
+    let reader: Box<dyn Read> = if input == Path::new(STDIO) {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(open(input)?)
+    };
+    let mut recorded = Recorded {
+        inner: reader,
+        bytes: Vec::new(),
+    };
+    let info = inspect_format(&mut recorded)?;
+    let detected = match info.format {
+        SrsFormat::Binary => InputFormat::Binary,
+        SrsFormat::Ptau => InputFormat::Ptau,
+        SrsFormat::Ckzg => InputFormat::Ckzg,
+        format => {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Cannot convert {:?} files",
+                format
+            )))
+        }
+    };
+    if let Some(from) = from.filter(|from| *from != detected) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The input is a {:?} file, not a {:?} one",
+            detected, from
+        )));
+    }
+    let Recorded { inner, bytes } = recorded;
+    Ok((
+        info,
+        detected,
+        Box::new(io::Cursor::new(bytes).chain(inner)),
+    ))
+}
+
+fn write_output<E: SrsCurve>(
+    srs: &MonomialSrs<E>,
+    output: &Path,
+    options: &OutputOptions,
+) -> Result<(), InterpolationError>
+where
+    E::BaseField: PrimeField,
+{
+    if options.signing.is_some() && matches!(options.basis, OutputBasis::Monomial) {
+        return Err(InterpolationError::InvalidParameters(
+            "Only conversions to the Lagrange basis have a manifest to sign".to_string(),
+        ));
+    }
+    let started_at = unix_seconds();
+    let lagrange = match options.basis {
+        OutputBasis::Monomial => None,
+        OutputBasis::Lagrange => Some(convert_with_progress(srs, options.ordering)?),
+    };
+    let finished_at = unix_seconds();
+    let points = lagrange
+        .as_ref()
+        .map_or(srs.points(), |lagrange| lagrange.points());
+    let write = |writer: Box<dyn Write>| -> Result<(), InterpolationError> {
+        let mut writer = match (options.layout, &lagrange) {
+            (Layout::Binary, None) => {
+                write_monomial(writer, srs, options.encoding, options.endianness)?
+            }
+            (Layout::Binary, Some(lagrange)) => {
+                write_lagrange(writer, lagrange, options.encoding, options.endianness)?
+            }
+            (Layout::Json, None) => write_json_monomial(writer, srs)?,
+            (Layout::Json, Some(lagrange)) => write_json_lagrange(writer, lagrange)?,
+            (Layout::Rapidsnark, _) => write_rapidsnark(writer, points)?,
+            (Layout::Abi, _) => write_abi(writer, points)?,
+            (Layout::Solidity, _) => write_solidity_library(writer, &options.library_name, points)?,
+        };
+        writer.flush()?;
+        Ok(())
+    };
+    let to_stdout = output == Path::new(STDIO);
+    if to_stdout {
+        write(Box::new(BufWriter::new(io::stdout().lock())))?;
+    } else {
+        // The output is written next to where it goes and renamed into place, so a failed conversion or write
+        // leaves any file already there as it was.
+        let temp = output.with_extension(format!("tmp-{}", process::id()));
+        let written = (|| -> Result<(), InterpolationError> {
+            let file = File::create(&temp)?;
+            write(Box::new(BufWriter::new(file.try_clone()?)))?;
+            file.sync_all()?;
+            fs::rename(&temp, output)?;
+            Ok(())
+        })();
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    }
+    // Messages go to stderr when stdout carries the SRS.
+    if to_stdout {
+        eprintln!("Wrote {} points to stdout", srs.len());
+    } else {
+        println!("Wrote {} points to {}", srs.len(), output.display());
+    }
+    if let (Some((key, path)), Some(lagrange)) = (&options.signing, &lagrange) {
+        let statement = ManifestStatement::new(srs, lagrange, started_at, finished_at);
+        let mut writer = BufWriter::new(File::create(path)?);
+        sign_manifest(statement, key).write(&mut writer)?;
+        writer.flush()?;
+        eprintln!("Wrote the signed manifest to {}", path.display());
+    }
+    Ok(())
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Reads the ed25519 key whose hex encoded seed is in the file at `path`.
+fn read_signing_key(path: &Path) -> Result<SigningKey, InterpolationError> {
+    let seed: [u8; 32] = decode_hex(fs::read_to_string(path)?.trim())?
+        .try_into()
+        .map_err(|_| {
+            InterpolationError::InvalidParameters("The signing key must be 32 bytes".to_string())
+        })?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn verify_signed_manifest(manifest: &Path, public_key: &str) -> Result<bool, InterpolationError> {
+    let public_key: [u8; 32] = decode_hex(public_key)?.try_into().map_err(|_| {
+        InterpolationError::InvalidParameters("The public key must be 32 bytes".to_string())
+    })?;
+    let key = VerifyingKey::from_bytes(&public_key)
+        .map_err(|e| InterpolationError::InvalidParameters(e.to_string()))?;
+    let manifest = SignedManifest::read(&mut open(manifest)?)?;
+    match verify_manifest(&manifest, &key) {
+        Ok(()) => {
+            print!("{}", manifest.statement.to_text());
+            println!("The manifest is signed by the key");
+            Ok(true)
+        }
+        Err(e) => {
+            println!("{}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Converts `srs` with a progress bar on stderr, which is hidden when stderr is not a terminal. The rounds each
+/// take about as long, so the estimate of the time left counts rounds. The rounds are only reported by the radix-2
+/// path, so SRSs that `dry_run` reports as four-step get a spinner and the four-step conversion instead.
+fn convert_with_progress<E: SrsCurve>(
+    srs: &MonomialSrs<E>,
+    ordering: PointOrdering,
+) -> Result<LagrangeSrs<E>, InterpolationError> {
+    if srs.len().is_power_of_two() && memory_requirements(srs.len().ilog2(), E::CURVE_ID)?.four_step
+    {
+        let spinner = ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("Converting [{elapsed_precise}] {spinner} four-step")
+                .expect("The progress bar template is valid"),
+        );
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        let lagrange = srs.to_lagrange_with_ordering(ordering);
+        spinner.finish_and_clear();
+        return lagrange;
+    }
+    let bar = ProgressBar::new(srs.len().checked_ilog2().unwrap_or(0).into()).with_style(
+        ProgressStyle::with_template(
+            "Converting [{elapsed_precise}] {bar:40} round {pos}/{len}, {msg}, {eta} left",
+        )
+        .expect("The progress bar template is valid"),
+    );
+    bar.set_message("first round running");
+    let mut round_start = Instant::now();
+    let lagrange = srs.to_lagrange_with_progress(ordering, |rounds_done, _| {
+        bar.set_position(rounds_done as u64);
+        bar.set_message(format!("last round {:.2?}", round_start.elapsed()));
+        round_start = Instant::now();
+    });
+    bar.finish_and_clear();
+    lagrange
+}
+
+fn read_binary<E: SrsCurve>(
+    reader: impl Read,
+    size: Option<usize>,
+) -> Result<MonomialSrs<E>, InterpolationError> {
+    truncate(read_monomial::<_, E>(reader)?, size)
+}
+
+fn ptau_size(size: Option<usize>) -> Result<usize, InterpolationError> {
+    size.ok_or(InterpolationError::InvalidParameters(
+        "--size is required for ptau files".to_string(),
+    ))
+}
+
+fn read_ckzg(mut reader: impl Read) -> Result<Vec<ark_bls12_381::G1Affine>, InterpolationError> {
+    CkzgTrustedSetup::read(&mut reader)?
+        .g1_monomial
+        .ok_or(InterpolationError::InvalidParameters(
+            "The trusted setup has no monomial G1 points".to_string(),
+        ))
+}
+
+fn convert(
+    input: &Path,
+    from: Option<InputFormat>,
+    output: &Path,
+    size: Option<usize>,
+    options: &OutputOptions,
+) -> Result<(), InterpolationError> {
+    let (info, from, mut reader) = open_input(input, from)?;
+    match from {
+        InputFormat::Binary => match info.curve {
+            CurveId::Bn254 => write_output(
+                &read_binary::<ark_bn254::g1::Config>(reader, size)?,
+                output,
+                options,
+            ),
+            CurveId::Bls12_381 => write_output(
+                &read_binary::<ark_bls12_381::g1::Config>(reader, size)?,
+                output,
+                options,
+            ),
+            curve => Err(unsupported(curve)),
+        },
+        InputFormat::Ptau => {
+            let (_, points) = read_tau_g1(&mut reader, ptau_size(size)?)?;
+            write_output(&MonomialSrs::new(points), output, options)
+        }
+        InputFormat::Ckzg => write_output(
+            &truncate(MonomialSrs::new(read_ckzg(reader)?), size)?,
+            output,
+            options,
+        ),
+    }
+}
+
+/// Reads every point of the input the same way a conversion would, which checks each is on the curve and in the
+/// prime order subgroup, and returns how many were read.
+fn validate_input(
+    mut reader: impl Read,
+    from: InputFormat,
+    curve: CurveId,
+    size: Option<usize>,
+) -> Result<usize, InterpolationError> {
+    match from {
+        InputFormat::Binary => match curve {
+            CurveId::Bn254 => Ok(read_binary::<ark_bn254::g1::Config>(reader, size)?.len()),
+            CurveId::Bls12_381 => Ok(read_binary::<ark_bls12_381::g1::Config>(reader, size)?.len()),
+            curve => Err(unsupported(curve)),
+        },
+        InputFormat::Ptau => Ok(read_tau_g1(&mut reader, ptau_size(size)?)?.1.len()),
+        InputFormat::Ckzg => Ok(truncate(MonomialSrs::new(read_ckzg(reader)?), size)?.len()),
+    }
+}
+
+fn dry_run(
+    input: &Path,
+    from: Option<InputFormat>,
+    size: Option<usize>,
+    basis: OutputBasis,
+    validate: bool,
+) -> Result<(), InterpolationError> {
+    let (info, from, reader) = open_input(input, from)?;
+    // Whether a trusted setup has monomial points is only known once its Lagrange and G2 points are read.
+    if info.format != SrsFormat::Ckzg && !info.bases.contains(&Basis::Monomial) {
+        return Err(InterpolationError::InvalidParameters(
+            "The input has no monomial points".to_string(),
+        ));
+    }
+    let available = usize::try_from(info.size).map_err(|_| InterpolationError::SizeError)?;
+    let size = match from {
+        InputFormat::Ptau => ptau_size(size)?,
+        _ => size.unwrap_or(available),
+    };
+    if size > available {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} were requested",
+            available, size
+        )));
+    }
+    println!("curve:      {}", info.curve.name());
+    println!("size:       {} of {} points", size, available);
+
+    match basis {
+        OutputBasis::Monomial => println!("algorithm:  none, the points are only re-encoded"),
+        OutputBasis::Lagrange => {
+            if !size.is_power_of_two() {
+                return Err(InterpolationError::SizeError);
+            }
+            let estimate = memory_requirements(size.ilog2(), info.curve)?;
+            println!(
+                "algorithm:  {}",
+                if estimate.four_step {
+                    "four-step"
+                } else {
+                    "radix-2"
+                }
+            );
+            println!("points:     {} bytes", estimate.points_bytes);
+            println!("scratch:    {} bytes", estimate.scratch_bytes);
+            println!("peak:       {} bytes", estimate.in_memory_bytes);
+        }
+    }
+
+    if validate {
+        let checked = validate_input(reader, from, info.curve, Some(size))?;
+        println!("validated:  {} points", checked);
+    }
+    Ok(())
+}
+
+fn verify_curve<E: CliCurve>(
+    monomial: &Path,
+    lagrange: Option<&Path>,
+    tau_g2: Option<&[u8]>,
+    streaming: bool,
+) -> Result<bool, InterpolationError> {
+    // A streaming check only needs the monomial SRS in memory to check the powers of tau.
+    let srs = match streaming && tau_g2.is_none() {
+        true => None,
+        false => Some(read_monomial::<_, E>(open(monomial)?)?),
+    };
+    let mut ok = true;
+    if let Some(lagrange) = lagrange {
+        let rng = &mut rand::thread_rng();
+        let matches = match (&srs, streaming) {
+            (Some(srs), false) => {
+                let lagrange = read_lagrange::<_, E>(open(lagrange)?)?;
+                check_lagrange_matches_monomial(srs, &lagrange, rng)?
+            }
+            _ => check_lagrange_files_match::<_, _, E, _>(
+                open(monomial)?,
+                open(lagrange)?,
+                STREAMING_CHUNK_SIZE,
+                rng,
+            )?,
+        };
+        println!(
+            "Lagrange SRS matches the monomial SRS: {}",
+            if matches { "ok" } else { "FAILED" }
+        );
+        ok &= matches;
+    }
+    if let Some(tau_g2) = tau_g2 {
+        let srs = srs
+            .as_ref()
+            .expect("The monomial SRS is read to check the powers");
+        let powers = E::check_powers(srs, tau_g2)?;
+        println!(
+            "Monomial points are powers of tau: {}",
+            if powers { "ok" } else { "FAILED" }
+        );
+        ok &= powers;
+    }
+    Ok(ok)
+}
+
+fn verify(
+    monomial: &Path,
+    lagrange: Option<&Path>,
+    tau_g2: Option<&str>,
+    streaming: bool,
+) -> Result<bool, InterpolationError> {
+    if lagrange.is_none() && tau_g2.is_none() {
+        return Err(InterpolationError::InvalidParameters(
+            "Nothing to check, pass --lagrange or --tau-g2".to_string(),
+        ));
+    }
+    let curve = read_curve(monomial)?;
+    if let Some(lagrange) = lagrange {
+        if read_curve(lagrange)? != curve {
+            return Err(InterpolationError::InvalidParameters(
+                "The two SRSs are on different curves".to_string(),
+            ));
+        }
+    }
+    let tau_g2 = tau_g2.map(decode_hex).transpose()?;
+    match curve {
+        CurveId::Bn254 => {
+            verify_curve::<ark_bn254::g1::Config>(monomial, lagrange, tau_g2.as_deref(), streaming)
+        }
+        CurveId::Bls12_381 => verify_curve::<ark_bls12_381::g1::Config>(
+            monomial,
+            lagrange,
+            tau_g2.as_deref(),
+            streaming,
+        ),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+fn inspect_curve<E: SrsCurve>(file: &Path) -> Result<(), InterpolationError> {
+    let reader = SrsReader::<_, E>::new(open(file)?)?;
+    let header = *reader.header();
+    println!("curve:      {}", header.curve.name());
+    println!(
+        "basis:      {}",
+        match header.basis {
+            Basis::Monomial => "monomial",
+            Basis::Lagrange => "lagrange",
+        }
+    );
+    println!("size:       {}", header.size);
+    println!(
+        "ordering:   {}",
+        match header.ordering {
+            PointOrdering::Natural => "natural",
+            PointOrdering::BitReversed => "bit-reversed",
+        }
+    );
+    println!(
+        "encoding:   {}",
+        match header.encoding {
+            PointEncoding::Compressed => "compressed",
+            PointEncoding::Uncompressed => "uncompressed",
+        }
+    );
+    println!(
+        "endianness: {}",
+        match header.endianness {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        }
+    );
+    if let Some(generator) = reader.generator() {
+        println!("generator:  {}", generator);
+    }
+
+    let mut hasher = PointsHasher::new();
+    for point in reader {
+        hasher.update(&point?);
+    }
+    println!("digest:     {}", hasher.finalize());
+    Ok(())
+}
+
+fn inspect(file: &Path) -> Result<(), InterpolationError> {
+    match read_curve(file)? {
+        CurveId::Bn254 => inspect_curve::<ark_bn254::g1::Config>(file),
+        CurveId::Bls12_381 => inspect_curve::<ark_bls12_381::g1::Config>(file),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+/// The name of the manifest `split` writes next to the shards.
+const MANIFEST_FILE: &str = "manifest.txt";
+
+fn split_curve<E: SrsCurve>(
+    input: &Path,
+    shard_size: u64,
+    output_dir: &Path,
+) -> Result<(), InterpolationError> {
+    fs::create_dir_all(output_dir)?;
+    let manifest = split_srs::<_, _, E>(open(input)?, shard_size, |index| {
+        let file = format!("shard-{:05}.srs", index);
+        let writer = BufWriter::new(File::create(output_dir.join(&file))?);
+        Ok((file, writer))
+    })?;
+    let mut writer = BufWriter::new(File::create(output_dir.join(MANIFEST_FILE))?);
+    manifest.write(&mut writer)?;
+    writer.flush()?;
+    println!(
+        "Wrote {} shards and {} to {}",
+        manifest.shards.len(),
+        MANIFEST_FILE,
+        output_dir.display()
+    );
+    Ok(())
+}
+
+fn split(input: &Path, shard_size: u64, output_dir: &Path) -> Result<(), InterpolationError> {
+    match read_curve(input)? {
+        CurveId::Bn254 => split_curve::<ark_bn254::g1::Config>(input, shard_size, output_dir),
+        CurveId::Bls12_381 => {
+            split_curve::<ark_bls12_381::g1::Config>(input, shard_size, output_dir)
+        }
+        curve => Err(unsupported(curve)),
+    }
+}
+
+fn merge_curve<E: SrsCurve>(
+    manifest: &ShardManifest,
+    dir: &Path,
+    output: &Path,
+) -> Result<(), InterpolationError> {
+    let writer = BufWriter::new(File::create(output)?);
+    let mut writer = merge_srs::<_, _, E>(manifest, writer, |shard| open(&dir.join(&shard.file)))?;
+    writer.flush()?;
+    println!(
+        "Wrote {} points from {} shards to {}",
+        manifest.header.size,
+        manifest.shards.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn merge(manifest_path: &Path, output: &Path) -> Result<(), InterpolationError> {
+    let manifest = ShardManifest::read(&mut open(manifest_path)?)?;
+    let dir = manifest_path.parent().unwrap_or(Path::new("."));
+    match manifest.header.curve {
+        CurveId::Bn254 => merge_curve::<ark_bn254::g1::Config>(&manifest, dir, output),
+        CurveId::Bls12_381 => merge_curve::<ark_bls12_381::g1::Config>(&manifest, dir, output),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+/// Writes intermediate points for a worker, uncompressed since they are read back soon after.
+fn write_points<E: SrsCurve>(
+    points: Vec<Affine<E>>,
+    path: &Path,
+) -> Result<(), InterpolationError> {
+    let writer = BufWriter::new(File::create(path)?);
+    let srs = MonomialSrs::new(points);
+    write_monomial(
+        writer,
+        &srs,
+        PointEncoding::Uncompressed,
+        Endianness::Little,
+    )?
+    .flush()?;
+    Ok(())
+}
+
+fn worker_curve<E: SrsCurve>(
+    stage: Stage,
+    size: usize,
+    first: usize,
+    input: &Path,
+    output: &Path,
+) -> Result<(), InterpolationError> {
+    let plan = DistributedPlan::<E>::new(size)?;
+    let mut points = read_monomial::<_, E>(open(input)?)?.into_points();
+    match stage {
+        Stage::Columns => plan.column_job(first, &mut points)?,
+        Stage::Rows => plan.row_job(&mut points)?,
+    }
+    write_points(points, output)
+}
+
+fn worker(
+    stage: Stage,
+    size: usize,
+    first: usize,
+    input: &Path,
+    output: &Path,
+) -> Result<(), InterpolationError> {
+    match read_curve(input)? {
+        CurveId::Bn254 => worker_curve::<ark_bn254::g1::Config>(stage, size, first, input, output),
+        CurveId::Bls12_381 => {
+            worker_curve::<ark_bls12_381::g1::Config>(stage, size, first, input, output)
+        }
+        curve => Err(unsupported(curve)),
+    }
+}
+
+/// Returns the command line that starts each worker, either read from `config` or this executable `workers` times.
+fn worker_commands(
+    workers: usize,
+    config: Option<&Path>,
+) -> Result<Vec<Vec<String>>, InterpolationError> {
+    let commands = match config {
+        Some(config) => fs::read_to_string(config)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.split_whitespace().map(str::to_string).collect())
+            .collect(),
+        None => {
+            let executable = env::current_exe()?.to_string_lossy().into_owned();
+            vec![vec![executable]; workers]
+        }
+    };
+    if commands.is_empty() {
+        return Err(InterpolationError::InvalidParameters(
+            "There are no workers to run the conversion on".to_string(),
+        ));
+    }
+    Ok(commands)
+}
+
+/// The worker processes of a stage, which are killed and waited on when dropped so a stage that fails part way
+/// does not leave the others running. Killing a worker that has already been waited on does nothing.
+struct Workers(Vec<process::Child>);
+
+impl Drop for Workers {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Runs one step of a distributed conversion on `points`, which are chunks of `chunk_len` points, giving each
+/// worker a shard of consecutive chunks and putting the shards they return back in place.
+fn run_stage<E: SrsCurve>(
+    commands: &[Vec<String>],
+    work_dir: &Path,
+    stage: Stage,
+    points: &mut [Affine<E>],
+    chunk_len: usize,
+) -> Result<(), InterpolationError> {
+    let size = points.len();
+    let per_job = (size / chunk_len).div_ceil(commands.len());
+    let mut workers = Workers(Vec::with_capacity(commands.len()));
+    let mut jobs = Vec::with_capacity(commands.len());
+    for (i, (shard, command)) in points
+        .chunks_mut(per_job * chunk_len)
+        .zip(commands)
+        .enumerate()
+    {
+        let input = work_dir.join(format!("{}-{}-in.srs", stage.name(), i));
+        let output = work_dir.join(format!("{}-{}-out.srs", stage.name(), i));
+        write_points(shard.to_vec(), &input)?;
+        let child = process::Command::new(&command[0])
+            .args(&command[1..])
+            .args(["worker", "--stage", stage.name()])
+            .args(["--size", &size.to_string()])
+            .args(["--first", &(i * per_job).to_string()])
+            .arg("--input")
+            .arg(&input)
+            .arg("--output")
+            .arg(&output)
+            .spawn()?;
+        workers.0.push(child);
+        jobs.push((input, output, shard));
+    }
+
+    for (i, (input, output, shard)) in jobs.into_iter().enumerate() {
+        let status = workers.0[i].wait()?;
+        if !status.success() {
+            return Err(InterpolationError::TaskError(format!(
+                "Worker {} on the {} failed with {}",
+                i,
+                stage.name(),
+                status
+            )));
+        }
+        let result = read_monomial::<_, E>(open(&output)?)?;
+        if result.len() != shard.len() {
+            return Err(InterpolationError::TaskError(format!(
+                "Worker {} returned {} points but was sent {}",
+                i,
+                result.len(),
+                shard.len()
+            )));
+        }
+        shard.copy_from_slice(result.points());
+        fs::remove_file(input)?;
+        fs::remove_file(output)?;
+    }
+    Ok(())
+}
+
+fn distribute_curve<E: SrsCurve>(
+    input: &Path,
+    output: &Path,
+    commands: &[Vec<String>],
+    work_dir: &Path,
+) -> Result<(), InterpolationError> {
+    let srs = read_monomial::<_, E>(open(input)?)?;
+    let plan = DistributedPlan::<E>::new(srs.len())?;
+    fs::create_dir_all(work_dir)?;
+
+    let mut columns = plan.to_columns(srs.points())?;
+    drop(srs);
+    run_stage(
+        commands,
+        work_dir,
+        Stage::Columns,
+        &mut columns,
+        plan.column_len(),
+    )?;
+    let mut rows = plan.to_rows(&columns)?;
+    drop(columns);
+    run_stage(commands, work_dir, Stage::Rows, &mut rows, plan.row_len())?;
+    let lagrange = plan.finish(&rows)?;
+
+    let writer = BufWriter::new(File::create(output)?);
+    write_lagrange(
+        writer,
+        &lagrange,
+        PointEncoding::Compressed,
+        Endianness::Little,
+    )?
+    .flush()?;
+    println!(
+        "Wrote {} points to {} using {} workers",
+        lagrange.len(),
+        output.display(),
+        commands.len()
+    );
+    Ok(())
+}
+
+fn distribute(
+    input: &Path,
+    output: &Path,
+    workers: usize,
+    worker_config: Option<&Path>,
+    work_dir: &Path,
+) -> Result<(), InterpolationError> {
+    let commands = worker_commands(workers, worker_config)?;
+    match read_curve(input)? {
+        CurveId::Bn254 => {
+            distribute_curve::<ark_bn254::g1::Config>(input, output, &commands, work_dir)
+        }
+        CurveId::Bls12_381 => {
+            distribute_curve::<ark_bls12_381::g1::Config>(input, output, &commands, work_dir)
+        }
+        curve => Err(unsupported(curve)),
+    }
+}
+
+fn bench_curve<E: SrsCurve>(log_size: u32) -> Result<(), InterpolationError> {
+    let size = 1usize
+        .checked_shl(log_size)
+        .ok_or(InterpolationError::SizeError)?;
+    println!("Generating {} points on {}", size, E::CURVE_ID.name());
+    let srs = insecure_monomial_srs::<E, _>(&mut rand::thread_rng(), size);
+    let (_, report) = srs_to_lagrange_with_report(srs.points())?;
+
+    println!("threads:     {}", report.threads);
+    println!("total:       {:?}", report.total);
+    println!(
+        "throughput:  {:.0} points/s",
+        size as f64 / report.total.as_secs_f64()
+    );
+    println!("reorder:     {:?}", report.reorder);
+    println!("rescale:     {:?}", report.rescale);
+    println!("utilization: {:.2}", report.thread_utilization());
+    println!("round       twiddle   butterflies");
+    for round in report.rounds.iter() {
+        println!(
+            "{:>5}  {:>12?}  {:>12?}",
+            round.round, round.twiddle, round.butterflies
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "grpc")]
+fn serve(addr: std::net::SocketAddr) -> Result<(), InterpolationError> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    eprintln!("Serving on {}", addr);
+    runtime.block_on(srs_interpolation::grpc::serve(addr))
+}
+
+fn unsupported(curve: CurveId) -> InterpolationError {
+    InterpolationError::InvalidParameters(format!("The curve {} is not supported", curve.name()))
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Convert {
+            input,
+            from,
+            size,
+            basis,
+            dry_run: true,
+            validate,
+            ..
+        } => dry_run(&input, from, size, basis, validate).map(|_| true),
+        Command::Convert {
+            input,
+            from,
+            output,
+            size,
+            basis,
+            ordering,
+            encoding,
+            endianness,
+            layout,
+            library_name,
+            signing_key,
+            signed_manifest,
+            ..
+        } => {
+            // The key is read before converting, so a bad key does not waste a long conversion.
+            signing_key
+                .map(|path| read_signing_key(&path))
+                .transpose()
+                .and_then(|key| {
+                    let options = OutputOptions {
+                        basis,
+                        ordering: match ordering {
+                            Ordering::Natural => PointOrdering::Natural,
+                            Ordering::BitReversed => PointOrdering::BitReversed,
+                        },
+                        encoding: match encoding {
+                            Encoding::Compressed => PointEncoding::Compressed,
+                            Encoding::Uncompressed => PointEncoding::Uncompressed,
+                        },
+                        endianness: match endianness {
+                            ByteOrder::Little => Endianness::Little,
+                            ByteOrder::Big => Endianness::Big,
+                        },
+                        layout,
+                        library_name,
+                        // Clap only lets the key be given with the manifest.
+                        signing: key.map(|key| {
+                            (key, signed_manifest.expect("--signed-manifest is required"))
+                        }),
+                    };
+                    // Clap only lets the output be left out of a dry run.
+                    let output = output.expect("--output is required");
+                    convert(&input, from, &output, size, &options)
+                })
+                .map(|_| true)
+        }
+        Command::Verify {
+            monomial,
+            lagrange,
+            tau_g2,
+            streaming,
+        } => verify(&monomial, lagrange.as_deref(), tau_g2.as_deref(), streaming),
+        Command::VerifyManifest {
+            manifest,
+            public_key,
+        } => verify_signed_manifest(&manifest, &public_key),
+        Command::Inspect { file } => inspect(&file).map(|_| true),
+        Command::Split {
+            input,
+            shard_size,
+            output_dir,
+        } => split(&input, shard_size, &output_dir).map(|_| true),
+        Command::Merge { manifest, output } => merge(&manifest, &output).map(|_| true),
+        Command::Distribute {
+            input,
+            output,
+            workers,
+            worker_config,
+            work_dir,
+        } => distribute(
+            &input,
+            &output,
+            workers,
+            worker_config.as_deref(),
+            &work_dir,
+        )
+        .map(|_| true),
+        Command::Worker {
+            stage,
+            size,
+            first,
+            input,
+            output,
+        } => worker(stage, size, first, &input, &output).map(|_| true),
+        Command::Bench { curve, log_size } => match curve {
+            BenchCurve::Bn254 => bench_curve::<ark_bn254::g1::Config>(log_size),
+            BenchCurve::Bls12_381 => bench_curve::<ark_bls12_381::g1::Config>(log_size),
+        }
+        .map(|_| true),
+        #[cfg(feature = "grpc")]
+        Command::Serve { addr } => serve(addr).map(|_| true),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
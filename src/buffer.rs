@@ -0,0 +1,200 @@
+//! This module lets integrators choose where the large buffers of a conversion are allocated, such as from huge
+//! pages or an arena, through the [`BufferProvider`] trait.
+//!
+//! A conversion of `n` points asks its provider for one buffer of `n` points, which is also the returned result,
+//! and one scratch buffer of up to `n` base field elements, never more than 16 MiB, for the batched additions.
+//! Domains that use the four-step decomposition ask for a second buffer of `n` points and many small scratch
+//! buffers, one per row and column.
+//!
+//! A buffer that cannot be allocated fails the conversion with [`InterpolationError::OutOfMemory`] rather than
+//! aborting the process, so a service can turn away an input too large for the machine.
+use crate::{domain_size_inv, error::InterpolationError, group_fft_in_place};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, mem::size_of, ops::DerefMut, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A source of the large buffers a conversion works in.
+pub trait BufferProvider: Sync {
+    /// A buffer handed out by the provider, which releases its memory when dropped.
+    type Buffer<T: Copy + Send + Sync>: DerefMut<Target = [T]> + Send;
+
+    /// This function returns a buffer of `len` copies of `value`.
+    fn buffer<T: Copy + Send + Sync>(
+        &self,
+        len: usize,
+        value: T,
+    ) -> Result<Self::Buffer<T>, InterpolationError>;
+}
+
+/// The provider that allocates every buffer as a [`Vec`] from the global allocator, which is what the conversions
+/// without a provider use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VecProvider;
+
+impl BufferProvider for VecProvider {
+    type Buffer<T: Copy + Send + Sync> = Vec<T>;
+
+    fn buffer<T: Copy + Send + Sync>(
+        &self,
+        len: usize,
+        value: T,
+    ) -> Result<Vec<T>, InterpolationError> {
+        try_filled(len, value)
+    }
+}
+
+/// This function reserves space for exactly `len` elements in `values`, returning
+/// [`InterpolationError::OutOfMemory`] if the allocation fails.
+fn try_reserve<T>(values: &mut Vec<T>, len: usize) -> Result<(), InterpolationError> {
+    values
+        .try_reserve_exact(len)
+        .map_err(|_| InterpolationError::OutOfMemory {
+            requested_bytes: len.saturating_mul(size_of::<T>()),
+        })
+}
+
+/// This function returns a vector with room for `len` elements, returning [`InterpolationError::OutOfMemory`]
+/// rather than aborting if it cannot be allocated.
+pub(crate) fn try_with_capacity<T>(len: usize) -> Result<Vec<T>, InterpolationError> {
+    let mut values = Vec::new();
+    try_reserve(&mut values, len)?;
+    Ok(values)
+}
+
+/// This function is the fallible form of `vec![value; len]`.
+pub(crate) fn try_filled<T: Clone>(len: usize, value: T) -> Result<Vec<T>, InterpolationError> {
+    let mut values = try_with_capacity(len)?;
+    values.resize(len, value);
+    Ok(values)
+}
+
+/// This function is the fallible form of `values.to_vec()`.
+pub(crate) fn try_copied<T: Copy>(values: &[T]) -> Result<Vec<T>, InterpolationError> {
+    let mut copy = try_with_capacity(values.len())?;
+    copy.extend_from_slice(values);
+    Ok(copy)
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] but takes every large buffer, including the one that
+/// holds the result, from `provider`.
+pub fn srs_to_lagrange_in<E, F, P>(
+    points: &[Affine<E>],
+    provider: &P,
+) -> Result<P::Buffer<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let mut buffer = copy_to(points, provider)?;
+    group_fft_in_place(&mut buffer, domain.group_gen_inv(), provider)?;
+
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(buffer).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(buffer)
+}
+
+/// This function is the same as [`crate::lagrange_to_srs`] but takes every large buffer, including the one that
+/// holds the result, from `provider`.
+pub fn lagrange_to_srs_in<E, F, P>(
+    points: &[Affine<E>],
+    provider: &P,
+) -> Result<P::Buffer<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let mut buffer = copy_to(points, provider)?;
+    group_fft_in_place(&mut buffer, domain.group_gen(), provider)?;
+    Ok(buffer)
+}
+
+/// This function copies `points` into a buffer from `provider`.
+fn copy_to<E: SWCurveConfig, P: BufferProvider>(
+    points: &[Affine<E>],
+    provider: &P,
+) -> Result<P::Buffer<Affine<E>>, InterpolationError> {
+    let mut buffer = provider.buffer(points.len(), Affine::<E>::identity())?;
+    buffer.copy_from_slice(points);
+    Ok(buffer)
+}
+
+/// This function returns a scratch buffer of `len` base field elements from `provider`.
+pub(crate) fn scratch<E: SWCurveConfig, P: BufferProvider>(
+    provider: &P,
+    len: usize,
+) -> Result<P::Buffer<E::BaseField>, InterpolationError> {
+    provider.buffer(len, E::BaseField::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lagrange_to_srs, srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A provider that counts the elements it hands out.
+    #[derive(Default)]
+    struct CountingProvider {
+        elements: AtomicUsize,
+    }
+
+    impl BufferProvider for CountingProvider {
+        type Buffer<T: Copy + Send + Sync> = Vec<T>;
+
+        fn buffer<T: Copy + Send + Sync>(
+            &self,
+            len: usize,
+            value: T,
+        ) -> Result<Vec<T>, InterpolationError> {
+            self.elements.fetch_add(len, Ordering::Relaxed);
+            Ok(vec![value; len])
+        }
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_in() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let provider = CountingProvider::default();
+
+        let lagrange = srs_to_lagrange_in(&srs, &provider).unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&srs).unwrap());
+        // One buffer of points and one of scratch.
+        assert_eq!(provider.elements.load(Ordering::Relaxed), 32);
+        assert_eq!(
+            lagrange_to_srs_in(&lagrange, &VecProvider).unwrap(),
+            lagrange_to_srs(&lagrange).unwrap()
+        );
+        assert!(srs_to_lagrange_in(&srs[..3], &VecProvider).is_err());
+    }
+
+    #[test]
+    fn test_failed_allocation() {
+        assert_eq!(try_filled(3, 7u64).unwrap(), [7, 7, 7]);
+        assert_eq!(try_copied(&[1u8, 2]).unwrap(), [1, 2]);
+        let len = 1 << 40;
+        assert!(matches!(
+            VecProvider.buffer(len, [0u64; 1 << 10]),
+            Err(InterpolationError::OutOfMemory { requested_bytes }) if requested_bytes == len << 13
+        ));
+        assert!(matches!(
+            try_with_capacity::<u64>(usize::MAX / 4),
+            Err(InterpolationError::OutOfMemory {
+                requested_bytes: usize::MAX
+            })
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+//! This module records the butterfly schedule of the group FFT, the pairs of positions each round combines and the
+//! power of the generator each pair is twiddled by, and exports it as JSON or as a Graphviz DOT graph. The schedule
+//! only depends on the size and the variant, so a port of the algorithm to other hardware can check its own network
+//! against it before running on any points.
+//!
+//! Writing `w` for the generator of the conversion, which is the inverse of the generator of the arkworks domain,
+//! and `a`, `b` for the points at the `top` and `bottom` of a butterfly with twiddle exponent `e`:
+//!
+//! - decimation in time replaces them by `a + w^e b` and `a - w^e b`, after first moving the point at index
+//!   `permutation[i]` to position `i`;
+//! - decimation in frequency replaces them by `a + b` and `w^e (a - b)`, and its permutation is the identity.
+//!
+//! Neither includes the multiplication of every point by `1 / n` that ends a conversion.
+use crate::{error::InterpolationError, srs::FftVariant, utils::bit_reverse};
+use ark_std::{fmt::Write, string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+/// A butterfly combining the points at two positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Butterfly {
+    /// The lower of the two positions.
+    pub top: usize,
+    /// The higher of the two positions.
+    pub bottom: usize,
+    /// The power of the generator of the conversion the butterfly is twiddled by.
+    pub twiddle_exponent: u64,
+}
+
+/// The butterflies of one round, which touch every position once and can run in any order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceRound {
+    /// The round, from one to the log of the size, which combines positions `2^(round - 1)` apart.
+    pub round: usize,
+    /// The butterflies, ordered by their top position.
+    pub butterflies: Vec<Butterfly>,
+}
+
+/// The schedule of the group FFT for one size and variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ButterflyTrace {
+    /// The number of points.
+    pub size: usize,
+    /// The variant the schedule is for.
+    pub variant: FftVariant,
+    /// The index of the input point each position starts with.
+    pub permutation: Vec<usize>,
+    /// The rounds, in the order they run.
+    pub rounds: Vec<TraceRound>,
+}
+
+impl ButterflyTrace {
+    /// This function records the schedule of the group FFT of `size` points in `variant`. It errors if `size` is
+    /// not a power of two.
+    pub fn new(size: usize, variant: FftVariant) -> Result<Self, InterpolationError> {
+        if !size.is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let log_size = size.ilog2() as usize;
+        let permutation = match variant {
+            FftVariant::DecimationInTime => (0..size)
+                .map(|index| bit_reverse(index, log_size))
+                .collect::<Result<_, _>>()?,
+            FftVariant::DecimationInFrequency => (0..size).collect(),
+        };
+        let round = |round: usize| {
+            let half = 1 << (round - 1);
+            let stride = (size >> round) as u64;
+            let butterflies = (0..size)
+                .step_by(2 * half)
+                .flat_map(|start| {
+                    (0..half).map(move |j| Butterfly {
+                        top: start + j,
+                        bottom: start + j + half,
+                        twiddle_exponent: j as u64 * stride,
+                    })
+                })
+                .collect();
+            TraceRound { round, butterflies }
+        };
+        let rounds = match variant {
+            FftVariant::DecimationInTime => (1..=log_size).map(round).collect(),
+            FftVariant::DecimationInFrequency => (1..=log_size).rev().map(round).collect(),
+        };
+        Ok(Self {
+            size,
+            variant,
+            permutation,
+            rounds,
+        })
+    }
+
+    /// This function returns the schedule as a JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serializing a trace to JSON is infallible")
+    }
+
+    /// This function returns the schedule as a Graphviz DOT graph with a column of nodes for the positions before
+    /// the first round and after each round. The node for position `i` after `r` rounds is `r_i`, the first column
+    /// is labelled with the input indices and each edge into a butterfly output is labelled with its coefficient
+    /// when that is not one.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph butterflies {\n  rankdir=LR;\n");
+        for (position, input) in self.permutation.iter().enumerate() {
+            let _ = writeln!(dot, "  \"0_{}\" [label=\"x{}\"];", position, input);
+        }
+        for (column, round) in self.rounds.iter().enumerate() {
+            let edge = |dot: &mut String, from: usize, to: usize, label: Option<String>| {
+                let _ = write!(
+                    dot,
+                    "  \"{}_{}\" -> \"{}_{}\"",
+                    column,
+                    from,
+                    column + 1,
+                    to
+                );
+                let _ = match label {
+                    Some(label) => writeln!(dot, " [label=\"{}\"];", label),
+                    None => writeln!(dot, ";"),
+                };
+            };
+            for butterfly in round.butterflies.iter() {
+                let (top, bottom, e) =
+                    (butterfly.top, butterfly.bottom, butterfly.twiddle_exponent);
+                let twiddle = |sign: &str| match e {
+                    0 => (!sign.is_empty()).then(|| sign.to_string()),
+                    _ => Some(format!("{}w^{}", sign, e)),
+                };
+                edge(&mut dot, top, top, None);
+                match self.variant {
+                    FftVariant::DecimationInTime => {
+                        edge(&mut dot, bottom, top, twiddle(""));
+                        edge(&mut dot, top, bottom, None);
+                        edge(&mut dot, bottom, bottom, twiddle("-"));
+                    }
+                    FftVariant::DecimationInFrequency => {
+                        edge(&mut dot, bottom, top, None);
+                        edge(&mut dot, top, bottom, twiddle(""));
+                        edge(&mut dot, bottom, bottom, twiddle("-"));
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::Field;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::UniformRand;
+
+    /// Runs the schedule over scalars in place of points.
+    fn run(trace: &ButterflyTrace, values: &[Fr], w: Fr) -> Vec<Fr> {
+        let mut result = trace
+            .permutation
+            .iter()
+            .map(|input| values[*input])
+            .collect::<Vec<_>>();
+        for round in trace.rounds.iter() {
+            for butterfly in round.butterflies.iter() {
+                let twiddle = w.pow([butterfly.twiddle_exponent]);
+                let (a, b) = (result[butterfly.top], result[butterfly.bottom]);
+                (result[butterfly.top], result[butterfly.bottom]) = match trace.variant {
+                    FftVariant::DecimationInTime => (a + twiddle * b, a - twiddle * b),
+                    FftVariant::DecimationInFrequency => (a + b, twiddle * (a - b)),
+                };
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_butterfly_trace() {
+        let rng = &mut ark_std::test_rng();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let values = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let mut expected = domain.ifft(&values);
+        expected
+            .iter_mut()
+            .for_each(|value| *value *= domain.size_as_field_element());
+
+        // Run over scalars, the schedule is the inverse FFT without the scaling, in bit-reversed order for DIF.
+        let dit = ButterflyTrace::new(16, FftVariant::DecimationInTime).unwrap();
+        assert_eq!(run(&dit, &values, domain.group_gen_inv()), expected);
+        let dif = ButterflyTrace::new(16, FftVariant::DecimationInFrequency).unwrap();
+        let reversed = run(&dif, &values, domain.group_gen_inv());
+        for (index, value) in reversed.iter().enumerate() {
+            assert_eq!(*value, expected[bit_reverse(index, 4).unwrap()]);
+        }
+        assert_eq!(
+            dif.rounds
+                .iter()
+                .map(|round| round.round)
+                .collect::<Vec<_>>(),
+            [4, 3, 2, 1]
+        );
+
+        let json = ButterflyTrace::new(2, FftVariant::DecimationInTime)
+            .unwrap()
+            .to_json();
+        assert_eq!(
+            json,
+            "{\"size\":2,\"variant\":\"decimation_in_time\",\"permutation\":[0,1],\"rounds\":[{\"round\":1,\
+             \"butterflies\":[{\"top\":0,\"bottom\":1,\"twiddle_exponent\":0}]}]}"
+        );
+        assert_eq!(
+            serde_json::from_str::<ButterflyTrace>(&dit.to_json()).unwrap(),
+            dit
+        );
+
+        let dot = dit.to_dot();
+        assert!(dot.starts_with("digraph butterflies {"));
+        assert!(dot.contains("\"0_0\" [label=\"x0\"];\n  \"0_1\" [label=\"x8\"];"));
+        assert!(dot.contains("\"3_10\" -> \"4_2\" [label=\"w^2\"];"));
+        assert!(dot.contains("\"0_1\" -> \"1_1\" [label=\"-\"];"));
+        assert_eq!(dot.matches("->").count(), 4 * 16 * 2);
+
+        assert!(ButterflyTrace::new(12, FftVariant::DecimationInTime).is_err());
+    }
+}
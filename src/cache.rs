@@ -0,0 +1,203 @@
+//! This module keeps converted Lagrange SRSs on disk, so a prover that restarts does not redo a conversion it
+//! has already done.
+//!
+//! Each entry is keyed by the curve, the number of points and the SHA-256 digest of the compressed monomial points,
+//! and holds the Lagrange points in natural order in this crate's binary format. Entries are written to a temporary
+//! file and renamed into place, so a crash never leaves a partial entry behind, and an entry that fails to read is
+//! converted again and replaced.
+use crate::{
+    curves::SrsCurve,
+    error::InterpolationError,
+    formats::binary::{read_lagrange, write_lagrange, Endianness, PointEncoding},
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{fmt, vec::Vec};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// The key of a conversion in a [`LagrangeCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// The name of the curve, as given by [`crate::curves::CurveId::name`].
+    pub curve: &'static str,
+    /// The number of points converted.
+    pub size: u64,
+    /// The SHA-256 digest of the concatenated compressed monomial points.
+    pub sha256: [u8; 32],
+}
+
+impl CacheKey {
+    /// This function returns the key of converting `srs`.
+    pub fn new<E: SrsCurve>(srs: &MonomialSrs<E>) -> Result<Self, InterpolationError> {
+        let mut hasher = Sha256::new();
+        let mut buffer = Vec::new();
+        for point in srs.points() {
+            buffer.clear();
+            point
+                .serialize_compressed(&mut buffer)
+                .map_err(|e| InterpolationError::SerializationError(e.to_string()))?;
+            hasher.update(&buffer);
+        }
+        Ok(Self {
+            curve: E::CURVE_ID.name(),
+            size: srs.len() as u64,
+            sha256: hasher.finalize().into(),
+        })
+    }
+}
+
+impl fmt::Display for CacheKey {
+    /// The key is displayed as the file name of its entry.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-", self.curve, self.size)?;
+        self.sha256
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))?;
+        write!(f, ".lagrange")
+    }
+}
+
+/// A directory of converted Lagrange SRSs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LagrangeCache {
+    dir: PathBuf,
+}
+
+impl LagrangeCache {
+    /// This function opens the cache in `dir`, creating the directory if it does not exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, InterpolationError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// The directory the entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The path of the entry for `key`, whether or not it exists.
+    pub fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.to_string())
+    }
+
+    /// This function returns the cached conversion of `srs` if there is a readable one.
+    pub fn get<E: SrsCurve>(
+        &self,
+        srs: &MonomialSrs<E>,
+    ) -> Result<Option<LagrangeSrs<E>>, InterpolationError> {
+        self.read(&self.path(&CacheKey::new(srs)?))
+    }
+
+    /// This function returns the conversion of `srs` with its points in `ordering`, loading it from the cache if it
+    /// is there and otherwise converting it and storing the result.
+    pub fn get_or_convert<E: SrsCurve>(
+        &self,
+        srs: &MonomialSrs<E>,
+        ordering: PointOrdering,
+    ) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let path = self.path(&CacheKey::new(srs)?);
+        let lagrange = match self.read(&path)? {
+            Some(lagrange) => lagrange,
+            None => {
+                let lagrange = srs.to_lagrange()?;
+                self.write(&path, &lagrange)?;
+                lagrange
+            }
+        };
+        lagrange.with_ordering(ordering)
+    }
+
+    /// This function removes the cached conversion of `srs`, returning whether there was one.
+    pub fn remove<E: SrsCurve>(&self, srs: &MonomialSrs<E>) -> Result<bool, InterpolationError> {
+        let path = self.path(&CacheKey::new(srs)?);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// This function reads the entry at `path`, treating a missing or unreadable entry as absent.
+    fn read<E: SrsCurve>(&self, path: &Path) -> Result<Option<LagrangeSrs<E>>, InterpolationError> {
+        let Ok(file) = File::open(path) else {
+            return Ok(None);
+        };
+        Ok(read_lagrange::<_, E>(BufReader::new(file))
+            .ok()
+            .filter(|lagrange| lagrange.ordering() == PointOrdering::Natural))
+    }
+
+    fn write<E: SrsCurve>(
+        &self,
+        path: &Path,
+        lagrange: &LagrangeSrs<E>,
+    ) -> Result<(), InterpolationError> {
+        let temp = path.with_extension(format!("tmp-{}", std::process::id()));
+        let writer = write_lagrange(
+            BufWriter::new(File::create(&temp)?),
+            lagrange,
+            PointEncoding::Uncompressed,
+            Endianness::Little,
+        )?;
+        writer
+            .into_inner()
+            .map_err(|e| InterpolationError::SerializationError(e.to_string()))?
+            .sync_all()?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_lagrange_cache() {
+        let rng = &mut ark_std::test_rng();
+        let dir = std::env::temp_dir().join(format!("srs-cache-test-{}", std::process::id()));
+        let cache = LagrangeCache::new(&dir).unwrap();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let key = CacheKey::new(&srs).unwrap();
+        assert_eq!(key.size, 16);
+        assert!(key.to_string().starts_with("bn254-16-"));
+        assert!(cache.get(&srs).unwrap().is_none());
+
+        // The first request converts and stores the SRS, and later ones read it back in any ordering.
+        let lagrange = cache.get_or_convert(&srs, PointOrdering::Natural).unwrap();
+        assert_eq!(lagrange, srs.to_lagrange().unwrap());
+        assert!(cache.path(&key).exists());
+        assert_eq!(cache.get(&srs).unwrap().unwrap(), lagrange);
+        assert_eq!(
+            cache
+                .get_or_convert(&srs, PointOrdering::BitReversed)
+                .unwrap(),
+            srs.to_lagrange_with_ordering(PointOrdering::BitReversed)
+                .unwrap()
+        );
+
+        // A different SRS of the same size has a different key.
+        let other = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        assert_ne!(CacheKey::new(&other).unwrap(), key);
+        assert!(cache.get(&other).unwrap().is_none());
+
+        // A corrupted entry is converted again and replaced.
+        fs::write(cache.path(&key), b"not an srs").unwrap();
+        assert!(cache.get(&srs).unwrap().is_none());
+        assert_eq!(
+            cache.get_or_convert(&srs, PointOrdering::Natural).unwrap(),
+            lagrange
+        );
+        assert!(cache.remove(&srs).unwrap());
+        assert!(!cache.remove(&srs).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
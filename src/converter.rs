@@ -0,0 +1,189 @@
+//! This module contains [`Converter`], which does the setup of a conversion once for a fixed size and curve so
+//! services converting many SRSs of the same shape, such as the per-circuit setups of a prover, only pay for it
+//! once.
+//!
+//! The setup is the domain, the inverse of its size, the bit reversal permutation, a table of the powers of the
+//! inverse generator every round's twiddles are taken from and the scratch space of the butterflies. A converter
+//! always takes the radix-2 rounds, whatever the size.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    utils::{bit_reverse, fft_round_with_scratch, scratch_len},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::Field;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_chunks_mut, cfg_iter_mut, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::sync::Mutex;
+
+/// A conversion from the monomial to the Lagrange basis over the arkworks domain of one size, ready to run on any
+/// number of SRSs of that size. It is [`Send`] and [`Sync`], and calls from several threads at once each get their
+/// own scratch space.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "E: SWCurveConfig"))]
+pub struct Converter<E: SWCurveConfig> {
+    domain: Radix2EvaluationDomain<E::ScalarField>,
+    size_inv: E::ScalarField,
+    /// The index each point moves to in the bit reversal permutation.
+    #[derivative(Debug = "ignore")]
+    permutation: Vec<usize>,
+    /// The powers `w^-j` of the inverse generator for `j < n / 2`.
+    #[derivative(Debug = "ignore")]
+    twiddles: Vec<E::ScalarField>,
+    #[derivative(Debug = "ignore")]
+    scratch: Mutex<Vec<E::BaseField>>,
+}
+
+impl<E: SWCurveConfig> Converter<E> {
+    /// This function sets up the conversion of SRSs of `size` points. It errors if `size` is not a power of two or
+    /// the scalar field has no domain that large.
+    pub fn new(size: usize) -> Result<Self, InterpolationError> {
+        if !size.is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(size)
+            .ok_or(InterpolationError::SizeError)?;
+        let log_size = size.ilog2() as usize;
+        let permutation = (0..size)
+            .map(|i| bit_reverse(i, log_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let gen_inv = domain.group_gen_inv();
+        let mut power = E::ScalarField::ONE;
+        let twiddles = (0..size / 2)
+            .map(|_| {
+                let current = power;
+                power *= gen_inv;
+                current
+            })
+            .collect();
+        Ok(Self {
+            domain,
+            size_inv: domain_size_inv(size)?,
+            permutation,
+            twiddles,
+            scratch: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// The number of points the converter takes.
+    pub fn size(&self) -> usize {
+        self.permutation.len()
+    }
+
+    /// The generator of the domain the Lagrange basis is taken over.
+    pub fn generator(&self) -> E::ScalarField {
+        self.domain.group_gen()
+    }
+
+    /// This function converts `points` to the Lagrange basis, the same as [`crate::srs_to_lagrange`]. It errors if
+    /// there are not exactly [`Converter::size`] points.
+    pub fn convert(&self, points: &[Affine<E>]) -> Result<Vec<Affine<E>>, InterpolationError> {
+        let size = self.size();
+        if points.len() != size {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The converter takes {} points but was given {}",
+                size,
+                points.len()
+            )));
+        }
+        let mut result = self
+            .permutation
+            .iter()
+            .map(|i| points[*i])
+            .collect::<Vec<_>>();
+
+        // A call that finds the scratch space in use allocates its own rather than waiting.
+        let mut own_scratch;
+        let mut shared_scratch = self.scratch.try_lock().ok();
+        let scratch = match shared_scratch.as_deref_mut() {
+            Some(scratch) => scratch,
+            None => {
+                own_scratch = Vec::new();
+                &mut own_scratch
+            }
+        };
+        scratch.resize(scratch_len::<E>(size).max(2), E::BaseField::ZERO);
+
+        for round in 1..=size.ilog2() as usize {
+            let k = 1usize << round;
+            if round != 1 {
+                // The jth twiddle of this round is w^(-j n / k).
+                let stride = size / k;
+                let twiddles = &self.twiddles;
+                cfg_chunks_mut!(result, k).for_each(|chunk: &mut [Affine<E>]| {
+                    chunk[k / 2..]
+                        .iter_mut()
+                        .enumerate()
+                        .skip(1)
+                        .for_each(|(j, point)| {
+                            *point = (*point * twiddles[j * stride]).into_affine()
+                        })
+                });
+            }
+            // The twiddles are already applied, so every round runs the butterflies alone.
+            fft_round_with_scratch::<E, E::ScalarField, true>(
+                &mut result,
+                E::ScalarField::ONE,
+                round,
+                scratch,
+            )?;
+        }
+
+        let size_inv = self.size_inv;
+        cfg_iter_mut!(result).for_each(|point| *point = (*point * size_inv).into_affine());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_converter() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Converter<BnConfig>>();
+
+        let rng = &mut ark_std::test_rng();
+        for size in [1, 2, 64] {
+            let converter = Converter::<BnConfig>::new(size).unwrap();
+            assert_eq!(converter.size(), size);
+            for _ in 0..2 {
+                let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+                assert_eq!(
+                    converter.convert(&srs).unwrap(),
+                    srs_to_lagrange(&srs).unwrap()
+                );
+            }
+        }
+
+        // Threads sharing one converter get the same results as converting alone.
+        let converter = Converter::<BnConfig>::new(32).unwrap();
+        let srss = (0..4)
+            .map(|_| gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap())
+            .collect::<Vec<_>>();
+        std::thread::scope(|scope| {
+            for srs in srss.iter() {
+                let converter = &converter;
+                scope.spawn(move || {
+                    assert_eq!(
+                        converter.convert(srs).unwrap(),
+                        srs_to_lagrange(srs).unwrap()
+                    )
+                });
+            }
+        });
+
+        assert!(converter.convert(&srss[0][..16]).is_err());
+        assert!(Converter::<BnConfig>::new(12).is_err());
+        assert!(Converter::<BnConfig>::new(0).is_err());
+    }
+}
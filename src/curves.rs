@@ -0,0 +1,196 @@
+//! This module contains identifiers for the curves whose SRSs this crate knows how to label.
+use ark_ec::short_weierstrass::SWCurveConfig;
+
+/// A stable identifier for a curve, used in file headers and cache keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CurveId {
+    /// The G1 group of BN254.
+    Bn254,
+    /// The G1 group of BLS12-381.
+    Bls12_381,
+    /// Bandersnatch, the curve over the BLS12-381 scalar field used by Verkle trees, in short Weierstrass form.
+    Bandersnatch,
+    /// Grumpkin, the curve over the BN254 scalar field whose scalar field is the BN254 base field.
+    Grumpkin,
+    /// Pallas, the Pasta curve whose scalar field is the base field of Vesta.
+    Pallas,
+    /// Vesta, the Pasta curve whose scalar field is the base field of Pallas.
+    Vesta,
+    /// The G1 group of BLS12-377.
+    Bls12_377,
+    /// The G1 group of BW6-761, the curve whose scalar field is the BLS12-377 base field.
+    Bw6_761,
+}
+
+impl CurveId {
+    /// The byte used to encode the curve in file headers.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            CurveId::Bn254 => 1,
+            CurveId::Bls12_381 => 2,
+            CurveId::Bandersnatch => 3,
+            CurveId::Grumpkin => 4,
+            CurveId::Pallas => 5,
+            CurveId::Vesta => 6,
+            CurveId::Bls12_377 => 7,
+            CurveId::Bw6_761 => 8,
+        }
+    }
+
+    /// This function returns the curve encoded by `byte`, if it is known.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(CurveId::Bn254),
+            2 => Some(CurveId::Bls12_381),
+            3 => Some(CurveId::Bandersnatch),
+            4 => Some(CurveId::Grumpkin),
+            5 => Some(CurveId::Pallas),
+            6 => Some(CurveId::Vesta),
+            7 => Some(CurveId::Bls12_377),
+            8 => Some(CurveId::Bw6_761),
+            _ => None,
+        }
+    }
+
+    /// A human readable name for the curve.
+    pub fn name(self) -> &'static str {
+        match self {
+            CurveId::Bn254 => "bn254",
+            CurveId::Bls12_381 => "bls12-381",
+            CurveId::Bandersnatch => "bandersnatch",
+            CurveId::Grumpkin => "grumpkin",
+            CurveId::Pallas => "pallas",
+            CurveId::Vesta => "vesta",
+            CurveId::Bls12_377 => "bls12-377",
+            CurveId::Bw6_761 => "bw6-761",
+        }
+    }
+}
+
+/// A curve configuration with a known [`CurveId`].
+pub trait SrsCurve: SWCurveConfig {
+    /// The identifier of the curve.
+    const CURVE_ID: CurveId;
+}
+
+impl SrsCurve for ark_bn254::g1::Config {
+    const CURVE_ID: CurveId = CurveId::Bn254;
+}
+
+#[cfg(feature = "bls12-381")]
+impl SrsCurve for ark_bls12_381::g1::Config {
+    const CURVE_ID: CurveId = CurveId::Bls12_381;
+}
+
+#[cfg(feature = "bandersnatch")]
+impl SrsCurve for ark_ed_on_bls12_381_bandersnatch::SWConfig {
+    const CURVE_ID: CurveId = CurveId::Bandersnatch;
+}
+
+impl SrsCurve for crate::grumpkin::GrumpkinConfig {
+    const CURVE_ID: CurveId = CurveId::Grumpkin;
+}
+
+#[cfg(feature = "pasta")]
+impl SrsCurve for ark_pallas::PallasConfig {
+    const CURVE_ID: CurveId = CurveId::Pallas;
+}
+
+#[cfg(feature = "pasta")]
+impl SrsCurve for ark_vesta::VestaConfig {
+    const CURVE_ID: CurveId = CurveId::Vesta;
+}
+
+#[cfg(feature = "bls12-377")]
+impl SrsCurve for ark_bls12_377::g1::Config {
+    const CURVE_ID: CurveId = CurveId::Bls12_377;
+}
+
+#[cfg(feature = "bw6-761")]
+impl SrsCurve for ark_bw6_761::g1::Config {
+    const CURVE_ID: CurveId = CurveId::Bw6_761;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{
+            read_lagrange, read_monomial, write_lagrange, write_monomial, Endianness, PointEncoding,
+        },
+        setups::insecure::insecure_monomial_srs,
+        srs::{commit_evals, PointOrdering},
+    };
+    use ark_ec::{short_weierstrass::Projective, CurveGroup, VariableBaseMSM};
+    use ark_ff::UniformRand;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::vec::Vec;
+
+    /// This function runs an SRS of `size` points on the curve `E` through conversion, commitment, reordering, the
+    /// binary format and back, so that nothing depends on the curve being BN254.
+    fn check_curve<E: SrsCurve>(size: usize) {
+        let rng = &mut ark_std::test_rng();
+        assert_eq!(CurveId::from_byte(E::CURVE_ID.to_byte()), Some(E::CURVE_ID));
+        let srs = insecure_monomial_srs::<E, _>(rng, size);
+        let lagrange = srs.to_lagrange().unwrap();
+        assert_eq!(lagrange.to_monomial().unwrap(), srs);
+
+        // Committing to the evaluations of a random polynomial matches committing to its coefficients.
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(size).unwrap();
+        let coeffs = (0..size)
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let evals = domain.fft(&coeffs);
+        let expected = Projective::<E>::msm_unchecked(srs.points(), &coeffs).into_affine();
+        assert_eq!(commit_evals(&evals, &lagrange).unwrap(), expected);
+        let mut precomputed = lagrange.clone();
+        precomputed.precompute(8).unwrap();
+        assert_eq!(precomputed.commit(&evals).unwrap(), expected);
+
+        // Both orderings and every encoding survive the binary format.
+        let reversed = srs
+            .to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert_eq!(reversed.to_monomial().unwrap(), srs);
+        for encoding in [PointEncoding::Compressed, PointEncoding::Uncompressed] {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let bytes = write_monomial(Vec::new(), &srs, encoding, endianness).unwrap();
+                assert_eq!(read_monomial::<_, E>(bytes.as_slice()).unwrap(), srs);
+                let bytes = write_lagrange(Vec::new(), &reversed, encoding, endianness).unwrap();
+                assert_eq!(read_lagrange::<_, E>(bytes.as_slice()).unwrap(), reversed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bn254() {
+        check_curve::<ark_bn254::g1::Config>(32);
+    }
+
+    #[cfg(feature = "pasta")]
+    #[test]
+    fn test_pasta() {
+        // Both Pasta scalar fields have 2-adicity 32.
+        check_curve::<ark_pallas::PallasConfig>(32);
+        check_curve::<ark_vesta::VestaConfig>(32);
+    }
+
+    #[cfg(feature = "bls12-377")]
+    #[test]
+    fn test_bls12_377() {
+        check_curve::<ark_bls12_377::g1::Config>(32);
+    }
+
+    #[cfg(feature = "bw6-761")]
+    #[test]
+    fn test_bw6_761() {
+        // The 761 bit base field makes every point three times the size of a BN254 one.
+        check_curve::<ark_bw6_761::g1::Config>(16);
+        // Its scratch is capped at fewer elements than a BN254 round would use.
+        assert_eq!(
+            crate::utils::scratch_len::<ark_bw6_761::g1::Config>(1 << 30),
+            1 << 17
+        );
+    }
+}
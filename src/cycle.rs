@@ -0,0 +1,116 @@
+//! This module converts the SRSs of both curves of a cycle, such as BN254 and Grumpkin, for recursive proving
+//! setups that commit on one curve and verify on the other.
+//!
+//! The curve whose scalar field has a large 2-adic subgroup, BN254 in the cycle with Grumpkin, is converted to a
+//! [`LagrangeSrs`] over roots of unity. The other is converted over an [`EcfftDomain`], since cycles of pairing
+//! friendly curves rarely have two 2-adic scalar fields.
+use crate::{
+    ecfft::{srs_to_lagrange_ecfft, EcfftDomain},
+    error::InterpolationError,
+    grumpkin::GrumpkinConfig,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+/// The Lagrange SRSs of the two curves of a cycle.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E1: SWCurveConfig, E2: SWCurveConfig"),
+    Debug(bound = "E1: SWCurveConfig, E2: SWCurveConfig"),
+    PartialEq(bound = "E1: SWCurveConfig, E2: SWCurveConfig")
+)]
+pub struct CycleSrs<E1: SWCurveConfig, E2: SWCurveConfig> {
+    primary: LagrangeSrs<E1>,
+    secondary: Vec<Affine<E2>>,
+    secondary_domain: EcfftDomain<E2::ScalarField>,
+}
+
+/// The Lagrange SRSs of BN254 and Grumpkin.
+pub type Bn254GrumpkinSrs = CycleSrs<ark_bn254::g1::Config, GrumpkinConfig>;
+
+impl<E1: SWCurveConfig, E2: SWCurveConfig> CycleSrs<E1, E2> {
+    /// The Lagrange SRS of the curve converted over roots of unity.
+    pub fn primary(&self) -> &LagrangeSrs<E1> {
+        &self.primary
+    }
+
+    /// The Lagrange SRS of the other curve, in the order of [`CycleSrs::secondary_domain`].
+    pub fn secondary(&self) -> &[Affine<E2>] {
+        &self.secondary
+    }
+
+    /// The domain the second SRS was converted over.
+    pub fn secondary_domain(&self) -> &EcfftDomain<E2::ScalarField> {
+        &self.secondary_domain
+    }
+
+    /// This function splits the pair into the two SRSs and the domain of the second.
+    pub fn into_parts(
+        self,
+    ) -> (
+        LagrangeSrs<E1>,
+        Vec<Affine<E2>>,
+        EcfftDomain<E2::ScalarField>,
+    ) {
+        (self.primary, self.secondary, self.secondary_domain)
+    }
+}
+
+/// This function converts `primary` over roots of unity and `secondary` over `secondary_domain`, which must have as
+/// many points as `secondary`.
+pub fn convert_cycle<E1, E2>(
+    primary: &MonomialSrs<E1>,
+    secondary: &MonomialSrs<E2>,
+    secondary_domain: EcfftDomain<E2::ScalarField>,
+) -> Result<CycleSrs<E1, E2>, InterpolationError>
+where
+    E1: SWCurveConfig,
+    E2: SWCurveConfig,
+    E2::ScalarField: PrimeField,
+{
+    Ok(CycleSrs {
+        primary: primary.to_lagrange()?,
+        secondary: srs_to_lagrange_ecfft(secondary.points(), &secondary_domain)?,
+        secondary_domain,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grumpkin::GrumpkinProjective, setups::insecure::insecure_monomial_srs};
+    use ark_bn254::Fq;
+    use ark_ec::VariableBaseMSM;
+    use ark_std::{UniformRand, Zero};
+
+    #[test]
+    fn test_convert_bn254_grumpkin() {
+        let rng = &mut ark_std::test_rng();
+        let bn254 = insecure_monomial_srs::<ark_bn254::g1::Config, _>(rng, 16);
+        let grumpkin = insecure_monomial_srs::<GrumpkinConfig, _>(rng, 8);
+        let domain = EcfftDomain::<Fq>::find(3, rng).unwrap();
+        let cycle: Bn254GrumpkinSrs = convert_cycle(&bn254, &grumpkin, domain).unwrap();
+        assert_eq!(cycle.primary(), &bn254.to_lagrange().unwrap());
+
+        // Committing to the evaluations of a polynomial over the domain matches committing to its coefficients.
+        let coeffs = (0..8).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+        let evals = cycle
+            .secondary_domain()
+            .elements()
+            .iter()
+            .map(|x| coeffs.iter().rev().fold(Fq::zero(), |acc, c| acc * x + c))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            GrumpkinProjective::msm_unchecked(cycle.secondary(), &evals),
+            GrumpkinProjective::msm_unchecked(grumpkin.points(), &coeffs)
+        );
+
+        // The BN254 scalar field is the Grumpkin base field, so the radix-2 conversion only reaches two points.
+        assert!(crate::srs_to_lagrange::<GrumpkinConfig, Fq>(grumpkin.points()).is_err());
+        let domain = EcfftDomain::<Fq>::find(2, rng).unwrap();
+        assert!(convert_cycle(&bn254, &grumpkin, domain).is_err());
+    }
+}
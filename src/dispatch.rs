@@ -0,0 +1,323 @@
+//! This module picks the algorithm for a conversion from the size of the SRS, the curve and the number of threads,
+//! so that callers get a good choice without tuning one themselves.
+//!
+//! The choice comes from a [`Calibration`] table for each curve. The smallest conversions run the butterflies in
+//! projective form where that kept up with the affine ones, larger ones run the radix-2 rounds, split over the
+//! threads when there are several, use decimation in frequency when the output is bit-reversed and the four-step
+//! decomposition once the points no longer fit in cache. The larger points of the larger base fields fill the
+//! cache at fewer of them.
+use crate::{
+    buffer::{try_copied, VecProvider},
+    curves::{CurveId, SrsCurve},
+    domain_size_inv,
+    error::InterpolationError,
+    four_step::{group_fft_four_step, FOUR_STEP_THRESHOLD},
+    group_fft_dif, group_fft_radix2, reorder_points,
+    srs::{FftVariant, PointOrdering},
+    srs_to_lagrange_point,
+    threads::srs_to_lagrange_threads,
+    utils::bit_reverse_permutation,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// An algorithm that converts a monomial SRS to the Lagrange basis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// One MSM against the monomial points for each Lagrange point, as [`crate::srs_to_lagrange_point`] does.
+    DirectMsm,
+    /// The radix-2 rounds in projective form, with a single normalization at the end.
+    Projective,
+    /// The radix-2 rounds with affine butterflies on a single thread, as [`crate::threads`] runs them.
+    Sequential,
+    /// The radix-2 rounds with affine butterflies split over the rayon pool, in the given variant.
+    Radix2(FftVariant),
+    /// The four-step decomposition, split over the rayon pool.
+    FourStep,
+}
+
+/// The sizes at which [`Calibration::select`] moves from one algorithm to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Calibration {
+    /// The largest size converted with [`Algorithm::DirectMsm`].
+    pub direct_msm_max: usize,
+    /// The largest size converted with [`Algorithm::Projective`].
+    pub projective_max: usize,
+    /// The smallest size whose rounds are split over several threads.
+    pub parallel_min: usize,
+    /// The smallest size converted with [`Algorithm::FourStep`] when the output is in natural order.
+    pub four_step_min: usize,
+}
+
+/// The largest size [`Calibration::for_curve`] converts with [`Algorithm::DirectMsm`]. The conversion of a single
+/// point is the point itself, while from two points on one MSM per point took between 1.3 and 1.9 times as long as
+/// the butterflies on BN254, BLS12-381 and BW6-761.
+const DIRECT_MSM_MAX: usize = 1;
+
+/// The smallest size [`Calibration::for_curve`] splits over several threads. Running the split rounds over four
+/// threads of a single core, which shows what splitting costs without any of its gain, stayed within the noise of
+/// one thread at every size measured from `2^4` to `2^11` BN254 points.
+const PARALLEL_MIN: usize = 1 << 4;
+
+impl Calibration {
+    /// The built in table for `curve`.
+    ///
+    /// The largest projective size is the largest at which the projective rounds kept up with the affine ones on a
+    /// single core, averaged over two or three runs: up to `2^4` BN254 points, after which they took 1.1 to 1.2
+    /// times as long, and only at `2^2` BLS12-381 points. On BW6-761 they took 1.1 to 1.6 times as long at every
+    /// size from `2^1` to `2^9`, so that curve never uses them. The curves over fields of the same size share the
+    /// values of the one measured.
+    ///
+    /// The four-step decomposition starts where [`crate::srs_to_lagrange`] starts it, at which `2^22` BN254 points
+    /// take 288 MiB and `2^22` BLS12-381 points 416 MiB. BW6-761 points are nearly twice as large again, so that
+    /// curve starts at half as many. At `2^12` and `2^14` BN254 points, which fit in cache, the four-step
+    /// decomposition stayed within the noise of the radix-2 rounds.
+    pub fn for_curve(curve: CurveId) -> Self {
+        let (projective_max, four_step_min) = match curve {
+            CurveId::Bn254
+            | CurveId::Bandersnatch
+            | CurveId::Grumpkin
+            | CurveId::Pallas
+            | CurveId::Vesta => (1 << 4, FOUR_STEP_THRESHOLD),
+            CurveId::Bls12_381 | CurveId::Bls12_377 => (1 << 2, FOUR_STEP_THRESHOLD),
+            CurveId::Bw6_761 => (DIRECT_MSM_MAX, FOUR_STEP_THRESHOLD / 2),
+        };
+        Self {
+            direct_msm_max: DIRECT_MSM_MAX,
+            projective_max,
+            parallel_min: PARALLEL_MIN,
+            four_step_min,
+        }
+    }
+
+    /// This function picks the algorithm for converting `size` points to `ordering` on `threads` threads.
+    pub fn select(&self, size: usize, threads: usize, ordering: PointOrdering) -> Algorithm {
+        if size <= self.direct_msm_max {
+            Algorithm::DirectMsm
+        } else if size <= self.projective_max {
+            Algorithm::Projective
+        } else if size >= self.four_step_min && ordering == PointOrdering::Natural {
+            Algorithm::FourStep
+        } else if threads <= 1 || size < self.parallel_min {
+            Algorithm::Sequential
+        } else if ordering == PointOrdering::BitReversed {
+            Algorithm::Radix2(FftVariant::DecimationInFrequency)
+        } else {
+            Algorithm::Radix2(FftVariant::DecimationInTime)
+        }
+    }
+}
+
+/// This function picks the algorithm for converting `size` points of `curve` to `ordering` on the threads this
+/// crate runs on, which is the rayon pool with the `parallel` feature and the calling thread without it.
+pub fn select_algorithm(curve: CurveId, size: usize, ordering: PointOrdering) -> Algorithm {
+    Calibration::for_curve(curve).select(size, thread_count(), ordering)
+}
+
+/// This function is the same as [`crate::srs_to_lagrange_with_ordering`] but converts with the algorithm
+/// [`select_algorithm`] picks.
+pub fn srs_to_lagrange_auto<E, F>(
+    points: &[Affine<E>],
+    ordering: PointOrdering,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SrsCurve<ScalarField = F>,
+    F: PrimeField,
+{
+    let algorithm = select_algorithm(E::CURVE_ID, points.len(), ordering);
+    srs_to_lagrange_with_algorithm(points, ordering, algorithm)
+}
+
+/// This function is the same as [`crate::srs_to_lagrange_with_ordering`] but converts with `algorithm`. It errors
+/// if the number of points is not a power of two.
+pub fn srs_to_lagrange_with_algorithm<E, F>(
+    points: &[Affine<E>],
+    ordering: PointOrdering,
+    algorithm: Algorithm,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let gen_inv = domain.group_gen_inv();
+    let size_inv = domain_size_inv::<F>(points.len())?;
+    // Only the butterflies that run in place need a copy of the input, the others build their output from it.
+    let (mut result, produced) = match algorithm {
+        Algorithm::DirectMsm => (
+            (0..points.len())
+                .map(|index| srs_to_lagrange_point(points, index))
+                .collect::<Result<_, _>>()?,
+            PointOrdering::Natural,
+        ),
+        Algorithm::Projective => (
+            projective_to_lagrange(points, gen_inv, size_inv)?,
+            PointOrdering::Natural,
+        ),
+        Algorithm::Sequential => (srs_to_lagrange_threads(points, 1)?, PointOrdering::Natural),
+        Algorithm::Radix2(FftVariant::DecimationInTime) => (
+            in_place(points, size_inv, |points| {
+                group_fft_radix2(points, gen_inv, &VecProvider)
+            })?,
+            PointOrdering::Natural,
+        ),
+        Algorithm::Radix2(FftVariant::DecimationInFrequency) => (
+            in_place(points, size_inv, |points| {
+                group_fft_dif(points, gen_inv, &VecProvider)
+            })?,
+            PointOrdering::BitReversed,
+        ),
+        Algorithm::FourStep => (
+            in_place(points, size_inv, |points| {
+                group_fft_four_step(points, gen_inv, &VecProvider)
+            })?,
+            PointOrdering::Natural,
+        ),
+    };
+    reorder_points(&mut result, produced, ordering)?;
+    Ok(result)
+}
+
+/// This function runs the radix-2 rounds with `gen_inv` over projective points and multiplies the result by
+/// `size_inv`, which saves the inversions of the affine butterflies at the price of more expensive additions.
+fn projective_to_lagrange<E, F>(
+    points: &[Affine<E>],
+    gen_inv: F,
+    size_inv: F,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = points.len();
+    let log_size = size.ilog2() as usize;
+    let mut result = points
+        .iter()
+        .map(|point| Projective::<E>::from(*point))
+        .collect::<Vec<_>>();
+    bit_reverse_permutation(&mut result, log_size)?;
+    for round in 1..=log_size {
+        let half = 1 << (round - 1);
+        let root = gen_inv.pow([(size >> round) as u64]);
+        for chunk in result.chunks_mut(2 * half) {
+            let (low, high) = chunk.split_at_mut(half);
+            let mut twiddle = F::one();
+            for (a, b) in low.iter_mut().zip(high.iter_mut()) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a += t;
+                twiddle *= root;
+            }
+        }
+    }
+    result.iter_mut().for_each(|point| *point *= size_inv);
+    Ok(Projective::<E>::normalize_batch(&result))
+}
+
+/// This function runs `fft` on a copy of `points` and multiplies the result by `size_inv`.
+fn in_place<E, F>(
+    points: &[Affine<E>],
+    size_inv: F,
+    fft: impl FnOnce(&mut [Affine<E>]) -> Result<(), InterpolationError>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let mut result = try_copied(points)?;
+    fft(&mut result)?;
+    cfg_iter_mut!(result).for_each(|point| *point = (*point * size_inv).into_affine());
+    Ok(result)
+}
+
+fn thread_count() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange_with_ordering, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_srs_to_lagrange_with_algorithm() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        let algorithms = [
+            Algorithm::DirectMsm,
+            Algorithm::Projective,
+            Algorithm::Sequential,
+            Algorithm::Radix2(FftVariant::DecimationInTime),
+            Algorithm::Radix2(FftVariant::DecimationInFrequency),
+            Algorithm::FourStep,
+        ];
+        for size in [1, 2, 8, 64] {
+            for ordering in [PointOrdering::Natural, PointOrdering::BitReversed] {
+                let expected = srs_to_lagrange_with_ordering(&srs[..size], ordering).unwrap();
+                for algorithm in algorithms {
+                    let points = srs_to_lagrange_with_algorithm(&srs[..size], ordering, algorithm);
+                    assert_eq!(points.unwrap(), expected, "{:?}", algorithm);
+                }
+                assert_eq!(
+                    srs_to_lagrange_auto(&srs[..size], ordering).unwrap(),
+                    expected
+                );
+            }
+        }
+        assert!(srs_to_lagrange_with_algorithm(
+            &srs[..6],
+            PointOrdering::Natural,
+            Algorithm::Projective
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_calibration_select() {
+        let table = Calibration::for_curve(CurveId::Bn254);
+        let natural = PointOrdering::Natural;
+        let reversed = PointOrdering::BitReversed;
+        assert_eq!(table.select(1, 8, natural), Algorithm::DirectMsm);
+        assert_eq!(table.select(16, 8, natural), Algorithm::Projective);
+        assert_eq!(table.select(32, 1, natural), Algorithm::Sequential);
+        assert_eq!(
+            table.select(32, 8, natural),
+            Algorithm::Radix2(FftVariant::DecimationInTime)
+        );
+        assert_eq!(table.select(1 << 16, 1, natural), Algorithm::Sequential);
+        assert_eq!(
+            table.select(1 << 16, 8, natural),
+            Algorithm::Radix2(FftVariant::DecimationInTime)
+        );
+        assert_eq!(
+            table.select(1 << 16, 8, reversed),
+            Algorithm::Radix2(FftVariant::DecimationInFrequency)
+        );
+        assert_eq!(table.select(1 << 22, 1, natural), Algorithm::FourStep);
+        assert_eq!(
+            table.select(1 << 22, 8, reversed),
+            Algorithm::Radix2(FftVariant::DecimationInFrequency)
+        );
+        let bls = Calibration::for_curve(CurveId::Bls12_381);
+        assert_eq!(bls.select(4, 8, natural), Algorithm::Projective);
+        assert_eq!(bls.select(8, 1, natural), Algorithm::Sequential);
+        // The projective rounds never kept up with the affine ones over the BW6-761 base field.
+        let bw6 = Calibration::for_curve(CurveId::Bw6_761);
+        assert_eq!(bw6.select(2, 1, natural), Algorithm::Sequential);
+    }
+}
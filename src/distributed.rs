@@ -0,0 +1,206 @@
+//! This module splits a conversion into jobs that can run in other processes or on other machines, for domains too
+//! large to convert on one.
+//!
+//! A [`DistributedPlan`] is the four-step decomposition used for large domains with each step's smaller operations
+//! handed out in batches. The points are transposed into columns, the column jobs run the operations down the
+//! columns, the columns are transposed into rows, the row jobs run the operations along the rows, and a last
+//! transpose and rescaling gives the Lagrange basis. Only the transposes need all the points in one place, and every
+//! job works on contiguous points, so a shard of the points is all a worker needs.
+use crate::{
+    buffer::{try_filled, VecProvider},
+    domain_size_inv,
+    error::InterpolationError,
+    four_step::transpose,
+    group_fft_radix2,
+    srs::{LagrangeSrs, PointOrdering},
+    utils::distribute_powers,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::Field;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_chunks_mut, cfg_iter_mut, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The shape of a conversion split into column and row jobs over the arkworks domain of one size.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "E: SWCurveConfig"), Debug(bound = "E: SWCurveConfig"))]
+pub struct DistributedPlan<E: SWCurveConfig> {
+    rows: usize,
+    cols: usize,
+    generator: E::ScalarField,
+    gen_inv: E::ScalarField,
+}
+
+impl<E: SWCurveConfig> DistributedPlan<E> {
+    /// This function plans the conversion of `size` points. It errors if `size` is not a power of two or the scalar
+    /// field has no domain that large.
+    pub fn new(size: usize) -> Result<Self, InterpolationError> {
+        if !size.is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(size)
+            .ok_or(InterpolationError::SizeError)?;
+        let rows = 1usize << size.ilog2().div_ceil(2);
+        Ok(Self {
+            rows,
+            cols: size / rows,
+            generator: domain.group_gen(),
+            gen_inv: domain.group_gen_inv(),
+        })
+    }
+
+    /// The number of points converted.
+    pub fn size(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// The number of columns, each of which has [`DistributedPlan::column_len`] points.
+    pub fn columns(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of points in a column, which is also the number of rows.
+    pub fn column_len(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of points in a row, which is also the number of columns.
+    pub fn row_len(&self) -> usize {
+        self.cols
+    }
+
+    /// This function transposes the monomial points into columns, one after another. It errors if there are not
+    /// [`DistributedPlan::size`] points.
+    pub fn to_columns(&self, points: &[Affine<E>]) -> Result<Vec<Affine<E>>, InterpolationError> {
+        self.check_len(points.len(), self.size())?;
+        let mut columns = try_filled(points.len(), Affine::<E>::identity())?;
+        transpose(points, &mut columns, self.rows, self.cols);
+        Ok(columns)
+    }
+
+    /// This function runs the job on the consecutive columns in `columns`, the first of which is column
+    /// `first_column`. It errors if `columns` is not a whole number of columns or runs past the last column.
+    pub fn column_job(
+        &self,
+        first_column: usize,
+        columns: &mut [Affine<E>],
+    ) -> Result<(), InterpolationError> {
+        self.check_job(first_column, columns.len(), self.rows, self.cols)?;
+        let column_root = self.gen_inv.pow([self.cols as u64]);
+        let gen_inv = self.gen_inv;
+        cfg_chunks_mut!(columns, self.rows)
+            .enumerate()
+            .try_for_each(|(i, column): (usize, &mut [Affine<E>])| {
+                group_fft_radix2(column, column_root, &VecProvider)?;
+                distribute_powers(column, gen_inv.pow([(first_column + i) as u64]));
+                Result::<(), InterpolationError>::Ok(())
+            })
+    }
+
+    /// This function transposes the columns, once every column job has run, into rows, one after another. It errors
+    /// if there are not [`DistributedPlan::size`] points.
+    pub fn to_rows(&self, columns: &[Affine<E>]) -> Result<Vec<Affine<E>>, InterpolationError> {
+        self.check_len(columns.len(), self.size())?;
+        let mut rows = try_filled(columns.len(), Affine::<E>::identity())?;
+        transpose(columns, &mut rows, self.cols, self.rows);
+        Ok(rows)
+    }
+
+    /// This function runs the job on the consecutive rows in `rows`. It errors if `rows` is not a whole number of
+    /// rows.
+    pub fn row_job(&self, rows: &mut [Affine<E>]) -> Result<(), InterpolationError> {
+        self.check_job(0, rows.len(), self.cols, self.rows)?;
+        let row_root = self.gen_inv.pow([self.rows as u64]);
+        cfg_chunks_mut!(rows, self.cols)
+            .try_for_each(|row: &mut [Affine<E>]| group_fft_radix2(row, row_root, &VecProvider))
+    }
+
+    /// This function transposes the rows, once every row job has run, back into place and rescales them, giving
+    /// the Lagrange basis in natural order. It errors if there are not [`DistributedPlan::size`] points.
+    pub fn finish(&self, rows: &[Affine<E>]) -> Result<LagrangeSrs<E>, InterpolationError> {
+        self.check_len(rows.len(), self.size())?;
+        let mut points = try_filled(rows.len(), Affine::<E>::identity())?;
+        transpose(rows, &mut points, self.rows, self.cols);
+        let size_inv = domain_size_inv::<E::ScalarField>(points.len())?;
+        cfg_iter_mut!(points).for_each(|point| *point = (*point * size_inv).into_affine());
+        Ok(LagrangeSrs::from_parts(
+            points,
+            self.generator,
+            PointOrdering::Natural,
+        ))
+    }
+
+    fn check_len(&self, len: usize, expected: usize) -> Result<(), InterpolationError> {
+        if len != expected {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Expected {} points but was given {}",
+                expected, len
+            )));
+        }
+        Ok(())
+    }
+
+    /// This function checks a job of `len` points starting from chunk `first` is a whole number of chunks of
+    /// `chunk_len` points and does not run past chunk `chunks`.
+    fn check_job(
+        &self,
+        first: usize,
+        len: usize,
+        chunk_len: usize,
+        chunks: usize,
+    ) -> Result<(), InterpolationError> {
+        if !len.is_multiple_of(chunk_len) || first + len / chunk_len > chunks {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "A job of {} points from {} does not fit chunks of {} points",
+                len, first, chunk_len
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_distributed_plan() {
+        let rng = &mut ark_std::test_rng();
+        // Square and non-square matrices, and the single point that has one of each.
+        for size in [1, 32, 64] {
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            let plan = DistributedPlan::<BnConfig>::new(size).unwrap();
+            assert_eq!(plan.columns() * plan.column_len(), size);
+
+            // Uneven batches, as a coordinator with a number of workers that does not divide the columns makes.
+            let mut columns = plan.to_columns(&srs).unwrap();
+            let batch = plan.columns().div_ceil(3);
+            for (i, chunk) in columns.chunks_mut(batch * plan.column_len()).enumerate() {
+                plan.column_job(i * batch, chunk).unwrap();
+            }
+            let mut rows = plan.to_rows(&columns).unwrap();
+            for chunk in rows.chunks_mut(plan.row_len()) {
+                plan.row_job(chunk).unwrap();
+            }
+            let lagrange = plan.finish(&rows).unwrap();
+            assert_eq!(lagrange.points(), srs_to_lagrange(&srs).unwrap());
+        }
+
+        let plan = DistributedPlan::<BnConfig>::new(32).unwrap();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap();
+        let mut columns = plan.to_columns(&srs).unwrap();
+        assert!(plan.column_job(0, &mut columns[..3]).is_err());
+        assert!(plan
+            .column_job(plan.columns(), &mut columns[..plan.column_len()])
+            .is_err());
+        assert!(plan.to_rows(&columns[..16]).is_err());
+        assert!(DistributedPlan::<BnConfig>::new(12).is_err());
+    }
+}
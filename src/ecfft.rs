@@ -0,0 +1,491 @@
+//! This module converts SRS-like point vectors over scalar fields without a large 2-adic subgroup, such as that of
+//! secp256k1, to an evaluation basis with the ECFFT of Ben-Sasson, Carmon, Kopparty and Levit.
+//!
+//! Instead of roots of unity, the evaluation points are the x-coordinates of a coset `Q + <G>` of a cyclic subgroup
+//! of order `n = 2^k` of an auxiliary elliptic curve over the scalar field. A chain of `k` isogenies of degree two
+//! maps each coset to one of half the size, two points to one, which gives these sets the recursive structure a
+//! radix-2 FFT gets from squaring roots of unity. Evaluating a polynomial whose coefficients are points on such a
+//! set takes `O(n log^2 n)` group operations.
+//!
+//! The Lagrange basis `[L_i(tau)] = [Z(tau) / (tau - x_i)] / Z'(x_i)` over such a set is the evaluation at `x_i` of
+//! the polynomial whose coefficients are the Toeplitz product of the vanishing polynomial `Z` with the SRS. That
+//! product takes one MSM per point, so [`srs_to_lagrange_ecfft`] costs `O(n^2 / log n)` group operations against
+//! the `O(n log n)` of [`crate::srs_to_lagrange`], and is meant for fields where the latter is not available.
+use crate::{
+    buffer::{try_copied, try_with_capacity},
+    error::InterpolationError,
+    utils::size_from_log,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{batch_inversion, Field, PrimeField};
+use ark_std::{cfg_into_iter, rand::RngCore, vec::Vec, Zero};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The number of curves [`EcfftDomain::find`] tries before giving up.
+const MAX_CURVES: usize = 1 << 16;
+
+/// An affine point on the auxiliary curve `y^2 = x^3 + a x + b`, or `None` for the point at infinity.
+type CurvePoint<F> = Option<(F, F)>;
+
+fn is_on_curve<F: Field>(a: F, b: F, (x, y): (F, F)) -> bool {
+    y.square() == x * x.square() + a * x + b
+}
+
+fn add<F: Field>(a: F, p: CurvePoint<F>, q: CurvePoint<F>) -> CurvePoint<F> {
+    let ((x1, y1), (x2, y2)) = match (p, q) {
+        (None, q) => return q,
+        (p, None) => return p,
+        (Some(p), Some(q)) => (p, q),
+    };
+    let lambda = if x1 != x2 {
+        (y2 - y1) * (x2 - x1).inverse()?
+    } else if (y1 + y2).is_zero() {
+        return None;
+    } else {
+        (x1.square() * F::from(3u64) + a) * y1.double().inverse()?
+    };
+    let x3 = lambda.square() - x1 - x2;
+    Some((x3, lambda * (x1 - x3) - y1))
+}
+
+/// This function returns every point `q` with `2q = p` on the curve `y^2 = (x - e_1)(x - e_2)(x - e_3)`.
+fn halve<F: Field>(a: F, b: F, roots: &[F; 3], (x, y): (F, F)) -> Vec<(F, F)> {
+    // A point is a double exactly when x - e_i is a square for every i, and then the x-coordinates of its halves
+    // are x + r_1 r_2 + r_1 r_3 + r_2 r_3 for the square roots r_i of x - e_i.
+    let Some(r) = roots
+        .iter()
+        .map(|root| (x - root).sqrt())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+    let mut halves = Vec::new();
+    for (s2, s3) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let r2 = if s2 == 1 { r[1] } else { -r[1] };
+        let r3 = if s3 == 1 { r[2] } else { -r[2] };
+        let half_x = x + r[0] * r2 + r[0] * r3 + r2 * r3;
+        let Some(half_y) = (half_x * half_x.square() + a * half_x + b).sqrt() else {
+            continue;
+        };
+        for half in [(half_x, half_y), (half_x, -half_y)] {
+            if add(a, Some(half), Some(half)) == Some((x, y)) && !halves.contains(&half) {
+                halves.push(half);
+            }
+        }
+    }
+    halves
+}
+
+/// This function searches the halving tree above `point`, which has order `2^order_log`, for a point of order
+/// `2^log_size`, visiting at most `budget` points.
+fn find_generator<F: Field>(
+    a: F,
+    b: F,
+    roots: &[F; 3],
+    point: (F, F),
+    order_log: usize,
+    log_size: usize,
+    budget: &mut usize,
+) -> Option<(F, F)> {
+    if order_log == log_size {
+        return Some(point);
+    }
+    for half in halve(a, b, roots, point) {
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        if let Some(generator) = find_generator(a, b, roots, half, order_log + 1, log_size, budget)
+        {
+            return Some(generator);
+        }
+    }
+    None
+}
+
+/// The evaluation domain of the ECFFT, with the sets of x-coordinates and the isogenies between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcfftDomain<F: PrimeField> {
+    /// The `j`th entry holds the `n >> j` x-coordinates of the `j`th coset, the first being the domain itself.
+    levels: Vec<Vec<F>>,
+    /// The `j`th isogeny maps `x` to `x + t / (x - x_0)` for the x-coordinate `x_0` of its kernel.
+    isogenies: Vec<(F, F)>,
+}
+
+impl<F: PrimeField> EcfftDomain<F> {
+    /// This function builds the domain from the curve `y^2 = x^3 + a x + b`, a `generator` whose order is a power
+    /// of two and an `offset` such that the x-coordinates of `offset + <generator>` are distinct.
+    pub fn new(a: F, b: F, generator: (F, F), offset: (F, F)) -> Result<Self, InterpolationError> {
+        if !is_on_curve(a, b, generator) || !is_on_curve(a, b, offset) {
+            return Err(InterpolationError::InvalidParameters(
+                "The generator and offset must be on the curve".to_string(),
+            ));
+        }
+
+        // Doubling the generator until it reaches infinity gives its order and the kernels of the isogenies.
+        let mut doubles = vec![generator];
+        while let Some(double) = add(
+            a,
+            Some(doubles[doubles.len() - 1]),
+            Some(doubles[doubles.len() - 1]),
+        ) {
+            if doubles.len() == usize::BITS as usize - 1 {
+                return Err(InterpolationError::InvalidParameters(
+                    "The order of the generator is not a power of two".to_string(),
+                ));
+            }
+            doubles.push(double);
+        }
+        let log_size = doubles.len();
+        let size = size_from_log(log_size as u32)?;
+
+        let mut coset = try_with_capacity(size)?;
+        let mut point = Some(offset);
+        for _ in 0..size {
+            let (x, _) = point.ok_or(InterpolationError::InvalidParameters(
+                "The offset must not be in the subgroup of the generator".to_string(),
+            ))?;
+            coset.push(x);
+            point = add(a, point, Some(generator));
+        }
+        let mut sorted = try_copied(&coset)?;
+        sorted.sort();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(InterpolationError::InvalidParameters(
+                "The x-coordinates of the coset are not distinct".to_string(),
+            ));
+        }
+
+        // The kernel of the jth isogeny is the image of [2^(k - 1 - j)] generator under the ones before it.
+        let mut kernels = doubles.iter().rev().map(|(x, _)| *x).collect::<Vec<_>>();
+        let mut levels = vec![coset];
+        let mut isogenies = Vec::with_capacity(log_size);
+        let mut a = a;
+        for j in 0..log_size {
+            let x0 = kernels[j];
+            let t = x0.square() * F::from(3u64) + a;
+            let map = |x: F| -> Result<F, InterpolationError> {
+                Ok(x + t
+                    * (x - x0).inverse().ok_or(InterpolationError::FieldError(
+                        "A point of the coset is in the kernel of an isogeny".to_string(),
+                    ))?)
+            };
+            let level = &levels[j];
+            let half = level.len() / 2;
+            let next = level[..half]
+                .iter()
+                .map(|x| map(*x))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (i, x) in level[half..].iter().enumerate() {
+                if map(*x)? != next[i] {
+                    return Err(InterpolationError::FieldError(
+                        "An isogeny does not pair up the points of the coset".to_string(),
+                    ));
+                }
+            }
+            for kernel in kernels[j + 1..].iter_mut() {
+                *kernel = map(*kernel)?;
+            }
+            isogenies.push((x0, t));
+            levels.push(next);
+            a -= t * F::from(5u64);
+        }
+        Ok(Self { levels, isogenies })
+    }
+
+    /// This function searches for a domain of size `2^log_size` over random curves with all of their 2-torsion
+    /// defined over `F`. The search takes longer the larger the domain, so large domains should be found once and
+    /// rebuilt with [`EcfftDomain::new`].
+    pub fn find<R: RngCore>(log_size: usize, rng: &mut R) -> Result<Self, InterpolationError> {
+        if log_size == 0 {
+            return Err(InterpolationError::SizeError);
+        }
+        for _ in 0..MAX_CURVES {
+            // The curve (x - e_1)(x - e_2)(x - e_3) with e_1 + e_2 + e_3 = 0 is in short Weierstrass form.
+            let (e1, e2) = (F::rand(rng), F::rand(rng));
+            let roots = [e1, e2, -(e1 + e2)];
+            if roots[0] == roots[1] || roots[0] == roots[2] || roots[1] == roots[2] {
+                continue;
+            }
+            let a = e1 * e2 - (e1 + e2).square();
+            let b = e1 * e2 * (e1 + e2);
+            let mut budget = 1 << log_size;
+            let Some(generator) = roots.iter().find_map(|root| {
+                find_generator(a, b, &roots, (*root, F::zero()), 1, log_size, &mut budget)
+            }) else {
+                continue;
+            };
+            let x = F::rand(rng);
+            let Some(y) = (x * x.square() + a * x + b).sqrt() else {
+                continue;
+            };
+            if let Ok(domain) = Self::new(a, b, generator, (x, y)) {
+                return Ok(domain);
+            }
+        }
+        Err(InterpolationError::InvalidParameters(format!(
+            "Could not find a curve with a subgroup of order 2^{}",
+            log_size
+        )))
+    }
+
+    /// The number of points in the domain.
+    pub fn size(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// The points of the domain, in the order evaluations over it are returned.
+    pub fn elements(&self) -> &[F] {
+        &self.levels[0]
+    }
+
+    /// This function takes the evaluations of a polynomial of degree less than `values.len()` at the points
+    /// `offset + stride * m` of the `level`th set, and returns its evaluations at the points `offset + stride / 2 +
+    /// stride * m`.
+    fn extend<E>(
+        &self,
+        values: &[Projective<E>],
+        level: usize,
+        stride: usize,
+        offset: usize,
+    ) -> Vec<Projective<E>>
+    where
+        E: SWCurveConfig<ScalarField = F>,
+    {
+        let n = values.len();
+        if n == 1 {
+            return values.to_vec();
+        }
+        let points = &self.levels[level];
+        let (x0, _) = self.isogenies[level];
+        let half = n / 2;
+        let source = |m: usize| points[offset + stride * m];
+        let target = |m: usize| points[offset + stride / 2 + stride * m];
+
+        // With psi = u / v the isogeny, p(X) = v(X)^(n/2 - 1) (p_0(psi(X)) + X p_1(psi(X))) for polynomials p_0
+        // and p_1 of degree less than n / 2, and the two points over each point of the next set give p_0 and p_1
+        // there.
+        let exponent = [half as u64 - 1];
+        let mut denominators = (0..half)
+            .flat_map(|m| {
+                let (s0, s1) = (source(m), source(m + half));
+                [(s0 - x0).pow(exponent), (s1 - x0).pow(exponent), s0 - s1]
+            })
+            .collect::<Vec<_>>();
+        batch_inversion(&mut denominators);
+        let (p0, p1): (Vec<_>, Vec<_>) = (0..half)
+            .map(|m| {
+                let (a0, a1) = if half == 1 {
+                    (values[m], values[m + half])
+                } else {
+                    (
+                        values[m] * denominators[3 * m],
+                        values[m + half] * denominators[3 * m + 1],
+                    )
+                };
+                let p1 = (a0 - a1) * denominators[3 * m + 2];
+                (a0 - p1 * source(m), p1)
+            })
+            .unzip();
+
+        let p0 = self.extend(&p0, level + 1, stride, offset);
+        let p1 = self.extend(&p1, level + 1, stride, offset);
+        let mut output = vec![Projective::<E>::zero(); n];
+        for m in 0..half {
+            for index in [m, m + half] {
+                let x = target(index);
+                let value = p0[m] + p1[m] * x;
+                output[index] = if half == 1 {
+                    value
+                } else {
+                    value * (x - x0).pow(exponent)
+                };
+            }
+        }
+        output
+    }
+
+    /// This function evaluates the polynomial with coefficients `coeffs` at the points `offset + stride * m` of the
+    /// domain, which must number `coeffs.len()`.
+    fn evaluate<E>(
+        &self,
+        coeffs: &[Projective<E>],
+        stride: usize,
+        offset: usize,
+    ) -> Vec<Projective<E>>
+    where
+        E: SWCurveConfig<ScalarField = F>,
+    {
+        let n = coeffs.len();
+        if n == 1 {
+            return coeffs.to_vec();
+        }
+
+        // p(X) = p_low(X) + X^(n/2) p_high(X), and both halves are evaluated on the even points then extended to
+        // the odd ones.
+        let half = n / 2;
+        let low = self.evaluate(&coeffs[..half], 2 * stride, offset);
+        let high = self.evaluate(&coeffs[half..], 2 * stride, offset);
+        let low_odd = self.extend(&low, 0, 2 * stride, offset);
+        let high_odd = self.extend(&high, 0, 2 * stride, offset);
+        let points = &self.levels[0];
+        let exponent = [half as u64];
+        let mut output = Vec::with_capacity(n);
+        for m in 0..half {
+            let even = points[offset + 2 * stride * m];
+            let odd = points[offset + stride + 2 * stride * m];
+            output.push(low[m] + high[m] * even.pow(exponent));
+            output.push(low_odd[m] + high_odd[m] * odd.pow(exponent));
+        }
+        output
+    }
+}
+
+/// This function evaluates the polynomial whose coefficients are `coeffs` at every point of `domain`, in the order
+/// of [`EcfftDomain::elements`].
+pub fn evaluate_points<E, F>(
+    coeffs: &[Affine<E>],
+    domain: &EcfftDomain<F>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if coeffs.len() != domain.size() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The domain has {} points but there are {} coefficients",
+            domain.size(),
+            coeffs.len()
+        )));
+    }
+    let coeffs = coeffs
+        .iter()
+        .map(|point| Projective::<E>::from(*point))
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::normalize_batch(
+        &domain.evaluate(&coeffs, 1, 0),
+    ))
+}
+
+/// This function converts a monomial SRS to its Lagrange basis over `domain`, in the order of
+/// [`EcfftDomain::elements`]. The SRS must have as many points as the domain.
+pub fn srs_to_lagrange_ecfft<E, F>(
+    points: &[Affine<E>],
+    domain: &EcfftDomain<F>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = domain.size();
+    if points.len() != size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The domain has {} points but the SRS has {}",
+            size,
+            points.len()
+        )));
+    }
+    let elements = domain.elements();
+
+    // The coefficients of the vanishing polynomial Z, and the inverses of its derivative on the domain.
+    let mut vanishing = vec![F::one()];
+    for x in elements {
+        vanishing.insert(0, F::zero());
+        for k in 0..vanishing.len() - 1 {
+            let next = vanishing[k + 1];
+            vanishing[k] -= next * x;
+        }
+    }
+    let mut weights = elements
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            elements
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, y)| *x - y)
+                .product::<F>()
+        })
+        .collect::<Vec<_>>();
+    batch_inversion(&mut weights);
+
+    // Z(X) / (X - x) = sum_m x^m sum_{k > m} z_k X^(k - m - 1), so [Z(tau) / (tau - x)] is the evaluation at x of
+    // the polynomial with coefficients sum_{k > m} z_k [tau^(k - m - 1)].
+    let coeffs = cfg_into_iter!(0..size)
+        .map(|m| Projective::<E>::msm_unchecked(&points[..size - m], &vanishing[m + 1..]))
+        .collect::<Vec<_>>();
+    let lagrange = domain
+        .evaluate(&coeffs, 1, 0)
+        .into_iter()
+        .zip(weights)
+        .map(|(point, weight)| point * weight)
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::normalize_batch(&lagrange))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_secp256k1::{Config as SecpConfig, Fr};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_evaluate_points() {
+        let rng = &mut ark_std::test_rng();
+        let domain = EcfftDomain::<Fr>::find(3, rng).unwrap();
+        assert_eq!(domain.size(), 8);
+        let coeffs = (0..8)
+            .map(|_| Projective::<SecpConfig>::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let evals = evaluate_points(&coeffs, &domain).unwrap();
+        for (x, eval) in domain.elements().iter().zip(evals) {
+            let expected = coeffs
+                .iter()
+                .rev()
+                .fold(Projective::<SecpConfig>::zero(), |acc, coeff| {
+                    acc * x + coeff
+                });
+            assert_eq!(eval, expected.into_affine());
+        }
+        assert!(evaluate_points(&coeffs[..4], &domain).is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_ecfft() {
+        // The scalar field of secp256k1 has 2-adicity 6, so a domain of 2^7 points has no roots of unity.
+        let rng = &mut ark_std::test_rng();
+        let domain = EcfftDomain::<Fr>::find(7, rng).unwrap();
+        let srs = gen_srs_for_testing::<SecpConfig, _>(rng, 127).unwrap();
+        let lagrange = srs_to_lagrange_ecfft(&srs, &domain).unwrap();
+
+        let coeffs = (0..128).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let evals = domain
+            .elements()
+            .iter()
+            .map(|x| coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Projective::<SecpConfig>::msm_unchecked(&lagrange, &evals),
+            Projective::<SecpConfig>::msm_unchecked(&srs, &coeffs)
+        );
+        assert!(srs_to_lagrange_ecfft(&srs[..64], &domain).is_err());
+    }
+
+    #[test]
+    fn test_domain_validation() {
+        let rng = &mut ark_std::test_rng();
+        let domain = EcfftDomain::<Fr>::find(4, rng).unwrap();
+        assert_eq!(domain.levels.len(), 5);
+        assert!(EcfftDomain::<Fr>::find(0, rng).is_err());
+
+        // A point off the curve is rejected.
+        let generator = (Fr::from(1u64), Fr::from(2u64));
+        assert!(EcfftDomain::new(Fr::from(3u64), Fr::from(5u64), generator, generator).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! This module computes EIP-4844 blob commitments from a BLS12-381 Lagrange SRS.
+//!
+//! A blob is 4096 field elements, each encoded as 32 big endian bytes, holding the evaluations of a polynomial over
+//! the domain of size 4096 in bit-reversed order. Its commitment is therefore the multi-scalar multiplication of the
+//! blob with the bit-reversed Lagrange points, compressed to 48 bytes.
+use crate::{
+    error::InterpolationError,
+    srs::{LagrangeSrs, PointOrdering},
+    utils::bit_reverse,
+};
+use ark_bls12_381::{g1::Config as G1Config, Fr, G1Projective};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInt, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The number of bytes in an encoded field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The number of bytes in a blob.
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// The number of bytes in a compressed commitment.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// This function decodes a 32 byte big endian field element, rejecting values that are not reduced as the spec's
+/// `bytes_to_bls_field` does.
+fn bytes_to_bls_field(bytes: &[u8]) -> Result<Fr, InterpolationError> {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let end = BYTES_PER_FIELD_ELEMENT - 8 * i;
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes.copy_from_slice(&bytes[end - 8..end]);
+        *limb = u64::from_be_bytes(limb_bytes);
+    }
+    Fr::from_bigint(BigInt::new(limbs)).ok_or(InterpolationError::FieldError(
+        "Blob field element is not reduced".to_string(),
+    ))
+}
+
+/// This function decodes a blob into its field elements, in the order they are stored.
+pub fn blob_to_field_elements(blob: &[u8]) -> Result<Vec<Fr>, InterpolationError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "A blob has {} bytes but {} were given",
+            BYTES_PER_BLOB,
+            blob.len()
+        )));
+    }
+    blob.chunks(BYTES_PER_FIELD_ELEMENT)
+        .map(bytes_to_bls_field)
+        .collect()
+}
+
+/// This function returns the compressed KZG commitment to `blob`, as the spec's `blob_to_kzg_commitment` does.
+/// `srs` must be the Lagrange SRS of size 4096 over the domain EIP-4844 uses; it may be stored in either ordering.
+pub fn blob_to_kzg_commitment(
+    srs: &LagrangeSrs<G1Config>,
+    blob: &[u8],
+) -> Result<[u8; BYTES_PER_COMMITMENT], InterpolationError> {
+    if srs.len() != FIELD_ELEMENTS_PER_BLOB {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but a blob needs {}",
+            srs.len(),
+            FIELD_ELEMENTS_PER_BLOB
+        )));
+    }
+    let scalars = blob_to_field_elements(blob)?;
+
+    // The blob is bit reversed, so we match it with the points in bit reversed order.
+    let bases = match srs.ordering() {
+        PointOrdering::BitReversed => srs.points().to_vec(),
+        PointOrdering::Natural => {
+            let log_size = FIELD_ELEMENTS_PER_BLOB.ilog2() as usize;
+            (0..FIELD_ELEMENTS_PER_BLOB)
+                .map(|i| Ok(srs.points()[bit_reverse(i, log_size)?]))
+                .collect::<Result<Vec<_>, InterpolationError>>()?
+        }
+    };
+    let commitment = G1Projective::msm(&bases, &scalars)
+        .map_err(|_| InterpolationError::SizeError)?
+        .into_affine();
+
+    let mut bytes = [0u8; BYTES_PER_COMMITMENT];
+    commitment
+        .serialize_compressed(&mut bytes[..])
+        .map_err(|e| InterpolationError::SerializationError(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G1Affine;
+    use ark_ec::AffineRepr;
+    use ark_ff::BigInteger;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::Zero;
+
+    fn encode_blob(elements: &[Fr]) -> Vec<u8> {
+        elements
+            .iter()
+            .flat_map(|element| element.into_bigint().to_bytes_be())
+            .collect()
+    }
+
+    #[test]
+    fn test_blob_to_kzg_commitment_orderings() {
+        // We only check which point each element is matched with, so distinct multiples of the generator
+        // stand in for the Lagrange points.
+        let mut point = G1Projective::zero();
+        let points = (0..FIELD_ELEMENTS_PER_BLOB)
+            .map(|_| {
+                point += G1Affine::generator();
+                point
+            })
+            .collect::<Vec<_>>();
+        let domain = Radix2EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+        let lagrange = LagrangeSrs::from_parts(
+            G1Projective::normalize_batch(&points),
+            domain.group_gen(),
+            PointOrdering::Natural,
+        );
+
+        // A blob with a single one at index `i` commits to the bit reversed Lagrange point at `i`.
+        let mut elements = vec![Fr::from(0u64); FIELD_ELEMENTS_PER_BLOB];
+        elements[3] = Fr::from(1u64);
+        let blob = encode_blob(&elements);
+        let mut expected = [0u8; BYTES_PER_COMMITMENT];
+        lagrange.points()[bit_reverse(3, 12).unwrap()]
+            .serialize_compressed(&mut expected[..])
+            .unwrap();
+        assert_eq!(blob_to_kzg_commitment(&lagrange, &blob).unwrap(), expected);
+        let reversed = lagrange
+            .clone()
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert_eq!(blob_to_kzg_commitment(&reversed, &blob).unwrap(), expected);
+
+        // Blobs of the wrong length or with unreduced elements are rejected.
+        assert!(blob_to_kzg_commitment(&lagrange, &blob[1..]).is_err());
+        let mut unreduced = blob;
+        unreduced[..32].copy_from_slice(&[0xff; 32]);
+        assert!(blob_to_kzg_commitment(&lagrange, &unreduced).is_err());
+    }
+
+    /// These vectors are the correct proof cases of the consensus spec `verify_blob_kzg_proof` tests, as vendored
+    /// by the kzg-rs crate.
+    #[cfg(feature = "ethereum-setup")]
+    #[test]
+    fn test_blob_to_kzg_commitment_spec_vectors() {
+        use crate::setups::ethereum::ethereum_kzg_lagrange;
+        fn to_hex(bytes: &[u8]) -> String {
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+        let srs = ethereum_kzg_lagrange();
+
+        // The zero blob commits to the point at infinity.
+        let blob = vec![0u8; BYTES_PER_BLOB];
+        assert_eq!(
+            to_hex(&blob_to_kzg_commitment(srs, &blob).unwrap()),
+            format!("c0{}", "00".repeat(47))
+        );
+
+        // Case 19b3f3f8c98ea31e has a single one at index 3211.
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+        blob[3211 * 32 + 31] = 1;
+        assert_eq!(
+            to_hex(&blob_to_kzg_commitment(srs, &blob).unwrap()),
+            "93efc82d2017e9c57834a1246463e64774e56183bb247c8fc9dd98c56817e878d97b05f5c8d900acf1fbbbca6f146556"
+        );
+
+        // Case 84d8089232bc23a8 is a full blob.
+        let blob = include_bytes!("testdata/eip4844_blob_84d8089232bc23a8.bin");
+        assert_eq!(
+            to_hex(&blob_to_kzg_commitment(srs, blob).unwrap()),
+            "8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7"
+        );
+    }
+}
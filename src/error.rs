@@ -2,6 +2,7 @@
 use ark_std::{
     error::Error,
     fmt::{Display, Formatter, Result},
+    io,
 };
 /// Enum for errors that can occur during interpolation.
 #[derive(Debug, Clone)]
@@ -12,6 +13,17 @@ pub enum InterpolationError {
     FieldError(String),
     /// The size of the provided SRS was not a power of two.
     SizeError,
+    /// Error reading or writing serialized points.
+    SerializationError(String),
+    /// A background task running the conversion failed.
+    TaskError(String),
+    /// Downloading a setup failed or the downloaded file did not match its expected digest.
+    DownloadError(String),
+    /// A buffer could not be allocated.
+    OutOfMemory {
+        /// The size of the buffer that could not be allocated.
+        requested_bytes: usize,
+    },
 }
 
 impl Display for InterpolationError {
@@ -29,8 +41,30 @@ impl Display for InterpolationError {
                     "Size error: the provided SRS size was not a power of two"
                 )
             }
+            InterpolationError::SerializationError(s) => {
+                write!(f, "Serialization error: {}", s)
+            }
+            InterpolationError::TaskError(s) => {
+                write!(f, "Task error: {}", s)
+            }
+            InterpolationError::DownloadError(s) => {
+                write!(f, "Download error: {}", s)
+            }
+            InterpolationError::OutOfMemory { requested_bytes } => {
+                write!(
+                    f,
+                    "Out of memory: failed to allocate {} bytes",
+                    requested_bytes
+                )
+            }
         }
     }
 }
 
 impl Error for InterpolationError {}
+
+impl From<io::Error> for InterpolationError {
+    fn from(e: io::Error) -> Self {
+        InterpolationError::SerializationError(e.to_string())
+    }
+}
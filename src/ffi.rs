@@ -0,0 +1,233 @@
+//! This module exports the conversion over a C ABI, so provers written in C, C++ or Go can link the `cdylib` this
+//! crate builds without going through any of its Rust types. The matching declarations are in
+//! `include/srs_interpolation.h`.
+//!
+//! A buffer of points holds each point as its affine `x` then `y` coordinate, each a canonical big-endian field
+//! element of [`SRS_BN254_FIELD_SIZE`] or [`SRS_BLS12_381_FIELD_SIZE`] bytes. The point at infinity, which has no
+//! affine coordinates, is written as zeros; no point of either curve has both coordinates zero. Every function
+//! returns [`SRS_OK`] on success or one of the other `SRS_ERROR_*` codes, and leaves the output untouched on error.
+use crate::{
+    error::InterpolationError,
+    formats::binary::{decode_prime_field, encode_prime_field, prime_field_size, Endianness},
+    srs::{MonomialSrs, PointOrdering},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::PrimeField;
+use ark_std::{panic, slice, vec::Vec};
+
+/// The conversion succeeded.
+pub const SRS_OK: i32 = 0;
+/// A pointer argument was null.
+pub const SRS_ERROR_NULL_POINTER: i32 = 1;
+/// The number of points was not a power of two.
+pub const SRS_ERROR_SIZE: i32 = 2;
+/// A point was not canonically encoded or not in the prime order subgroup.
+pub const SRS_ERROR_INVALID_POINT: i32 = 3;
+/// The arguments were rejected for another reason.
+pub const SRS_ERROR_INVALID_PARAMETERS: i32 = 4;
+/// The conversion failed unexpectedly.
+pub const SRS_ERROR_INTERNAL: i32 = 5;
+/// A buffer of the conversion could not be allocated.
+pub const SRS_ERROR_OUT_OF_MEMORY: i32 = 6;
+
+/// The number of bytes in a coordinate of a BN254 G1 point.
+pub const SRS_BN254_FIELD_SIZE: usize = 32;
+/// The number of bytes in a coordinate of a BLS12-381 G1 point.
+pub const SRS_BLS12_381_FIELD_SIZE: usize = 48;
+
+pub(crate) fn error_code(e: &InterpolationError) -> i32 {
+    match e {
+        InterpolationError::SizeError => SRS_ERROR_SIZE,
+        InterpolationError::SerializationError(_) => SRS_ERROR_INVALID_POINT,
+        InterpolationError::InvalidParameters(_) => SRS_ERROR_INVALID_PARAMETERS,
+        InterpolationError::OutOfMemory { .. } => SRS_ERROR_OUT_OF_MEMORY,
+        _ => SRS_ERROR_INTERNAL,
+    }
+}
+
+/// This function decodes a point from its coordinates, checking it is in the prime order subgroup.
+fn decode_coordinates<E>(bytes: &[u8]) -> Result<Affine<E>, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+{
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(Affine::<E>::identity());
+    }
+    let (x, y) = bytes.split_at(bytes.len() / 2);
+    let point = Affine::<E>::new_unchecked(
+        decode_prime_field(x, Endianness::Big)?,
+        decode_prime_field(y, Endianness::Big)?,
+    );
+    if !(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()) {
+        return Err(InterpolationError::SerializationError(
+            "Point is not in the prime order subgroup".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+/// This function appends the coordinates of `point` to `bytes`.
+fn encode_coordinates<E>(point: &Affine<E>, bytes: &mut Vec<u8>)
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+{
+    match point.xy() {
+        None => bytes.resize(bytes.len() + 2 * prime_field_size::<E::BaseField>(), 0),
+        Some((x, y)) => {
+            encode_prime_field(x, Endianness::Big, bytes);
+            encode_prime_field(y, Endianness::Big, bytes);
+        }
+    }
+}
+
+/// This function converts the monomial points in `input` and returns the coordinates of the Lagrange points.
+fn convert<E>(input: &[u8], ordering: PointOrdering) -> Result<Vec<u8>, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+{
+    let points = input
+        .chunks_exact(2 * prime_field_size::<E::BaseField>())
+        .map(decode_coordinates::<E>)
+        .collect::<Result<Vec<_>, InterpolationError>>()?;
+    let lagrange = MonomialSrs::new(points).to_lagrange_with_ordering(ordering)?;
+    let mut output = Vec::with_capacity(input.len());
+    lagrange
+        .points()
+        .iter()
+        .for_each(|point| encode_coordinates(point, &mut output));
+    Ok(output)
+}
+
+/// This function checks the arguments of an exported function, runs the conversion and copies its result to
+/// `output`, catching any panic so that it never unwinds into the caller.
+///
+/// # Safety
+///
+/// `input` and `output` must each be null or valid for `num_points` points of the curve `E`, and must not overlap.
+unsafe fn convert_raw<E>(
+    input: *const u8,
+    output: *mut u8,
+    num_points: usize,
+    bit_reversed: bool,
+) -> i32
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+{
+    if input.is_null() || output.is_null() {
+        return SRS_ERROR_NULL_POINTER;
+    }
+    let Some(len) = num_points.checked_mul(2 * prime_field_size::<E::BaseField>()) else {
+        return SRS_ERROR_INVALID_PARAMETERS;
+    };
+    let input = slice::from_raw_parts(input, len);
+    let ordering = if bit_reversed {
+        PointOrdering::BitReversed
+    } else {
+        PointOrdering::Natural
+    };
+    match panic::catch_unwind(|| convert::<E>(input, ordering)) {
+        Ok(Ok(bytes)) => {
+            slice::from_raw_parts_mut(output, len).copy_from_slice(&bytes);
+            SRS_OK
+        }
+        Ok(Err(e)) => error_code(&e),
+        Err(_) => SRS_ERROR_INTERNAL,
+    }
+}
+
+/// This function converts the `num_points` BN254 G1 points of a monomial SRS in `input` to the Lagrange basis over
+/// the arkworks domain of that size, writing the same number of points to `output`, in bit-reversed order if
+/// `bit_reversed` is set.
+///
+/// # Safety
+///
+/// `input` must be readable and `output` writable for `num_points * 2 * SRS_BN254_FIELD_SIZE` bytes, and the two
+/// buffers must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn srs_to_lagrange_bn254(
+    input: *const u8,
+    output: *mut u8,
+    num_points: usize,
+    bit_reversed: bool,
+) -> i32 {
+    convert_raw::<ark_bn254::g1::Config>(input, output, num_points, bit_reversed)
+}
+
+/// This function converts the `num_points` BLS12-381 G1 points of a monomial SRS in `input` to the Lagrange basis
+/// over the arkworks domain of that size, writing the same number of points to `output`, in bit-reversed order if
+/// `bit_reversed` is set.
+///
+/// # Safety
+///
+/// `input` must be readable and `output` writable for `num_points * 2 * SRS_BLS12_381_FIELD_SIZE` bytes, and the
+/// two buffers must not overlap.
+#[cfg(feature = "bls12-381")]
+#[no_mangle]
+pub unsafe extern "C" fn srs_to_lagrange_bls12_381(
+    input: *const u8,
+    output: *mut u8,
+    num_points: usize,
+    bit_reversed: bool,
+) -> i32 {
+    convert_raw::<ark_bls12_381::g1::Config>(input, output, num_points, bit_reversed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fq};
+    use ark_std::ptr;
+
+    #[test]
+    fn test_srs_to_lagrange_bn254() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        points[3] = Affine::<BnConfig>::identity();
+        let srs = MonomialSrs::new(points);
+        let mut input = Vec::new();
+        srs.points()
+            .iter()
+            .for_each(|point| encode_coordinates(point, &mut input));
+        assert_eq!(input.len(), 16 * 2 * SRS_BN254_FIELD_SIZE);
+        #[cfg(feature = "bls12-381")]
+        assert_eq!(
+            prime_field_size::<ark_bls12_381::Fq>(),
+            SRS_BLS12_381_FIELD_SIZE
+        );
+
+        let mut output = vec![0u8; input.len()];
+        let code = unsafe { srs_to_lagrange_bn254(input.as_ptr(), output.as_mut_ptr(), 16, true) };
+        assert_eq!(code, SRS_OK);
+        let mut expected = Vec::new();
+        srs.to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap()
+            .points()
+            .iter()
+            .for_each(|point| encode_coordinates(point, &mut expected));
+        assert_eq!(output, expected);
+
+        let code = unsafe { srs_to_lagrange_bn254(ptr::null(), output.as_mut_ptr(), 16, false) };
+        assert_eq!(code, SRS_ERROR_NULL_POINTER);
+        let code = unsafe { srs_to_lagrange_bn254(input.as_ptr(), output.as_mut_ptr(), 12, false) };
+        assert_eq!(code, SRS_ERROR_SIZE);
+
+        // The point (1, y) is off the curve, so it is rejected and the output is left alone.
+        let mut bad = input.clone();
+        let mut one = Vec::new();
+        encode_prime_field(&Fq::from(1u64), Endianness::Big, &mut one);
+        bad[..SRS_BN254_FIELD_SIZE].copy_from_slice(&one);
+        let mut untouched = vec![7u8; input.len()];
+        let code =
+            unsafe { srs_to_lagrange_bn254(bad.as_ptr(), untouched.as_mut_ptr(), 16, false) };
+        assert_eq!(code, SRS_ERROR_INVALID_POINT);
+        assert!(untouched.iter().all(|b| *b == 7));
+    }
+}
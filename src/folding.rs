@@ -0,0 +1,112 @@
+//! This module derives the per-round keys of Gemini and Zeromorph style reductions from multilinear to univariate
+//! commitments, so their provers can precompute them from a univariate SRS.
+//!
+//! Both reductions work with polynomials of halving size: Gemini folds a polynomial of `2^v` coefficients into
+//! ones of `2^(v - 1)`, ..., `2` coefficients, and Zeromorph commits to quotients `q_k` of `2^k` coefficients. A
+//! prover holding these as evaluations commits to them against the Lagrange basis of the domain of their size,
+//! which is the conversion of the first `2^k` points of the SRS. Zeromorph also checks the degree of each `q_k` by
+//! committing to `X^(N - 2^k) q_k(X)`, where `N` is the size of the SRS, which is the conversion of the last `2^k`
+//! points as in [`crate::shifted`].
+use crate::{
+    error::InterpolationError,
+    shifted::{shifted_to_lagrange, PowerOrder, ShiftedLagrangeSrs},
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+/// The keys of the round that handles polynomials of `2^k` coefficients.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct FoldingRound<E: SWCurveConfig> {
+    /// The Lagrange basis over the arkworks domain of size `2^k`, in natural order.
+    pub lagrange: LagrangeSrs<E>,
+    /// The same basis multiplied by `tau^(N - 2^k)`, for the degree check of a polynomial of `2^k` coefficients.
+    pub degree_check: ShiftedLagrangeSrs<E>,
+}
+
+/// This function returns the keys of the rounds for polynomials of `2^k` coefficients for every `k` from zero to
+/// `num_vars`, indexed by `k`. The SRS needs at least `2^num_vars` points.
+pub fn folding_rounds<E, F>(
+    srs: &MonomialSrs<E>,
+    num_vars: usize,
+) -> Result<Vec<FoldingRound<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let largest = u32::try_from(num_vars)
+        .ok()
+        .and_then(|num_vars| 1usize.checked_shl(num_vars))
+        .ok_or(InterpolationError::SizeError)?;
+    if srs.len() < largest {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} variables need {}",
+            srs.len(),
+            num_vars,
+            largest
+        )));
+    }
+    (0..=num_vars)
+        .map(|k| {
+            let size = 1 << k;
+            let shift = srs.len() - size;
+            Ok(FoldingRound {
+                lagrange: MonomialSrs::new(srs.points()[..size].to_vec()).to_lagrange()?,
+                degree_check: shifted_to_lagrange(
+                    &srs.points()[shift..],
+                    shift,
+                    PowerOrder::Ascending,
+                )?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs::commit_evals, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    };
+
+    #[test]
+    fn test_folding_rounds() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 19).unwrap());
+        let rounds = folding_rounds(&srs, 3).unwrap();
+        assert_eq!(rounds.len(), 4);
+
+        for (k, round) in rounds.iter().enumerate() {
+            let size = 1 << k;
+            let polynomial = DensePolynomial::<Fr>::rand(size - 1, rng);
+            let evals = Radix2EvaluationDomain::<Fr>::new(size)
+                .unwrap()
+                .fft(&polynomial.coeffs);
+            let commit = |shift: usize| {
+                G1Projective::msm(&srs.points()[shift..shift + size], &polynomial.coeffs)
+                    .unwrap()
+                    .into_affine()
+            };
+            assert_eq!(commit_evals(&evals, &round.lagrange).unwrap(), commit(0));
+            assert_eq!(round.degree_check.shift(), 20 - size);
+            assert_eq!(
+                commit_evals(&evals, round.degree_check.srs()).unwrap(),
+                commit(20 - size)
+            );
+        }
+
+        assert!(folding_rounds(&srs, 5).is_err());
+        assert!(folding_rounds(&srs, 1 << 32).is_err());
+    }
+}
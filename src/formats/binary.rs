@@ -0,0 +1,700 @@
+//! This module contains this crate's own versioned binary format for monomial and Lagrange SRSs.
+//!
+//! A file starts with an 18 byte header:
+//!
+//! | offset | size | contents                                              |
+//! |--------|------|-------------------------------------------------------|
+//! | 0      | 4    | the magic bytes `SRSI`                                |
+//! | 4      | 1    | the format version, currently `1`                     |
+//! | 5      | 1    | the curve, see [`CurveId::to_byte`]                   |
+//! | 6      | 1    | the basis, `0` for monomial and `1` for Lagrange      |
+//! | 7      | 1    | the ordering, `0` for natural and `1` for bit-reversed|
+//! | 8      | 1    | the point encoding, `0` compressed, `1` uncompressed  |
+//! | 9      | 1    | the endianness, `0` for little and `1` for big        |
+//! | 10     | 8    | the number of points as a `u64`                       |
+//!
+//! A Lagrange SRS then stores the generator of its domain as a scalar field element. Finally every point is
+//! written as a flag byte, `0` for the identity and otherwise `1` (or `2` if a compressed point has the larger
+//! of its two possible y-coordinates), followed by its x-coordinate and, if uncompressed, its y-coordinate.
+//! The identity has zeroed coordinates so every point takes up the same number of bytes. Field elements are
+//! written as their canonical integers, with extension fields written one base prime field coefficient at a time.
+//! Everything after the magic and version is written with the endianness given in the header.
+use crate::{
+    curves::{CurveId, SrsCurve},
+    error::InterpolationError,
+    formats::chunked::{read_decoded, DECODE_CHUNK_SIZE},
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+    utils::{is_primitive_root, size_from_u64},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_std::{
+    io::{Read, Write},
+    marker::PhantomData,
+    vec::Vec,
+};
+
+/// The magic bytes at the start of every file.
+pub const MAGIC: [u8; 4] = *b"SRSI";
+/// The current version of the format.
+pub const VERSION: u8 = 1;
+/// The number of bytes in a header.
+pub const HEADER_SIZE: usize = 18;
+
+/// The flag byte of the point at infinity.
+const FLAG_IDENTITY: u8 = 0;
+/// The flag byte of a point whose y-coordinate is the smaller of the two options or that is stored uncompressed.
+const FLAG_SMALLER: u8 = 1;
+/// The flag byte of a compressed point whose y-coordinate is the larger of the two options.
+const FLAG_LARGER: u8 = 2;
+
+/// The basis an SRS is expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Basis {
+    /// The powers of tau.
+    Monomial,
+    /// The Lagrange polynomials evaluated at tau.
+    Lagrange,
+}
+
+/// How points are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PointEncoding {
+    /// Only the x-coordinate and the sign of the y-coordinate is stored.
+    #[default]
+    Compressed,
+    /// Both coordinates are stored.
+    Uncompressed,
+}
+
+/// The byte order of the integers and field elements in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Endianness {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+/// The header of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BinaryHeader {
+    /// The curve the points are on.
+    pub curve: CurveId,
+    /// The basis the SRS is expressed in.
+    pub basis: Basis,
+    /// The order of the points, always [`PointOrdering::Natural`] for a monomial SRS.
+    pub ordering: PointOrdering,
+    /// How the points are encoded.
+    pub encoding: PointEncoding,
+    /// The byte order of the file.
+    pub endianness: Endianness,
+    /// The number of points.
+    pub size: u64,
+}
+
+impl BinaryHeader {
+    /// Writes the header.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        bytes.push(self.curve.to_byte());
+        bytes.push(match self.basis {
+            Basis::Monomial => 0,
+            Basis::Lagrange => 1,
+        });
+        bytes.push(match self.ordering {
+            PointOrdering::Natural => 0,
+            PointOrdering::BitReversed => 1,
+        });
+        bytes.push(match self.encoding {
+            PointEncoding::Compressed => 0,
+            PointEncoding::Uncompressed => 1,
+        });
+        bytes.push(match self.endianness {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        });
+        match self.endianness {
+            Endianness::Little => bytes.extend_from_slice(&self.size.to_le_bytes()),
+            Endianness::Big => bytes.extend_from_slice(&self.size.to_be_bytes()),
+        }
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Reads a header, checking the magic bytes and version.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut bytes = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut bytes)?;
+        if bytes[..4] != MAGIC {
+            return Err(InterpolationError::SerializationError(
+                "Missing magic bytes".to_string(),
+            ));
+        }
+        if bytes[4] != VERSION {
+            return Err(InterpolationError::SerializationError(format!(
+                "Unsupported version {}",
+                bytes[4]
+            )));
+        }
+        let invalid = |field: &str, byte: u8| {
+            InterpolationError::SerializationError(format!("Invalid {} byte {}", field, byte))
+        };
+        let curve = CurveId::from_byte(bytes[5]).ok_or(invalid("curve", bytes[5]))?;
+        let basis = match bytes[6] {
+            0 => Basis::Monomial,
+            1 => Basis::Lagrange,
+            byte => return Err(invalid("basis", byte)),
+        };
+        let ordering = match bytes[7] {
+            0 => PointOrdering::Natural,
+            1 => PointOrdering::BitReversed,
+            byte => return Err(invalid("ordering", byte)),
+        };
+        let encoding = match bytes[8] {
+            0 => PointEncoding::Compressed,
+            1 => PointEncoding::Uncompressed,
+            byte => return Err(invalid("encoding", byte)),
+        };
+        let endianness = match bytes[9] {
+            0 => Endianness::Little,
+            1 => Endianness::Big,
+            byte => return Err(invalid("endianness", byte)),
+        };
+        let mut size = [0u8; 8];
+        size.copy_from_slice(&bytes[10..]);
+        let size = match endianness {
+            Endianness::Little => u64::from_le_bytes(size),
+            Endianness::Big => u64::from_be_bytes(size),
+        };
+        if basis == Basis::Monomial && ordering != PointOrdering::Natural {
+            return Err(InterpolationError::SerializationError(
+                "A monomial SRS must be in natural order".to_string(),
+            ));
+        }
+        Ok(Self {
+            curve,
+            basis,
+            ordering,
+            encoding,
+            endianness,
+            size,
+        })
+    }
+
+    /// The number of bytes taken up by each point on the curve `E`.
+    pub fn point_size<E: SWCurveConfig>(&self) -> usize {
+        let coordinates = match self.encoding {
+            PointEncoding::Compressed => 1,
+            PointEncoding::Uncompressed => 2,
+        };
+        1 + coordinates * base_field_size::<E::BaseField>()
+    }
+}
+
+/// The number of bytes needed to encode an element of the prime field `F`.
+pub(crate) fn prime_field_size<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize).div_ceil(8)
+}
+
+/// The number of bytes needed to encode an element of the field `F`.
+fn base_field_size<F: Field>() -> usize {
+    F::extension_degree() as usize * prime_field_size::<F::BasePrimeField>()
+}
+
+/// This function appends the encoding of a prime field element to `bytes`.
+pub(crate) fn encode_prime_field<F: PrimeField>(
+    element: &F,
+    endianness: Endianness,
+    bytes: &mut Vec<u8>,
+) {
+    let mut element_bytes = element.into_bigint().to_bytes_le();
+    element_bytes.truncate(prime_field_size::<F>());
+    if endianness == Endianness::Big {
+        element_bytes.reverse();
+    }
+    bytes.extend_from_slice(&element_bytes);
+}
+
+/// This function decodes a prime field element, erroring if it is not canonical.
+pub(crate) fn decode_prime_field<F: PrimeField>(
+    bytes: &[u8],
+    endianness: Endianness,
+) -> Result<F, InterpolationError> {
+    let element = match endianness {
+        Endianness::Little => F::from_le_bytes_mod_order(bytes),
+        Endianness::Big => F::from_be_bytes_mod_order(bytes),
+    };
+    let mut encoded = Vec::with_capacity(bytes.len());
+    encode_prime_field(&element, endianness, &mut encoded);
+    if encoded != bytes {
+        return Err(InterpolationError::SerializationError(
+            "Field element is not canonical".to_string(),
+        ));
+    }
+    Ok(element)
+}
+
+/// This function appends the encoding of a field element to `bytes`.
+fn encode_field<F: Field>(element: &F, endianness: Endianness, bytes: &mut Vec<u8>) {
+    for coefficient in element.to_base_prime_field_elements() {
+        encode_prime_field(&coefficient, endianness, bytes);
+    }
+}
+
+/// This function decodes a field element written by [`encode_field`].
+fn decode_field<F: Field>(bytes: &[u8], endianness: Endianness) -> Result<F, InterpolationError> {
+    let coefficients = bytes
+        .chunks(prime_field_size::<F::BasePrimeField>())
+        .map(|chunk| decode_prime_field(chunk, endianness))
+        .collect::<Result<Vec<F::BasePrimeField>, InterpolationError>>()?;
+    F::from_base_prime_field_elems(&coefficients).ok_or(InterpolationError::SerializationError(
+        "Wrong number of field coefficients".to_string(),
+    ))
+}
+
+/// This function appends the encoding of `point` to `bytes`.
+pub(crate) fn encode_point<E: SWCurveConfig>(
+    point: &Affine<E>,
+    header: &BinaryHeader,
+    bytes: &mut Vec<u8>,
+) {
+    let zero = E::BaseField::zero();
+    let (flag, x, y) = match point.xy() {
+        None => (FLAG_IDENTITY, &zero, &zero),
+        Some((x, y)) => match header.encoding {
+            PointEncoding::Compressed if *y > -*y => (FLAG_LARGER, x, y),
+            _ => (FLAG_SMALLER, x, y),
+        },
+    };
+    bytes.push(flag);
+    encode_field(x, header.endianness, bytes);
+    if header.encoding == PointEncoding::Uncompressed {
+        encode_field(y, header.endianness, bytes);
+    }
+}
+
+/// This function decodes a point written by [`encode_point`], checking it is in the prime order subgroup if `validate` is set.
+pub(crate) fn decode_point<E: SWCurveConfig>(
+    bytes: &[u8],
+    header: &BinaryHeader,
+    validate: bool,
+) -> Result<Affine<E>, InterpolationError> {
+    let field_size = base_field_size::<E::BaseField>();
+    let (flag, coordinates) = (bytes[0], &bytes[1..]);
+    if flag == FLAG_IDENTITY {
+        if coordinates.iter().any(|b| *b != 0) {
+            return Err(InterpolationError::SerializationError(
+                "Point at infinity has non-zero coordinates".to_string(),
+            ));
+        }
+        return Ok(Affine::<E>::identity());
+    }
+    let x = decode_field(&coordinates[..field_size], header.endianness)?;
+    let point = match (header.encoding, flag) {
+        (PointEncoding::Uncompressed, FLAG_SMALLER) => {
+            let y = decode_field(&coordinates[field_size..], header.endianness)?;
+            Affine::<E>::new_unchecked(x, y)
+        }
+        (PointEncoding::Compressed, FLAG_SMALLER | FLAG_LARGER) => {
+            let (smaller, larger) = Affine::<E>::get_ys_from_x_unchecked(x).ok_or(
+                InterpolationError::SerializationError(
+                    "Compressed point is not on the curve".to_string(),
+                ),
+            )?;
+            let y = if flag == FLAG_LARGER { larger } else { smaller };
+            Affine::<E>::new_unchecked(x, y)
+        }
+        _ => {
+            return Err(InterpolationError::SerializationError(format!(
+                "Invalid point flag {}",
+                flag
+            )))
+        }
+    };
+    if validate && !(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()) {
+        return Err(InterpolationError::SerializationError(
+            "Point is not in the prime order subgroup".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+/// This function encodes the header, followed by the generator for a Lagrange SRS.
+pub(crate) fn encode_header<E: SrsCurve>(
+    header: &BinaryHeader,
+    generator: Option<E::ScalarField>,
+) -> Result<Vec<u8>, InterpolationError> {
+    if header.curve != E::CURVE_ID {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Header is for {} but the points are on {}",
+            header.curve.name(),
+            E::CURVE_ID.name()
+        )));
+    }
+    let mut bytes = Vec::with_capacity(HEADER_SIZE);
+    header.write(&mut bytes)?;
+    match (header.basis, generator) {
+        (Basis::Monomial, None) => {}
+        (Basis::Lagrange, Some(generator)) => {
+            encode_prime_field(&generator, header.endianness, &mut bytes)
+        }
+        _ => {
+            return Err(InterpolationError::InvalidParameters(
+                "Exactly the Lagrange basis stores a domain generator".to_string(),
+            ))
+        }
+    }
+    Ok(bytes)
+}
+
+/// A writer that streams points into a file one at a time.
+pub struct SrsWriter<W: Write, E: SrsCurve> {
+    writer: W,
+    header: BinaryHeader,
+    remaining: u64,
+    buffer: Vec<u8>,
+    _curve: PhantomData<E>,
+}
+
+impl<W: Write, E: SrsCurve> SrsWriter<W, E> {
+    /// This function writes the header and returns a writer expecting `header.size` points.
+    /// A Lagrange SRS must provide the generator of its domain and a monomial SRS must not.
+    pub fn new(
+        mut writer: W,
+        header: BinaryHeader,
+        generator: Option<E::ScalarField>,
+    ) -> Result<Self, InterpolationError> {
+        writer.write_all(&encode_header::<E>(&header, generator)?)?;
+        Ok(Self {
+            writer,
+            header,
+            remaining: header.size,
+            buffer: Vec::with_capacity(header.point_size::<E>()),
+            _curve: PhantomData,
+        })
+    }
+
+    /// Writes the next point, erroring if all the points in the header have already been written.
+    pub fn write_point(&mut self, point: &Affine<E>) -> Result<(), InterpolationError> {
+        if self.remaining == 0 {
+            return Err(InterpolationError::InvalidParameters(
+                "More points written than the header declared".to_string(),
+            ));
+        }
+        self.buffer.clear();
+        encode_point(point, &self.header, &mut self.buffer);
+        self.writer.write_all(&self.buffer)?;
+        self.remaining -= 1;
+        Ok(())
+    }
+
+    /// Finishes writing, erroring if fewer points were written than the header declared.
+    pub fn finish(mut self) -> Result<W, InterpolationError> {
+        if self.remaining != 0 {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "{} points declared in the header were not written",
+                self.remaining
+            )));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// A reader that streams points out of a file one at a time.
+pub struct SrsReader<R: Read, E: SrsCurve> {
+    reader: R,
+    header: BinaryHeader,
+    generator: Option<E::ScalarField>,
+    remaining: u64,
+    validate: bool,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read, E: SrsCurve> SrsReader<R, E> {
+    /// This function reads the header and returns a reader that checks every point is in the prime order subgroup.
+    pub fn new(reader: R) -> Result<Self, InterpolationError> {
+        Self::new_with_validation(reader, true)
+    }
+
+    /// This function is the same as [`SrsReader::new`] but the points are not checked.
+    pub fn new_unchecked(reader: R) -> Result<Self, InterpolationError> {
+        Self::new_with_validation(reader, false)
+    }
+
+    fn new_with_validation(mut reader: R, validate: bool) -> Result<Self, InterpolationError> {
+        let header = BinaryHeader::read(&mut reader)?;
+        if header.curve != E::CURVE_ID {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "File is for {} but the points are expected on {}",
+                header.curve.name(),
+                E::CURVE_ID.name()
+            )));
+        }
+        // A file with more points than this target can index is rejected up front rather than partly read.
+        size_from_u64(header.size)?;
+        let generator = match header.basis {
+            Basis::Monomial => None,
+            Basis::Lagrange => {
+                let mut bytes = vec![0u8; prime_field_size::<E::ScalarField>()];
+                reader.read_exact(&mut bytes)?;
+                Some(decode_prime_field(&bytes, header.endianness)?)
+            }
+        };
+        Ok(Self {
+            reader,
+            header,
+            generator,
+            remaining: header.size,
+            validate,
+            buffer: vec![0u8; header.point_size::<E>()],
+        })
+    }
+
+    /// The header of the file.
+    pub fn header(&self) -> &BinaryHeader {
+        &self.header
+    }
+
+    /// The generator of the domain of a Lagrange SRS.
+    pub fn generator(&self) -> Option<E::ScalarField> {
+        self.generator
+    }
+
+    /// Reads the next point, returning `None` once all the points in the header have been read.
+    pub fn read_point(&mut self) -> Result<Option<Affine<E>>, InterpolationError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut self.buffer)?;
+        self.remaining -= 1;
+        decode_point(&self.buffer, &self.header, self.validate).map(Some)
+    }
+
+    /// This function reads the next `count` points, or all the points that are left if there are fewer. The points
+    /// are decoded a chunk at a time on other threads while the next chunk is read, which is much faster than
+    /// reading them one by one for a large file.
+    pub fn read_points(&mut self, count: usize) -> Result<Vec<Affine<E>>, InterpolationError> {
+        let count = count.min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let (header, validate) = (self.header, self.validate);
+        let points = read_decoded(
+            &mut self.reader,
+            count,
+            self.buffer.len(),
+            DECODE_CHUNK_SIZE,
+            |bytes| decode_point(bytes, &header, validate),
+        );
+        // A failed read leaves the stream at an unknown point, so nothing more is read from it.
+        self.remaining = match points {
+            Ok(_) => self.remaining - count as u64,
+            Err(_) => 0,
+        };
+        points
+    }
+
+    /// This function reads all the points that are left, as [`SrsReader::read_points`] does.
+    pub fn read_remaining(&mut self) -> Result<Vec<Affine<E>>, InterpolationError> {
+        self.read_points(size_from_u64(self.remaining)?)
+    }
+}
+
+impl<R: Read, E: SrsCurve> Iterator for SrsReader<R, E> {
+    type Item = Result<Affine<E>, InterpolationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_point().transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+/// This function writes a monomial SRS.
+pub fn write_monomial<W: Write, E: SrsCurve>(
+    writer: W,
+    srs: &MonomialSrs<E>,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError> {
+    let header = BinaryHeader {
+        curve: E::CURVE_ID,
+        basis: Basis::Monomial,
+        ordering: PointOrdering::Natural,
+        encoding,
+        endianness,
+        size: srs.len() as u64,
+    };
+    let mut writer = SrsWriter::<W, E>::new(writer, header, None)?;
+    for point in srs.points() {
+        writer.write_point(point)?;
+    }
+    writer.finish()
+}
+
+/// This function writes a Lagrange SRS.
+pub fn write_lagrange<W: Write, E: SrsCurve>(
+    writer: W,
+    srs: &LagrangeSrs<E>,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError> {
+    let header = BinaryHeader {
+        curve: E::CURVE_ID,
+        basis: Basis::Lagrange,
+        ordering: srs.ordering(),
+        encoding,
+        endianness,
+        size: srs.len() as u64,
+    };
+    let mut writer = SrsWriter::<W, E>::new(writer, header, Some(srs.generator()))?;
+    for point in srs.points() {
+        writer.write_point(point)?;
+    }
+    writer.finish()
+}
+
+/// This function reads a monomial SRS, checking every point.
+pub fn read_monomial<R: Read, E: SrsCurve>(
+    reader: R,
+) -> Result<MonomialSrs<E>, InterpolationError> {
+    let mut reader = SrsReader::<R, E>::new(reader)?;
+    if reader.header().basis != Basis::Monomial {
+        return Err(InterpolationError::SerializationError(
+            "Expected a monomial SRS".to_string(),
+        ));
+    }
+    Ok(MonomialSrs::new(reader.read_remaining()?))
+}
+
+/// This function reads a Lagrange SRS, checking every point and that the generator matches the size of the domain.
+pub fn read_lagrange<R: Read, E: SrsCurve>(
+    reader: R,
+) -> Result<LagrangeSrs<E>, InterpolationError> {
+    let mut reader = SrsReader::<R, E>::new(reader)?;
+    let (ordering, generator) = match (reader.header().basis, reader.generator()) {
+        (Basis::Lagrange, Some(generator)) => (reader.header().ordering, generator),
+        _ => {
+            return Err(InterpolationError::SerializationError(
+                "Expected a Lagrange SRS".to_string(),
+            ))
+        }
+    };
+    let points = reader.read_remaining()?;
+    if !is_primitive_root(generator, points.len()) {
+        return Err(InterpolationError::SerializationError(
+            "The generator does not match the size of the domain".to_string(),
+        ));
+    }
+    Ok(LagrangeSrs::from_parts(points, generator, ordering))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, G1Affine};
+
+    #[test]
+    fn test_binary_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let srs = MonomialSrs::new(points.clone());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+        points[3] = G1Affine::identity();
+        let srs_with_identity = MonomialSrs::new(points);
+
+        for encoding in [PointEncoding::Compressed, PointEncoding::Uncompressed] {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let bytes =
+                    write_monomial(Vec::new(), &srs_with_identity, encoding, endianness).unwrap();
+                let point_size = if encoding == PointEncoding::Compressed {
+                    33
+                } else {
+                    65
+                };
+                assert_eq!(bytes.len(), HEADER_SIZE + 16 * point_size);
+                let read_srs = read_monomial::<_, BnConfig>(bytes.as_slice()).unwrap();
+                assert_eq!(read_srs, srs_with_identity);
+                assert!(read_lagrange::<_, BnConfig>(bytes.as_slice()).is_err());
+
+                let bytes =
+                    write_lagrange(Vec::new(), &lagrange_srs, encoding, endianness).unwrap();
+                assert_eq!(bytes.len(), HEADER_SIZE + 32 + 16 * point_size);
+                let read_srs = read_lagrange::<_, BnConfig>(bytes.as_slice()).unwrap();
+                assert_eq!(read_srs, lagrange_srs);
+
+                // Reading in parts gives the same points, and asking for more than are left stops at the end.
+                let mut reader = SrsReader::<_, BnConfig>::new(bytes.as_slice()).unwrap();
+                let mut parts = reader.read_points(5).unwrap();
+                parts.extend(reader.read_points(100).unwrap());
+                assert_eq!(parts, lagrange_srs.points());
+                assert!(reader.read_remaining().unwrap().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_layout() {
+        let srs = MonomialSrs::new(vec![G1Affine::generator()]);
+        let bytes = write_monomial(
+            Vec::new(),
+            &srs,
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        let mut expected = b"SRSI".to_vec();
+        expected.extend_from_slice(&[1, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1]);
+        expected.extend_from_slice(&[0; 31]);
+        expected.push(1);
+        expected.extend_from_slice(&[0; 31]);
+        expected.push(2);
+        assert_eq!(bytes, expected);
+
+        // A truncated file, an unknown version and an off-curve point are all rejected.
+        assert!(read_monomial::<_, BnConfig>(&bytes[..bytes.len() - 1]).is_err());
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 2;
+        assert!(read_monomial::<_, BnConfig>(bad_version.as_slice()).is_err());
+        let mut off_curve = bytes.clone();
+        off_curve[bytes.len() - 1] = 3;
+        assert!(read_monomial::<_, BnConfig>(off_curve.as_slice()).is_err());
+        let mut reader = SrsReader::<_, BnConfig>::new_unchecked(off_curve.as_slice()).unwrap();
+        assert!(reader.read_point().unwrap().is_some());
+        assert!(reader.read_point().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_binary_writer_counts_points() {
+        let header = BinaryHeader {
+            curve: CurveId::Bn254,
+            basis: Basis::Monomial,
+            ordering: PointOrdering::Natural,
+            encoding: PointEncoding::Compressed,
+            endianness: Endianness::Little,
+            size: 1,
+        };
+        let writer = SrsWriter::<_, BnConfig>::new(Vec::new(), header, None).unwrap();
+        assert!(writer.finish().is_err());
+        let mut writer = SrsWriter::<_, BnConfig>::new(Vec::new(), header, None).unwrap();
+        writer.write_point(&G1Affine::generator()).unwrap();
+        assert!(writer.write_point(&G1Affine::generator()).is_err());
+        assert!(SrsWriter::<_, BnConfig>::new(Vec::new(), header, Some(1u64.into())).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! This module reads fixed size items from a stream in chunks and decodes each chunk on another thread while the
+//! next one is read, splitting the decoding of a chunk over the rayon pool with the `parallel` feature. Decoding and
+//! checking points is far slower than reading their bytes, so the readers of the large formats go through this.
+use crate::{buffer::try_with_capacity, error::InterpolationError};
+use ark_std::{cfg_chunks, io::Read, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{sync::mpsc, thread};
+
+/// The number of points the readers decode at a time, which bounds the bytes held on top of the decoded points to
+/// two chunks.
+pub(crate) const DECODE_CHUNK_SIZE: usize = 1 << 14;
+
+/// This function reads `count` items of `item_size` bytes each from `reader`, `chunk_size` at a time, and decodes
+/// each item with `decode`. It returns the first error of `decode` if there is one and otherwise the first error of
+/// reading, so a file that is both corrupt and short reports the corruption.
+pub(crate) fn read_decoded<R, T, D>(
+    reader: &mut R,
+    count: usize,
+    item_size: usize,
+    chunk_size: usize,
+    decode: D,
+) -> Result<Vec<T>, InterpolationError>
+where
+    R: Read,
+    T: Send,
+    D: Fn(&[u8]) -> Result<T, InterpolationError> + Sync,
+{
+    count
+        .checked_mul(item_size)
+        .ok_or(InterpolationError::SizeError)?;
+    let decode = &decode;
+    thread::scope(|scope| {
+        // One chunk waits in the channel while the next is read, so at most two are held besides the one decoding.
+        let (sender, receiver) = mpsc::sync_channel::<Vec<u8>>(1);
+        let decoder = scope.spawn(move || {
+            let mut items = try_with_capacity(count)?;
+            for bytes in receiver {
+                let chunk = cfg_chunks!(bytes, item_size)
+                    .map(decode)
+                    .collect::<Result<Vec<_>, _>>()?;
+                items.extend(chunk);
+            }
+            Ok::<_, InterpolationError>(items)
+        });
+
+        let mut read = Ok(());
+        let mut remaining = count;
+        while remaining > 0 {
+            let len = remaining.min(chunk_size.max(1));
+            let mut bytes = vec![0u8; len * item_size];
+            if let Err(e) = reader.read_exact(&mut bytes) {
+                read = Err(e);
+                break;
+            }
+            // The decoder only hangs up after an error, which is returned below.
+            if sender.send(bytes).is_err() {
+                break;
+            }
+            remaining -= len;
+        }
+        drop(sender);
+
+        let items = decoder.join().map_err(|_| {
+            InterpolationError::TaskError("The decoding thread panicked".to_string())
+        })??;
+        read?;
+        Ok(items)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_u32(bytes: &[u8]) -> Result<u32, InterpolationError> {
+        let value = u32::from_le_bytes(bytes.try_into().unwrap());
+        match value {
+            13 => Err(InterpolationError::InvalidParameters("Unlucky".to_string())),
+            _ => Ok(value),
+        }
+    }
+
+    #[test]
+    fn test_read_decoded() {
+        let bytes = (0..10u32).flat_map(u32::to_le_bytes).collect::<Vec<_>>();
+        for chunk_size in [1, 3, 10, 100] {
+            let items = read_decoded(&mut bytes.as_slice(), 10, 4, chunk_size, decode_u32).unwrap();
+            assert_eq!(items, (0..10).collect::<Vec<_>>());
+        }
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            read_decoded(&mut reader, 4, 4, 3, decode_u32).unwrap(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(reader.len(), 24);
+        assert!(read_decoded(&mut bytes.as_slice(), 0, 4, 3, decode_u32)
+            .unwrap()
+            .is_empty());
+
+        // A short stream fails, and a decoding error comes before a read error.
+        assert!(matches!(
+            read_decoded(&mut bytes.as_slice(), 11, 4, 3, decode_u32),
+            Err(InterpolationError::SerializationError(_))
+        ));
+        let corrupt = (8..14u32).flat_map(u32::to_le_bytes).collect::<Vec<_>>();
+        assert!(matches!(
+            read_decoded(&mut corrupt.as_slice(), 8, 4, 2, decode_u32),
+            Err(InterpolationError::InvalidParameters(_))
+        ));
+    }
+}
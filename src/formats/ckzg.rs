@@ -0,0 +1,129 @@
+//! This module contains a reader for the `trusted_setup.txt` files shipped with c-kzg-4844.
+//!
+//! The file is text. It starts with the number of G1 points and the number of G2 points, followed by the G1 points
+//! in the Lagrange basis, the G2 points in the monomial basis and, in newer versions of the file, the G1 points in
+//! the monomial basis. Every point is a hex string of its compressed BLS12-381 encoding.
+use crate::error::InterpolationError;
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{cfg_into_iter, io::Read, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The contents of a c-kzg-4844 trusted setup file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CkzgTrustedSetup {
+    /// The G1 points in the Lagrange basis, in natural order.
+    pub g1_lagrange: Vec<G1Affine>,
+    /// The G2 points in the monomial basis.
+    pub g2_monomial: Vec<G2Affine>,
+    /// The G1 points in the monomial basis, which older versions of the file do not contain.
+    pub g1_monomial: Option<Vec<G1Affine>>,
+}
+
+impl CkzgTrustedSetup {
+    /// Reads a trusted setup file, checking every point is in the prime order subgroup.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut tokens = text.split_whitespace();
+
+        let mut read_count = || -> Result<usize, InterpolationError> {
+            tokens.next().and_then(|token| token.parse().ok()).ok_or(
+                InterpolationError::SerializationError(
+                    "Missing point count in trusted setup".to_string(),
+                ),
+            )
+        };
+        let g1_size = read_count()?;
+        let g2_size = read_count()?;
+
+        let g1_lagrange = read_points(&mut tokens, g1_size)?;
+        let g2_monomial = read_points(&mut tokens, g2_size)?;
+        let g1_monomial = match tokens.clone().next() {
+            Some(_) => Some(read_points(&mut tokens, g1_size)?),
+            None => None,
+        };
+        if tokens.next().is_some() {
+            return Err(InterpolationError::SerializationError(
+                "Unexpected data at the end of the trusted setup".to_string(),
+            ));
+        }
+        Ok(Self {
+            g1_lagrange,
+            g2_monomial,
+            g1_monomial,
+        })
+    }
+}
+
+/// This function decodes the next `size` hex encoded compressed points, in parallel with the `parallel` feature.
+fn read_points<'a, P, I>(tokens: &mut I, size: usize) -> Result<Vec<P>, InterpolationError>
+where
+    P: CanonicalDeserialize + Send,
+    I: Iterator<Item = &'a str>,
+{
+    let tokens = tokens.take(size).collect::<Vec<_>>();
+    if tokens.len() < size {
+        return Err(InterpolationError::SerializationError(
+            "Trusted setup has too few points".to_string(),
+        ));
+    }
+    cfg_into_iter!(tokens)
+        .map(|token| {
+            let bytes = decode_hex(token)?;
+            P::deserialize_compressed(bytes.as_slice())
+                .map_err(|e| InterpolationError::SerializationError(e.to_string()))
+        })
+        .collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, InterpolationError> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return Err(InterpolationError::SerializationError(
+            "Hex string has an odd number of digits".to_string(),
+        ));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or(InterpolationError::SerializationError(
+                    "Invalid hex digit".to_string(),
+                ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+
+    /// The first two Lagrange and monomial G1 points and the first G2 point of the c-kzg-4844 setup, with the
+    /// counts at the top of the file edited to match.
+    const FIXTURE: &str = include_str!("testdata/ckzg_trusted_setup_prefix.txt");
+
+    #[test]
+    fn test_read_trusted_setup() {
+        let setup = CkzgTrustedSetup::read(&mut FIXTURE.as_bytes()).unwrap();
+        assert_eq!(setup.g1_lagrange.len(), 2);
+        assert_eq!(setup.g2_monomial, vec![G2Affine::generator()]);
+        let g1_monomial = setup.g1_monomial.unwrap();
+        assert_eq!(g1_monomial[0], G1Affine::generator());
+
+        // Older files without the monomial points are accepted.
+        let lines = FIXTURE.lines().collect::<Vec<_>>();
+        let old = lines[..5].join("\n");
+        let setup = CkzgTrustedSetup::read(&mut old.as_bytes()).unwrap();
+        assert!(setup.g1_monomial.is_none());
+
+        // A truncated or padded file is rejected.
+        let truncated = lines[..6].join("\n");
+        assert!(CkzgTrustedSetup::read(&mut truncated.as_bytes()).is_err());
+        let padded = format!("{}\n{}", FIXTURE, lines[2]);
+        assert!(CkzgTrustedSetup::read(&mut padded.as_bytes()).is_err());
+    }
+}
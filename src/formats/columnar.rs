@@ -0,0 +1,184 @@
+//! This module contains writers for Parquet and Arrow IPC files of the points of an SRS, so auditors can load them
+//! into dataframes and query them.
+//!
+//! Each point is a row with its `index`, an `infinity` flag and its `x` and `y` coordinates as fixed size big endian
+//! binaries, which are zero for the identity. The metadata of the schema records the `curve` and the `basis`. The
+//! rows are written in batches of [`BATCH_SIZE`], so a large SRS is not copied into Arrow buffers all at once.
+use crate::{curves::SrsCurve, error::InterpolationError, formats::binary::Basis};
+use ark_ec::short_weierstrass::Affine;
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{io::Write, sync::Arc, vec::Vec};
+use arrow_array::{
+    builder::{BooleanBuilder, FixedSizeBinaryBuilder, UInt64Builder},
+    ArrayRef, RecordBatch,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use std::collections::HashMap;
+
+/// The number of rows in each record batch.
+pub const BATCH_SIZE: usize = 1 << 16;
+
+fn arrow_error(e: impl ToString) -> InterpolationError {
+    InterpolationError::SerializationError(e.to_string())
+}
+
+/// The size in bytes of a coordinate on the curve `E`.
+fn coordinate_size<E: SrsCurve>() -> usize
+where
+    E::BaseField: PrimeField,
+{
+    (E::BaseField::MODULUS_BIT_SIZE as usize).div_ceil(64) * 8
+}
+
+/// The schema of the points of an SRS on the curve `E` in the basis `basis`.
+pub fn points_schema<E: SrsCurve>(basis: Basis) -> SchemaRef
+where
+    E::BaseField: PrimeField,
+{
+    let coordinate = DataType::FixedSizeBinary(coordinate_size::<E>() as i32);
+    let metadata = HashMap::from([
+        ("curve".to_string(), E::CURVE_ID.name().to_string()),
+        ("basis".to_string(), format!("{:?}", basis).to_lowercase()),
+    ]);
+    Arc::new(
+        Schema::new(vec![
+            Field::new("index", DataType::UInt64, false),
+            Field::new("infinity", DataType::Boolean, false),
+            Field::new("x", coordinate.clone(), false),
+            Field::new("y", coordinate, false),
+        ])
+        .with_metadata(metadata),
+    )
+}
+
+/// This function builds the record batch of `points`, the first of which is point `first_index` of the SRS.
+pub fn points_to_record_batch<E: SrsCurve>(
+    schema: SchemaRef,
+    first_index: usize,
+    points: &[Affine<E>],
+) -> Result<RecordBatch, InterpolationError>
+where
+    E::BaseField: PrimeField,
+{
+    let size = coordinate_size::<E>();
+    let mut index = UInt64Builder::with_capacity(points.len());
+    let mut infinity = BooleanBuilder::with_capacity(points.len());
+    let mut xs = FixedSizeBinaryBuilder::with_capacity(points.len(), size as i32);
+    let mut ys = FixedSizeBinaryBuilder::with_capacity(points.len(), size as i32);
+    let zero = vec![0u8; size];
+    for (i, point) in points.iter().enumerate() {
+        index.append_value((first_index + i) as u64);
+        infinity.append_value(point.infinity);
+        for (builder, coordinate) in [(&mut xs, point.x), (&mut ys, point.y)] {
+            match point.infinity {
+                true => builder.append_value(&zero),
+                false => builder.append_value(&coordinate.into_bigint().to_bytes_be()[..]),
+            }
+            .map_err(arrow_error)?;
+        }
+    }
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(index.finish()),
+        Arc::new(infinity.finish()),
+        Arc::new(xs.finish()),
+        Arc::new(ys.finish()),
+    ];
+    RecordBatch::try_new(schema, columns).map_err(arrow_error)
+}
+
+/// This function writes `points`, an SRS in the basis `basis`, as a Parquet file, returning the writer.
+pub fn write_parquet<E, W>(
+    writer: W,
+    points: &[Affine<E>],
+    basis: Basis,
+) -> Result<W, InterpolationError>
+where
+    E: SrsCurve,
+    E::BaseField: PrimeField,
+    W: Write + Send,
+{
+    let schema = points_schema::<E>(basis);
+    let mut writer = ArrowWriter::try_new(writer, schema.clone(), None).map_err(arrow_error)?;
+    for (i, batch) in points.chunks(BATCH_SIZE).enumerate() {
+        let batch = points_to_record_batch(schema.clone(), i * BATCH_SIZE, batch)?;
+        writer.write(&batch).map_err(arrow_error)?;
+    }
+    writer.into_inner().map_err(arrow_error)
+}
+
+/// This function writes `points`, an SRS in the basis `basis`, as an Arrow IPC file, returning the writer.
+pub fn write_arrow_ipc<E, W>(
+    writer: W,
+    points: &[Affine<E>],
+    basis: Basis,
+) -> Result<W, InterpolationError>
+where
+    E: SrsCurve,
+    E::BaseField: PrimeField,
+    W: Write,
+{
+    let schema = points_schema::<E>(basis);
+    let mut writer = FileWriter::try_new(writer, &schema).map_err(arrow_error)?;
+    for (i, batch) in points.chunks(BATCH_SIZE).enumerate() {
+        let batch = points_to_record_batch(schema.clone(), i * BATCH_SIZE, batch)?;
+        writer.write(&batch).map_err(arrow_error)?;
+    }
+    writer.finish().map_err(arrow_error)?;
+    writer.into_inner().map_err(arrow_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fq};
+    use arrow_array::{cast::AsArray, types::UInt64Type};
+    use arrow_ipc::reader::FileReader;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::{fs::File, io::Cursor};
+
+    fn check_batch(schema: &Schema, batch: &RecordBatch, points: &[Affine<BnConfig>]) {
+        assert_eq!(schema.metadata()["curve"], "bn254");
+        assert_eq!(schema.metadata()["basis"], "lagrange");
+        assert_eq!(batch.num_rows(), points.len());
+        let index = batch.column(0).as_primitive::<UInt64Type>();
+        let infinity = batch.column(1).as_boolean();
+        let xs = batch.column(2).as_fixed_size_binary();
+        for (i, point) in points.iter().enumerate() {
+            assert_eq!(index.value(i), i as u64);
+            assert_eq!(infinity.value(i), point.infinity);
+            if !point.infinity {
+                assert_eq!(Fq::from_be_bytes_mod_order(xs.value(i)), point.x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_columnar_export() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        points[5] = Affine::identity();
+
+        let ipc = write_arrow_ipc(Vec::new(), &points, Basis::Lagrange).unwrap();
+        let reader = FileReader::try_new(Cursor::new(ipc), None).unwrap();
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        check_batch(&schema, &batches[0], &points);
+
+        let path = std::env::temp_dir().join(format!("srs-columnar-test-{}", std::process::id()));
+        write_parquet(File::create(&path).unwrap(), &points, Basis::Lagrange).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let schema = builder.schema().clone();
+        let batches = builder
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        check_batch(&schema, &batches[0], &points);
+    }
+}
@@ -0,0 +1,146 @@
+//! This module contains a reader for the phase1 files of Filecoin's powers of tau ceremony, which ran on the
+//! BLS12-381 fork of the `powersoftau` tool.
+//!
+//! Both the challenge and the response files of a round start with a 64 byte BLAKE2b hash, followed by the
+//! `2^(power + 1) - 1` tau G1 points, the `2^power` tau G2 points, the `2^power` alpha tau G1 points, the `2^power`
+//! beta tau G1 points and the beta G2 point. Challenge files store the points uncompressed and response files store
+//! them compressed, in both cases in the big endian encoding with flag bits used by Zcash, which is also the one
+//! arkworks uses for BLS12-381. A response file ends with the public key of its contribution.
+//!
+//! The fork keeps the layout of the original tool, so the files of Zcash's powers of tau ceremony, which Sapling's
+//! parameters were built on, are read the same way with [`Phase1Params::zcash`].
+use crate::{
+    error::InterpolationError,
+    formats::{
+        binary::PointEncoding,
+        chunked::{read_decoded, DECODE_CHUNK_SIZE},
+    },
+    utils::size_from_log,
+};
+use ark_bls12_381::G1Affine;
+use ark_ec::AffineRepr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::{io::Read, vec::Vec};
+
+/// The size in bytes of the hash at the start of a phase1 file.
+const HASH_SIZE: usize = 64;
+/// The power of every file of Zcash's powers of tau ceremony, which the original tool fixed at compile time.
+pub const ZCASH_POWER: u32 = 21;
+
+/// The parameters of a phase1 file, which the file itself does not record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase1Params {
+    /// The file holds the powers of tau for domains of size up to `2^power`.
+    pub power: u32,
+    /// How the points are encoded, which is [`PointEncoding::Uncompressed`] for challenge files and
+    /// [`PointEncoding::Compressed`] for response files.
+    pub encoding: PointEncoding,
+}
+
+impl Phase1Params {
+    /// The parameters of a file of Zcash's powers of tau ceremony with points encoded as `encoding`.
+    pub fn zcash(encoding: PointEncoding) -> Self {
+        Self {
+            power: ZCASH_POWER,
+            encoding,
+        }
+    }
+
+    /// The number of monomial G1 points in a domain of the largest size the file supports. It errors if that
+    /// number does not fit in a [`usize`] on this target.
+    pub fn max_size(&self) -> Result<usize, InterpolationError> {
+        size_from_log(self.power)
+    }
+}
+
+/// This function reads the first `size` tau G1 points `[1], [tau], ..., [tau^(size - 1)]` from a phase1 file with
+/// the parameters `params`, checking each point is in the prime order subgroup, and returns them along with the hash
+/// at the start of the file. Nothing after the requested points is read.
+pub fn read_phase1_tau_g1<R: Read>(
+    reader: &mut R,
+    params: Phase1Params,
+    size: usize,
+) -> Result<([u8; HASH_SIZE], Vec<G1Affine>), InterpolationError> {
+    // A file too large to index on this target still holds any size that fits.
+    let max_size = params.max_size().unwrap_or(usize::MAX);
+    if size > max_size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The phase1 file holds at most {} points but {} were requested",
+            max_size, size
+        )));
+    }
+    let mut hash = [0u8; HASH_SIZE];
+    reader.read_exact(&mut hash)?;
+    let compress = match params.encoding {
+        PointEncoding::Compressed => Compress::Yes,
+        PointEncoding::Uncompressed => Compress::No,
+    };
+    let point_size = G1Affine::zero().serialized_size(compress);
+    let points = read_decoded(reader, size, point_size, DECODE_CHUNK_SIZE, |bytes| {
+        G1Affine::deserialize_with_mode(bytes, compress, Validate::Yes)
+            .map_err(|e| InterpolationError::SerializationError(e.to_string()))
+    })?;
+    Ok((hash, points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Fr, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_serialize::CanonicalSerialize;
+
+    /// This function writes a phase1 file with `tau = 2` laid out as the `powersoftau` tool writes it, with the
+    /// alpha and beta points left out since nothing reads them.
+    fn phase1_for_testing(power: u32, compress: Compress) -> Vec<u8> {
+        let mut bytes = vec![7u8; HASH_SIZE];
+        let mut g1 = G1Affine::generator();
+        for _ in 0..(2usize << power) - 1 {
+            g1.serialize_with_mode(&mut bytes, compress).unwrap();
+            g1 = (g1 * Fr::from(2u64)).into_affine();
+        }
+        let mut g2 = G2Affine::generator();
+        for _ in 0..1usize << power {
+            g2.serialize_with_mode(&mut bytes, compress).unwrap();
+            g2 = (g2 * Fr::from(2u64)).into_affine();
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_phase1_tau_g1() {
+        for (encoding, compress, prefix) in [
+            (PointEncoding::Compressed, Compress::Yes, 0x97),
+            (PointEncoding::Uncompressed, Compress::No, 0x17),
+        ] {
+            let bytes = phase1_for_testing(2, compress);
+            // The generator in the Zcash encoding, with the compression flag set only when compressed.
+            assert_eq!((bytes[HASH_SIZE], bytes[HASH_SIZE + 1]), (prefix, 0xf1));
+
+            let params = Phase1Params { power: 2, encoding };
+            let (hash, points) = read_phase1_tau_g1(&mut bytes.as_slice(), params, 4).unwrap();
+            assert_eq!(hash, [7u8; HASH_SIZE]);
+            let mut expected = G1Affine::generator();
+            for point in points {
+                assert_eq!(point, expected);
+                expected = (expected * Fr::from(2u64)).into_affine();
+            }
+
+            // The file does not hold enough points for a domain of size 8.
+            assert!(read_phase1_tau_g1(&mut bytes.as_slice(), params, 8).is_err());
+
+            // Corrupting a coordinate is caught.
+            let mut corrupted = bytes.clone();
+            corrupted[HASH_SIZE + 20] ^= 1;
+            assert!(read_phase1_tau_g1(&mut corrupted.as_slice(), params, 4).is_err());
+        }
+
+        // Only the requested points are read, so a short file is enough to check the Zcash parameters.
+        let bytes = phase1_for_testing(2, Compress::Yes);
+        let params = Phase1Params::zcash(PointEncoding::Compressed);
+        assert_eq!(params.max_size().unwrap(), 1 << 21);
+        let (_, points) = read_phase1_tau_g1(&mut bytes.as_slice(), params, 4).unwrap();
+        assert_eq!(points[0], G1Affine::generator());
+        assert!(read_phase1_tau_g1(&mut bytes.as_slice(), params, (1 << 21) + 1).is_err());
+    }
+}
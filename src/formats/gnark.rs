@@ -0,0 +1,411 @@
+//! This module contains a reader and writer for gnark-crypto's BN254 `kzg.SRS` serialization format.
+//!
+//! gnark writes the proving key first, a big endian `u32` length followed by the monomial G1 points,
+//! and then the verifying key `[G2], [tau]G2, [G1]`. Points are big endian and the two most significant bits
+//! of the first byte are metadata flags. `WriteTo` uses compressed points and `WriteRawTo` uncompressed points,
+//! the reader accepts either, point by point, in the same way as gnark's decoder.
+//!
+//! Newer versions of gnark-crypto follow the verifying key with the pairing lines they precompute for its two G2
+//! points, [`GNARK_LINES`] elements of the quadratic extension written raw in either encoding. The reader accepts
+//! the verifying key with or without them and keeps them as read, so a file round trips byte for byte.
+use crate::error::InterpolationError;
+#[cfg(feature = "inspect")]
+use crate::formats::binary::PointEncoding;
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_std::{
+    io::{Read, Write},
+    vec::Vec,
+};
+
+/// The mask for the metadata bits of the first byte.
+const M_MASK: u8 = 0b11 << 6;
+/// Flag for an uncompressed point.
+const M_UNCOMPRESSED: u8 = 0b00 << 6;
+/// Flag for the point at infinity.
+const M_INFINITY: u8 = 0b01 << 6;
+/// Flag for a compressed point whose y-coordinate is the lexicographically smallest of the two options.
+const M_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+/// Flag for a compressed point whose y-coordinate is the lexicographically largest of the two options.
+const M_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+/// This function returns how a point whose first byte is `byte` is encoded, or `None` for the point at infinity,
+/// which gnark flags the same way in both encodings.
+#[cfg(feature = "inspect")]
+pub(crate) fn point_encoding(byte: u8) -> Option<PointEncoding> {
+    match byte & M_MASK {
+        M_UNCOMPRESSED => Some(PointEncoding::Uncompressed),
+        M_INFINITY => None,
+        _ => Some(PointEncoding::Compressed),
+    }
+}
+
+/// The number of extension field elements in the precomputed pairing lines of the newer verifying key: two
+/// coefficients for each of the two lines of the 66 steps of the Miller loop, for each of the two G2 points.
+pub const GNARK_LINES: usize = 2 * 2 * 66 * 2;
+
+/// The contents of a gnark-crypto `kzg.SRS` over BN254.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GnarkSrs {
+    /// The proving key, the monomial G1 points `[1], [tau], [tau^2], ...`.
+    pub g1: Vec<G1Affine>,
+    /// The verifying key G2 points `[1]` and `[tau]`.
+    pub g2: [G2Affine; 2],
+    /// The verifying key G1 generator.
+    pub vk_g1: G1Affine,
+    /// The [`GNARK_LINES`] coefficients of the precomputed pairing lines in the order gnark writes them, or `None`
+    /// for the older verifying key without them.
+    pub lines: Option<Vec<Fq2>>,
+}
+
+impl GnarkSrs {
+    /// This function builds a gnark SRS from the monomial G1 points and the G2 points `[1]` and `[tau]`, with the
+    /// older verifying key that has no precomputed lines.
+    pub fn from_srs(
+        g1: &[G1Affine],
+        g2: G2Affine,
+        tau_g2: G2Affine,
+    ) -> Result<Self, InterpolationError> {
+        let vk_g1 = *g1.first().ok_or(InterpolationError::SizeError)?;
+        Ok(Self {
+            g1: g1.to_vec(),
+            g2: [g2, tau_g2],
+            vk_g1,
+            lines: None,
+        })
+    }
+
+    /// Writes the SRS with compressed points, the same as gnark's `WriteTo`.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.write(writer, true)
+    }
+
+    /// Writes the SRS with uncompressed points, the same as gnark's `WriteRawTo`.
+    pub fn write_raw_to<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.write(writer, false)
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, compressed: bool) -> Result<(), InterpolationError> {
+        let len = u32::try_from(self.g1.len()).map_err(|_| {
+            InterpolationError::InvalidParameters(format!(
+                "gnark cannot encode {} points",
+                self.g1.len()
+            ))
+        })?;
+        writer.write_all(&len.to_be_bytes())?;
+        for point in self.g1.iter() {
+            write_point(writer, point, compressed)?;
+        }
+        write_point(writer, &self.g2[0], compressed)?;
+        write_point(writer, &self.g2[1], compressed)?;
+        write_point(writer, &self.vk_g1, compressed)?;
+        match &self.lines {
+            Some(lines) if lines.len() != GNARK_LINES => {
+                Err(InterpolationError::InvalidParameters(format!(
+                    "gnark writes {} coefficients of pairing lines, not {}",
+                    GNARK_LINES,
+                    lines.len()
+                )))
+            }
+            Some(lines) => lines.iter().try_for_each(|line| line.write_be(writer)),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads an SRS written by gnark's `WriteTo` or `WriteRawTo`, checking every point is in the prime order subgroup.
+    /// The precomputed lines are read if anything follows the verifying key, which then has to hold all of them.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut len_bytes = [0u8; 4];
+        reader.read_exact(&mut len_bytes)?;
+        let len = u32::from_be_bytes(len_bytes);
+        let g1 = (0..len)
+            .map(|_| read_point(reader))
+            .collect::<Result<Vec<G1Affine>, InterpolationError>>()?;
+        let g2 = [read_point(reader)?, read_point(reader)?];
+        let vk_g1 = read_point(reader)?;
+        let mut bytes = vec![0u8; Fq2::SIZE];
+        let lines = match reader.read(&mut bytes[..1])? {
+            0 => None,
+            _ => {
+                let mut lines = Vec::with_capacity(GNARK_LINES);
+                reader.read_exact(&mut bytes[1..])?;
+                lines.push(Fq2::read_be(&bytes)?);
+                for _ in 1..GNARK_LINES {
+                    reader.read_exact(&mut bytes)?;
+                    lines.push(Fq2::read_be(&bytes)?);
+                }
+                Some(lines)
+            }
+        };
+        Ok(Self {
+            g1,
+            g2,
+            vk_g1,
+            lines,
+        })
+    }
+}
+
+/// The base fields of the curves gnark serializes, encoded the same way as gnark-crypto.
+trait GnarkField: Field {
+    /// The byte length of an encoded field element.
+    const SIZE: usize;
+    /// Writes the field element as big endian bytes, for extension fields the highest coefficient comes first.
+    fn write_be<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError>;
+    /// Reads a field element written by [`GnarkField::write_be`].
+    fn read_be(bytes: &[u8]) -> Result<Self, InterpolationError>;
+}
+
+impl GnarkField for Fq {
+    const SIZE: usize = 32;
+
+    fn write_be<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        writer.write_all(&self.into_bigint().to_bytes_be())?;
+        Ok(())
+    }
+
+    fn read_be(bytes: &[u8]) -> Result<Self, InterpolationError> {
+        let mut repr = <Fq as PrimeField>::BigInt::default();
+        for (limb, chunk) in repr.0.iter_mut().zip(bytes.rchunks(8)) {
+            let mut limb_bytes = [0u8; 8];
+            limb_bytes[8 - chunk.len()..].copy_from_slice(chunk);
+            *limb = u64::from_be_bytes(limb_bytes);
+        }
+        Fq::from_bigint(repr).ok_or(InterpolationError::SerializationError(
+            "Field element is not reduced".to_string(),
+        ))
+    }
+}
+
+impl GnarkField for Fq2 {
+    const SIZE: usize = 2 * Fq::SIZE;
+
+    fn write_be<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.c1.write_be(writer)?;
+        self.c0.write_be(writer)
+    }
+
+    fn read_be(bytes: &[u8]) -> Result<Self, InterpolationError> {
+        let c1 = Fq::read_be(&bytes[..Fq::SIZE])?;
+        let c0 = Fq::read_be(&bytes[Fq::SIZE..])?;
+        Ok(Fq2::new(c0, c1))
+    }
+}
+
+/// This function writes a point with gnark's metadata flags. gnark's notion of the lexicographically largest
+/// y-coordinate agrees with the ordering arkworks defines on field elements.
+fn write_point<E, W>(
+    writer: &mut W,
+    point: &Affine<E>,
+    compressed: bool,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: GnarkField,
+    W: Write,
+{
+    let size = if compressed {
+        E::BaseField::SIZE
+    } else {
+        2 * E::BaseField::SIZE
+    };
+    let mut bytes = Vec::with_capacity(size);
+    match point.xy() {
+        // gnark's decoder treats the infinity flag as uncompressed, so it can't read back a compressed identity.
+        None if compressed => {
+            return Err(InterpolationError::InvalidParameters(
+                "gnark cannot decode a compressed point at infinity".to_string(),
+            ));
+        }
+        None => {
+            bytes.resize(size, 0);
+            bytes[0] = M_INFINITY;
+        }
+        Some((x, y)) => {
+            x.write_be(&mut bytes)?;
+            if compressed {
+                bytes[0] |= if *y > -*y {
+                    M_COMPRESSED_LARGEST
+                } else {
+                    M_COMPRESSED_SMALLEST
+                };
+            } else {
+                y.write_be(&mut bytes)?;
+                bytes[0] |= M_UNCOMPRESSED;
+            }
+        }
+    }
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// This function reads a point written by [`write_point`], the flags determine whether it is compressed.
+/// As in gnark, the point at infinity is always uncompressed.
+fn read_point<E, R>(reader: &mut R) -> Result<Affine<E>, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: GnarkField,
+    R: Read,
+{
+    let mut bytes = vec![0u8; E::BaseField::SIZE];
+    reader.read_exact(&mut bytes)?;
+    let flags = bytes[0] & M_MASK;
+    bytes[0] &= !M_MASK;
+
+    let point = match flags {
+        M_INFINITY => {
+            let mut y_bytes = vec![0u8; E::BaseField::SIZE];
+            reader.read_exact(&mut y_bytes)?;
+            if bytes.iter().chain(y_bytes.iter()).any(|b| *b != 0) {
+                return Err(InterpolationError::SerializationError(
+                    "Point at infinity has non-zero coordinates".to_string(),
+                ));
+            }
+            return Ok(Affine::<E>::identity());
+        }
+        M_UNCOMPRESSED => {
+            let x = E::BaseField::read_be(&bytes)?;
+            reader.read_exact(&mut bytes)?;
+            let y = E::BaseField::read_be(&bytes)?;
+            Affine::<E>::new_unchecked(x, y)
+        }
+        _ => {
+            let x = E::BaseField::read_be(&bytes)?;
+            let (smaller, larger) = Affine::<E>::get_ys_from_x_unchecked(x).ok_or(
+                InterpolationError::SerializationError(
+                    "Compressed point is not on the curve".to_string(),
+                ),
+            )?;
+            let y = if flags == M_COMPRESSED_LARGEST {
+                larger
+            } else {
+                smaller
+            };
+            Affine::<E>::new_unchecked(x, y)
+        }
+    };
+    if !(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()) {
+        return Err(InterpolationError::SerializationError(
+            "Point is not in the prime order subgroup".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, G2Projective};
+    use ark_ec::CurveGroup;
+    use ark_std::UniformRand;
+
+    /// A PLONK verifying key written by gnark's `WriteTo`, unmodified from `vk-artifacts/plonk_vk.bin` of the
+    /// `sp1-verifier` 6.9.0 crate, sha256 `5a093a2fcb46394f5cadfe55c44d4d572fad9cec7aeb38026b0278322ef07fac`. SP1 built
+    /// it at commit `b8387fa61e1361d90ee7de49f551863418ca0f08`, whose `go.mod` pins the gnark and gnark-crypto
+    /// versions. Its KZG verifying key is in the newer layout with the precomputed lines, which this key leaves zero.
+    const SP1_PLONK_VK: &[u8] = include_bytes!("testdata/sp1_plonk_vk.bin");
+    /// The offsets in [`SP1_PLONK_VK`] of the eight compressed G1 commitments to the permutation and selector
+    /// polynomials, of the one to the commitment selector after its count, and of the KZG verifying key, which
+    /// gnark's PLONK key writes as `[G1], [G2], [tau]G2` followed by the lines.
+    const COMMITMENTS: core::ops::Range<usize> = 112..368;
+    const QCP: core::ops::Range<usize> = 372..404;
+    const KZG_G1: core::ops::Range<usize> = 404..436;
+    const KZG_G2: core::ops::Range<usize> = 436..564;
+    const KZG_LINES: core::ops::Range<usize> = 564..564 + GNARK_LINES * 64;
+
+    /// This function re-encodes the compressed points in `bytes` and checks the encoding is unchanged.
+    fn check_reencodes<E>(bytes: &[u8]) -> Vec<Affine<E>>
+    where
+        E: SWCurveConfig,
+        E::BaseField: GnarkField,
+    {
+        let mut reader = bytes;
+        let points = (0..bytes.len() / E::BaseField::SIZE)
+            .map(|_| read_point::<E, _>(&mut reader).unwrap())
+            .collect::<Vec<_>>();
+        let mut encoded = Vec::new();
+        points
+            .iter()
+            .for_each(|point| write_point(&mut encoded, point, true).unwrap());
+        assert_eq!(encoded, bytes);
+        points
+    }
+
+    #[test]
+    fn test_gnark_fixture() {
+        // Decoding and re-encoding the points gnark wrote gives back its bytes, flags included.
+        let commitments = check_reencodes::<BnConfig>(&SP1_PLONK_VK[COMMITMENTS]);
+        assert_eq!(commitments.len(), 8);
+        check_reencodes::<BnConfig>(&SP1_PLONK_VK[QCP]);
+        let vk_g1 = check_reencodes::<BnConfig>(&SP1_PLONK_VK[KZG_G1])[0];
+        let g2 = check_reencodes::<ark_bn254::g2::Config>(&SP1_PLONK_VK[KZG_G2]);
+        assert!(SP1_PLONK_VK[KZG_LINES].iter().all(|byte| *byte == 0));
+
+        // Laid out as a kzg.SRS in the newer layout, which puts [G1] after the G2 points, the pieces read back and
+        // write out unchanged.
+        let mut file = 8u32.to_be_bytes().to_vec();
+        file.extend_from_slice(&SP1_PLONK_VK[COMMITMENTS]);
+        file.extend_from_slice(&SP1_PLONK_VK[KZG_G2]);
+        file.extend_from_slice(&SP1_PLONK_VK[KZG_G1]);
+        file.extend_from_slice(&SP1_PLONK_VK[KZG_LINES]);
+        let srs = GnarkSrs::read_from(&mut file.as_slice()).unwrap();
+        assert_eq!(srs.g1, commitments);
+        assert_eq!(srs.g2, [g2[0], g2[1]]);
+        assert_eq!(srs.vk_g1, vk_g1);
+        assert_eq!(srs.lines.as_ref().map(Vec::len), Some(GNARK_LINES));
+        let mut bytes = Vec::new();
+        srs.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, file);
+
+        // Without the lines it is the older layout, and truncated lines are an error.
+        let legacy = GnarkSrs::read_from(&mut &file[..4 + COMMITMENTS.len() + 160]).unwrap();
+        assert_eq!(legacy.lines, None);
+        assert!(GnarkSrs::read_from(&mut &file[..file.len() - 1]).is_err());
+        let mut bad = srs.clone();
+        bad.lines.as_mut().unwrap().pop();
+        assert!(bad.write_to(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_gnark_round_trip() {
+        let rng = &mut ark_std::test_rng();
+        let g1 = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let g2 = G2Projective::rand(rng).into_affine();
+        let tau_g2 = G2Projective::rand(rng).into_affine();
+        let srs = GnarkSrs::from_srs(&g1, g2, tau_g2).unwrap();
+
+        let mut compressed = Vec::new();
+        srs.write_to(&mut compressed).unwrap();
+        assert_eq!(compressed.len(), 4 + 16 * 32 + 2 * 64 + 32);
+        assert_eq!(
+            GnarkSrs::read_from(&mut compressed.as_slice()).unwrap(),
+            srs
+        );
+
+        let mut raw = Vec::new();
+        srs.write_raw_to(&mut raw).unwrap();
+        assert_eq!(raw.len(), 4 + 16 * 64 + 2 * 128 + 64);
+        assert_eq!(GnarkSrs::read_from(&mut raw.as_slice()).unwrap(), srs);
+
+        // The point at infinity can only be written uncompressed.
+        let mut srs_with_identity = srs.clone();
+        srs_with_identity.g1[5] = G1Affine::identity();
+        assert!(srs_with_identity.write_to(&mut Vec::new()).is_err());
+        let mut raw = Vec::new();
+        srs_with_identity.write_raw_to(&mut raw).unwrap();
+        assert_eq!(
+            GnarkSrs::read_from(&mut raw.as_slice()).unwrap(),
+            srs_with_identity
+        );
+
+        // Flipping the sign flag of a compressed point negates it.
+        compressed[4] ^= 0b0100_0000;
+        let read_srs = GnarkSrs::read_from(&mut compressed.as_slice()).unwrap();
+        assert_eq!(read_srs.g1[0], -srs.g1[0]);
+    }
+}
@@ -0,0 +1,408 @@
+//! This module contains a reader and writer for halo2's `ParamsKZG` serialization format over BN254.
+//!
+//! halo2 writes its parameters as `k` (a little endian `u32`), followed by the `2^k` monomial G1 points,
+//! the `2^k` Lagrange G1 points and finally the two G2 points `g2` and `s_g2`. Each point is written
+//! in one of the formats described by [`SerdeFormat`], the legacy `Params::write` uses [`SerdeFormat::RawBytes`].
+//!
+//! halo2curves uses `7` as the multiplicative generator of the BN254 scalar field whereas arkworks uses `5`,
+//! so the two libraries disagree on the `2^k`th root of unity. We interpolate with respect to halo2's root
+//! so that the Lagrange points we produce match halo2's point-for-point.
+use crate::{error::InterpolationError, srs_to_lagrange_with_generator, utils::size_from_log};
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInt, BigInteger, FftField, Field, PrimeField, Zero};
+use ark_std::{
+    io::{Read, Write},
+    vec::Vec,
+};
+
+/// The multiplicative generator halo2curves uses for the BN254 scalar field.
+const HALO2_GENERATOR: u64 = 7;
+
+/// The ways halo2 can encode a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerdeFormat {
+    /// Compressed points, the x-coordinate in canonical little endian form with the parity of y in the top bit.
+    Processed,
+    /// Uncompressed points with coordinates in Montgomery form, checked to be valid when read.
+    RawBytes,
+    /// Uncompressed points with coordinates in Montgomery form, not checked to be on the curve when read.
+    RawBytesUnchecked,
+}
+
+/// The contents of a halo2 `ParamsKZG<Bn256>` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Halo2Params {
+    /// The log of the number of G1 points.
+    pub k: u32,
+    /// The monomial G1 points `[1], [tau], ..., [tau^(2^k - 1)]`.
+    pub g: Vec<G1Affine>,
+    /// The commitments to the Lagrange basis over halo2's domain of size `2^k`.
+    pub g_lagrange: Vec<G1Affine>,
+    /// The G2 generator.
+    pub g2: G2Affine,
+    /// The G2 generator multiplied by `tau`.
+    pub s_g2: G2Affine,
+}
+
+impl Halo2Params {
+    /// This function builds halo2 parameters from a monomial SRS by computing the Lagrange points over halo2's domain.
+    /// It errors if the number of points is not a power of two.
+    pub fn from_srs(
+        g: &[G1Affine],
+        g2: G2Affine,
+        s_g2: G2Affine,
+    ) -> Result<Self, InterpolationError> {
+        if g.is_empty() || !g.len().is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let k = g.len().ilog2();
+        let g_lagrange = srs_to_lagrange_with_generator(g, halo2_root_of_unity(k)?)?;
+        Ok(Self {
+            k,
+            g: g.to_vec(),
+            g_lagrange,
+            g2,
+            s_g2,
+        })
+    }
+
+    /// Writes the parameters in the same way as halo2's `Params::write`.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.write_custom(writer, SerdeFormat::RawBytes)
+    }
+
+    /// Writes the parameters in the same way as halo2's `ParamsKZG::write_custom`.
+    pub fn write_custom<W: Write>(
+        &self,
+        writer: &mut W,
+        format: SerdeFormat,
+    ) -> Result<(), InterpolationError> {
+        let n = size_from_log(self.k)?;
+        if self.g.len() != n || self.g_lagrange.len() != n {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Expected {} monomial and Lagrange points, found {} and {}",
+                n,
+                self.g.len(),
+                self.g_lagrange.len()
+            )));
+        }
+        writer.write_all(&self.k.to_le_bytes())?;
+        for point in self.g.iter().chain(self.g_lagrange.iter()) {
+            write_point(writer, point, format)?;
+        }
+        write_point(writer, &self.g2, format)?;
+        write_point(writer, &self.s_g2, format)?;
+        Ok(())
+    }
+
+    /// Reads parameters written by halo2's `Params::write`.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        Self::read_custom(reader, SerdeFormat::RawBytes)
+    }
+
+    /// Reads parameters written by halo2's `ParamsKZG::write_custom`.
+    pub fn read_custom<R: Read>(
+        reader: &mut R,
+        format: SerdeFormat,
+    ) -> Result<Self, InterpolationError> {
+        let mut k_bytes = [0u8; 4];
+        reader.read_exact(&mut k_bytes)?;
+        let k = u32::from_le_bytes(k_bytes);
+        if k >= Fr::TWO_ADICITY || k >= usize::BITS {
+            return Err(InterpolationError::SerializationError(format!(
+                "Invalid value of k: {}",
+                k
+            )));
+        }
+        let n = 1usize << k;
+        let g = (0..n)
+            .map(|_| read_point(reader, format))
+            .collect::<Result<Vec<G1Affine>, InterpolationError>>()?;
+        let g_lagrange = (0..n)
+            .map(|_| read_point(reader, format))
+            .collect::<Result<Vec<G1Affine>, InterpolationError>>()?;
+        let g2 = read_point(reader, format)?;
+        let s_g2 = read_point(reader, format)?;
+        Ok(Self {
+            k,
+            g,
+            g_lagrange,
+            g2,
+            s_g2,
+        })
+    }
+}
+
+/// This function returns the `2^k`th root of unity halo2 uses for its evaluation domain.
+fn halo2_root_of_unity(k: u32) -> Result<Fr, InterpolationError> {
+    if k > Fr::TWO_ADICITY {
+        return Err(InterpolationError::SizeError);
+    }
+    let mut root = Fr::from(HALO2_GENERATOR).pow(Fr::TRACE);
+    for _ in k..Fr::TWO_ADICITY {
+        root.square_in_place();
+    }
+    Ok(root)
+}
+
+/// The base fields of the curves halo2 serializes, encoded the same way as halo2curves.
+trait Halo2Field: Field {
+    /// Writes the field element as little endian Montgomery form limbs.
+    fn write_raw<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError>;
+    /// Reads a field element written by [`Halo2Field::write_raw`].
+    fn read_raw<R: Read>(reader: &mut R) -> Result<Self, InterpolationError>;
+    /// Writes the field element in canonical little endian form.
+    fn write_canonical<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError>;
+    /// Reads a field element written by [`Halo2Field::write_canonical`].
+    fn read_canonical(bytes: &[u8]) -> Result<Self, InterpolationError>;
+    /// The byte length of a canonically encoded field element.
+    const SIZE: usize;
+    /// The parity halo2curves uses for the sign of a compressed point, the lowest bit of the first encoded byte.
+    fn sign(&self) -> u8;
+}
+
+impl Halo2Field for Fq {
+    fn write_raw<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        // The inner big integer of an arkworks field element is already in Montgomery form.
+        for limb in self.0 .0.iter() {
+            writer.write_all(&limb.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn read_raw<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut limbs = [0u64; 4];
+        for limb in limbs.iter_mut() {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            *limb = u64::from_le_bytes(bytes);
+        }
+        let repr = BigInt::new(limbs);
+        if repr >= Fq::MODULUS {
+            return Err(InterpolationError::SerializationError(
+                "Field element is not reduced".to_string(),
+            ));
+        }
+        Ok(Fq::new_unchecked(repr))
+    }
+
+    fn write_canonical<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        writer.write_all(&self.into_bigint().to_bytes_le())?;
+        Ok(())
+    }
+
+    fn read_canonical(bytes: &[u8]) -> Result<Self, InterpolationError> {
+        Fq::from_bigint(bigint_from_le_bytes(bytes)).ok_or(InterpolationError::SerializationError(
+            "Field element is not reduced".to_string(),
+        ))
+    }
+
+    const SIZE: usize = 32;
+
+    fn sign(&self) -> u8 {
+        (self.into_bigint().0[0] & 1) as u8
+    }
+}
+
+impl Halo2Field for Fq2 {
+    fn write_raw<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.c0.write_raw(writer)?;
+        self.c1.write_raw(writer)
+    }
+
+    fn read_raw<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let c0 = Fq::read_raw(reader)?;
+        let c1 = Fq::read_raw(reader)?;
+        Ok(Fq2::new(c0, c1))
+    }
+
+    fn write_canonical<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        self.c0.write_canonical(writer)?;
+        self.c1.write_canonical(writer)
+    }
+
+    fn read_canonical(bytes: &[u8]) -> Result<Self, InterpolationError> {
+        let c0 = Fq::read_canonical(&bytes[..Fq::SIZE])?;
+        let c1 = Fq::read_canonical(&bytes[Fq::SIZE..])?;
+        Ok(Fq2::new(c0, c1))
+    }
+
+    const SIZE: usize = 2 * Fq::SIZE;
+
+    fn sign(&self) -> u8 {
+        self.c0.sign()
+    }
+}
+
+/// This function converts little endian bytes to a big integer, the bytes must fit in four limbs.
+fn bigint_from_le_bytes(bytes: &[u8]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes[..chunk.len()].copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(limb_bytes);
+    }
+    BigInt::new(limbs)
+}
+
+/// This function writes a point in the given format. halo2curves represents the identity as `(0, 0)`.
+fn write_point<E, W>(
+    writer: &mut W,
+    point: &Affine<E>,
+    format: SerdeFormat,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: Halo2Field,
+    W: Write,
+{
+    let (x, y) = point
+        .xy()
+        .map(|(x, y)| (*x, *y))
+        .unwrap_or((E::BaseField::zero(), E::BaseField::zero()));
+    match format {
+        SerdeFormat::Processed => {
+            let mut bytes = Vec::with_capacity(E::BaseField::SIZE);
+            x.write_canonical(&mut bytes)?;
+            if !point.is_zero() {
+                bytes[E::BaseField::SIZE - 1] |= y.sign() << 7;
+            }
+            writer.write_all(&bytes)?;
+        }
+        SerdeFormat::RawBytes | SerdeFormat::RawBytesUnchecked => {
+            x.write_raw(writer)?;
+            y.write_raw(writer)?;
+        }
+    }
+    Ok(())
+}
+
+/// This function reads a point in the given format, checking it is in the prime order subgroup unless
+/// the format is [`SerdeFormat::RawBytesUnchecked`].
+fn read_point<E, R>(reader: &mut R, format: SerdeFormat) -> Result<Affine<E>, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: Halo2Field,
+    R: Read,
+{
+    let point = match format {
+        SerdeFormat::Processed => {
+            let mut bytes = vec![0u8; E::BaseField::SIZE];
+            reader.read_exact(&mut bytes)?;
+            let y_sign = bytes[E::BaseField::SIZE - 1] >> 7;
+            bytes[E::BaseField::SIZE - 1] &= 0b0111_1111;
+            let x = E::BaseField::read_canonical(&bytes)?;
+            if x.is_zero() && y_sign == 0 {
+                return Ok(Affine::<E>::identity());
+            }
+            let (y, neg_y) = Affine::<E>::get_ys_from_x_unchecked(x).ok_or(
+                InterpolationError::SerializationError(
+                    "Compressed point is not on the curve".to_string(),
+                ),
+            )?;
+            if y.sign() == y_sign {
+                Affine::<E>::new_unchecked(x, y)
+            } else {
+                Affine::<E>::new_unchecked(x, neg_y)
+            }
+        }
+        SerdeFormat::RawBytes | SerdeFormat::RawBytesUnchecked => {
+            let x = E::BaseField::read_raw(reader)?;
+            let y = E::BaseField::read_raw(reader)?;
+            if x.is_zero() && y.is_zero() {
+                return Ok(Affine::<E>::identity());
+            }
+            Affine::<E>::new_unchecked(x, y)
+        }
+    };
+    if format != SerdeFormat::RawBytesUnchecked
+        && !(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve())
+    {
+        return Err(InterpolationError::SerializationError(
+            "Point is not in the prime order subgroup".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, G1Projective, G2Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_std::{One, UniformRand};
+
+    fn params_for_testing(k: u32) -> Halo2Params {
+        let rng = &mut ark_std::test_rng();
+        let g = gen_srs_for_testing::<BnConfig, _>(rng, (1 << k) - 1).unwrap();
+        let g2 = G2Projective::rand(rng).into_affine();
+        let s_g2 = G2Projective::rand(rng).into_affine();
+        Halo2Params::from_srs(&g, g2, s_g2).unwrap()
+    }
+
+    #[test]
+    fn test_halo2_lagrange_domain() {
+        let params = params_for_testing(5);
+        let omega = halo2_root_of_unity(params.k).unwrap();
+        assert_eq!(omega.pow([1u64 << params.k]), Fr::one());
+        assert_ne!(omega.pow([1u64 << (params.k - 1)]), Fr::one());
+
+        // Since sum_i omega^i * L_i(X) = X the Lagrange points should recombine to [tau].
+        let scalars = (0..params.g_lagrange.len())
+            .map(|i| omega.pow([i as u64]).into_bigint())
+            .collect::<Vec<_>>();
+        let tau = G1Projective::msm_bigint(&params.g_lagrange, &scalars).into_affine();
+        assert_eq!(tau, params.g[1]);
+    }
+
+    #[test]
+    fn test_halo2_round_trip() {
+        let mut params = params_for_testing(4);
+        params.g_lagrange[3] = G1Affine::identity();
+        for format in [
+            SerdeFormat::Processed,
+            SerdeFormat::RawBytes,
+            SerdeFormat::RawBytesUnchecked,
+        ] {
+            let mut bytes = Vec::new();
+            params.write_custom(&mut bytes, format).unwrap();
+            let point_size = if format == SerdeFormat::Processed {
+                32
+            } else {
+                64
+            };
+            assert_eq!(bytes.len(), 4 + 2 * 16 * point_size + 2 * 2 * point_size);
+            let read_params = Halo2Params::read_custom(&mut bytes.as_slice(), format).unwrap();
+            assert_eq!(params, read_params);
+        }
+    }
+
+    #[test]
+    fn test_halo2_raw_layout() {
+        // The G1 generator (1, 2) in halo2curves' Montgomery form.
+        let expected = "9d0d8fc58d435dd33d0bc7f528eb780a2c4679786fa36e662fdf079ac1770a0e\
+                        3a1b1e8b1b87baa67b168eeb51d6f114588cf2f0de46ddcc5ebe0f3483ef141c";
+        let mut bytes = Vec::new();
+        write_point(&mut bytes, &G1Affine::generator(), SerdeFormat::RawBytes).unwrap();
+        let hex = bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        assert_eq!(hex, expected);
+
+        // An off-curve point is rejected unless the format is unchecked.
+        let mut bytes = Vec::new();
+        let bad_point = G1Affine::new_unchecked(Fq::one(), Fq::one());
+        write_point(&mut bytes, &bad_point, SerdeFormat::RawBytes).unwrap();
+        assert!(read_point::<BnConfig, _>(&mut bytes.as_slice(), SerdeFormat::RawBytes).is_err());
+        assert!(
+            read_point::<BnConfig, _>(&mut bytes.as_slice(), SerdeFormat::RawBytesUnchecked)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! This module describes an SRS file without reading its points, so a file of unknown origin can be checked before
+//! committing to a long conversion.
+//!
+//! The format is detected from the first four bytes: the magic bytes `SRSI` and `ptau` identify this crate's binary
+//! format and snarkjs files, ASCII digits begin a c-kzg-4844 trusted setup, and otherwise the bytes are a point
+//! count. halo2 writes a little endian `k` below 32 whereas gnark writes a big endian number of points, so the two
+//! are told apart by which reading is plausible. A gnark file of at least `2^24` points whose count is a multiple
+//! of `2^24` would read as halo2, but such a file would be over a terabyte.
+use crate::{
+    curves::CurveId,
+    error::InterpolationError,
+    formats::{
+        binary::{Basis, BinaryHeader, PointEncoding, HEADER_SIZE, MAGIC},
+        gnark, ptau,
+    },
+    srs::PointOrdering,
+};
+use ark_std::{io::Read, vec::Vec};
+use sha2::{Digest, Sha256};
+
+/// The bound on halo2's `k` below which the first four bytes are read as halo2 rather than gnark.
+const MAX_HALO2_K: u32 = 32;
+
+/// The most bytes read looking for the end of the two counts at the start of a c-kzg-4844 trusted setup.
+const MAX_CKZG_HEADER: usize = 64;
+
+/// The file formats [`inspect`] recognises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SrsFormat {
+    /// This crate's own binary format, see [`crate::formats::binary`].
+    Binary,
+    /// A snarkjs powers of tau file, see [`crate::formats::ptau`].
+    Ptau,
+    /// A gnark-crypto `kzg.SRS`, see [`crate::formats::gnark`].
+    Gnark,
+    /// A halo2 `ParamsKZG`, see [`crate::formats::halo2`].
+    Halo2,
+    /// A c-kzg-4844 trusted setup, see `crate::formats::ckzg`.
+    Ckzg,
+}
+
+/// A description of an SRS file, read from its header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrsInfo {
+    /// The format of the file.
+    pub format: SrsFormat,
+    /// The curve the points are on.
+    pub curve: CurveId,
+    /// The bases of the G1 lists in the file, in the order they are stored.
+    pub bases: Vec<Basis>,
+    /// The number of points in each G1 list. For a ptau file this is the size of the largest domain it supports.
+    pub size: u64,
+    /// The largest power of two no greater than `size`, which is the largest SRS the file can be converted to, or
+    /// `0` for an empty file.
+    pub domain_size: u64,
+    /// The order of the Lagrange points, or `None` if the file has none.
+    pub ordering: Option<PointOrdering>,
+    /// How the points are encoded, or `None` if that cannot be told from the header and first point.
+    pub encoding: Option<PointEncoding>,
+    /// The SHA-256 digest of the header bytes, which identifies the file's layout but not its points.
+    pub header_sha256: [u8; 32],
+}
+
+impl SrsInfo {
+    fn new(
+        format: SrsFormat,
+        curve: CurveId,
+        bases: Vec<Basis>,
+        size: u64,
+        ordering: Option<PointOrdering>,
+        encoding: Option<PointEncoding>,
+        header: &[u8],
+    ) -> Self {
+        let domain_size = match size {
+            0 => 0,
+            size => 1 << size.ilog2(),
+        };
+        Self {
+            format,
+            curve,
+            bases,
+            size,
+            domain_size,
+            ordering,
+            encoding,
+            header_sha256: Sha256::digest(header).into(),
+        }
+    }
+}
+
+/// This function detects the format of the SRS file in `reader` and describes it, reading only the header and at
+/// most the first byte of the first point.
+pub fn inspect<R: Read>(reader: &mut R) -> Result<SrsInfo, InterpolationError> {
+    let mut prefix = [0u8; 4];
+    reader.read_exact(&mut prefix)?;
+
+    if prefix == MAGIC {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[..4].copy_from_slice(&prefix);
+        reader.read_exact(&mut bytes[4..])?;
+        let header = BinaryHeader::read(&mut bytes.as_slice())?;
+        let ordering = match header.basis {
+            Basis::Monomial => None,
+            Basis::Lagrange => Some(header.ordering),
+        };
+        return Ok(SrsInfo::new(
+            SrsFormat::Binary,
+            header.curve,
+            vec![header.basis],
+            header.size,
+            ordering,
+            Some(header.encoding),
+            &bytes,
+        ));
+    }
+
+    if &prefix == b"ptau" {
+        let (header, bytes) = ptau::read_header_section(&mut prefix.as_slice().chain(reader))?;
+        if header.power >= u64::BITS {
+            return Err(InterpolationError::SerializationError(format!(
+                "Invalid ptau power {}",
+                header.power
+            )));
+        }
+        return Ok(SrsInfo::new(
+            SrsFormat::Ptau,
+            CurveId::Bn254,
+            vec![Basis::Monomial],
+            1 << header.power,
+            None,
+            Some(PointEncoding::Uncompressed),
+            &bytes,
+        ));
+    }
+
+    // A trusted setup with fewer than a thousand points has its second count within the first four bytes.
+    if prefix[0].is_ascii_digit()
+        && prefix
+            .iter()
+            .all(|byte| byte.is_ascii_digit() || byte.is_ascii_whitespace())
+    {
+        return inspect_ckzg(prefix.as_slice().chain(reader));
+    }
+
+    let k = u32::from_le_bytes(prefix);
+    if k < MAX_HALO2_K {
+        // Each point is either 32 bytes compressed or 64 bytes in Montgomery form, which the first byte cannot tell.
+        return Ok(SrsInfo::new(
+            SrsFormat::Halo2,
+            CurveId::Bn254,
+            vec![Basis::Monomial, Basis::Lagrange],
+            1 << k,
+            Some(PointOrdering::Natural),
+            None,
+            &prefix,
+        ));
+    }
+
+    let len = u32::from_be_bytes(prefix);
+    let mut flags = [0u8; 1];
+    reader.read_exact(&mut flags)?;
+    Ok(SrsInfo::new(
+        SrsFormat::Gnark,
+        CurveId::Bn254,
+        vec![Basis::Monomial],
+        len.into(),
+        None,
+        gnark::point_encoding(flags[0]),
+        &prefix,
+    ))
+}
+
+/// This function describes a c-kzg-4844 trusted setup from the two point counts at its start.
+fn inspect_ckzg<R: Read>(mut reader: R) -> Result<SrsInfo, InterpolationError> {
+    // The counts are each on their own line, so the header ends at the second newline. Reading a byte at a time
+    // stops there rather than buffering the points, and the counts are short so a long header is malformed.
+    let mut header = Vec::new();
+    let mut newlines = 0;
+    let mut byte = [0u8; 1];
+    while newlines < 2 && header.len() < MAX_CKZG_HEADER {
+        if reader.read(&mut byte)? == 0 {
+            break;
+        }
+        header.push(byte[0]);
+        if byte[0] == b'\n' {
+            newlines += 1;
+        }
+    }
+    let text = core::str::from_utf8(&header).map_err(|_| {
+        InterpolationError::SerializationError("Trusted setup header is not text".to_string())
+    })?;
+    let mut counts = text.split_whitespace().map(str::parse::<u64>);
+    let size = match (counts.next(), counts.next()) {
+        (Some(Ok(size)), Some(Ok(_))) => size,
+        _ => {
+            return Err(InterpolationError::SerializationError(
+                "Missing point count in trusted setup".to_string(),
+            ))
+        }
+    };
+    Ok(SrsInfo::new(
+        SrsFormat::Ckzg,
+        CurveId::Bls12_381,
+        vec![Basis::Lagrange],
+        size,
+        Some(PointOrdering::Natural),
+        Some(PointEncoding::Compressed),
+        text.trim_end().as_bytes(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::{
+            binary::{write_lagrange, write_monomial, Endianness},
+            gnark::GnarkSrs,
+            halo2::Halo2Params,
+        },
+        srs::MonomialSrs,
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::{g1::Config as BnConfig, G2Affine};
+    use ark_ec::AffineRepr;
+
+    #[test]
+    fn test_inspect_binary() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::<BnConfig>::new(gen_srs_for_testing(rng, 7).unwrap());
+        let bytes = write_monomial(
+            Vec::new(),
+            &srs,
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.format, SrsFormat::Binary);
+        assert_eq!(info.curve, CurveId::Bn254);
+        assert_eq!(info.bases, vec![Basis::Monomial]);
+        assert_eq!((info.size, info.domain_size), (8, 8));
+        assert_eq!(info.ordering, None);
+        assert_eq!(info.encoding, Some(PointEncoding::Uncompressed));
+        let header_sha256: [u8; 32] = Sha256::digest(&bytes[..HEADER_SIZE]).into();
+        assert_eq!(info.header_sha256, header_sha256);
+
+        let lagrange = srs
+            .to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        let bytes = write_lagrange(
+            Vec::new(),
+            &lagrange,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.bases, vec![Basis::Lagrange]);
+        assert_eq!(info.ordering, Some(PointOrdering::BitReversed));
+        assert_eq!(info.encoding, Some(PointEncoding::Compressed));
+    }
+
+    #[test]
+    fn test_inspect_ptau() {
+        let hex = include_str!("testdata/bn254_power_2.ptau.hex")
+            .split_whitespace()
+            .collect::<String>();
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.format, SrsFormat::Ptau);
+        assert_eq!(info.curve, CurveId::Bn254);
+        assert_eq!((info.size, info.domain_size), (4, 4));
+        assert_eq!(info.encoding, Some(PointEncoding::Uncompressed));
+
+        assert!(inspect(&mut &b"ptau"[..]).is_err());
+    }
+
+    #[test]
+    fn test_inspect_gnark() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 5).unwrap();
+        let srs =
+            GnarkSrs::from_srs(&points, G2Affine::generator(), G2Affine::generator()).unwrap();
+
+        let mut bytes = Vec::new();
+        srs.write_to(&mut bytes).unwrap();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.format, SrsFormat::Gnark);
+        assert_eq!((info.size, info.domain_size), (6, 4));
+        assert_eq!(info.encoding, Some(PointEncoding::Compressed));
+
+        let mut bytes = Vec::new();
+        srs.write_raw_to(&mut bytes).unwrap();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.encoding, Some(PointEncoding::Uncompressed));
+    }
+
+    #[test]
+    fn test_inspect_halo2() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let params =
+            Halo2Params::from_srs(&points, G2Affine::generator(), G2Affine::generator()).unwrap();
+        let mut bytes = Vec::new();
+        params.write(&mut bytes).unwrap();
+        let info = inspect(&mut bytes.as_slice()).unwrap();
+        assert_eq!(info.format, SrsFormat::Halo2);
+        assert_eq!(info.bases, vec![Basis::Monomial, Basis::Lagrange]);
+        assert_eq!((info.size, info.domain_size), (16, 16));
+        assert_eq!(info.ordering, Some(PointOrdering::Natural));
+        assert_eq!(info.encoding, None);
+    }
+
+    #[test]
+    fn test_inspect_ckzg() {
+        let text = "4096\n65\na0b1\n";
+        let info = inspect(&mut text.as_bytes()).unwrap();
+        assert_eq!(info.format, SrsFormat::Ckzg);
+        assert_eq!(info.curve, CurveId::Bls12_381);
+        assert_eq!((info.size, info.domain_size), (4096, 4096));
+        let header_sha256: [u8; 32] = Sha256::digest(b"4096\n65").into();
+        assert_eq!(info.header_sha256, header_sha256);
+
+        let info = inspect(&mut "8\n2\na0b1\n".as_bytes()).unwrap();
+        assert_eq!((info.format, info.size), (SrsFormat::Ckzg, 8));
+
+        assert!(inspect(&mut "4096".as_bytes()).is_err());
+    }
+}
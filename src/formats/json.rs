@@ -0,0 +1,199 @@
+//! This module contains a writer and a reader for a JSON export of an SRS, for browser verifiers and dashboards that
+//! have a JSON parser but no binary one.
+//!
+//! The document is an object with a `header` describing the SRS and a `points` array of the hex strings of the
+//! compressed points, in the same encoding the `serde` implementations of the SRS types use:
+//!
+//! ```json
+//! {"header":{"curve":"bn254","basis":"lagrange","size":2,"ordering":"natural","generator":"..."},"points":["...","..."]}
+//! ```
+//!
+//! The `ordering` and `generator` of the domain are only present for an SRS in the Lagrange basis.
+use crate::{
+    curves::SrsCurve,
+    error::InterpolationError,
+    formats::binary::Basis,
+    serde_impls::{from_hex, to_hex},
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+};
+use ark_ec::short_weierstrass::Affine;
+use ark_serialize::Valid;
+use ark_std::{
+    io::{Read, Write},
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+
+/// The metadata at the start of a JSON export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonHeader {
+    /// The name of the curve, as given by [`crate::curves::CurveId::name`].
+    pub curve: String,
+    /// The basis of the points.
+    pub basis: Basis,
+    /// The number of points.
+    pub size: usize,
+    /// The order of the points of an SRS in the Lagrange basis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ordering: Option<PointOrdering>,
+    /// The hex string of the generator of the domain of an SRS in the Lagrange basis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSrs {
+    header: JsonHeader,
+    points: Vec<String>,
+}
+
+fn json_error(e: serde_json::Error) -> InterpolationError {
+    InterpolationError::SerializationError(e.to_string())
+}
+
+fn write_json<E: SrsCurve, W: Write>(
+    mut writer: W,
+    header: JsonHeader,
+    points: &[Affine<E>],
+) -> Result<W, InterpolationError> {
+    let document = JsonSrs {
+        header,
+        points: points.iter().map(to_hex).collect(),
+    };
+    serde_json::to_writer(&mut writer, &document).map_err(json_error)?;
+    Ok(writer)
+}
+
+/// This function reads a JSON export, checking it is of an SRS on the curve `E` in the basis `basis` and decoding
+/// its points.
+fn read_json<E: SrsCurve, R: Read>(
+    reader: R,
+    basis: Basis,
+) -> Result<(JsonHeader, Vec<Affine<E>>), InterpolationError> {
+    let document: JsonSrs = serde_json::from_reader(reader).map_err(json_error)?;
+    let header = document.header;
+    if header.curve != E::CURVE_ID.name() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Expected an SRS on {} but the export is on {}",
+            E::CURVE_ID.name(),
+            header.curve
+        )));
+    }
+    if header.basis != basis {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Expected an SRS in the {:?} basis but the export is in the {:?} basis",
+            basis, header.basis
+        )));
+    }
+    if header.size != document.points.len() {
+        return Err(InterpolationError::SerializationError(format!(
+            "The header gives {} points but the export has {}",
+            header.size,
+            document.points.len()
+        )));
+    }
+    let points = document
+        .points
+        .iter()
+        .map(|point| from_hex(point).map_err(json_error))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((header, points))
+}
+
+/// This function writes `srs` as a JSON export, returning the writer.
+pub fn write_json_monomial<E: SrsCurve, W: Write>(
+    writer: W,
+    srs: &MonomialSrs<E>,
+) -> Result<W, InterpolationError> {
+    let header = JsonHeader {
+        curve: E::CURVE_ID.name().to_string(),
+        basis: Basis::Monomial,
+        size: srs.len(),
+        ordering: None,
+        generator: None,
+    };
+    write_json(writer, header, srs.points())
+}
+
+/// This function writes `srs` as a JSON export, returning the writer.
+pub fn write_json_lagrange<E: SrsCurve, W: Write>(
+    writer: W,
+    srs: &LagrangeSrs<E>,
+) -> Result<W, InterpolationError> {
+    let header = JsonHeader {
+        curve: E::CURVE_ID.name().to_string(),
+        basis: Basis::Lagrange,
+        size: srs.points().len(),
+        ordering: Some(srs.ordering()),
+        generator: Some(to_hex(&srs.generator())),
+    };
+    write_json(writer, header, srs.points())
+}
+
+/// This function reads a JSON export of a monomial SRS on the curve `E`.
+pub fn read_json_monomial<E: SrsCurve, R: Read>(
+    reader: R,
+) -> Result<MonomialSrs<E>, InterpolationError> {
+    let (_, points) = read_json::<E, _>(reader, Basis::Monomial)?;
+    Ok(MonomialSrs::new(points))
+}
+
+/// This function reads a JSON export of a Lagrange SRS on the curve `E`, checking its generator matches its size.
+pub fn read_json_lagrange<E: SrsCurve, R: Read>(
+    reader: R,
+) -> Result<LagrangeSrs<E>, InterpolationError> {
+    let (header, points) = read_json::<E, _>(reader, Basis::Lagrange)?;
+    let missing = |field: &str| {
+        InterpolationError::SerializationError(format!(
+            "The export of a Lagrange SRS has no {}",
+            field
+        ))
+    };
+    let generator =
+        from_hex(&header.generator.ok_or_else(|| missing("generator"))?).map_err(json_error)?;
+    let ordering = header.ordering.ok_or_else(|| missing("ordering"))?;
+    let srs = LagrangeSrs::from_parts(points, generator, ordering);
+    srs.check()
+        .map_err(|e| InterpolationError::SerializationError(e.to_string()))?;
+    Ok(srs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_json_export() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap());
+        let lagrange = srs.to_lagrange().unwrap();
+
+        let json = write_json_monomial(Vec::new(), &srs).unwrap();
+        assert_eq!(
+            read_json_monomial::<BnConfig, _>(json.as_slice()).unwrap(),
+            srs
+        );
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(
+            value["header"],
+            serde_json::json!({"curve": "bn254", "basis": "monomial", "size": 8})
+        );
+        // A monomial export is not read as a Lagrange one.
+        assert!(read_json_lagrange::<BnConfig, _>(json.as_slice()).is_err());
+
+        let json = write_json_lagrange(Vec::new(), &lagrange).unwrap();
+        assert_eq!(
+            read_json_lagrange::<BnConfig, _>(json.as_slice()).unwrap(),
+            lagrange
+        );
+        let mut value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(value["header"]["ordering"], "natural");
+
+        // A header that disagrees with the points is caught.
+        value["header"]["size"] = 4.into();
+        let json = serde_json::to_vec(&value).unwrap();
+        assert!(read_json_lagrange::<BnConfig, _>(json.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! This module contains readers and writers for the SRS file formats used by other libraries.
+pub mod binary;
+mod chunked;
+#[cfg(feature = "bls12-381")]
+pub mod ckzg;
+#[cfg(feature = "parquet")]
+pub mod columnar;
+#[cfg(feature = "bls12-381")]
+pub mod filecoin;
+pub mod gnark;
+pub mod halo2;
+#[cfg(feature = "inspect")]
+pub mod inspect;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod ptau;
+pub mod rapidsnark;
+#[cfg(feature = "manifest")]
+pub mod shard;
+pub mod solidity;
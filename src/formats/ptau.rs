@@ -0,0 +1,547 @@
+//! This module contains a reader for the BN254 powers of tau files written by snarkjs, such as the Hermez
+//! `powersOfTau28_hez_final_*.ptau` setups.
+//!
+//! A ptau file starts with the magic `ptau`, a little endian `u32` version and a `u32` section count. Each section is
+//! a `u32` type and a `u64` length followed by its contents. Section 1 is the header, holding the size in bytes of a
+//! base field element, the base field modulus, the power of the file and the power of the ceremony. Section 2 holds
+//! the `2^(power + 1) - 1` tau G1 points, each stored as its two coordinates in little endian Montgomery form.
+//!
+//! Sections 3, 4 and 5 hold the `2^power` tau G2, alpha tau G1 and beta tau G1 points, a G2 point stored as the
+//! two halves of each coordinate in turn.
+//!
+//! `snarkjs powersoftau prepare phase2` adds sections 12 to 15, the points of sections 2 to 5 in the Lagrange basis
+//! of every domain the file supports, and the zkeys snarkjs sets up for circom circuits take their Lagrange points
+//! from them. This module writes those sections from this crate's conversion, so they can be spliced into a ptau
+//! file in place of the slow JavaScript ones.
+use crate::{
+    buffer::try_with_capacity,
+    dispatch::{srs_to_lagrange_with_algorithm, Algorithm},
+    error::InterpolationError,
+    formats::chunked::{read_decoded, DECODE_CHUNK_SIZE},
+    srs::PointOrdering,
+    srs_to_lagrange,
+    utils::size_from_log,
+};
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use ark_serialize::Valid;
+use ark_std::{
+    io::{self, Read, Write},
+    vec::Vec,
+    Zero,
+};
+
+/// The magic bytes at the start of a ptau file.
+const MAGIC: &[u8; 4] = b"ptau";
+/// The type of the header section.
+const HEADER_SECTION: u32 = 1;
+/// The type of the tau G1 section.
+const TAU_G1_SECTION: u32 = 2;
+/// The type of the tau G2 section.
+const TAU_G2_SECTION: u32 = 3;
+/// The type of the alpha tau G1 section.
+const ALPHA_TAU_G1_SECTION: u32 = 4;
+/// The type of the beta tau G1 section.
+const BETA_TAU_G1_SECTION: u32 = 5;
+/// The type of the Lagrange tau G1 section.
+pub const LAGRANGE_G1_SECTION: u32 = 12;
+/// The type of the Lagrange tau G2 section.
+pub const LAGRANGE_G2_SECTION: u32 = 13;
+/// The type of the Lagrange alpha tau G1 section.
+pub const LAGRANGE_ALPHA_G1_SECTION: u32 = 14;
+/// The type of the Lagrange beta tau G1 section.
+pub const LAGRANGE_BETA_G1_SECTION: u32 = 15;
+/// The size in bytes of a BN254 base field element.
+const FIELD_SIZE: usize = 32;
+/// The size in bytes of a G1 point.
+const G1_SIZE: usize = 2 * FIELD_SIZE;
+/// The size in bytes of a G2 point.
+const G2_SIZE: usize = 4 * FIELD_SIZE;
+
+/// The contents of the header section of a ptau file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtauHeader {
+    /// The file holds the powers of tau for domains of size up to `2^power`.
+    pub power: u32,
+    /// The power of the ceremony the file was produced by.
+    pub ceremony_power: u32,
+}
+
+/// The points of sections 2 to 5 of a ptau file, which its Lagrange sections are converted from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PtauPowers {
+    /// The tau G1 points `[1], [tau], [tau^2], ...`.
+    pub tau_g1: Vec<G1Affine>,
+    /// The tau G2 points.
+    pub tau_g2: Vec<G2Affine>,
+    /// The tau G1 points multiplied by alpha.
+    pub alpha_tau_g1: Vec<G1Affine>,
+    /// The tau G1 points multiplied by beta.
+    pub beta_tau_g1: Vec<G1Affine>,
+}
+
+impl PtauHeader {
+    /// The number of monomial G1 points in a domain of the largest size the file supports. It errors if that
+    /// number does not fit in a [`usize`] on this target.
+    pub fn max_size(&self) -> Result<usize, InterpolationError> {
+        size_from_log(self.power)
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, InterpolationError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, InterpolationError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// This function reads a base field element stored as little endian Montgomery limbs, which is also the layout of
+/// the inner big integer of an arkworks field element.
+fn read_montgomery<R: Read>(reader: &mut R) -> Result<Fq, InterpolationError> {
+    let mut limbs = [0u64; 4];
+    for limb in limbs.iter_mut() {
+        *limb = read_u64(reader)?;
+    }
+    let repr = BigInt::new(limbs);
+    if repr >= Fq::MODULUS {
+        return Err(InterpolationError::SerializationError(
+            "Field element is not reduced".to_string(),
+        ));
+    }
+    Ok(Fq::new_unchecked(repr))
+}
+
+/// This function reads a point stored as its two coordinates in little endian Montgomery form, checking it is on the
+/// curve, with two zeros as the identity.
+fn read_point<R: Read>(reader: &mut R) -> Result<G1Affine, InterpolationError> {
+    let x = read_montgomery(reader)?;
+    let y = read_montgomery(reader)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    point.check().map_err(|_| {
+        InterpolationError::SerializationError("ptau point is not on the curve".to_string())
+    })?;
+    Ok(point)
+}
+
+/// This function reads a G2 point stored as the two halves of each coordinate in little endian Montgomery form,
+/// checking it is in the prime order subgroup, with four zeros as the identity.
+fn read_g2_point<R: Read>(reader: &mut R) -> Result<G2Affine, InterpolationError> {
+    let x = Fq2::new(read_montgomery(reader)?, read_montgomery(reader)?);
+    let y = Fq2::new(read_montgomery(reader)?, read_montgomery(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    point.check().map_err(|_| {
+        InterpolationError::SerializationError("ptau point is not on the curve".to_string())
+    })?;
+    Ok(point)
+}
+
+fn write_montgomery<W: Write>(writer: &mut W, element: Fq) -> Result<(), InterpolationError> {
+    for limb in element.0 .0 {
+        writer.write_all(&limb.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// This function writes a point as its two coordinates in little endian Montgomery form, with the identity as two
+/// zeros.
+fn write_point<W: Write>(writer: &mut W, point: &G1Affine) -> Result<(), InterpolationError> {
+    let (x, y) = match point.infinity {
+        true => (Fq::zero(), Fq::zero()),
+        false => (point.x, point.y),
+    };
+    write_montgomery(writer, x)?;
+    write_montgomery(writer, y)
+}
+
+/// This function writes a G2 point as the two halves of each coordinate in little endian Montgomery form, with the
+/// identity as four zeros.
+fn write_g2_point<W: Write>(writer: &mut W, point: &G2Affine) -> Result<(), InterpolationError> {
+    let (x, y) = match point.infinity {
+        true => (Fq2::zero(), Fq2::zero()),
+        false => (point.x, point.y),
+    };
+    for element in [x.c0, x.c1, y.c0, y.c1] {
+        write_montgomery(writer, element)?;
+    }
+    Ok(())
+}
+
+/// This function writes the section `section` holding the Lagrange bases of the domains of size
+/// `1, 2, 4, ..., points.len()` one after another, each in natural order and converted from the start of `points`,
+/// which is a power of two long. The affine butterflies do not handle the identity, so a domain with the identity in
+/// it is converted with [`Algorithm::Projective`].
+fn write_lagrange_levels<W, E, F>(
+    writer: &mut W,
+    section: u32,
+    points: &[Affine<E>],
+    point_size: usize,
+    write: fn(&mut W, &Affine<E>) -> Result<(), InterpolationError>,
+) -> Result<(), InterpolationError>
+where
+    W: Write,
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let num_points = 2 * points.len() as u64 - 1;
+    writer.write_all(&section.to_le_bytes())?;
+    writer.write_all(&(num_points * point_size as u64).to_le_bytes())?;
+    let mut size = 1;
+    while size <= points.len() {
+        let domain = &points[..size];
+        let level = match domain.iter().any(|point| point.is_zero()) {
+            true => srs_to_lagrange_with_algorithm(
+                domain,
+                PointOrdering::Natural,
+                Algorithm::Projective,
+            )?,
+            false => srs_to_lagrange(domain)?,
+        };
+        for point in level.iter() {
+            write(writer, point)?;
+        }
+        size *= 2;
+    }
+    Ok(())
+}
+
+/// This function writes the Lagrange sections 12 to 15 of a ptau file for domains of size up to `2^power` from
+/// `powers`, as `snarkjs powersoftau prepare phase2` lays them out. Sections 13 to 15 hold the domains of size up to
+/// `2^power` and section 12 one more, of size `2^(power + 1)`. That domain needs one more tau G1 point than section
+/// 2 of a file of this power holds, so if `powers` holds only `2^(power + 1) - 1` of them it is padded with the
+/// identity, as snarkjs does. It errors if `powers` holds fewer points than that, or fewer than `2^power` of the
+/// others.
+pub fn write_lagrange_sections<W: Write>(
+    writer: &mut W,
+    powers: &PtauPowers,
+    power: u32,
+) -> Result<(), InterpolationError> {
+    let size = size_from_log(power)?;
+    let top_size = size_from_log(power + 1)?;
+    for (name, len, needed) in [
+        ("tau G1", powers.tau_g1.len(), top_size - 1),
+        ("tau G2", powers.tau_g2.len(), size),
+        ("alpha tau G1", powers.alpha_tau_g1.len(), size),
+        ("beta tau G1", powers.beta_tau_g1.len(), size),
+    ] {
+        if len < needed {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The sections of power {} need {} {} points but were given {}",
+                power, needed, name, len
+            )));
+        }
+    }
+    let mut tau_g1 = try_with_capacity(top_size)?;
+    tau_g1.extend_from_slice(&powers.tau_g1[..top_size.min(powers.tau_g1.len())]);
+    tau_g1.resize(top_size, G1Affine::identity());
+
+    write_lagrange_levels(writer, LAGRANGE_G1_SECTION, &tau_g1, G1_SIZE, write_point)?;
+    write_lagrange_levels(
+        writer,
+        LAGRANGE_G2_SECTION,
+        &powers.tau_g2[..size],
+        G2_SIZE,
+        write_g2_point,
+    )?;
+    write_lagrange_levels(
+        writer,
+        LAGRANGE_ALPHA_G1_SECTION,
+        &powers.alpha_tau_g1[..size],
+        G1_SIZE,
+        write_point,
+    )?;
+    write_lagrange_levels(
+        writer,
+        LAGRANGE_BETA_G1_SECTION,
+        &powers.beta_tau_g1[..size],
+        G1_SIZE,
+        write_point,
+    )
+}
+
+/// This function reads the header section, checking that the file is over BN254.
+fn read_header<R: Read>(reader: &mut R) -> Result<PtauHeader, InterpolationError> {
+    if read_u32(reader)? as usize != FIELD_SIZE {
+        return Err(InterpolationError::SerializationError(
+            "Unexpected field element size in ptau header".to_string(),
+        ));
+    }
+    let mut modulus = [0u8; FIELD_SIZE];
+    reader.read_exact(&mut modulus)?;
+    if modulus[..] != Fq::MODULUS.to_bytes_le()[..] {
+        return Err(InterpolationError::SerializationError(
+            "The ptau file is not over BN254".to_string(),
+        ));
+    }
+    Ok(PtauHeader {
+        power: read_u32(reader)?,
+        ceremony_power: read_u32(reader)?,
+    })
+}
+
+/// This function reads the magic bytes and version at the start of a ptau file and returns the number of sections.
+fn read_preamble<R: Read>(reader: &mut R) -> Result<u32, InterpolationError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(InterpolationError::SerializationError(
+            "Missing ptau magic bytes".to_string(),
+        ));
+    }
+    let _version = read_u32(reader)?;
+    read_u32(reader)
+}
+
+/// This function reads the header section of a ptau file, skipping any sections before it, and returns it along
+/// with the raw bytes of the section.
+#[cfg(feature = "inspect")]
+pub(crate) fn read_header_section<R: Read>(
+    reader: &mut R,
+) -> Result<(PtauHeader, Vec<u8>), InterpolationError> {
+    for _ in 0..read_preamble(reader)? {
+        let section = read_u32(reader)?;
+        let length = read_u64(reader)?;
+        if section == HEADER_SECTION {
+            let mut bytes = Vec::new();
+            reader.by_ref().take(length).read_to_end(&mut bytes)?;
+            return Ok((read_header(&mut bytes.as_slice())?, bytes));
+        }
+        io::copy(&mut reader.by_ref().take(length), &mut io::sink())?;
+    }
+    Err(InterpolationError::SerializationError(
+        "The ptau file has no header section".to_string(),
+    ))
+}
+
+/// This function reads the first `size` tau G1 points `[1], [tau], ..., [tau^(size - 1)]` from a ptau file,
+/// checking each point is on the curve. Sections after the tau G1 section are not read, so `reader` is left
+/// positioned inside the tau G1 section.
+pub fn read_tau_g1<R: Read>(
+    reader: &mut R,
+    size: usize,
+) -> Result<(PtauHeader, Vec<G1Affine>), InterpolationError> {
+    let num_sections = read_preamble(reader)?;
+    let mut header = None;
+    for _ in 0..num_sections {
+        let section = read_u32(reader)?;
+        let length = read_u64(reader)?;
+        match section {
+            HEADER_SECTION => {
+                let mut section = reader.by_ref().take(length);
+                header = Some(read_header(&mut section)?);
+                io::copy(&mut section, &mut io::sink())?;
+            }
+            TAU_G1_SECTION => {
+                let header = header.ok_or(InterpolationError::SerializationError(
+                    "The ptau tau G1 section comes before the header".to_string(),
+                ))?;
+                // A file too large to index on this target still holds any size that fits.
+                let max_size = header.max_size().unwrap_or(usize::MAX);
+                if size > max_size {
+                    return Err(InterpolationError::InvalidParameters(format!(
+                        "The ptau file holds at most {} points but {} were requested",
+                        max_size, size
+                    )));
+                }
+                let points = read_decoded(
+                    reader,
+                    size,
+                    2 * FIELD_SIZE,
+                    DECODE_CHUNK_SIZE,
+                    |mut bytes| read_point(&mut bytes),
+                )?;
+                return Ok((header, points));
+            }
+            _ => {
+                io::copy(&mut reader.by_ref().take(length), &mut io::sink())?;
+            }
+        }
+    }
+    Err(InterpolationError::SerializationError(
+        "The ptau file has no tau G1 section".to_string(),
+    ))
+}
+
+/// This function reads the points of sections 2 to 5 of a ptau file, checking each is in the prime order subgroup,
+/// along with its header.
+pub fn read_powers<R: Read>(
+    reader: &mut R,
+) -> Result<(PtauHeader, PtauPowers), InterpolationError> {
+    fn count(length: u64, point_size: usize) -> Result<usize, InterpolationError> {
+        if !length.is_multiple_of(point_size as u64) {
+            return Err(InterpolationError::SerializationError(format!(
+                "A ptau section of {} bytes does not hold a whole number of points",
+                length
+            )));
+        }
+        usize::try_from(length / point_size as u64).map_err(|_| InterpolationError::SizeError)
+    }
+
+    let num_sections = read_preamble(reader)?;
+    let mut header = None;
+    let mut powers = PtauPowers::default();
+    for _ in 0..num_sections {
+        let section = read_u32(reader)?;
+        let length = read_u64(reader)?;
+        let mut section_reader = reader.by_ref().take(length);
+        match section {
+            HEADER_SECTION => header = Some(read_header(&mut section_reader)?),
+            TAU_G1_SECTION | ALPHA_TAU_G1_SECTION | BETA_TAU_G1_SECTION => {
+                let points = read_decoded(
+                    &mut section_reader,
+                    count(length, G1_SIZE)?,
+                    G1_SIZE,
+                    DECODE_CHUNK_SIZE,
+                    |mut bytes| read_point(&mut bytes),
+                )?;
+                match section {
+                    TAU_G1_SECTION => powers.tau_g1 = points,
+                    ALPHA_TAU_G1_SECTION => powers.alpha_tau_g1 = points,
+                    _ => powers.beta_tau_g1 = points,
+                }
+            }
+            TAU_G2_SECTION => {
+                powers.tau_g2 = read_decoded(
+                    &mut section_reader,
+                    count(length, G2_SIZE)?,
+                    G2_SIZE,
+                    DECODE_CHUNK_SIZE,
+                    |mut bytes| read_g2_point(&mut bytes),
+                )?
+            }
+            _ => {}
+        }
+        io::copy(&mut section_reader, &mut io::sink())?;
+    }
+    let header = header.ok_or(InterpolationError::SerializationError(
+        "The ptau file has no header section".to_string(),
+    ))?;
+    Ok((header, powers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    /// A ptau file with `tau = 2` and power 2, generated by a short Python script that follows the snarkjs layout
+    /// and computes the points from the curve constants. Its tau G2 section is zero filled.
+    const FIXTURE: &str = include_str!("testdata/bn254_power_2.ptau.hex");
+
+    fn decode_hex(hex: &str) -> Vec<u8> {
+        let hex = hex.split_whitespace().collect::<String>();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_read_tau_g1() {
+        let bytes = decode_hex(FIXTURE);
+        let (header, points) = read_tau_g1(&mut bytes.as_slice(), 4).unwrap();
+        assert_eq!(
+            header,
+            PtauHeader {
+                power: 2,
+                ceremony_power: 2
+            }
+        );
+        let mut expected = G1Affine::generator();
+        for point in points {
+            assert_eq!(point, expected);
+            expected = (expected * Fr::from(2u64)).into_affine();
+        }
+
+        // The file does not hold enough points for a domain of size 8.
+        assert!(read_tau_g1(&mut bytes.as_slice(), 8).is_err());
+
+        // Corrupting a coordinate is caught.
+        let mut corrupted = bytes.clone();
+        corrupted[12 + 44 + 12 + 70] ^= 1;
+        assert!(read_tau_g1(&mut corrupted.as_slice(), 4).is_err());
+    }
+
+    /// The Hermez power 8 file as snarkjs wrote it after `powersoftau prepare phase2`.
+    const HERMEZ_08: &[u8] = include_bytes!("testdata/powersOfTau28_hez_final_08.ptau");
+
+    /// This function returns the contents of section `section` of the sections in `bytes`, which follow a preamble
+    /// if `preamble` is set.
+    fn section(mut bytes: &[u8], preamble: bool, section: u32) -> &[u8] {
+        if preamble {
+            bytes = &bytes[12..];
+        }
+        while !bytes.is_empty() {
+            let kind = read_u32(&mut bytes).unwrap();
+            let length = read_u64(&mut bytes).unwrap() as usize;
+            if kind == section {
+                return &bytes[..length];
+            }
+            bytes = &bytes[length..];
+        }
+        panic!("No section {}", section)
+    }
+
+    #[test]
+    fn test_write_lagrange_sections() {
+        use ark_bn254::Bn254;
+        use ark_ec::pairing::Pairing;
+
+        let (header, powers) = read_powers(&mut &HERMEZ_08[..]).unwrap();
+        assert_eq!(header.power, 8);
+        assert_eq!(powers.tau_g1.len(), 511);
+        let mut written = Vec::new();
+        write_lagrange_sections(&mut written, &powers, 8).unwrap();
+        for kind in [
+            LAGRANGE_G2_SECTION,
+            LAGRANGE_ALPHA_G1_SECTION,
+            LAGRANGE_BETA_G1_SECTION,
+        ] {
+            assert_eq!(
+                section(&written, false, kind),
+                section(HERMEZ_08, true, kind)
+            );
+        }
+
+        // The file was cut down from the power 28 one, so its domain of size 512 was converted with the real
+        // [tau^511] where this pads with the identity. Only the first point is taken from that domain, and the
+        // point missing from the padding is recovered from it.
+        let (ours, theirs) = (
+            section(&written, false, LAGRANGE_G1_SECTION),
+            section(HERMEZ_08, true, LAGRANGE_G1_SECTION),
+        );
+        assert_eq!(ours.len(), theirs.len());
+        assert_eq!(ours[..511 * G1_SIZE], theirs[..511 * G1_SIZE]);
+        let first = |bytes: &[u8]| read_point(&mut &bytes[511 * G1_SIZE..]).unwrap();
+        let missing = ((first(theirs).into_group() - first(ours)) * Fr::from(512u64)).into_affine();
+        assert_eq!(
+            Bn254::pairing(missing, G2Affine::generator()),
+            Bn254::pairing(powers.tau_g1[510], powers.tau_g2[1])
+        );
+        let mut full = powers.clone();
+        full.tau_g1.push(missing);
+        let mut written = Vec::new();
+        write_lagrange_sections(&mut written, &full, 8).unwrap();
+        assert_eq!(section(&written, false, LAGRANGE_G1_SECTION), theirs);
+
+        let mut short = powers.clone();
+        short.beta_tau_g1.truncate(255);
+        assert!(write_lagrange_sections(&mut Vec::new(), &short, 8).is_err());
+        assert!(write_lagrange_sections(&mut Vec::new(), &powers, 9).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! This module contains a writer and a reader for the packed point layout rapidsnark maps into memory.
+//!
+//! rapidsnark takes the point sections of a zkey as arrays of its affine point structs without copying them, so the
+//! layout is that of the struct: the `x` then the `y` coordinate, each as the limbs of its Montgomery form in little
+//! endian order, with no header or padding and the identity as all zeros. That is also how snarkjs stores points in
+//! zkeys and ptau files, so a file written here can be mapped by a native prover and spliced into those files as is.
+use crate::error::InterpolationError;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_serialize::Valid;
+use ark_std::{
+    io::{Read, Write},
+    vec::Vec,
+    One, Zero,
+};
+
+/// This function returns the Montgomery constant `R = 2^(64 * limbs)` of the field, so that the Montgomery form of an
+/// element `x` is the canonical form of `x * R`.
+fn montgomery_r<F: PrimeField>() -> F {
+    F::from(2u64).pow([64 * F::BigInt::NUM_LIMBS as u64])
+}
+
+/// The size in bytes of a point in the layout.
+pub fn point_size<E: SWCurveConfig>() -> usize
+where
+    E::BaseField: PrimeField,
+{
+    2 * 8 * <E::BaseField as PrimeField>::BigInt::NUM_LIMBS
+}
+
+/// This function writes `points` in the layout, returning the writer.
+pub fn write_rapidsnark<E, W>(mut writer: W, points: &[Affine<E>]) -> Result<W, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+    W: Write,
+{
+    let r = montgomery_r::<E::BaseField>();
+    for point in points {
+        let (x, y) = match point.infinity {
+            true => (E::BaseField::zero(), E::BaseField::zero()),
+            false => (point.x, point.y),
+        };
+        for coordinate in [x, y] {
+            writer.write_all(&(coordinate * r).into_bigint().to_bytes_le())?;
+        }
+    }
+    Ok(writer)
+}
+
+/// This function reads `size` points in the layout, checking each is in the prime order subgroup.
+pub fn read_rapidsnark<E, R>(
+    reader: &mut R,
+    size: usize,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+    R: Read,
+{
+    let r_inv = montgomery_r::<E::BaseField>()
+        .inverse()
+        .unwrap_or(E::BaseField::one());
+    let mut bytes = vec![0u8; point_size::<E>() / 2];
+    let mut read_coordinate = |reader: &mut R| -> Result<E::BaseField, InterpolationError> {
+        reader.read_exact(&mut bytes)?;
+        let mut repr = <E::BaseField as PrimeField>::BigInt::default();
+        for (limb, chunk) in repr.as_mut().iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("Limbs are eight bytes"));
+        }
+        E::BaseField::from_bigint(repr)
+            .map(|montgomery| montgomery * r_inv)
+            .ok_or(InterpolationError::SerializationError(
+                "Field element is not reduced".to_string(),
+            ))
+    };
+    (0..size)
+        .map(|_| {
+            let x = read_coordinate(reader)?;
+            let y = read_coordinate(reader)?;
+            if x.is_zero() && y.is_zero() {
+                return Ok(Affine::<E>::identity());
+            }
+            let point = Affine::<E>::new_unchecked(x, y);
+            point.check().map_err(|_| {
+                InterpolationError::SerializationError(
+                    "Point is not in the prime order subgroup".to_string(),
+                )
+            })?;
+            Ok(point)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formats::ptau::read_powers, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    /// The Hermez power 8 file as snarkjs wrote it, whose G1 sections store points in the same layout.
+    const HERMEZ_08: &[u8] = include_bytes!("testdata/powersOfTau28_hez_final_08.ptau");
+
+    #[test]
+    fn test_rapidsnark_layout() {
+        let (_, powers) = read_powers(&mut &HERMEZ_08[..]).unwrap();
+        // The tau G1 and alpha tau G1 sections, with the offset of their contents and their number of points.
+        for (points, start, len) in [
+            (&powers.tau_g1, 80, 511),
+            (&powers.alpha_tau_g1, 65576, 256),
+        ] {
+            let written = write_rapidsnark(Vec::new(), points).unwrap();
+            assert_eq!(written.len(), len * point_size::<BnConfig>());
+            assert_eq!(written, HERMEZ_08[start..start + written.len()]);
+            assert_eq!(
+                read_rapidsnark::<BnConfig, _>(&mut written.as_slice(), len).unwrap(),
+                *points
+            );
+        }
+        // The Lagrange tau G1 section that snarkjs added.
+        let lagrange = &HERMEZ_08[181684..181684 + 1023 * point_size::<BnConfig>()];
+        let points = read_rapidsnark::<BnConfig, _>(&mut &lagrange[..], 1023).unwrap();
+        assert_eq!(write_rapidsnark(Vec::new(), &points).unwrap(), lagrange);
+
+        let rng = &mut ark_std::test_rng();
+        let mut points = gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap();
+        points[3] = Affine::identity();
+        let written = write_rapidsnark(Vec::new(), &points).unwrap();
+        assert_eq!(
+            read_rapidsnark::<BnConfig, _>(&mut written.as_slice(), 8).unwrap(),
+            points
+        );
+
+        let mut corrupted = written.clone();
+        corrupted[5] ^= 1;
+        assert!(read_rapidsnark::<BnConfig, _>(&mut corrupted.as_slice(), 8).is_err());
+    }
+}
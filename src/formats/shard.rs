@@ -0,0 +1,359 @@
+//! This module splits a file in this crate's binary format into shards of a fixed number of points and merges the
+//! shards back into one file, so multi-gigabyte setups can be moved and processed piecewise.
+//!
+//! Every shard is itself a file in the binary format with the header of the original apart from its size, and the
+//! shards of a Lagrange SRS each store the generator of the whole domain. A [`ShardManifest`] records the header of
+//! the original together with the range of points and the digest of each shard, and merging checks every shard
+//! against it. The manifest is text with one entry per line:
+//!
+//! ```text
+//! srs-shards 1
+//! header <the header of the original file in hex>
+//! shard <index of the first point> <number of points> <file name> <sha256 in hex> <blake3 in hex>
+//! ```
+use crate::{
+    curves::SrsCurve,
+    error::InterpolationError,
+    formats::binary::{BinaryHeader, SrsReader, SrsWriter, HEADER_SIZE},
+    manifest::{decode_hex, encode_hex, PointsDigest, PointsHasher},
+};
+use ark_std::{
+    io::{Read, Write},
+    string::String,
+    vec::Vec,
+};
+
+/// The first line of every manifest.
+const MANIFEST_VERSION: &str = "srs-shards 1";
+
+/// One shard of a split SRS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    /// The index of the first point of the shard in the original file.
+    pub start: u64,
+    /// The number of points in the shard.
+    pub len: u64,
+    /// The name the shard was stored under, which has no whitespace.
+    pub file: String,
+    /// The digest of the points of the shard.
+    pub digest: PointsDigest,
+}
+
+/// The description of an SRS split into shards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardManifest {
+    /// The header of the original file.
+    pub header: BinaryHeader,
+    /// The shards, in the order of their points.
+    pub shards: Vec<Shard>,
+}
+
+impl ShardManifest {
+    /// Writes the manifest.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        self.header.write(&mut header)?;
+        writeln!(writer, "{}", MANIFEST_VERSION)?;
+        writeln!(writer, "header {}", encode_hex(&header))?;
+        for shard in self.shards.iter() {
+            writeln!(
+                writer,
+                "shard {} {} {} {} {}",
+                shard.start,
+                shard.len,
+                shard.file,
+                encode_hex(&shard.digest.sha256),
+                encode_hex(&shard.digest.blake3)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reads a manifest, checking its shards cover the points of the original file in order.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(MANIFEST_VERSION) {
+            return Err(InterpolationError::SerializationError(
+                "Missing shard manifest version".to_string(),
+            ));
+        }
+        let header = match lines.next().and_then(|line| line.strip_prefix("header ")) {
+            Some(hex) => BinaryHeader::read(&mut decode_hex::<HEADER_SIZE>(hex)?.as_slice())?,
+            None => {
+                return Err(InterpolationError::SerializationError(
+                    "Missing header in shard manifest".to_string(),
+                ))
+            }
+        };
+        let shards = lines
+            .filter(|line| !line.is_empty())
+            .map(parse_shard)
+            .collect::<Result<Vec<_>, _>>()?;
+        let manifest = Self { header, shards };
+        manifest.check()?;
+        Ok(manifest)
+    }
+
+    /// This function checks the shards are non-empty, follow on from each other and add up to the size in the
+    /// header.
+    fn check(&self) -> Result<(), InterpolationError> {
+        let mut next = 0;
+        for shard in self.shards.iter() {
+            if shard.start != next || shard.len == 0 {
+                return Err(InterpolationError::InvalidParameters(format!(
+                    "Shard {} does not follow on from the shard before it",
+                    shard.file
+                )));
+            }
+            next += shard.len;
+        }
+        if next != self.header.size {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The shards hold {} points but the SRS has {}",
+                next, self.header.size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// This function parses a `shard` line of a manifest.
+fn parse_shard(line: &str) -> Result<Shard, InterpolationError> {
+    let invalid =
+        || InterpolationError::SerializationError(format!("Invalid shard manifest line {}", line));
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    match fields.as_slice() {
+        ["shard", start, len, file, sha256, blake3] => Ok(Shard {
+            start: start.parse().map_err(|_| invalid())?,
+            len: len.parse().map_err(|_| invalid())?,
+            file: file.to_string(),
+            digest: PointsDigest {
+                len: len.parse().map_err(|_| invalid())?,
+                sha256: decode_hex(sha256)?,
+                blake3: decode_hex(blake3)?,
+            },
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+/// This function splits the file in `reader` into shards of `shard_len` points, the last of which may be shorter.
+/// It calls `create` with the index of each shard for the name it is stored under and where to write it, and
+/// returns the manifest of the shards. It errors if `shard_len` is zero, a name is empty or has whitespace, or a
+/// point of the file is not in the prime order subgroup.
+pub fn split_srs<R, W, E>(
+    reader: R,
+    shard_len: u64,
+    mut create: impl FnMut(usize) -> Result<(String, W), InterpolationError>,
+) -> Result<ShardManifest, InterpolationError>
+where
+    R: Read,
+    W: Write,
+    E: SrsCurve,
+{
+    if shard_len == 0 {
+        return Err(InterpolationError::InvalidParameters(
+            "Shards must hold at least one point".to_string(),
+        ));
+    }
+    let mut reader = SrsReader::<_, E>::new(reader)?;
+    let header = *reader.header();
+    let generator = reader.generator();
+
+    let mut shards = Vec::new();
+    let mut start = 0;
+    while start < header.size {
+        let len = shard_len.min(header.size - start);
+        let (file, writer) = create(shards.len())?;
+        if file.is_empty() || file.contains(char::is_whitespace) {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Invalid shard name {:?}",
+                file
+            )));
+        }
+        let mut writer = SrsWriter::<_, E>::new(
+            writer,
+            BinaryHeader {
+                size: len,
+                ..header
+            },
+            generator,
+        )?;
+        let mut hasher = PointsHasher::new();
+        for _ in 0..len {
+            let point = reader.read_point()?.ok_or(InterpolationError::SizeError)?;
+            hasher.update(&point);
+            writer.write_point(&point)?;
+        }
+        writer.finish()?;
+        shards.push(Shard {
+            start,
+            len,
+            file,
+            digest: hasher.finalize(),
+        });
+        start += len;
+    }
+    Ok(ShardManifest { header, shards })
+}
+
+/// This function merges the shards of `manifest` into one file written to `writer`, calling `open` for each shard
+/// in order. It errors if a shard's header does not match the manifest, the shards of a Lagrange SRS store
+/// different generators or the points of a shard do not match its digest.
+pub fn merge_srs<W, R, E>(
+    manifest: &ShardManifest,
+    writer: W,
+    mut open: impl FnMut(&Shard) -> Result<R, InterpolationError>,
+) -> Result<W, InterpolationError>
+where
+    W: Write,
+    R: Read,
+    E: SrsCurve,
+{
+    manifest.check()?;
+    let mut readers = manifest.shards.iter().map(|shard| {
+        let reader = SrsReader::<_, E>::new(open(shard)?)?;
+        if *reader.header()
+            != (BinaryHeader {
+                size: shard.len,
+                ..manifest.header
+            })
+        {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The header of shard {} does not match the manifest",
+                shard.file
+            )));
+        }
+        Ok((shard, reader))
+    });
+
+    // The generator of a Lagrange SRS is only known once the first shard is open.
+    let first = readers.next().transpose()?;
+    let generator = first.as_ref().and_then(|(_, reader)| reader.generator());
+    let mut writer = SrsWriter::<_, E>::new(writer, manifest.header, generator)?;
+    for next in first.map(Ok).into_iter().chain(readers) {
+        let (shard, reader) = next?;
+        if reader.generator() != generator {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Shard {} is over a different domain",
+                shard.file
+            )));
+        }
+        let mut hasher = PointsHasher::new();
+        for point in reader {
+            let point = point?;
+            hasher.update(&point);
+            writer.write_point(&point)?;
+        }
+        if hasher.finalize() != shard.digest {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The points of shard {} do not match the manifest",
+                shard.file
+            )));
+        }
+    }
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{write_lagrange, write_monomial, Endianness, PointEncoding},
+        srs::MonomialSrs,
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::g1::Config as BnConfig;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// A writer into a buffer the test keeps a handle to.
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn split_to_buffers(file: &[u8], shard_len: u64) -> (ShardManifest, Vec<Vec<u8>>) {
+        let mut buffers = Vec::new();
+        let manifest = split_srs::<_, _, BnConfig>(file, shard_len, |index| {
+            let buffer = Rc::new(RefCell::new(Vec::new()));
+            buffers.push(buffer.clone());
+            Ok((format!("shard-{}.srs", index), Shared(buffer)))
+        })
+        .unwrap();
+        let buffers = buffers.into_iter().map(|buffer| buffer.take()).collect();
+        (manifest, buffers)
+    }
+
+    #[test]
+    fn test_split_merge() {
+        let rng = &mut ark_std::test_rng();
+        let monomial = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let files = [
+            write_monomial(
+                Vec::new(),
+                &monomial,
+                PointEncoding::Compressed,
+                Endianness::Little,
+            )
+            .unwrap(),
+            write_lagrange(
+                Vec::new(),
+                &monomial.to_lagrange().unwrap(),
+                PointEncoding::Uncompressed,
+                Endianness::Big,
+            )
+            .unwrap(),
+        ];
+        for file in files {
+            let (manifest, shards) = split_to_buffers(&file, 6);
+            assert_eq!(
+                manifest
+                    .shards
+                    .iter()
+                    .map(|shard| (shard.start, shard.len))
+                    .collect::<Vec<_>>(),
+                [(0, 6), (6, 6), (12, 4)]
+            );
+
+            let mut text = Vec::new();
+            manifest.write(&mut text).unwrap();
+            assert_eq!(ShardManifest::read(&mut text.as_slice()).unwrap(), manifest);
+
+            let open = |shard: &Shard| Ok(shards[(shard.start / 6) as usize].as_slice());
+            let merged = merge_srs::<_, _, BnConfig>(&manifest, Vec::new(), open).unwrap();
+            assert_eq!(merged, file);
+
+            // A shard whose points were changed is caught by its digest.
+            let swapped =
+                |shard: &Shard| Ok(shards[1 - (shard.start / 6).min(1) as usize].as_slice());
+            assert!(merge_srs::<_, _, BnConfig>(&manifest, Vec::new(), swapped).is_err());
+            let mut gap = manifest.clone();
+            gap.shards.remove(1);
+            assert!(merge_srs::<_, _, BnConfig>(&gap, Vec::new(), open).is_err());
+        }
+
+        let file = write_monomial(
+            Vec::new(),
+            &monomial,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+        assert!(split_srs::<_, Vec<u8>, BnConfig>(file.as_slice(), 0, |_| unreachable!()).is_err());
+        assert!(split_srs::<_, _, BnConfig>(file.as_slice(), 4, |_| Ok((
+            "a b".to_string(),
+            Vec::new()
+        )))
+        .is_err());
+        assert!(ShardManifest::read(&mut "srs-shards 2\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! This module contains writers for publishing points on the EVM, either as ABI encoded data or as a Solidity library.
+//!
+//! Every coordinate is a big endian `uint256`, and the identity is the point `(0, 0)`, as the BN254 precompiles take
+//! it. [`write_abi`] writes the ABI encoding of a single `uint256[2][]`, so contracts can read the points with
+//! `abi.decode(data, (uint256[2][]))`. [`write_solidity_library`] writes a library holding the points packed into a
+//! `bytes` constant, since Solidity has no constant arrays, with a function that loads one point.
+use crate::error::InterpolationError;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{io::Write, vec::Vec};
+
+/// The size in bytes of an ABI word.
+const WORD_SIZE: usize = 32;
+
+/// This function writes the coordinates of `point` as two big endian words.
+fn write_words<E, W>(writer: &mut W, point: &Affine<E>) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+    W: Write,
+{
+    for coordinate in [point.x, point.y] {
+        let bytes = match point.infinity {
+            true => Vec::new(),
+            false => coordinate.into_bigint().to_bytes_be(),
+        };
+        // The check that the base field fits in a word ensures only zeros are dropped.
+        let start = bytes.len().saturating_sub(WORD_SIZE);
+        writer.write_all(&[0u8; WORD_SIZE][..WORD_SIZE - (bytes.len() - start)])?;
+        writer.write_all(&bytes[start..])?;
+    }
+    Ok(())
+}
+
+fn check_word_size<E>() -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+{
+    if E::BaseField::MODULUS_BIT_SIZE as usize > 8 * WORD_SIZE {
+        return Err(InterpolationError::InvalidParameters(
+            "The coordinates of the curve do not fit in a uint256".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// This function writes `points` as the ABI encoding of a `uint256[2][]`, returning the writer. It errors if the
+/// coordinates of the curve do not fit in a `uint256`.
+pub fn write_abi<E, W>(mut writer: W, points: &[Affine<E>]) -> Result<W, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+    W: Write,
+{
+    check_word_size::<E>()?;
+    let mut word = [0u8; WORD_SIZE];
+    // The offset of the array, which follows the head, and its length.
+    for value in [WORD_SIZE, points.len()] {
+        word[WORD_SIZE - 8..].copy_from_slice(&(value as u64).to_be_bytes());
+        writer.write_all(&word)?;
+    }
+    for point in points {
+        write_words(&mut writer, point)?;
+    }
+    Ok(writer)
+}
+
+/// This function writes a Solidity library called `name` holding `points`, returning the writer. The library has a
+/// `SIZE` constant and a `point(i)` function returning the coordinates of point `i`. It errors if `name` is not a
+/// Solidity identifier or the coordinates of the curve do not fit in a `uint256`.
+///
+/// The code size limit of the EVM caps a deployed library at a few hundred points, so larger SRSs are better
+/// published with [`write_abi`].
+pub fn write_solidity_library<E, W>(
+    mut writer: W,
+    name: &str,
+    points: &[Affine<E>],
+) -> Result<W, InterpolationError>
+where
+    E: SWCurveConfig,
+    E::BaseField: PrimeField,
+    W: Write,
+{
+    check_word_size::<E>()?;
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if !is_identifier {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "{} is not a Solidity identifier",
+            name
+        )));
+    }
+    let mut packed = Vec::with_capacity(2 * WORD_SIZE * points.len());
+    for point in points {
+        write_words(&mut packed, point)?;
+    }
+    let hex = packed
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    write!(
+        writer,
+        "// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// The {size} points of an SRS, each as its two coordinates, with the identity as (0, 0).
+library {name} {{
+    uint256 internal constant SIZE = {size};
+
+    bytes internal constant POINTS = hex\"{hex}\";
+
+    /// Returns the coordinates of point `i`, which must be less than `SIZE`.
+    function point(uint256 i) internal pure returns (uint256 x, uint256 y) {{
+        require(i < SIZE, \"{name}: index out of range\");
+        bytes memory points = POINTS;
+        assembly {{
+            let offset := add(add(points, 32), mul(i, 64))
+            x := mload(offset)
+            y := mload(add(offset, 32))
+        }}
+    }}
+}}
+",
+        size = points.len(),
+    )?;
+    Ok(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{g1::Config as BnConfig, G1Affine};
+    use ark_ec::AffineRepr;
+
+    #[test]
+    fn test_write_abi() {
+        let points = [G1Affine::generator(), G1Affine::identity()];
+        let bytes = write_abi(Vec::new(), &points).unwrap();
+        // The head, the length, then the generator (1, 2) and the identity (0, 0).
+        let mut expected = vec![0u8; 6 * WORD_SIZE];
+        for (word, value) in [(0, 32), (1, 2), (2, 1), (3, 2)] {
+            expected[(word + 1) * WORD_SIZE - 1] = value;
+        }
+        assert_eq!(bytes, expected);
+
+        #[cfg(feature = "bls12-381")]
+        assert!(write_abi(Vec::new(), &[ark_bls12_381::G1Affine::generator()]).is_err());
+    }
+
+    #[test]
+    fn test_write_solidity_library() {
+        let points = [G1Affine::generator()];
+        let library = write_solidity_library(Vec::new(), "BnSrs", &points).unwrap();
+        let library = String::from_utf8(library).unwrap();
+        assert!(library.contains("library BnSrs {"));
+        assert!(library.contains("uint256 internal constant SIZE = 1;"));
+        let expected = format!("hex\"{:0>64}{:0>64}\"", "1", "2");
+        assert!(library.contains(&expected));
+
+        assert!(write_solidity_library::<BnConfig, _>(Vec::new(), "1Srs", &[]).is_err());
+        assert!(write_solidity_library::<BnConfig, _>(Vec::new(), "", &[]).is_err());
+    }
+}
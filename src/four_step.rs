@@ -0,0 +1,124 @@
+//! This module contains the four-step decomposition of the FFT style operation, which [`crate::group_fft_in_place`]
+//! switches to for domains of at least [`FOUR_STEP_THRESHOLD`] points.
+//!
+//! The rounds of the radix-2 operation pair up points `2^i` apart, so once the points no longer fit in cache every
+//! late round misses on nearly every access. Writing the `n = n_1 n_2` points as an `n_1` by `n_2` matrix, the
+//! operation is instead `n_2` operations of size `n_1` down the columns, a multiplication of each entry by a power
+//! of the root of unity, and `n_1` operations of size `n_2` along the rows. Transposing between the steps makes
+//! every smaller operation run on contiguous points that fit in cache, at the price of `n` extra scalar
+//! multiplications and a scratch copy of the points.
+use crate::{
+    buffer::BufferProvider, error::InterpolationError, group_fft_radix2, utils::distribute_powers,
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_std::cfg_chunks_mut;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The number of points from which [`crate::group_fft_in_place`] uses the four-step decomposition.
+pub(crate) const FOUR_STEP_THRESHOLD: usize = 1 << 22;
+
+/// The side of the square blocks the transposes copy at a time.
+const TRANSPOSE_BLOCK: usize = 16;
+
+/// This function writes the transpose of the `rows` by `cols` row-major matrix `src` to `dst`.
+pub(crate) fn transpose<T: Copy + Send + Sync>(src: &[T], dst: &mut [T], rows: usize, cols: usize) {
+    // Each block of rows of `dst` reads a block of columns of `src`, a few cache lines per row.
+    cfg_chunks_mut!(dst, rows * TRANSPOSE_BLOCK)
+        .enumerate()
+        .for_each(|(block, dst)| {
+            let first_col = block * TRANSPOSE_BLOCK;
+            let block_cols = dst.len() / rows;
+            for first_row in (0..rows).step_by(TRANSPOSE_BLOCK) {
+                for row in first_row..rows.min(first_row + TRANSPOSE_BLOCK) {
+                    for col in 0..block_cols {
+                        dst[col * rows + row] = src[row * cols + first_col + col];
+                    }
+                }
+            }
+        });
+}
+
+/// This function is the same as [`crate::group_fft_in_place`] but uses the four-step decomposition. The number of
+/// points must be a power of two. The scratch copy of the points and the scratch of each smaller operation are
+/// taken from `provider`.
+pub(crate) fn group_fft_four_step<E, F, P>(
+    points: &mut [Affine<E>],
+    gen: F,
+    provider: &P,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    enter_span!(DEBUG, "four_step", size = points.len());
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let log_size = points.len().ilog2();
+    let rows = 1usize << log_size.div_ceil(2);
+    let cols = points.len() / rows;
+    let mut scratch = provider.buffer(points.len(), Affine::<E>::identity())?;
+
+    // The point at n_2 j_1 + j_2 goes to row j_2 so that each column becomes contiguous, and after the operation on
+    // it the entry for k_1 is multiplied by gen^(j_2 k_1).
+    transpose(points, &mut scratch, rows, cols);
+    let column_root = gen.pow([cols as u64]);
+    cfg_chunks_mut!(scratch, rows).enumerate().try_for_each(
+        |(j2, column): (usize, &mut [Affine<E>])| {
+            group_fft_radix2(column, column_root, provider)?;
+            distribute_powers(column, gen.pow([j2 as u64]));
+            Result::<(), InterpolationError>::Ok(())
+        },
+    )?;
+
+    // Transposing back gives row k_1 the entries the operations along the rows need.
+    transpose(&scratch, points, cols, rows);
+    let row_root = gen.pow([rows as u64]);
+    cfg_chunks_mut!(points, cols)
+        .try_for_each(|row: &mut [Affine<E>]| group_fft_radix2(row, row_root, provider))?;
+
+    // The result for k_1 + n_1 k_2 is now in row k_1 and column k_2, so a last transpose puts it in place.
+    transpose(points, &mut scratch, rows, cols);
+    points.copy_from_slice(&scratch);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{buffer::VecProvider, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+    #[test]
+    fn test_transpose() {
+        let src = (0..6 * 20).collect::<Vec<_>>();
+        let mut dst = vec![0; src.len()];
+        transpose(&src, &mut dst, 6, 20);
+        for row in 0..6 {
+            for col in 0..20 {
+                assert_eq!(dst[col * 6 + row], src[row * 20 + col]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_fft_four_step() {
+        // Both even and odd powers of two, so the matrix is square or twice as tall as it is wide.
+        let rng = &mut ark_std::test_rng();
+        for log_size in [1, 4, 5] {
+            let size = 1 << log_size;
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            let gen = Radix2EvaluationDomain::<Fr>::new(size).unwrap().group_gen();
+
+            let mut expected = srs.clone();
+            group_fft_radix2(&mut expected, gen, &VecProvider).unwrap();
+            let mut points = srs;
+            group_fft_four_step(&mut points, gen, &VecProvider).unwrap();
+            assert_eq!(points, expected);
+        }
+    }
+}
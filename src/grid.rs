@@ -0,0 +1,163 @@
+//! This module converts product structured SRSs over a grid, such as the setups of 2D commitments with a row and a
+//! column domain, to the Lagrange basis over the product of two radix-2 domains.
+//!
+//! A grid SRS of `rows` by `columns` points holds `[tau_1^i tau_2^j]` at row `i` and column `j`. The Lagrange
+//! polynomial of the product domain at `(i, j)` is `L_i(X_1) L_j(X_2)`, so the basis is a 2D group FFT: converting
+//! every row over the column domain gives `[tau_1^i L_j(tau_2)]`, and converting every column of that over the row
+//! domain gives `[L_i(tau_1) L_j(tau_2)]`.
+use crate::{converter::Converter, error::InterpolationError};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_std::{cfg_chunks, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The points of an SRS over a grid, stored row by row.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct GridSrs<E: SWCurveConfig> {
+    rows: usize,
+    columns: usize,
+    points: Vec<Affine<E>>,
+}
+
+impl<E: SWCurveConfig> GridSrs<E> {
+    /// This function makes a grid of `rows` by `columns` from `points`, where the point at row `i` and column `j`
+    /// is at index `i * columns + j`. It errors if there are not `rows * columns` points.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        points: Vec<Affine<E>>,
+    ) -> Result<Self, InterpolationError> {
+        if rows.checked_mul(columns) != Some(points.len()) {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "A grid of {} by {} cannot hold {} points",
+                rows,
+                columns,
+                points.len()
+            )));
+        }
+        Ok(Self {
+            rows,
+            columns,
+            points,
+        })
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The points, row by row.
+    pub fn points(&self) -> &[Affine<E>] {
+        &self.points
+    }
+
+    /// The point at row `row` and column `column`, if the grid has it.
+    pub fn get(&self, row: usize, column: usize) -> Option<&Affine<E>> {
+        (row < self.rows && column < self.columns)
+            .then(|| &self.points[row * self.columns + column])
+    }
+
+    /// Consumes the grid and returns its points, row by row.
+    pub fn into_points(self) -> Vec<Affine<E>> {
+        self.points
+    }
+}
+
+/// This function converts every run of [`Converter::size`] points of `points` with `converter`.
+fn convert_chunks<E: SWCurveConfig>(
+    converter: &Converter<E>,
+    points: &[Affine<E>],
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    let chunks = cfg_chunks!(points, converter.size())
+        .map(|chunk| converter.convert(chunk))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+fn transpose<E: SWCurveConfig>(
+    points: &[Affine<E>],
+    rows: usize,
+    columns: usize,
+) -> Vec<Affine<E>> {
+    (0..columns)
+        .flat_map(|j| (0..rows).map(move |i| points[i * columns + j]))
+        .collect()
+}
+
+/// This function converts `grid` to the Lagrange basis over the product of the arkworks domains of its numbers of
+/// rows and columns, which must both be powers of two. The point at row `i` and column `j` of the result is
+/// `[L_i(tau_1) L_j(tau_2)]`, with both bases in natural order.
+pub fn grid_to_lagrange<E: SWCurveConfig>(
+    grid: &GridSrs<E>,
+) -> Result<GridSrs<E>, InterpolationError> {
+    let row_converter = Converter::<E>::new(grid.columns)?;
+    let column_converter = Converter::<E>::new(grid.rows)?;
+    let rows_done = convert_chunks(&row_converter, &grid.points)?;
+    let columns_done = convert_chunks(
+        &column_converter,
+        &transpose(&rows_done, grid.rows, grid.columns),
+    )?;
+    GridSrs::new(
+        grid.rows,
+        grid.columns,
+        transpose(&columns_done, grid.columns, grid.rows),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::Field;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_grid_to_lagrange() {
+        let rng = &mut ark_std::test_rng();
+        let (tau_1, tau_2) = (Fr::rand(rng), Fr::rand(rng));
+        let (rows, columns) = (4, 8);
+        let g = G1Affine::generator();
+        let points = (0..rows)
+            .flat_map(|i| {
+                (0..columns).map(move |j| (g * (tau_1.pow([i]) * tau_2.pow([j]))).into_affine())
+            })
+            .collect();
+        let grid = GridSrs::new(rows as usize, columns as usize, points).unwrap();
+        let lagrange = grid_to_lagrange(&grid).unwrap();
+
+        let row_lagrange = Radix2EvaluationDomain::<Fr>::new(4)
+            .unwrap()
+            .evaluate_all_lagrange_coefficients(tau_1);
+        let column_lagrange = Radix2EvaluationDomain::<Fr>::new(8)
+            .unwrap()
+            .evaluate_all_lagrange_coefficients(tau_2);
+        for (i, row_coeff) in row_lagrange.iter().enumerate() {
+            for (j, column_coeff) in column_lagrange.iter().enumerate() {
+                let expected = (g * (*row_coeff * column_coeff)).into_affine();
+                assert_eq!(lagrange.get(i, j), Some(&expected));
+            }
+        }
+        assert_eq!(lagrange.get(4, 0), None);
+
+        assert!(GridSrs::new(3, 8, grid.points().to_vec()).is_err());
+        let uneven = GridSrs::new(2, 16, grid.into_points()).unwrap();
+        assert!(grid_to_lagrange(&uneven).is_ok());
+        let odd = GridSrs::new(1, 3, lagrange.points()[..3].to_vec()).unwrap();
+        assert!(grid_to_lagrange(&odd).is_err());
+    }
+}
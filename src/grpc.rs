@@ -0,0 +1,466 @@
+//! This module exposes the conversion, the Lagrange check and the inspection of SRS files as a gRPC service, so
+//! infrastructure teams can run one shared converter instead of shipping the binary to every machine that needs
+//! it. The service is defined in `proto/srs.proto`.
+//!
+//! Files in this crate's binary format are streamed in chunks both ways, so a request is not bound by the message
+//! size limit. A request may not send more bytes than the header of its file declares. A conversion still needs
+//! the whole SRS in memory and runs on the blocking pool of the runtime, but its output is sent as it is encoded.
+use crate::{
+    curves::{CurveId, SrsCurve},
+    error::InterpolationError,
+    formats::{
+        binary::{
+            prime_field_size, read_lagrange, read_monomial, write_lagrange, Basis, BinaryHeader,
+            Endianness, PointEncoding, HEADER_SIZE,
+        },
+        inspect::inspect,
+    },
+    srs::PointOrdering,
+    verify::check_lagrange_matches_monomial,
+};
+use ark_std::vec::Vec;
+use std::{
+    io::{self, Read, Write},
+    mem,
+    net::SocketAddr,
+};
+use tokio::sync::mpsc;
+use tonic::{
+    codegen::tokio_stream::wrappers::ReceiverStream, transport::Server, Request, Response, Status,
+    Streaming,
+};
+
+/// The code generated from `proto/srs.proto`.
+#[allow(clippy::all, missing_docs)]
+pub mod proto {
+    tonic::include_proto!("srs_interpolation");
+}
+
+use proto::{
+    convert_request, srs_interpolation_server::SrsInterpolationServer, verify_request, Chunk,
+    ConvertOptions, ConvertRequest, InspectResponse, VerifyRequest, VerifyResponse,
+};
+
+/// The number of bytes of a converted SRS sent in each message.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// The number of bytes an inspection reads while looking for the end of the header before giving up.
+const MAX_INSPECT_BYTES: usize = 1 << 16;
+
+/// The stream of chunks a conversion is answered with.
+type ChunkStream = tonic::codegen::BoxStream<Chunk>;
+
+fn status(e: InterpolationError) -> Status {
+    match e {
+        InterpolationError::InvalidParameters(_)
+        | InterpolationError::SizeError
+        | InterpolationError::SerializationError(_) => Status::invalid_argument(e.to_string()),
+        InterpolationError::DownloadError(_) => Status::unavailable(e.to_string()),
+        InterpolationError::OutOfMemory { .. } => Status::resource_exhausted(e.to_string()),
+        InterpolationError::FieldError(_) | InterpolationError::TaskError(_) => {
+            Status::internal(e.to_string())
+        }
+    }
+}
+
+fn unsupported(curve: CurveId) -> InterpolationError {
+    InterpolationError::InvalidParameters(format!("The curve {} is not supported", curve.name()))
+}
+
+/// This function runs `f` on the blocking pool, since conversions and checks take far longer than a request
+/// handler should hold up the runtime for.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, InterpolationError> + Send + 'static,
+) -> Result<T, Status> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .map_err(status)
+}
+
+/// This function returns the number of bytes in the file that starts with `header`, or `None` if it does not fit in
+/// a [`u64`].
+fn file_len_curve<E: SrsCurve>(header: &BinaryHeader) -> Option<u64> {
+    let generator = match header.basis {
+        Basis::Monomial => 0,
+        Basis::Lagrange => prime_field_size::<E::ScalarField>(),
+    };
+    header
+        .size
+        .checked_mul(header.point_size::<E>() as u64)?
+        .checked_add((HEADER_SIZE + generator) as u64)
+}
+
+/// This function returns the number of bytes in the file that starts with `header`, erroring if its curve is not
+/// supported.
+fn file_len(header: &BinaryHeader) -> Result<Option<u64>, InterpolationError> {
+    match header.curve {
+        CurveId::Bn254 => Ok(file_len_curve::<ark_bn254::g1::Config>(header)),
+        CurveId::Bls12_381 => Ok(file_len_curve::<ark_bls12_381::g1::Config>(header)),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+/// A file streamed in by a request, which errors as soon as it holds more bytes than its header declares.
+#[derive(Debug, Default)]
+struct RequestFile {
+    bytes: Vec<u8>,
+    len: Option<u64>,
+}
+
+impl RequestFile {
+    /// Appends the next chunk of the file.
+    #[allow(clippy::result_large_err)]
+    fn extend(&mut self, data: &[u8]) -> Result<(), Status> {
+        self.bytes.try_reserve(data.len()).map_err(|_| {
+            status(InterpolationError::OutOfMemory {
+                requested_bytes: data.len(),
+            })
+        })?;
+        self.bytes.extend_from_slice(data);
+        if self.len.is_none() && self.bytes.len() >= HEADER_SIZE {
+            let header = BinaryHeader::read(&mut &self.bytes[..HEADER_SIZE]).map_err(status)?;
+            self.len = Some(file_len(&header).map_err(status)?.ok_or_else(|| {
+                Status::resource_exhausted("The header declares more bytes than fit in a u64")
+            })?);
+        }
+        match self.len {
+            Some(len) if self.bytes.len() as u64 > len => Err(Status::resource_exhausted(format!(
+                "The file is longer than the {} bytes its header declares",
+                len
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A writer that sends what is written to it as [`CHUNK_SIZE`] byte chunks of a response, so the response is
+/// streamed as it is encoded rather than built in full first.
+struct ChunkWriter {
+    sender: mpsc::Sender<Result<Chunk, Status>>,
+    buffer: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        let data = mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_SIZE));
+        self.sender
+            .blocking_send(Ok(Chunk { data }))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "The response was dropped"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let len = data.len().min(CHUNK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&data[..len]);
+        if self.buffer.len() == CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.send()?;
+        }
+        Ok(())
+    }
+}
+
+async fn convert_curve<E: SrsCurve>(
+    monomial: Vec<u8>,
+    options: ConvertOptions,
+) -> Result<ChunkStream, Status> {
+    let ordering = match options.bit_reversed {
+        false => PointOrdering::Natural,
+        true => PointOrdering::BitReversed,
+    };
+    let encoding = match options.uncompressed {
+        false => PointEncoding::Compressed,
+        true => PointEncoding::Uncompressed,
+    };
+    let endianness = match options.big_endian {
+        false => Endianness::Little,
+        true => Endianness::Big,
+    };
+    let lagrange = blocking(move || {
+        let srs = read_monomial::<_, E>(monomial.as_slice())?;
+        drop(monomial);
+        srs.to_lagrange_with_ordering(ordering)
+    })
+    .await?;
+
+    // An error while encoding can only be sent after the chunks before it, so it ends the stream.
+    let (sender, receiver) = mpsc::channel(1);
+    tokio::task::spawn_blocking(move || {
+        let writer = ChunkWriter {
+            sender: sender.clone(),
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        };
+        if let Err(e) = write_lagrange(writer, &lagrange, encoding, endianness) {
+            let _ = sender.blocking_send(Err(status(e)));
+        }
+    });
+    Ok(Box::pin(ReceiverStream::new(receiver)))
+}
+
+/// This function converts a monomial SRS file to a Lagrange SRS file written with `options`, returning the stream
+/// the file is sent in.
+async fn convert_file(monomial: Vec<u8>, options: ConvertOptions) -> Result<ChunkStream, Status> {
+    match BinaryHeader::read(&mut monomial.as_slice())
+        .map_err(status)?
+        .curve
+    {
+        CurveId::Bn254 => convert_curve::<ark_bn254::g1::Config>(monomial, options).await,
+        CurveId::Bls12_381 => convert_curve::<ark_bls12_381::g1::Config>(monomial, options).await,
+        curve => Err(status(unsupported(curve))),
+    }
+}
+
+fn verify_curve<E: SrsCurve>(monomial: &[u8], lagrange: &[u8]) -> Result<bool, InterpolationError> {
+    check_lagrange_matches_monomial(
+        &read_monomial::<_, E>(monomial)?,
+        &read_lagrange::<_, E>(lagrange)?,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// This function checks that a Lagrange SRS file is the conversion of a monomial SRS file.
+fn verify_files(monomial: &[u8], lagrange: &[u8]) -> Result<bool, InterpolationError> {
+    let curve = BinaryHeader::read(&mut &monomial[..])?.curve;
+    if BinaryHeader::read(&mut &lagrange[..])?.curve != curve {
+        return Err(InterpolationError::InvalidParameters(
+            "The two SRSs are on different curves".to_string(),
+        ));
+    }
+    match curve {
+        CurveId::Bn254 => verify_curve::<ark_bn254::g1::Config>(monomial, lagrange),
+        CurveId::Bls12_381 => verify_curve::<ark_bls12_381::g1::Config>(monomial, lagrange),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+/// A reader over the bytes of a request received so far, which records whether a read went past them.
+struct Received<'a> {
+    bytes: &'a [u8],
+    exhausted: bool,
+}
+
+impl Read for Received<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.bytes.is_empty() && !buf.is_empty() {
+            self.exhausted = true;
+        }
+        self.bytes.read(buf)
+    }
+}
+
+/// The service, which holds no state between requests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SrsService;
+
+#[tonic::async_trait]
+impl proto::srs_interpolation_server::SrsInterpolation for SrsService {
+    type ConvertStream = ChunkStream;
+
+    async fn convert(
+        &self,
+        request: Request<Streaming<ConvertRequest>>,
+    ) -> Result<Response<Self::ConvertStream>, Status> {
+        let mut stream = request.into_inner();
+        let mut options = None;
+        let mut monomial = RequestFile::default();
+        while let Some(message) = stream.message().await? {
+            match message.part {
+                Some(convert_request::Part::Options(given))
+                    if options.is_none() && monomial.bytes.is_empty() =>
+                {
+                    options = Some(given)
+                }
+                Some(convert_request::Part::Options(_)) => {
+                    return Err(Status::invalid_argument(
+                        "The options must be the first message and sent once",
+                    ))
+                }
+                Some(convert_request::Part::Data(data)) => monomial.extend(&data)?,
+                None => {}
+            }
+        }
+        let options = options.unwrap_or_default();
+        Ok(Response::new(convert_file(monomial.bytes, options).await?))
+    }
+
+    async fn verify(
+        &self,
+        request: Request<Streaming<VerifyRequest>>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        let mut stream = request.into_inner();
+        let (mut monomial, mut lagrange) = (RequestFile::default(), RequestFile::default());
+        while let Some(message) = stream.message().await? {
+            match message.part {
+                Some(verify_request::Part::Monomial(data)) => monomial.extend(&data)?,
+                Some(verify_request::Part::Lagrange(data)) => lagrange.extend(&data)?,
+                None => {}
+            }
+        }
+        let matches = blocking(move || verify_files(&monomial.bytes, &lagrange.bytes)).await?;
+        Ok(Response::new(VerifyResponse { matches }))
+    }
+
+    async fn inspect(
+        &self,
+        request: Request<Streaming<Chunk>>,
+    ) -> Result<Response<InspectResponse>, Status> {
+        // The header is at most a few hundred bytes, so the chunks after the one that completes it are not read. Only
+        // an inspection that ran out of bytes waits for the next chunk, and any other error is returned straight away.
+        let mut stream = request.into_inner();
+        let mut bytes = Vec::new();
+        let info = loop {
+            let mut received = Received {
+                bytes: &bytes,
+                exhausted: false,
+            };
+            match inspect(&mut received) {
+                Ok(info) => break info,
+                Err(e) if !received.exhausted => return Err(status(e)),
+                Err(e) => match stream.message().await? {
+                    Some(_) if bytes.len() >= MAX_INSPECT_BYTES => {
+                        return Err(Status::resource_exhausted(format!(
+                            "No header was found in the first {} bytes",
+                            MAX_INSPECT_BYTES
+                        )))
+                    }
+                    Some(chunk) => bytes.extend_from_slice(&chunk.data),
+                    None => return Err(status(e)),
+                },
+            }
+        };
+        Ok(Response::new(InspectResponse {
+            format: format!("{:?}", info.format).to_lowercase(),
+            curve: info.curve.name().to_string(),
+            bases: info
+                .bases
+                .iter()
+                .map(|basis| format!("{:?}", basis).to_lowercase())
+                .collect(),
+            size: info.size,
+            domain_size: info.domain_size,
+            header_sha256: info.header_sha256.to_vec(),
+        }))
+    }
+}
+
+/// This function serves the service on `addr` until the server fails.
+pub async fn serve(addr: SocketAddr) -> Result<(), InterpolationError> {
+    Server::builder()
+        .add_service(SrsInterpolationServer::new(SrsService))
+        .serve(addr)
+        .await
+        .map_err(|e| InterpolationError::TaskError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formats::binary::write_monomial, srs::MonomialSrs, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+    use proto::srs_interpolation_client::SrsInterpolationClient;
+    use tokio::net::TcpListener;
+    use tonic::{codegen::tokio_stream, transport::server::TcpIncoming};
+
+    #[tokio::test]
+    async fn test_service() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(SrsInterpolationServer::new(SrsService))
+                .serve_with_incoming(incoming),
+        );
+        let mut client = SrsInterpolationClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let rng = &mut ark_std::test_rng();
+        let monomial = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let file = write_monomial(
+            Vec::new(),
+            &monomial,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        // The file is sent in small chunks so every request spans several messages.
+        let requests = file
+            .chunks(100)
+            .map(|data| ConvertRequest {
+                part: Some(convert_request::Part::Data(data.to_vec())),
+            })
+            .collect::<Vec<_>>();
+        let mut stream = client
+            .convert(tokio_stream::iter(requests))
+            .await
+            .unwrap()
+            .into_inner();
+        let mut lagrange = Vec::new();
+        while let Some(chunk) = stream.message().await.unwrap() {
+            lagrange.extend_from_slice(&chunk.data);
+        }
+        assert_eq!(
+            read_lagrange::<_, BnConfig>(lagrange.as_slice()).unwrap(),
+            monomial.to_lagrange().unwrap()
+        );
+
+        let requests = file
+            .chunks(100)
+            .map(|data| VerifyRequest {
+                part: Some(verify_request::Part::Monomial(data.to_vec())),
+            })
+            .chain([VerifyRequest {
+                part: Some(verify_request::Part::Lagrange(lagrange.clone())),
+            }])
+            .collect::<Vec<_>>();
+        let response = client.verify(tokio_stream::iter(requests)).await.unwrap();
+        assert!(response.into_inner().matches);
+
+        let chunks = file.chunks(4).map(|data| Chunk {
+            data: data.to_vec(),
+        });
+        let info = client
+            .inspect(tokio_stream::iter(chunks.collect::<Vec<_>>()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!((info.format.as_str(), info.size), ("binary", 16));
+
+        let bad = [ConvertRequest {
+            part: Some(convert_request::Part::Data(vec![0; 10])),
+        }];
+        let error = client.convert(tokio_stream::iter(bad)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        // A request that sends more than its header declares is cut off.
+        let long = [file.clone(), vec![0; 1]].map(|data| ConvertRequest {
+            part: Some(convert_request::Part::Data(data)),
+        });
+        let error = client.convert(tokio_stream::iter(long)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+
+        // An invalid header is reported without waiting for the rest of the stream.
+        let mut header = file[..HEADER_SIZE].to_vec();
+        header[4] = 99;
+        let chunks = tokio_stream::StreamExt::chain(
+            tokio_stream::iter([Chunk { data: header }]),
+            tokio_stream::pending(),
+        );
+        let error =
+            tokio::time::timeout(std::time::Duration::from_secs(10), client.inspect(chunks))
+                .await
+                .unwrap()
+                .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+    }
+}
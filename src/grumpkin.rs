@@ -0,0 +1,63 @@
+//! This module contains Grumpkin, the curve `y^2 = x^3 - 17` over the BN254 scalar field that forms a cycle with
+//! BN254: the order of each curve is the size of the other's base field. Stacks such as Aztec's commit to BN254
+//! circuits with KZG and verify them inside Grumpkin circuits.
+//!
+//! The Grumpkin scalar field is the BN254 base field, whose 2-adicity is one, so only SRSs of up to two points can
+//! be converted with [`crate::srs_to_lagrange`]. Larger ones are converted with [`crate::ecfft`].
+use ark_bn254::{Fq, Fr};
+use ark_ec::{
+    models::CurveConfig,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+};
+use ark_ff::{Field, MontFp};
+
+/// The configuration of Grumpkin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GrumpkinConfig;
+
+/// A Grumpkin point in affine form.
+pub type GrumpkinAffine = Affine<GrumpkinConfig>;
+/// A Grumpkin point in projective form.
+pub type GrumpkinProjective = Projective<GrumpkinConfig>;
+
+impl CurveConfig for GrumpkinConfig {
+    type BaseField = Fr;
+    type ScalarField = Fq;
+
+    /// The group of points has prime order.
+    const COFACTOR: &'static [u64] = &[0x1];
+    const COFACTOR_INV: Fq = Fq::ONE;
+}
+
+impl SWCurveConfig for GrumpkinConfig {
+    const COEFF_A: Fr = Fr::ZERO;
+    const COEFF_B: Fr = MontFp!("-17");
+
+    /// The generator `(1, sqrt(-16))` used by Aztec.
+    const GENERATOR: GrumpkinAffine = GrumpkinAffine::new_unchecked(
+        Fr::ONE,
+        MontFp!("17631683881184975370165255887551781615748388533673675138860"),
+    );
+
+    #[inline(always)]
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Fr::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::PrimeField;
+    use ark_std::Zero;
+
+    #[test]
+    fn test_grumpkin() {
+        let generator = GrumpkinAffine::generator();
+        assert!(generator.is_on_curve());
+        // The order of the group is the BN254 base field modulus.
+        assert!(generator.mul_bigint(Fq::MODULUS).is_zero());
+        assert!(!(generator * Fq::from(2u64)).into_affine().is_zero());
+    }
+}
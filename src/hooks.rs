@@ -0,0 +1,152 @@
+//! This module hands out the intermediate points of the radix-2 conversion after every round, so that ports of the
+//! algorithm to other languages or hardware can diff their state against this implementation round by round.
+//!
+//! The conversion first moves the monomial points into bit-reversed order and then runs `log n` rounds of the FFT
+//! style operation with the inverse generator of the domain. After round `k` every block of `2^k` consecutive points
+//! holds the group FFT, over the domain of size `2^k`, of the points the block started with. No round multiplies by
+//! `1 / n`, which happens once after the last, so the points of the last round are the Lagrange points times `n`.
+#[cfg(feature = "manifest")]
+use crate::manifest::{digest_points, PointsDigest};
+use crate::{
+    buffer::{try_copied, VecProvider},
+    domain_size_inv,
+    error::InterpolationError,
+    group_fft_radix2_with,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The points of a conversion right after one of its rounds.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Copy(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig")
+)]
+pub struct RoundState<'a, E: SWCurveConfig> {
+    /// The number of rounds done, from one to [`RoundState::rounds`].
+    pub round: usize,
+    /// The number of rounds of the conversion, which is the log of the number of points.
+    pub rounds: usize,
+    /// The points after the round, in the layout the module documentation describes.
+    pub points: &'a [Affine<E>],
+}
+
+impl<E: SWCurveConfig> RoundState<'_, E> {
+    /// The digest of the points after the round, which is cheaper to log and compare than the points themselves.
+    #[cfg(feature = "manifest")]
+    pub fn digest(&self) -> PointsDigest {
+        digest_points(self.points)
+    }
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] but calls `hook` with the points after each round. It
+/// always takes the radix-2 rounds, whatever the size, and `hook` is not called for a single point, which has none.
+pub fn srs_to_lagrange_with_hook<E, F>(
+    points: &[Affine<E>],
+    mut hook: impl FnMut(RoundState<'_, E>),
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    enter_span!(DEBUG, "srs_to_lagrange_with_hook", size = points.len());
+    let mut result = try_copied(points)?;
+    group_fft_radix2_with(
+        &mut result,
+        domain.group_gen_inv(),
+        &VecProvider,
+        |points, round, rounds| {
+            hook(RoundState {
+                round,
+                rounds,
+                points,
+            });
+            Ok(())
+        },
+    )?;
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(result).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(result)
+}
+
+/// This function converts `points` as [`srs_to_lagrange_with_hook`] does and returns the digest of the points
+/// after each round, indexed by the round less one.
+#[cfg(feature = "manifest")]
+pub fn round_digests<E, F>(points: &[Affine<E>]) -> Result<Vec<PointsDigest>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let mut digests = Vec::new();
+    srs_to_lagrange_with_hook(points, |state| digests.push(state.digest()))?;
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr};
+
+    #[test]
+    fn test_srs_to_lagrange_with_hook() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap();
+        let mut states = Vec::new();
+        let lagrange = srs_to_lagrange_with_hook(&srs, |state| {
+            states.push((state.round, state.rounds, state.points.to_vec()))
+        })
+        .unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&srs).unwrap());
+        assert_eq!(
+            states
+                .iter()
+                .map(|(round, rounds, _)| (*round, *rounds))
+                .collect::<Vec<_>>(),
+            [(1, 3), (2, 3), (3, 3)]
+        );
+
+        // The first round adds and subtracts the points an index of four apart, which bit reversal made neighbours.
+        let first = &states[0].2;
+        assert_eq!(first[0], (srs[0] + srs[4]).into_affine());
+        assert_eq!(first[1], (srs[0] - srs[4]).into_affine());
+        assert_eq!(first[6], (srs[3] + srs[7]).into_affine());
+        // The last round is the result before multiplying by the inverse of the size.
+        let last = &states[2].2;
+        for (point, expected) in last.iter().zip(lagrange.iter()) {
+            assert_eq!(*point, (*expected * Fr::from(8u64)).into_affine());
+        }
+
+        assert_eq!(
+            srs_to_lagrange_with_hook(&srs[..1], |_| panic!("No rounds")).unwrap(),
+            srs[..1]
+        );
+        assert!(srs_to_lagrange_with_hook(&srs[..6], |_| ()).is_err());
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn test_round_digests() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let mut expected = Vec::new();
+        srs_to_lagrange_with_hook(&srs, |state| expected.push(digest_points(state.points)))
+            .unwrap();
+        assert_eq!(round_digests(&srs).unwrap(), expected);
+        assert_eq!(expected.len(), 4);
+    }
+}
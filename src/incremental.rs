@@ -0,0 +1,170 @@
+//! This module contains [`ConversionState`], a conversion that runs a bounded amount of work each time it is
+//! stepped, for single-threaded hosts such as a browser's main thread or a cooperative scheduler that cannot give a
+//! conversion long enough to finish in one go.
+//!
+//! A conversion is the bit reversal permutation, the rounds of the FFT style operation and the final rescaling.
+//! Each step moves or rescales a number of points up to its budget, or runs the butterflies on a number of pairs
+//! up to its budget, but never works across two of those stages.
+use crate::{
+    buffer::try_filled,
+    domain_size_inv,
+    error::InterpolationError,
+    utils::{bit_reverse, fft_round_pairs, scratch_len},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::Field;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+/// The part of a conversion that the next step works on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// The bit reversal permutation, from the given index.
+    Permute(usize),
+    /// The given round, from the given pair.
+    Round(usize, usize),
+    /// The rescaling, from the given index.
+    Rescale(usize),
+    /// Nothing is left to do.
+    Done,
+}
+
+/// A conversion from the monomial to the Lagrange basis over the arkworks domain that is run a step at a time,
+/// giving the same result as [`crate::srs_to_lagrange`].
+#[derive(Derivative)]
+#[derivative(Clone(bound = "E: SWCurveConfig"), Debug(bound = "E: SWCurveConfig"))]
+pub struct ConversionState<E: SWCurveConfig> {
+    points: Vec<Affine<E>>,
+    gen_inv: E::ScalarField,
+    stage: Stage,
+    #[derivative(Debug = "ignore")]
+    scratch: Vec<E::BaseField>,
+}
+
+impl<E: SWCurveConfig> ConversionState<E> {
+    /// This function starts the conversion of `points` without doing any of the work. It errors if the number of
+    /// points is not a power of two.
+    pub fn new(points: Vec<Affine<E>>) -> Result<Self, InterpolationError> {
+        if !points.len().is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(points.len())
+            .ok_or(InterpolationError::SizeError)?;
+        let scratch = try_filled(scratch_len::<E>(points.len()).max(2), E::BaseField::ZERO)?;
+        Ok(Self {
+            points,
+            gen_inv: domain.group_gen_inv(),
+            stage: Stage::Permute(0),
+            scratch,
+        })
+    }
+
+    /// Whether the conversion is complete.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done
+    }
+
+    /// This function does at most `budget` units of work, each the move or rescaling of one point or one
+    /// butterfly, and returns whether the conversion is complete. Every step makes progress, even with a budget of
+    /// zero, and a step on a complete conversion does nothing.
+    pub fn step(&mut self, budget: usize) -> Result<bool, InterpolationError> {
+        let size = self.points.len();
+        let log_size = size.ilog2() as usize;
+        let budget = budget.max(1);
+        self.stage = match self.stage {
+            Stage::Permute(start) => {
+                let end = size.min(start.saturating_add(budget));
+                for i in start..end {
+                    let j = bit_reverse(i, log_size)?;
+                    if i < j {
+                        self.points.swap(i, j);
+                    }
+                }
+                match (end, log_size) {
+                    (end, _) if end < size => Stage::Permute(end),
+                    (_, 0) => Stage::Rescale(0),
+                    _ => Stage::Round(1, 0),
+                }
+            }
+            Stage::Round(round, start) => {
+                // The pairs of a step must be a power of two that fits in the scratch, which the start of every
+                // step stays aligned to.
+                let max_pairs = (self.scratch.len() / 2).min(size / 2).min(budget);
+                let mut pairs = 1 << max_pairs.ilog2();
+                while !start.is_multiple_of(pairs) {
+                    pairs >>= 1;
+                }
+                let root = self.gen_inv.pow([(size >> round) as u64]);
+                fft_round_pairs(
+                    &mut self.points,
+                    root,
+                    round,
+                    start,
+                    pairs,
+                    &mut self.scratch,
+                )?;
+                match start + pairs {
+                    end if end < size / 2 => Stage::Round(round, end),
+                    _ if round < log_size => Stage::Round(round + 1, 0),
+                    _ => Stage::Rescale(0),
+                }
+            }
+            Stage::Rescale(start) => {
+                let end = size.min(start.saturating_add(budget));
+                let size_inv = domain_size_inv::<E::ScalarField>(size)?;
+                for point in self.points[start..end].iter_mut() {
+                    *point = (*point * size_inv).into_affine();
+                }
+                if end < size {
+                    Stage::Rescale(end)
+                } else {
+                    Stage::Done
+                }
+            }
+            Stage::Done => Stage::Done,
+        };
+        Ok(self.is_done())
+    }
+
+    /// This function returns the Lagrange points, running whatever work is left first.
+    pub fn finish(mut self) -> Result<Vec<Affine<E>>, InterpolationError> {
+        while !self.step(usize::MAX)? {}
+        Ok(self.points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_conversion_state() {
+        let rng = &mut ark_std::test_rng();
+        for size in [1, 2, 64] {
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            let expected = srs_to_lagrange(&srs).unwrap();
+            // Budgets that do and do not divide the size, including ones that straddle stages.
+            for budget in [0, 1, 3, 5, 16, 1000] {
+                let mut state = ConversionState::new(srs.clone()).unwrap();
+                let mut steps = 0;
+                while !state.step(budget).unwrap() {
+                    steps += 1;
+                    assert!(steps < 10_000);
+                }
+                assert!(state.step(budget).unwrap());
+                assert_eq!(state.finish().unwrap(), expected);
+            }
+            assert_eq!(
+                ConversionState::new(srs).unwrap().finish().unwrap(),
+                expected
+            );
+        }
+        assert!(ConversionState::<BnConfig>::new(vec![]).is_err());
+    }
+}
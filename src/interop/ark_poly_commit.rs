@@ -0,0 +1,185 @@
+//! This module converts between this crate's SRS types and the KZG10 parameters of `ark-poly-commit`.
+//!
+//! The conversions into `ark-poly-commit` only carry the powers of `g`. The powers of `gamma * g` used for hiding
+//! commitments and the negative powers of `h` cannot be recovered from an SRS, so they are left empty unless they
+//! come from existing parameters.
+use crate::{
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs},
+    srs_to_lagrange,
+};
+use ::ark_poly_commit::kzg10::{Powers, UniversalParams};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_std::{borrow::Cow, collections::BTreeMap, vec::Vec};
+
+/// This function returns the powers of `g` of `params` as a monomial SRS.
+pub fn monomial_srs_from_params<P, E>(params: &UniversalParams<P>) -> MonomialSrs<E>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    MonomialSrs::new(params.powers_of_g.clone())
+}
+
+/// This function returns the powers of `g` of a committer key as a monomial SRS.
+pub fn monomial_srs_from_powers<P, E>(powers: &Powers<'_, P>) -> MonomialSrs<E>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    MonomialSrs::new(powers.powers_of_g.to_vec())
+}
+
+/// This function builds KZG10 parameters from a monomial SRS and the G2 points `h` and `beta * h`.
+/// The parameters have no powers of `gamma * g` or negative powers of `h`, so they only support non-hiding
+/// commitments and the verification of degree bounds is not possible with them.
+pub fn universal_params_from_srs<P, E>(
+    srs: &MonomialSrs<E>,
+    h: P::G2Affine,
+    beta_h: P::G2Affine,
+) -> UniversalParams<P>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    UniversalParams {
+        powers_of_g: srs.points().to_vec(),
+        powers_of_gamma_g: BTreeMap::new(),
+        h,
+        beta_h,
+        neg_powers_of_h: BTreeMap::new(),
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    }
+}
+
+/// This function returns a non-hiding committer key borrowing the points of a monomial SRS.
+pub fn powers_from_srs<P, E>(srs: &MonomialSrs<E>) -> Powers<'_, P>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    Powers {
+        powers_of_g: Cow::Borrowed(srs.points()),
+        powers_of_gamma_g: Cow::Owned(Vec::new()),
+    }
+}
+
+/// This function returns a non-hiding committer key borrowing the points of a Lagrange SRS. Committing to a
+/// "polynomial" with this key commits to the polynomial whose evaluations over the domain of the SRS are its
+/// coefficients, in the order the points are stored.
+pub fn powers_from_lagrange<P, E>(srs: &LagrangeSrs<E>) -> Powers<'_, P>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    Powers {
+        powers_of_g: Cow::Borrowed(srs.points()),
+        powers_of_gamma_g: Cow::Owned(Vec::new()),
+    }
+}
+
+/// This function trims `params` to the smallest power of two domain supporting polynomials of degree
+/// `supported_degree` and returns a committer key whose powers of `g` are in the Lagrange basis over the arkworks
+/// domain, in natural order. If `params` has enough powers of `gamma * g` they are converted too, so the key also
+/// supports hiding commitments.
+pub fn trim_lagrange<P, E>(
+    params: &UniversalParams<P>,
+    supported_degree: usize,
+) -> Result<Powers<'static, P>, InterpolationError>
+where
+    P: Pairing<G1Affine = Affine<E>, ScalarField = E::ScalarField>,
+    E: SWCurveConfig,
+{
+    let size = (supported_degree + 1).next_power_of_two();
+    if params.powers_of_g.len() < size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The parameters have {} powers of g but {} are needed",
+            params.powers_of_g.len(),
+            size
+        )));
+    }
+    let powers_of_g = srs_to_lagrange(&params.powers_of_g[..size])?;
+
+    let powers_of_gamma_g = (0..size)
+        .map(|i| params.powers_of_gamma_g.get(&i).copied())
+        .collect::<Option<Vec<_>>>();
+    let powers_of_gamma_g = match powers_of_gamma_g {
+        Some(powers) => srs_to_lagrange(&powers)?,
+        None => Vec::new(),
+    };
+
+    Ok(Powers {
+        powers_of_g: Cow::Owned(powers_of_g),
+        powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::ark_poly_commit::kzg10::KZG10;
+    use ark_bn254::{g1::Config as BnConfig, Bn254, Fr};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    };
+
+    type Kzg = KZG10<Bn254, DensePolynomial<Fr>>;
+
+    #[test]
+    fn test_lagrange_committer_key() {
+        let rng = &mut ark_std::test_rng();
+        let params = Kzg::setup(15, false, rng).unwrap();
+        let srs = monomial_srs_from_params::<Bn254, BnConfig>(&params);
+        assert_eq!(srs.len(), 16);
+
+        // Committing to the evaluations with the Lagrange key matches committing to the polynomial.
+        let polynomial = DensePolynomial::<Fr>::rand(12, rng);
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let evaluations = DensePolynomial::from_coefficients_vec(domain.fft(&polynomial.coeffs));
+        let (commitment, _) = Kzg::commit(&powers_from_srs(&srs), &polynomial, None, None).unwrap();
+        let lagrange_powers = trim_lagrange(&params, 12).unwrap();
+        assert_eq!(lagrange_powers.powers_of_gamma_g.len(), 16);
+        let (lagrange_commitment, _) =
+            Kzg::commit(&lagrange_powers, &evaluations, None, None).unwrap();
+        assert_eq!(commitment, lagrange_commitment);
+
+        let lagrange_srs = srs.to_lagrange().unwrap();
+        let (commitment, _) = Kzg::commit(
+            &powers_from_lagrange(&lagrange_srs),
+            &evaluations,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(commitment, lagrange_commitment);
+        assert!(trim_lagrange(&params, 16).is_err());
+
+        // The parameters survive a round trip through the SRS.
+        let rebuilt = universal_params_from_srs::<Bn254, _>(&srs, params.h, params.beta_h);
+        assert_eq!(rebuilt.powers_of_g, params.powers_of_g);
+        assert_eq!(
+            monomial_srs_from_powers::<Bn254, _>(&powers_from_srs(&srs)),
+            srs
+        );
+    }
+
+    #[test]
+    fn test_hiding_lagrange_committer_key() {
+        let rng = &mut ark_std::test_rng();
+        let params = Kzg::setup(15, false, rng).unwrap();
+        let powers = trim_lagrange(&params, 7).unwrap();
+        assert_eq!(powers.powers_of_g.len(), 8);
+        assert_eq!(powers.powers_of_gamma_g.len(), 8);
+        let gamma_powers = (0..8)
+            .map(|i| params.powers_of_gamma_g[&i])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            powers.powers_of_gamma_g.to_vec(),
+            srs_to_lagrange(&gamma_powers).unwrap()
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! This module converts points between arkworks 0.4, which this crate is built on, and arkworks 0.5, so projects
+//! that have moved to the newer generation can convert their SRSs without the rest of their stack changing with it.
+//!
+//! Both generations encode a short Weierstrass point as its little endian coordinates with the infinity flag in the
+//! top bits of the last byte, so the conversions go through the uncompressed encoding, which costs a copy of the
+//! coordinates and none of the square roots of the compressed one. [`Arkworks05Curve`] pairs up the configurations
+//! of the two generations, for the G1 curves of BN254 and BLS12-381.
+use crate::{error::InterpolationError, srs_to_lagrange};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec_05::short_weierstrass::{Affine as Affine05, SWCurveConfig as SWCurveConfig05};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_serialize_05::{
+    CanonicalDeserialize as CanonicalDeserialize05, CanonicalSerialize as CanonicalSerialize05,
+};
+use ark_std::vec::Vec;
+
+/// An arkworks 0.4 curve with an arkworks 0.5 counterpart.
+pub trait Arkworks05Curve: SWCurveConfig {
+    /// The arkworks 0.5 configuration of the same curve.
+    type Config05: SWCurveConfig05;
+}
+
+impl Arkworks05Curve for ark_bn254::g1::Config {
+    type Config05 = ark_bn254_05::g1::Config;
+}
+
+impl Arkworks05Curve for ark_bls12_381::g1::Config {
+    type Config05 = ark_bls12_381_05::g1::Config;
+}
+
+/// This function returns the uncompressed encoding of an arkworks 0.5 point.
+fn encode_05<C: SWCurveConfig05>(point: &Affine05<C>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    CanonicalSerialize05::serialize_uncompressed(point, &mut bytes)
+        .expect("serializing into a vector is infallible");
+    bytes
+}
+
+/// This function reports a point arkworks 0.4 will not decode.
+fn invalid_point(e: SerializationError) -> InterpolationError {
+    InterpolationError::InvalidParameters(format!("invalid arkworks 0.5 point: {}", e))
+}
+
+/// This function converts a point from arkworks 0.5 without checking that it is on the curve or in the subgroup.
+pub fn affine_from_05_unchecked<E: Arkworks05Curve>(
+    point: &Affine05<E::Config05>,
+) -> Result<Affine<E>, InterpolationError> {
+    Affine::deserialize_uncompressed_unchecked(encode_05(point).as_slice()).map_err(invalid_point)
+}
+
+/// This function converts a point from arkworks 0.5, checking that it is on the curve and in the subgroup.
+pub fn affine_from_05<E: Arkworks05Curve>(
+    point: &Affine05<E::Config05>,
+) -> Result<Affine<E>, InterpolationError> {
+    Affine::deserialize_uncompressed(encode_05(point).as_slice()).map_err(invalid_point)
+}
+
+/// This function converts a point to arkworks 0.5. Like the point itself, the result is unchecked.
+pub fn affine_to_05<E: Arkworks05Curve>(point: &Affine<E>) -> Affine05<E::Config05> {
+    let mut bytes = Vec::new();
+    point
+        .serialize_uncompressed(&mut bytes)
+        .expect("serializing into a vector is infallible");
+    CanonicalDeserialize05::deserialize_uncompressed_unchecked(bytes.as_slice())
+        .expect("both generations encode the coordinates of a curve the same way")
+}
+
+/// This function converts a slice of points from arkworks 0.5, checking every point.
+pub fn affines_from_05<E: Arkworks05Curve>(
+    points: &[Affine05<E::Config05>],
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    points.iter().map(affine_from_05).collect()
+}
+
+/// This function converts a slice of points to arkworks 0.5.
+pub fn affines_to_05<E: Arkworks05Curve>(points: &[Affine<E>]) -> Vec<Affine05<E::Config05>> {
+    points.iter().map(affine_to_05).collect()
+}
+
+/// This function converts a monomial SRS held as arkworks 0.5 points to the Lagrange basis, returning arkworks 0.5
+/// points. The roots of unity of the curves did not change between the generations, so neither does the result.
+pub fn srs_to_lagrange_05<E, F>(
+    points: &[Affine05<E::Config05>],
+) -> Result<Vec<Affine05<E::Config05>>, InterpolationError>
+where
+    E: Arkworks05Curve<ScalarField = F>,
+    F: PrimeField,
+{
+    let points = affines_from_05::<E>(points)?;
+    Ok(affines_to_05(&srs_to_lagrange(&points)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bls12_381::g1::Config as BlsConfig;
+    use ark_bn254::g1::Config as BnConfig;
+    use ark_ec::AffineRepr;
+    use ark_ec_05::AffineRepr as AffineRepr05;
+    use ark_ff::One;
+
+    fn check_round_trip<E: Arkworks05Curve>(points: &[Affine<E>]) {
+        let converted = affines_to_05(points);
+        assert_eq!(affines_from_05(&converted).unwrap(), points);
+        assert_eq!(
+            affine_to_05(&Affine::<E>::identity()),
+            Affine05::<E::Config05>::zero()
+        );
+        assert_eq!(
+            affine_to_05(&Affine::<E>::generator()),
+            Affine05::<E::Config05>::generator()
+        );
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_05() {
+        let rng = &mut ark_std::test_rng();
+        let bn_points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        check_round_trip(&bn_points);
+        let bls_points = gen_srs_for_testing::<BlsConfig, _>(rng, 7).unwrap();
+        check_round_trip(&bls_points);
+
+        let lagrange = srs_to_lagrange_05::<BnConfig, _>(&affines_to_05(&bn_points)).unwrap();
+        assert_eq!(
+            affines_from_05(&lagrange).unwrap(),
+            srs_to_lagrange(&bn_points).unwrap()
+        );
+
+        // A point off the curve is rejected by the checked conversion only.
+        let off_curve = affine_to_05(&ark_bn254::G1Affine::new_unchecked(
+            ark_bn254::Fq::one(),
+            ark_bn254::Fq::one(),
+        ));
+        assert!(affine_from_05::<BnConfig>(&off_curve).is_err());
+        assert!(affine_from_05_unchecked::<BnConfig>(&off_curve).is_ok());
+    }
+}
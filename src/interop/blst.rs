@@ -0,0 +1,231 @@
+//! This module converts BLS12-381 points between arkworks and `blst`. Both libraries store base field elements as
+//! six little endian 64 bit limbs in Montgomery form with the same Montgomery constant, so the conversions copy limbs
+//! directly instead of going through a byte encoding. The only difference in layout is the point at infinity, which
+//! arkworks marks with a flag and `blst` represents as the all zero point.
+use crate::{point::AffinePoint, srs_to_lagrange, InterpolationError};
+use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_serialize::{SerializationError, Valid};
+use ark_std::vec::Vec;
+use blst::{blst_fp, blst_fp2, blst_p1_affine, blst_p2_affine};
+
+/// This function converts a base field element to `blst`.
+fn fp_to_blst(value: &Fq) -> blst_fp {
+    blst_fp { l: value.0 .0 }
+}
+
+/// This function converts a base field element from `blst`, checking that it is reduced.
+fn fp_from_blst(value: &blst_fp) -> Result<Fq, InterpolationError> {
+    let limbs = BigInt::new(value.l);
+    if limbs >= Fq::MODULUS {
+        return Err(InterpolationError::FieldError(
+            "blst field element is not reduced".to_string(),
+        ));
+    }
+    Ok(Fq::new_unchecked(limbs))
+}
+
+/// This function converts a quadratic extension field element to `blst`.
+fn fp2_to_blst(value: &Fq2) -> blst_fp2 {
+    blst_fp2 {
+        fp: [fp_to_blst(&value.c0), fp_to_blst(&value.c1)],
+    }
+}
+
+/// This function converts a quadratic extension field element from `blst`, checking that it is reduced.
+fn fp2_from_blst(value: &blst_fp2) -> Result<Fq2, InterpolationError> {
+    Ok(Fq2::new(
+        fp_from_blst(&value.fp[0])?,
+        fp_from_blst(&value.fp[1])?,
+    ))
+}
+
+/// This function checks that a converted point is on the curve and in the prime order subgroup.
+fn check_point<P: Valid>(point: P) -> Result<P, InterpolationError> {
+    point.check().map_err(|e: SerializationError| {
+        InterpolationError::InvalidParameters(format!("invalid blst point: {}", e))
+    })?;
+    Ok(point)
+}
+
+/// This function converts a G1 point to `blst`.
+pub fn p1_affine_to_blst(point: &G1Affine) -> blst_p1_affine {
+    if point.infinity {
+        return blst_p1_affine::default();
+    }
+    blst_p1_affine {
+        x: fp_to_blst(&point.x),
+        y: fp_to_blst(&point.y),
+    }
+}
+
+/// This function converts a G1 point from `blst` without checking that it is on the curve or in the subgroup.
+///
+/// The coordinates must be reduced, which is always the case for points produced by `blst` itself.
+pub fn p1_affine_from_blst_unchecked(
+    point: &blst_p1_affine,
+) -> Result<G1Affine, InterpolationError> {
+    let x = fp_from_blst(&point.x)?;
+    let y = fp_from_blst(&point.y)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    Ok(G1Affine::new_unchecked(x, y))
+}
+
+/// This function converts a G1 point from `blst`, checking that it is on the curve and in the subgroup.
+pub fn p1_affine_from_blst(point: &blst_p1_affine) -> Result<G1Affine, InterpolationError> {
+    check_point(p1_affine_from_blst_unchecked(point)?)
+}
+
+/// This function converts a G2 point to `blst`.
+pub fn p2_affine_to_blst(point: &G2Affine) -> blst_p2_affine {
+    if point.infinity {
+        return blst_p2_affine::default();
+    }
+    blst_p2_affine {
+        x: fp2_to_blst(&point.x),
+        y: fp2_to_blst(&point.y),
+    }
+}
+
+/// This function converts a G2 point from `blst` without checking that it is on the curve or in the subgroup.
+///
+/// The coordinates must be reduced, which is always the case for points produced by `blst` itself.
+pub fn p2_affine_from_blst_unchecked(
+    point: &blst_p2_affine,
+) -> Result<G2Affine, InterpolationError> {
+    let x = fp2_from_blst(&point.x)?;
+    let y = fp2_from_blst(&point.y)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    Ok(G2Affine::new_unchecked(x, y))
+}
+
+/// This function converts a G2 point from `blst`, checking that it is on the curve and in the subgroup.
+pub fn p2_affine_from_blst(point: &blst_p2_affine) -> Result<G2Affine, InterpolationError> {
+    check_point(p2_affine_from_blst_unchecked(point)?)
+}
+
+impl AffinePoint for blst_p1_affine {
+    type Config = ark_bls12_381::g1::Config;
+
+    fn identity() -> Self {
+        blst_p1_affine::default()
+    }
+
+    fn is_identity(&self) -> bool {
+        *self == blst_p1_affine::default()
+    }
+
+    fn xy(&self) -> Result<(Fq, Fq), InterpolationError> {
+        Ok((fp_from_blst(&self.x)?, fp_from_blst(&self.y)?))
+    }
+
+    fn from_xy_unchecked(x: Fq, y: Fq) -> Self {
+        blst_p1_affine {
+            x: fp_to_blst(&x),
+            y: fp_to_blst(&y),
+        }
+    }
+}
+
+/// This function converts a slice of G1 points to `blst`.
+pub fn p1_affines_to_blst(points: &[G1Affine]) -> Vec<blst_p1_affine> {
+    points.iter().map(p1_affine_to_blst).collect()
+}
+
+/// This function converts a slice of G1 points from `blst`, checking every point.
+pub fn p1_affines_from_blst(
+    points: &[blst_p1_affine],
+) -> Result<Vec<G1Affine>, InterpolationError> {
+    points.iter().map(p1_affine_from_blst).collect()
+}
+
+/// This function converts a monomial SRS held as `blst` points to the Lagrange basis, returning `blst` points.
+pub fn srs_to_lagrange_blst(
+    points: &[blst_p1_affine],
+) -> Result<Vec<blst_p1_affine>, InterpolationError> {
+    let points = p1_affines_from_blst(points)?;
+    Ok(p1_affines_to_blst(&srs_to_lagrange(&points)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bls12_381::g1::Config as BlsConfig;
+    use ark_ec::AffineRepr;
+    use ark_serialize::CanonicalSerialize;
+    use blst::{blst_p1_affine_compress, blst_p2_affine_compress};
+
+    #[test]
+    fn test_generators_match() {
+        // SAFETY: blst returns pointers to static generator points.
+        let (g1, g2) = unsafe {
+            (
+                *blst::blst_p1_affine_generator(),
+                *blst::blst_p2_affine_generator(),
+            )
+        };
+        assert_eq!(p1_affine_to_blst(&G1Affine::generator()), g1);
+        assert_eq!(p2_affine_to_blst(&G2Affine::generator()), g2);
+        assert_eq!(p1_affine_from_blst(&g1).unwrap(), G1Affine::generator());
+        assert_eq!(p2_affine_from_blst(&g2).unwrap(), G2Affine::generator());
+    }
+
+    #[test]
+    fn test_compressed_bytes_match() {
+        let rng = &mut ark_std::test_rng();
+        let mut points = gen_srs_for_testing::<BlsConfig, _>(rng, 7).unwrap();
+        points.push(G1Affine::identity());
+        for point in points.iter() {
+            let mut expected = Vec::new();
+            point.serialize_compressed(&mut expected).unwrap();
+            let mut bytes = [0u8; 48];
+            // SAFETY: `bytes` is large enough for a compressed G1 point.
+            unsafe { blst_p1_affine_compress(bytes.as_mut_ptr(), &p1_affine_to_blst(point)) };
+            assert_eq!(bytes.as_slice(), expected.as_slice());
+        }
+
+        let g2 = (G2Affine::generator() * ark_bls12_381::Fr::from(5u64)).into();
+        for point in [g2, G2Affine::identity()] {
+            let mut expected = Vec::new();
+            point.serialize_compressed(&mut expected).unwrap();
+            let mut bytes = [0u8; 96];
+            // SAFETY: `bytes` is large enough for a compressed G2 point.
+            unsafe { blst_p2_affine_compress(bytes.as_mut_ptr(), &p2_affine_to_blst(&point)) };
+            assert_eq!(bytes.as_slice(), expected.as_slice());
+            assert_eq!(
+                p2_affine_from_blst(&p2_affine_to_blst(&point)).unwrap(),
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_blst() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BlsConfig, _>(rng, 15).unwrap();
+        let lagrange = srs_to_lagrange_blst(&p1_affines_to_blst(&points)).unwrap();
+        assert_eq!(
+            p1_affines_from_blst(&lagrange).unwrap(),
+            srs_to_lagrange(&points).unwrap()
+        );
+        assert_eq!(
+            crate::point::srs_to_lagrange_points(&p1_affines_to_blst(&points)).unwrap(),
+            lagrange
+        );
+
+        // An unreduced coordinate is rejected.
+        let mut bad = p1_affine_to_blst(&points[1]);
+        bad.x.l = [u64::MAX; 6];
+        assert!(p1_affine_from_blst(&bad).is_err());
+        // A point off the curve is rejected by the checked conversion only.
+        let mut off_curve = p1_affine_to_blst(&points[1]);
+        off_curve.y = off_curve.x;
+        assert!(p1_affine_from_blst(&off_curve).is_err());
+        assert!(p1_affine_from_blst_unchecked(&off_curve).is_ok());
+    }
+}
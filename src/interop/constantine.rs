@@ -0,0 +1,302 @@
+//! This module converts SRSs held in the point structs of Constantine, the constant time library some Ethereum
+//! clients take their curve arithmetic from, in place.
+//!
+//! Constantine is written in Nim and is not a Rust dependency here, so the structs below mirror the layout of the
+//! `bn254_snarks_g1_aff` and `bls12_381_g1_aff` types of its C headers on 64 bit targets, where a field element is
+//! its Montgomery residue in little endian 64 bit limbs. Arkworks has the same Montgomery constant for both curves,
+//! so the coordinates move across as limbs. Constantine marks the point at infinity as the all zero point.
+//!
+//! In place means the result is written back over the caller's points, not that the rounds run on them: the points
+//! are copied into arkworks points for the rounds and the result is copied back, limb for limb and without any
+//! serialization, which takes two more copies of the points in memory for the length of the conversion.
+//!
+//! With the `ffi` feature the in place conversions are also exported over the C ABI, as declared in
+//! `include/srs_interpolation.h`, so a C caller can pass the points it holds in Constantine's types directly.
+use crate::{
+    error::InterpolationError,
+    point::{AffinePoint, BaseField},
+    srs::{MonomialSrs, PointOrdering},
+};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{BigInt, Fp, FpConfig, PrimeField};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+/// Constantine's `bn254_snarks_fp`, a BN254 base field element.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bn254SnarksFp {
+    /// The Montgomery residue, least significant limb first.
+    pub limbs: [u64; 4],
+}
+
+/// Constantine's `bn254_snarks_g1_aff`, an affine BN254 G1 point.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bn254SnarksG1Aff {
+    /// The x-coordinate.
+    pub x: Bn254SnarksFp,
+    /// The y-coordinate.
+    pub y: Bn254SnarksFp,
+}
+
+/// Constantine's `bls12_381_fp`, a BLS12-381 base field element.
+#[cfg(feature = "bls12-381")]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bls12381Fp {
+    /// The Montgomery residue, least significant limb first.
+    pub limbs: [u64; 6],
+}
+
+/// Constantine's `bls12_381_g1_aff`, an affine BLS12-381 G1 point.
+#[cfg(feature = "bls12-381")]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bls12381G1Aff {
+    /// The x-coordinate.
+    pub x: Bls12381Fp,
+    /// The y-coordinate.
+    pub y: Bls12381Fp,
+}
+
+/// This function converts the limbs of a Montgomery residue to arkworks, checking that they are reduced.
+fn fp_from_limbs<P: FpConfig<N>, const N: usize>(
+    limbs: [u64; N],
+) -> Result<Fp<P, N>, InterpolationError> {
+    let limbs = BigInt::new(limbs);
+    if limbs >= Fp::<P, N>::MODULUS {
+        return Err(InterpolationError::SerializationError(
+            "Constantine field element is not reduced".to_string(),
+        ));
+    }
+    Ok(Fp(limbs, PhantomData))
+}
+
+/// This macro implements [`AffinePoint`] for a mirrored Constantine point with coordinates of type `$fp` on the
+/// curve `$config`.
+macro_rules! impl_affine_point {
+    ($point:ty, $fp:ident, $config:ty) => {
+        impl AffinePoint for $point {
+            type Config = $config;
+
+            fn identity() -> Self {
+                Self::default()
+            }
+
+            fn is_identity(&self) -> bool {
+                *self == Self::default()
+            }
+
+            fn xy(&self) -> Result<(BaseField<Self>, BaseField<Self>), InterpolationError> {
+                Ok((fp_from_limbs(self.x.limbs)?, fp_from_limbs(self.y.limbs)?))
+            }
+
+            fn from_xy_unchecked(x: BaseField<Self>, y: BaseField<Self>) -> Self {
+                Self {
+                    x: $fp { limbs: x.0 .0 },
+                    y: $fp { limbs: y.0 .0 },
+                }
+            }
+        }
+    };
+}
+
+impl_affine_point!(Bn254SnarksG1Aff, Bn254SnarksFp, ark_bn254::g1::Config);
+#[cfg(feature = "bls12-381")]
+impl_affine_point!(Bls12381G1Aff, Bls12381Fp, ark_bls12_381::g1::Config);
+
+/// This function converts the monomial SRS in `points` to the Lagrange basis over the arkworks domain of its size,
+/// overwriting the points with the result in `ordering`. Every point is checked to be in the prime order subgroup,
+/// and on error the points are left untouched. It errors if the number of points is not a power of two.
+pub fn srs_to_lagrange_in_place<P, F>(
+    points: &mut [P],
+    ordering: PointOrdering,
+) -> Result<(), InterpolationError>
+where
+    P: AffinePoint,
+    P::Config: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let monomial = points
+        .iter()
+        .map(|point| {
+            let point = point.to_arkworks()?;
+            if !(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()) {
+                return Err(InterpolationError::SerializationError(
+                    "Point is not in the prime order subgroup".to_string(),
+                ));
+            }
+            Ok(point)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let lagrange = MonomialSrs::new(monomial).to_lagrange_with_ordering(ordering)?;
+    points
+        .iter_mut()
+        .zip(lagrange.points())
+        .for_each(|(point, lagrange)| *point = P::from_arkworks(lagrange));
+    Ok(())
+}
+
+#[cfg(feature = "ffi")]
+mod exports {
+    use super::*;
+    use crate::ffi::{error_code, SRS_ERROR_INTERNAL, SRS_ERROR_NULL_POINTER, SRS_OK};
+    use ark_std::{panic, slice};
+
+    /// This function converts `num_points` points at `points` in place, catching any panic so that it never
+    /// unwinds into the caller.
+    ///
+    /// # Safety
+    ///
+    /// `points` must be null or valid for reads and writes of `num_points` points.
+    unsafe fn convert_raw<P, F>(points: *mut P, num_points: usize, bit_reversed: bool) -> i32
+    where
+        P: AffinePoint,
+        P::Config: SWCurveConfig<ScalarField = F>,
+        F: PrimeField,
+    {
+        if points.is_null() {
+            return SRS_ERROR_NULL_POINTER;
+        }
+        let points = slice::from_raw_parts_mut(points, num_points);
+        let ordering = if bit_reversed {
+            PointOrdering::BitReversed
+        } else {
+            PointOrdering::Natural
+        };
+        match panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            srs_to_lagrange_in_place(points, ordering)
+        })) {
+            Ok(Ok(())) => SRS_OK,
+            Ok(Err(e)) => error_code(&e),
+            Err(_) => SRS_ERROR_INTERNAL,
+        }
+    }
+
+    /// This function converts the `num_points` BN254 G1 points of a monomial SRS held as Constantine's
+    /// `bn254_snarks_g1_aff` to the Lagrange basis in place, in bit-reversed order if `bit_reversed` is set.
+    ///
+    /// # Safety
+    ///
+    /// `points` must be valid for reads and writes of `num_points` points.
+    #[no_mangle]
+    pub unsafe extern "C" fn srs_to_lagrange_bn254_constantine(
+        points: *mut Bn254SnarksG1Aff,
+        num_points: usize,
+        bit_reversed: bool,
+    ) -> i32 {
+        convert_raw(points, num_points, bit_reversed)
+    }
+
+    /// This function converts the `num_points` BLS12-381 G1 points of a monomial SRS held as Constantine's
+    /// `bls12_381_g1_aff` to the Lagrange basis in place, in bit-reversed order if `bit_reversed` is set.
+    ///
+    /// # Safety
+    ///
+    /// `points` must be valid for reads and writes of `num_points` points.
+    #[cfg(feature = "bls12-381")]
+    #[no_mangle]
+    pub unsafe extern "C" fn srs_to_lagrange_bls12_381_constantine(
+        points: *mut Bls12381G1Aff,
+        num_points: usize,
+        bit_reversed: bool,
+    ) -> i32 {
+        convert_raw(points, num_points, bit_reversed)
+    }
+}
+
+#[cfg(feature = "ffi")]
+pub use exports::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fq, G1Affine};
+    use ark_ec::AffineRepr;
+    use ark_ff::MontFp;
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(core::mem::size_of::<Bn254SnarksG1Aff>(), 64);
+        #[cfg(feature = "bls12-381")]
+        assert_eq!(core::mem::size_of::<Bls12381G1Aff>(), 96);
+
+        // The Montgomery residue of one is R mod p, which is what Constantine stores for one.
+        let one = Bn254SnarksG1Aff::from_arkworks(&G1Affine::generator()).x;
+        let r: Fq =
+            MontFp!("6350874878119819312338956282401532409788428879151445726012394534686998597021");
+        assert_eq!(one.limbs, r.into_bigint().0);
+        assert_eq!(
+            Bn254SnarksG1Aff::from_arkworks(&G1Affine::identity()),
+            Bn254SnarksG1Aff::default()
+        );
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_in_place() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let mut points = srs
+            .iter()
+            .map(Bn254SnarksG1Aff::from_arkworks)
+            .collect::<Vec<_>>();
+        srs_to_lagrange_in_place(&mut points, PointOrdering::BitReversed).unwrap();
+        let expected = MonomialSrs::new(srs.clone())
+            .to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert_eq!(
+            points
+                .iter()
+                .map(|point| point.to_arkworks().unwrap())
+                .collect::<Vec<_>>(),
+            expected.points()
+        );
+
+        // An unreduced coordinate is rejected and leaves the points alone.
+        let mut bad = srs
+            .iter()
+            .map(Bn254SnarksG1Aff::from_arkworks)
+            .collect::<Vec<_>>();
+        bad[1].x.limbs = [u64::MAX; 4];
+        let before = bad.clone();
+        assert!(srs_to_lagrange_in_place(&mut bad, PointOrdering::Natural).is_err());
+        assert_eq!(bad, before);
+        assert!(srs_to_lagrange_in_place(&mut bad[..3], PointOrdering::Natural).is_err());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_srs_to_lagrange_bn254_constantine() {
+        use crate::ffi::{SRS_ERROR_INVALID_POINT, SRS_ERROR_NULL_POINTER, SRS_ERROR_SIZE, SRS_OK};
+
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap();
+        let mut points = srs
+            .iter()
+            .map(Bn254SnarksG1Aff::from_arkworks)
+            .collect::<Vec<_>>();
+        let code = unsafe { srs_to_lagrange_bn254_constantine(points.as_mut_ptr(), 8, false) };
+        assert_eq!(code, SRS_OK);
+        let expected = crate::srs_to_lagrange(&srs).unwrap();
+        assert_eq!(
+            points,
+            expected
+                .iter()
+                .map(Bn254SnarksG1Aff::from_arkworks)
+                .collect::<Vec<_>>()
+        );
+
+        let code = unsafe { srs_to_lagrange_bn254_constantine(ark_std::ptr::null_mut(), 8, false) };
+        assert_eq!(code, SRS_ERROR_NULL_POINTER);
+        let code = unsafe { srs_to_lagrange_bn254_constantine(points.as_mut_ptr(), 6, false) };
+        assert_eq!(code, SRS_ERROR_SIZE);
+        // The point (1, 1) is off the curve.
+        points[0] = Bn254SnarksG1Aff::from_arkworks(&G1Affine::new_unchecked(
+            Fq::from(1u64),
+            Fq::from(1u64),
+        ));
+        let code = unsafe { srs_to_lagrange_bn254_constantine(points.as_mut_ptr(), 8, false) };
+        assert_eq!(code, SRS_ERROR_INVALID_POINT);
+    }
+}
@@ -0,0 +1,238 @@
+//! This module converts G1 points between arkworks and `halo2curves`, for the BN254 curve that `halo2curves` calls
+//! `bn256` and for BLS12-381, so halo2 provers can convert the points of their KZG parameters in memory.
+//!
+//! Both libraries keep field elements in Montgomery form but `halo2curves` does not expose the limbs, so the
+//! conversions go through the canonical encodings of the coordinates, which are little endian in both. The point
+//! at infinity is the all zero point in `halo2curves`, as in `blst`.
+//!
+//! halo2 takes the domain of size `2^k` to be generated by its own root of unity. That agrees with arkworks for
+//! BLS12-381 but only up to 32 points for BN254, so [`srs_to_lagrange_halo2`] converts over the halo2 domain to
+//! match the `g_lagrange` of halo2's parameters.
+use crate::{error::InterpolationError, srs_to_lagrange_with_generator};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveConfig,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::vec::Vec;
+use halo2curves::{
+    bls12381, bn256,
+    ff::{Field, PrimeField},
+    Coordinates, CurveAffine,
+};
+
+/// A `halo2curves` affine point whose curve has an arkworks configuration.
+pub trait Halo2Affine: CurveAffine {
+    /// The arkworks configuration of the curve.
+    type Config: SWCurveConfig;
+}
+
+impl Halo2Affine for bn256::G1Affine {
+    type Config = ark_bn254::g1::Config;
+}
+
+impl Halo2Affine for bls12381::G1Affine {
+    type Config = ark_bls12_381::g1::Config;
+}
+
+/// This function converts a `halo2curves` field element to arkworks, checking that its encoding is reduced.
+fn field_to_arkworks<A: PrimeField, B: CanonicalDeserialize>(
+    value: &A,
+) -> Result<B, InterpolationError> {
+    B::deserialize_compressed(value.to_repr().as_ref()).map_err(|e| {
+        InterpolationError::FieldError(format!("halo2curves field element is invalid: {}", e))
+    })
+}
+
+/// This function converts an arkworks field element to `halo2curves`.
+fn field_from_arkworks<A: PrimeField, B: CanonicalSerialize>(value: &B) -> A {
+    let mut repr = A::Repr::default();
+    value
+        .serialize_compressed(repr.as_mut())
+        .expect("the encodings of a field have the same length in both libraries");
+    Option::from(A::from_repr(repr)).expect("an arkworks field element is reduced")
+}
+
+/// This function converts a G1 point from `halo2curves` without checking that it is on the curve or in the subgroup.
+/// The coordinates must still be reduced, which is always the case for the public fields of its points.
+pub fn affine_from_halo2_unchecked<P: Halo2Affine>(
+    point: &P,
+) -> Result<Affine<P::Config>, InterpolationError> {
+    if bool::from(point.is_identity()) {
+        return Ok(Affine::identity());
+    }
+    let coordinates: Option<Coordinates<P>> = point.coordinates().into();
+    let coordinates = coordinates.ok_or(InterpolationError::InvalidParameters(
+        "The halo2curves point has no coordinates".to_string(),
+    ))?;
+    Ok(Affine::new_unchecked(
+        field_to_arkworks(coordinates.x())?,
+        field_to_arkworks(coordinates.y())?,
+    ))
+}
+
+/// This function converts a G1 point from `halo2curves`, checking that it is on the curve and in the subgroup.
+pub fn affine_from_halo2<P: Halo2Affine>(
+    point: &P,
+) -> Result<Affine<P::Config>, InterpolationError> {
+    let point = affine_from_halo2_unchecked(point)?;
+    point.check().map_err(|e| {
+        InterpolationError::InvalidParameters(format!("invalid halo2curves point: {}", e))
+    })?;
+    Ok(point)
+}
+
+/// This function converts a G1 point to `halo2curves`. It errors if the point is not on the curve.
+pub fn affine_to_halo2<P: Halo2Affine>(point: &Affine<P::Config>) -> Result<P, InterpolationError> {
+    let (x, y) = match point.xy() {
+        Some(xy) => xy,
+        None => return Ok(P::identity()),
+    };
+    Option::from(P::from_xy(field_from_arkworks(x), field_from_arkworks(y))).ok_or(
+        InterpolationError::InvalidParameters("The point is not on the curve".to_string()),
+    )
+}
+
+/// This function converts a slice of G1 points from `halo2curves`, checking every point.
+pub fn affines_from_halo2<P: Halo2Affine>(
+    points: &[P],
+) -> Result<Vec<Affine<P::Config>>, InterpolationError> {
+    points.iter().map(affine_from_halo2).collect()
+}
+
+/// This function converts a slice of G1 points to `halo2curves`.
+pub fn affines_to_halo2<P: Halo2Affine>(
+    points: &[Affine<P::Config>],
+) -> Result<Vec<P>, InterpolationError> {
+    points.iter().map(affine_to_halo2).collect()
+}
+
+/// This function converts a scalar from `halo2curves` to arkworks.
+pub fn scalar_from_halo2<P: Halo2Affine>(
+    scalar: &P::ScalarExt,
+) -> Result<<P::Config as CurveConfig>::ScalarField, InterpolationError> {
+    field_to_arkworks(scalar)
+}
+
+/// This function returns the generator halo2 takes for the domain of `size` points, which is its root of unity
+/// squared until it has order `size`. It errors if `size` is not a power of two or the field has no such root.
+fn halo2_generator<P: Halo2Affine>(
+    size: usize,
+) -> Result<<P::Config as CurveConfig>::ScalarField, InterpolationError> {
+    if !size.is_power_of_two() || size.ilog2() > P::ScalarExt::S {
+        return Err(InterpolationError::SizeError);
+    }
+    let log_size = size.ilog2();
+    let mut omega = P::ScalarExt::ROOT_OF_UNITY;
+    for _ in log_size..P::ScalarExt::S {
+        omega = omega.square();
+    }
+    scalar_from_halo2::<P>(&omega)
+}
+
+/// This function converts a monomial SRS held as `halo2curves` points to the Lagrange basis over the domain halo2
+/// uses, giving the `g_lagrange` points of halo2's KZG parameters. It errors if the number of points is not a power
+/// of two.
+pub fn srs_to_lagrange_halo2<P: Halo2Affine>(points: &[P]) -> Result<Vec<P>, InterpolationError> {
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let gen = halo2_generator::<P>(points.len())?;
+    let points = affines_from_halo2(points)?;
+    affines_to_halo2(&srs_to_lagrange_with_generator(&points, gen)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bls12_381::g1::Config as BlsConfig;
+    use ark_bn254::g1::Config as BnConfig;
+    use ark_ec::CurveGroup;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use halo2curves::{
+        fft::best_fft,
+        group::{prime::PrimeCurveAffine, Curve},
+    };
+
+    fn check_round_trip<P: Halo2Affine>(points: &[Affine<P::Config>]) {
+        let converted = affines_to_halo2::<P>(points).unwrap();
+        assert_eq!(affines_from_halo2(&converted).unwrap(), points);
+        assert_eq!(
+            affine_to_halo2::<P>(&Affine::identity()).unwrap(),
+            P::identity()
+        );
+        assert_eq!(
+            affine_from_halo2(&P::identity()).unwrap(),
+            Affine::identity()
+        );
+    }
+
+    #[test]
+    fn test_generators_match() {
+        assert_eq!(
+            affine_from_halo2(&bn256::G1Affine::generator()).unwrap(),
+            ark_bn254::G1Affine::generator()
+        );
+        assert_eq!(
+            affine_from_halo2(&bls12381::G1Affine::generator()).unwrap(),
+            ark_bls12_381::G1Affine::generator()
+        );
+
+        // Scalar multiplication agrees, which checks the scalars convert too.
+        let scalar = bn256::Fr::from(12345u64);
+        let halo2_point = (bn256::G1Affine::generator() * scalar).to_affine();
+        let expected = ark_bn254::G1Affine::generator()
+            * scalar_from_halo2::<bn256::G1Affine>(&scalar).unwrap();
+        assert_eq!(
+            affine_from_halo2(&halo2_point).unwrap(),
+            expected.into_affine()
+        );
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_halo2() {
+        let rng = &mut ark_std::test_rng();
+        let bn_points = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        check_round_trip::<bn256::G1Affine>(&bn_points);
+        let bls_points = gen_srs_for_testing::<BlsConfig, _>(rng, 7).unwrap();
+        check_round_trip::<bls12381::G1Affine>(&bls_points);
+
+        // halo2 computes its Lagrange points with an FFT over its own domain.
+        let halo2_points = affines_to_halo2::<bn256::G1Affine>(&bn_points).unwrap();
+        let omega_inv = bn256::Fr::ROOT_OF_UNITY_INV.pow([1 << (bn256::Fr::S - 6)]);
+        let mut expected = halo2_points
+            .iter()
+            .map(|point| point.to_curve())
+            .collect::<Vec<_>>();
+        best_fft(&mut expected, omega_inv, 6);
+        let size_inv = bn256::Fr::from(64u64).invert().unwrap();
+        let expected = expected
+            .iter()
+            .map(|point| (point * size_inv).to_affine())
+            .collect::<Vec<_>>();
+        let lagrange = srs_to_lagrange_halo2(&halo2_points).unwrap();
+        assert_eq!(lagrange, expected);
+        // The roots of unity of halo2 and arkworks only agree up to 32 points on BN254, so these come in another
+        // order.
+        assert_eq!(
+            halo2_generator::<bn256::G1Affine>(32).unwrap(),
+            Radix2EvaluationDomain::<ark_bn254::Fr>::new(32)
+                .unwrap()
+                .group_gen()
+        );
+        assert_ne!(
+            affines_from_halo2(&lagrange).unwrap(),
+            srs_to_lagrange(&bn_points).unwrap()
+        );
+        // On BLS12-381 they always agree.
+        let bls_lagrange =
+            srs_to_lagrange_halo2(&affines_to_halo2::<bls12381::G1Affine>(&bls_points).unwrap())
+                .unwrap();
+        assert_eq!(
+            affines_from_halo2(&bls_lagrange).unwrap(),
+            srs_to_lagrange(&bls_points).unwrap()
+        );
+        assert!(srs_to_lagrange_halo2::<bn256::G1Affine>(&[]).is_err());
+    }
+}
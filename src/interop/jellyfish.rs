@@ -0,0 +1,245 @@
+//! This module converts between this crate's SRS types and the univariate KZG parameters of Jellyfish (`jf-pcs`).
+//!
+//! Jellyfish is not published on crates.io, so rather than depending on it this module mirrors the layout of its
+//! `UnivariateUniversalParams` and `UnivariateProverParam` structs. Both derive `CanonicalSerialize` in Jellyfish,
+//! which writes their fields in declaration order, and the types here serialize the same fields in the same order.
+//! Parameters therefore move between the crates by serializing on one side and deserializing on the other.
+use crate::{
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::vec::Vec;
+
+/// The layout of Jellyfish's `UnivariateUniversalParams`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JellyfishUniversalParams<P: Pairing> {
+    /// The monomial G1 points `[1], [beta], [beta^2], ...`.
+    pub powers_of_g: Vec<P::G1Affine>,
+    /// The G2 generator.
+    pub h: P::G2Affine,
+    /// The G2 point `[beta]`.
+    pub beta_h: P::G2Affine,
+    /// The monomial G2 points `[1], [beta], [beta^2], ...`.
+    pub powers_of_h: Vec<P::G2Affine>,
+}
+
+/// The layout of Jellyfish's `UnivariateProverParam`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JellyfishProverParam<P: Pairing> {
+    /// The G1 points commitments are computed against.
+    pub powers_of_g: Vec<P::G1Affine>,
+}
+
+impl<P, E> JellyfishUniversalParams<P>
+where
+    P: Pairing<G1Affine = Affine<E>, ScalarField = E::ScalarField>,
+    E: SWCurveConfig,
+{
+    /// This function builds the parameters from a monomial SRS and the monomial G2 points, of which there must be
+    /// at least two.
+    pub fn from_srs(
+        srs: &MonomialSrs<E>,
+        powers_of_h: Vec<P::G2Affine>,
+    ) -> Result<Self, InterpolationError> {
+        if powers_of_h.len() < 2 {
+            return Err(InterpolationError::InvalidParameters(
+                "At least two G2 powers are needed".to_string(),
+            ));
+        }
+        Ok(Self {
+            powers_of_g: srs.points().to_vec(),
+            h: powers_of_h[0],
+            beta_h: powers_of_h[1],
+            powers_of_h,
+        })
+    }
+
+    /// The monomial G1 points of the parameters.
+    pub fn to_monomial(&self) -> MonomialSrs<E> {
+        MonomialSrs::new(self.powers_of_g.clone())
+    }
+
+    /// This function trims the parameters to the smallest power of two domain supporting polynomials of degree
+    /// `supported_degree` and returns a prover parameter whose points are in the Lagrange basis over the arkworks
+    /// domain, in natural order.
+    pub fn trim_lagrange(
+        &self,
+        supported_degree: usize,
+    ) -> Result<JellyfishProverParam<P>, InterpolationError> {
+        let size = (supported_degree + 1).next_power_of_two();
+        if self.powers_of_g.len() < size {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The parameters have {} powers of g but {} are needed",
+                self.powers_of_g.len(),
+                size
+            )));
+        }
+        let srs = MonomialSrs::new(self.powers_of_g[..size].to_vec()).to_lagrange()?;
+        Ok(srs.into())
+    }
+}
+
+impl<P, E> From<LagrangeSrs<E>> for JellyfishProverParam<P>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    fn from(srs: LagrangeSrs<E>) -> Self {
+        Self {
+            powers_of_g: srs.into_points(),
+        }
+    }
+}
+
+impl<P, E> From<MonomialSrs<E>> for JellyfishProverParam<P>
+where
+    P: Pairing<G1Affine = Affine<E>>,
+    E: SWCurveConfig,
+{
+    fn from(srs: MonomialSrs<E>) -> Self {
+        Self {
+            powers_of_g: srs.into_points(),
+        }
+    }
+}
+
+impl<P: Pairing> Valid for JellyfishUniversalParams<P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.powers_of_g.check()?;
+        self.h.check()?;
+        self.beta_h.check()?;
+        self.powers_of_h.check()
+    }
+}
+
+impl<P: Pairing> CanonicalSerialize for JellyfishUniversalParams<P> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.powers_of_g
+            .serialize_with_mode(&mut writer, compress)?;
+        self.h.serialize_with_mode(&mut writer, compress)?;
+        self.beta_h.serialize_with_mode(&mut writer, compress)?;
+        self.powers_of_h.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.powers_of_g.serialized_size(compress)
+            + self.h.serialized_size(compress)
+            + self.beta_h.serialized_size(compress)
+            + self.powers_of_h.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing> CanonicalDeserialize for JellyfishUniversalParams<P> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            powers_of_g: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            h: P::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?,
+            beta_h: P::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?,
+            powers_of_h: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+impl<P: Pairing> Valid for JellyfishProverParam<P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.powers_of_g.check()
+    }
+}
+
+impl<P: Pairing> CanonicalSerialize for JellyfishProverParam<P> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.powers_of_g.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing> CanonicalDeserialize for JellyfishProverParam<P> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            powers_of_g: Vec::deserialize_with_mode(reader, compress, validate)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Bn254, Fr, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn test_jellyfish_params() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let powers_of_h = vec![
+            G2Affine::generator(),
+            (G2Affine::generator() * Fr::from(3u64)).into_affine(),
+        ];
+        assert!(
+            JellyfishUniversalParams::<Bn254>::from_srs(&srs, powers_of_h[..1].to_vec()).is_err()
+        );
+        let params = JellyfishUniversalParams::<Bn254>::from_srs(&srs, powers_of_h).unwrap();
+        assert_eq!(params.to_monomial(), srs);
+
+        // The serialization is the fields in declaration order, the same as the derived one in Jellyfish.
+        let mut bytes = Vec::new();
+        params.serialize_compressed(&mut bytes).unwrap();
+        let mut expected = Vec::new();
+        params
+            .powers_of_g
+            .serialize_compressed(&mut expected)
+            .unwrap();
+        params.h.serialize_compressed(&mut expected).unwrap();
+        params.beta_h.serialize_compressed(&mut expected).unwrap();
+        params
+            .powers_of_h
+            .serialize_compressed(&mut expected)
+            .unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(
+            JellyfishUniversalParams::<Bn254>::deserialize_compressed(bytes.as_slice()).unwrap(),
+            params
+        );
+
+        let prover_param = params.trim_lagrange(10).unwrap();
+        assert_eq!(
+            prover_param.powers_of_g,
+            srs.to_lagrange().unwrap().into_points()
+        );
+        let mut bytes = Vec::new();
+        prover_param.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            JellyfishProverParam::<Bn254>::deserialize_compressed(bytes.as_slice()).unwrap(),
+            prover_param
+        );
+        assert!(params.trim_lagrange(16).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! This module contains conversions between arkworks points and the point types of other libraries.
+#[cfg(feature = "ark-poly-commit")]
+pub mod ark_poly_commit;
+#[cfg(feature = "arkworks-05")]
+pub mod arkworks_05;
+#[cfg(feature = "blst")]
+pub mod blst;
+#[cfg(all(feature = "constantine", target_pointer_width = "64"))]
+pub mod constantine;
+#[cfg(feature = "halo2curves")]
+pub mod halo2curves;
+#[cfg(feature = "jellyfish")]
+pub mod jellyfish;
@@ -0,0 +1,180 @@
+//! This module computes KZG opening proofs directly from the evaluations of a polynomial over the domain of a
+//! Lagrange SRS, without converting the polynomial to coefficient form.
+//!
+//! For a polynomial `f` given by its evaluations `f_i` at the roots `w^i` of a domain of size `n`, the value at a
+//! point `z` outside the domain is given by the barycentric formula
+//! `f(z) = (z^n - 1) / n * sum_i f_i w^i / (z - w^i)`, and the quotient `q(X) = (f(X) - f(z)) / (X - z)` has the
+//! evaluations `q_i = (f_i - f(z)) / (w^i - z)`. When `z = w^k` is in the domain the quotient at `w^k` is the
+//! derivative `f'(w^k) = sum_{j != k} (f_j - f_k) w^(j - k) / (w^k - w^j)` instead. The quotient has degree less
+//! than `n`, so its commitment is the multi-scalar multiplication of its evaluations with the Lagrange points.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    reorder_points,
+    srs::{commit_evals, LagrangeSrs, PointOrdering},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{batch_inversion, PrimeField};
+use ark_std::{cfg_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// This function returns the evaluations, in natural order, of the quotient of the polynomial with natural order
+/// evaluations `evals` at the powers of `gen` by `X - z`, along with the value of the polynomial at `z`.
+fn quotient_evals<F: PrimeField>(
+    evals: &[F],
+    z: F,
+    gen: F,
+) -> Result<(F, Vec<F>), InterpolationError> {
+    let size = evals.len();
+    let roots = ark_std::iter::successors(Some(F::one()), |root| Some(*root * gen))
+        .take(size)
+        .collect::<Vec<_>>();
+
+    if let Some(k) = roots.iter().position(|root| *root == z) {
+        let value = evals[k];
+        let mut inverses = roots.iter().map(|root| *root - z).collect::<Vec<_>>();
+        inverses[k] = F::one();
+        batch_inversion(&mut inverses);
+        let z_inv = z.inverse().ok_or(InterpolationError::FieldError(
+            "Could not invert a root of unity".to_string(),
+        ))?;
+
+        // For j != k, q_j = (f_j - f_k) / (w^j - z) and the jth term of q_k is
+        // -(f_j - f_k) w^(j - k) / (w^j - w^k) = -q_j w^j / z.
+        let mut quotient = cfg_iter!(evals)
+            .zip(cfg_iter!(inverses))
+            .map(|(eval, inverse)| (*eval - value) * inverse)
+            .collect::<Vec<_>>();
+        quotient[k] = -quotient
+            .iter()
+            .zip(&roots)
+            .map(|(q, root)| *q * root)
+            .sum::<F>()
+            * z_inv;
+        return Ok((value, quotient));
+    }
+
+    let mut inverses = roots.iter().map(|root| z - *root).collect::<Vec<_>>();
+    batch_inversion(&mut inverses);
+    let scale = (z.pow([size as u64]) - F::one()) * domain_size_inv::<F>(size)?;
+    let value = evals
+        .iter()
+        .zip(&roots)
+        .zip(&inverses)
+        .map(|((eval, root), inverse)| *eval * root * inverse)
+        .sum::<F>()
+        * scale;
+    let quotient = cfg_iter!(evals)
+        .zip(cfg_iter!(inverses))
+        .map(|(eval, inverse)| (value - *eval) * inverse)
+        .collect();
+    Ok((value, quotient))
+}
+
+/// This function opens the polynomial whose evaluations over the domain of `lagrange_srs` are `evals`, stored in
+/// the same order as its points, at `z`. It returns the value of the polynomial at `z` and the commitment to the
+/// quotient `(f(X) - f(z)) / (X - z)` with `quotient_srs`, which must be a Lagrange SRS over the same domain.
+/// `quotient_srs` is usually `lagrange_srs` itself, but it may store its points in the other ordering.
+pub fn open_at<E>(
+    evals: &[E::ScalarField],
+    z: E::ScalarField,
+    lagrange_srs: &LagrangeSrs<E>,
+    quotient_srs: &LagrangeSrs<E>,
+) -> Result<(E::ScalarField, Affine<E>), InterpolationError>
+where
+    E: SWCurveConfig,
+{
+    if evals.len() != lagrange_srs.len() || evals.is_empty() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} evaluations were given",
+            lagrange_srs.len(),
+            evals.len()
+        )));
+    }
+    if quotient_srs.len() != lagrange_srs.len()
+        || quotient_srs.generator() != lagrange_srs.generator()
+    {
+        return Err(InterpolationError::InvalidParameters(
+            "The quotient SRS is not over the domain of the evaluations".to_string(),
+        ));
+    }
+
+    let mut natural = evals.to_vec();
+    reorder_points(
+        &mut natural,
+        lagrange_srs.ordering(),
+        PointOrdering::Natural,
+    )?;
+    let (value, mut quotient) = quotient_evals(&natural, z, lagrange_srs.generator())?;
+    reorder_points(
+        &mut quotient,
+        PointOrdering::Natural,
+        quotient_srs.ordering(),
+    )?;
+    Ok((value, commit_evals(&quotient, quotient_srs)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs::MonomialSrs, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_poly::{
+        univariate::{DenseOrSparsePolynomial, DensePolynomial},
+        DenseUVPolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
+    };
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_open_at() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+        let reversed_srs = lagrange_srs
+            .clone()
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+        let evals = domain.fft(&polynomial.coeffs);
+        let mut reversed_evals = evals.clone();
+        reorder_points(
+            &mut reversed_evals,
+            PointOrdering::Natural,
+            PointOrdering::BitReversed,
+        )
+        .unwrap();
+
+        // The proof matches the commitment to the quotient computed in coefficient form, both outside and inside
+        // the domain.
+        for z in [Fr::rand(rng), domain.element(5)] {
+            let value = polynomial.evaluate(&z);
+            let numerator: DenseOrSparsePolynomial<Fr> =
+                (&polynomial - &DensePolynomial::from_coefficients_vec(vec![value])).into();
+            let divisor: DenseOrSparsePolynomial<Fr> =
+                DensePolynomial::from_coefficients_vec(vec![-z, Fr::from(1u64)]).into();
+            let (quotient, _) = numerator.divide_with_q_and_r(&divisor).unwrap();
+            let proof = G1Projective::msm(&srs.points()[..quotient.coeffs.len()], &quotient.coeffs)
+                .unwrap()
+                .into_affine();
+
+            assert_eq!(
+                open_at(&evals, z, &lagrange_srs, &lagrange_srs).unwrap(),
+                (value, proof)
+            );
+            assert_eq!(
+                open_at(&reversed_evals, z, &reversed_srs, &lagrange_srs).unwrap(),
+                (value, proof)
+            );
+        }
+
+        assert!(open_at(&evals[1..], Fr::from(3u64), &lagrange_srs, &lagrange_srs).is_err());
+        let small_srs = MonomialSrs::new(srs.points()[..8].to_vec())
+            .to_lagrange()
+            .unwrap();
+        assert!(open_at(&evals, Fr::from(3u64), &lagrange_srs, &small_srs).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! This module holds Laurent SRSs, whose powers of tau run over a range of exponents that may be negative, as the
+//! `[tau^-d], ..., [tau^d]` of Sonic style setups, and converts windows of them to the Lagrange basis.
+//!
+//! As in [`crate::shifted`], converting the window `[tau^s], ..., [tau^(s + n - 1)]` gives `[tau^s L_i(tau)]`, now
+//! with `s` possibly negative, and committing to the evaluations of `f` with it gives `[tau^s f(tau)]`. The only
+//! bookkeeping is mapping exponents to the indices of the points.
+use crate::{
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+/// The points `[tau^e]` for the exponents `e` from [`LaurentSrs::min_exponent`] to [`LaurentSrs::max_exponent`].
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct LaurentSrs<E: SWCurveConfig> {
+    points: Vec<Affine<E>>,
+    min_exponent: i64,
+}
+
+/// A Lagrange SRS multiplied by a possibly negative power of tau, whose points are `[tau^shift L_i(tau)]`.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct LaurentLagrangeSrs<E: SWCurveConfig> {
+    srs: LagrangeSrs<E>,
+    shift: i64,
+}
+
+impl<E: SWCurveConfig> LaurentLagrangeSrs<E> {
+    /// The shifted points as a Lagrange SRS, so that [`crate::srs::commit_evals`] gives `[tau^shift f(tau)]`.
+    pub fn srs(&self) -> &LagrangeSrs<E> {
+        &self.srs
+    }
+
+    /// Consumes the SRS and returns the shifted points as a Lagrange SRS.
+    pub fn into_srs(self) -> LagrangeSrs<E> {
+        self.srs
+    }
+
+    /// The power of tau the Lagrange basis is multiplied by.
+    pub fn shift(&self) -> i64 {
+        self.shift
+    }
+}
+
+impl<E: SWCurveConfig> LaurentSrs<E> {
+    /// This function makes a Laurent SRS from `points`, the first of which is `[tau^min_exponent]`, with the
+    /// exponents increasing by one from each point to the next.
+    pub fn new(points: Vec<Affine<E>>, min_exponent: i64) -> Result<Self, InterpolationError> {
+        i64::try_from(points.len())
+            .ok()
+            .and_then(|len| min_exponent.checked_add(len))
+            .ok_or(InterpolationError::SizeError)?;
+        Ok(Self {
+            points,
+            min_exponent,
+        })
+    }
+
+    /// This function makes the Laurent SRS `[tau^-d], ..., [tau^d]` from its `2d + 1` points. It errors if there
+    /// is an even number of points.
+    pub fn symmetric(points: Vec<Affine<E>>) -> Result<Self, InterpolationError> {
+        if points.len() % 2 != 1 {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "A symmetric Laurent SRS has an odd number of points, not {}",
+                points.len()
+            )));
+        }
+        let min_exponent = -((points.len() / 2) as i64);
+        Self::new(points, min_exponent)
+    }
+
+    /// The points, from the lowest exponent to the highest.
+    pub fn points(&self) -> &[Affine<E>] {
+        &self.points
+    }
+
+    /// The exponent of the first point.
+    pub fn min_exponent(&self) -> i64 {
+        self.min_exponent
+    }
+
+    /// The exponent of the last point, which is one less than [`LaurentSrs::min_exponent`] for an empty SRS.
+    pub fn max_exponent(&self) -> i64 {
+        self.min_exponent + self.points.len() as i64 - 1
+    }
+
+    /// The point `[tau^exponent]`, if the SRS has it.
+    pub fn get(&self, exponent: i64) -> Option<&Affine<E>> {
+        self.index(exponent).map(|index| &self.points[index])
+    }
+
+    fn index(&self, exponent: i64) -> Option<usize> {
+        exponent
+            .checked_sub(self.min_exponent)
+            .and_then(|index| usize::try_from(index).ok())
+            .filter(|index| *index < self.points.len())
+    }
+
+    /// This function returns the monomial SRS of the non-negative powers, which starts at `[1]`. It errors if the
+    /// SRS does not have `[1]`.
+    pub fn non_negative(&self) -> Result<MonomialSrs<E>, InterpolationError> {
+        let start = self.index(0).ok_or(InterpolationError::InvalidParameters(
+            "The Laurent SRS does not have [1]".to_string(),
+        ))?;
+        Ok(MonomialSrs::new(self.points[start..].to_vec()))
+    }
+
+    /// This function converts the window of `size` points starting at `[tau^first_exponent]` to the Lagrange basis,
+    /// giving `[tau^first_exponent L_i(tau)]`. It errors if the window is not in the SRS or `size` is not a power of
+    /// two.
+    pub fn to_lagrange<F>(
+        &self,
+        first_exponent: i64,
+        size: usize,
+    ) -> Result<LaurentLagrangeSrs<E>, InterpolationError>
+    where
+        E: SWCurveConfig<ScalarField = F>,
+        F: PrimeField,
+    {
+        let window = self
+            .index(first_exponent)
+            .and_then(|start| Some(start..start.checked_add(size)?))
+            .filter(|window| window.end <= self.points.len())
+            .ok_or(InterpolationError::InvalidParameters(format!(
+                "The window of {} points from the exponent {} is not in the range {} to {}",
+                size,
+                first_exponent,
+                self.min_exponent,
+                self.max_exponent()
+            )))?;
+        Ok(LaurentLagrangeSrs {
+            srs: MonomialSrs::new(self.points[window].to_vec()).to_lagrange()?,
+            shift: first_exponent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srs::commit_evals;
+    use ark_bn254::{Fr, G1Affine, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+    use ark_ff::Field;
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    };
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_laurent_srs() {
+        let rng = &mut ark_std::test_rng();
+        let tau = Fr::rand(rng);
+        let tau_inv = tau.inverse().unwrap();
+        let g = G1Affine::generator();
+        let power = |e: i64| match e < 0 {
+            true => tau_inv.pow([e.unsigned_abs()]),
+            false => tau.pow([e as u64]),
+        };
+        let points = (-10..=10)
+            .map(|e| (g * power(e)).into_affine())
+            .collect::<Vec<_>>();
+        let srs = LaurentSrs::symmetric(points.clone()).unwrap();
+        assert_eq!((srs.min_exponent(), srs.max_exponent()), (-10, 10));
+        assert_eq!(srs.get(-3), Some(&points[7]));
+        assert_eq!(srs.get(11), None);
+        assert_eq!(srs.non_negative().unwrap().points(), &points[10..]);
+
+        // Committing to the evaluations against the window from tau^-6 gives [tau^-6 f(tau)].
+        let polynomial = DensePolynomial::<Fr>::rand(7, rng);
+        let evals = Radix2EvaluationDomain::<Fr>::new(8)
+            .unwrap()
+            .fft(&polynomial.coeffs);
+        let shifted = srs.to_lagrange(-6, 8).unwrap();
+        assert_eq!(shifted.shift(), -6);
+        let expected = G1Projective::msm(&points[4..12], &polynomial.coeffs)
+            .unwrap()
+            .into_affine();
+        assert_eq!(commit_evals(&evals, shifted.srs()).unwrap(), expected);
+        assert_eq!(
+            srs.to_lagrange(3, 8).unwrap().into_srs(),
+            MonomialSrs::new(points[13..].to_vec())
+                .to_lagrange()
+                .unwrap()
+        );
+
+        assert!(srs.to_lagrange(4, 8).is_err());
+        assert!(srs.to_lagrange(-11, 8).is_err());
+        assert!(srs.to_lagrange(-6, 6).is_err());
+        assert!(LaurentSrs::symmetric(points[1..].to_vec()).is_err());
+        assert!(LaurentSrs::new(points[11..].to_vec(), 1)
+            .unwrap()
+            .non_negative()
+            .is_err());
+    }
+}
@@ -1,152 +1,549 @@
 use ark_ec::{
-    short_weierstrass::{Affine, SWCurveConfig},
-    CurveGroup,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
 };
 
 use ark_ff::PrimeField;
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
-use ark_std::{cfg_into_iter, cfg_iter};
-use error::InterpolationError;
+use ark_std::{cfg_iter_mut, ops::Range};
+use buffer::{try_copied, BufferProvider, VecProvider};
+pub use error::InterpolationError;
+#[cfg(feature = "fast-bls12-381")]
+pub use monomorphic::{lagrange_to_srs_bls12_381, srs_to_lagrange_bls12_381};
+#[cfg(feature = "fast-bn254")]
+pub use monomorphic::{lagrange_to_srs_bn254, srs_to_lagrange_bn254};
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use utils::{bit_reverse, fft_round};
+use srs::{FftVariant, PointOrdering};
+use utils::{bit_reverse_permutation, fft_round_with_scratch, is_primitive_root, twiddle_round};
+
+/// [`srs_to_lagrange_subset`] computes each point by its own MSM for at most this many indices per bit of the
+/// domain size. On BN254 one MSM took between a 35th of the full conversion at `2^10` points and a 110th at `2^16`,
+/// so this stays below the crossover at every size.
+pub const SUBSET_MSMS_PER_LOG_SIZE: usize = 3;
+
+/// This macro enters a `tracing` span at the given level until the end of the enclosing block when the `tracing`
+/// feature is on, and expands to nothing otherwise.
+macro_rules! enter_span {
+    ($level:ident, $($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $($args)*).entered();
+    };
+}
+
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod batch;
+pub mod buffer;
+#[cfg(feature = "butterfly-trace")]
+pub mod butterfly_trace;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod converter;
+pub mod curves;
+pub mod cycle;
+pub mod dispatch;
+pub mod distributed;
+pub mod ecfft;
+#[cfg(feature = "eip4844")]
+pub mod eip4844;
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod folding;
+pub mod formats;
+mod four_step;
+pub mod grid;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod grumpkin;
+pub mod hooks;
+pub mod incremental;
+pub mod interop;
+pub mod kzg;
+pub mod laurent;
+pub mod lookup;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+pub mod memory;
+#[cfg(feature = "manifest")]
+pub mod merkle;
+#[cfg(any(feature = "fast-bn254", feature = "fast-bls12-381"))]
+mod monomorphic;
+pub mod msm;
+pub mod multilinear;
+#[cfg(feature = "napi")]
+pub mod node;
+#[cfg(all(feature = "numa", target_os = "linux"))]
+pub mod numa;
+pub mod peerdas;
+pub mod plonk;
+pub mod point;
+#[cfg(feature = "reference")]
+pub mod reference;
+pub mod report;
+#[cfg(feature = "round-backend")]
+pub mod round_backend;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod setups;
+pub mod shifted;
+#[cfg(feature = "signing")]
+pub mod signed_manifest;
+pub mod srs;
+pub mod stream;
+pub mod threads;
+#[cfg(feature = "manifest")]
+pub mod transcript;
+pub mod universal;
 mod utils;
+pub mod verify;
+pub mod verkle;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 // To begin with we assume our SRS is given to us in ascending order of the powers of tau,
 // that is [1], [tau], [tau^2], [tau^3], ... [tau^d]. Since we are doing many point additions
 // we want to use the batch inversion trick and work in affine form to speed this up.
 
 /// This function takes a list of points in the SRS and converts them to the commitments to the Lagrange basis.
-/// It errors if the number of points is not a power of two.
+/// It errors if the number of points is not a power of two, which includes an empty list.
+///
+/// The smallest domains are well defined too. For one point the only Lagrange polynomial is the constant `1`, so
+/// `[1]` is returned unchanged. For two points the domain is `{1, -1}`, whose Lagrange polynomials are `(1 + X) / 2`
+/// and `(1 - X) / 2`, so the result is `([1] + [tau]) / 2` and `([1] - [tau]) / 2`.
 pub fn srs_to_lagrange<E, F>(points: &[Affine<E>]) -> Result<Vec<Affine<E>>, InterpolationError>
 where
     E: SWCurveConfig<ScalarField = F>,
     F: PrimeField,
 {
-    // First we check that the number of points is a power of two.
-    let log_point_size = points.len().ilog2() as usize;
-    let point_size = 1usize << log_point_size;
-    if points.len() != point_size {
-        return Err(InterpolationError::SizeError);
-    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    srs_to_lagrange_with_generator(points, domain.group_gen())
+}
 
-    if points.len() == 1 {
-        return Ok(points.to_vec());
+/// This function is the same as [`srs_to_lagrange`] but writes the result into `out`, which may be a memory-mapped
+/// file, instead of returning a new vector. It errors if `out` does not hold exactly as many points as `points`.
+pub fn srs_to_lagrange_into<E, F>(
+    points: &[Affine<E>],
+    out: &mut [Affine<E>],
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if out.len() != points.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The output holds {} points but the SRS has {}",
+            out.len(),
+            points.len()
+        )));
     }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    enter_span!(DEBUG, "srs_to_lagrange", size = points.len());
+    out.copy_from_slice(points);
+    srs_to_lagrange_unscaled(out, domain.group_gen())?;
+
+    enter_span!(TRACE, "rescale");
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(out).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(())
+}
 
-    // First we order the points so that it is convenient to perform the FFT style operation.
-    let mut ordered_points = cfg_into_iter!(0..points.len())
-        .map(|i| Ok(points[bit_reverse(i, log_point_size)?]))
-        .collect::<Result<Vec<Affine<E>>, InterpolationError>>()?;
+/// This function is the same as [`srs_to_lagrange`] but returns the points in the given `ordering`.
+/// Consumers such as c-kzg-4844 expect the Lagrange points in bit-reversed order.
+pub fn srs_to_lagrange_with_ordering<E, F>(
+    points: &[Affine<E>],
+    ordering: PointOrdering,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let mut lagrange_points = srs_to_lagrange(points)?;
+    reorder_points(&mut lagrange_points, PointOrdering::Natural, ordering)?;
+    Ok(lagrange_points)
+}
 
-    // We need the |2^log_point_size|th root of unity in the field.
+/// This function is the same as [`srs_to_lagrange_with_ordering`] but runs the group FFT as `variant`. With
+/// [`FftVariant::DecimationInFrequency`] a bit-reversed output skips both permutations of the decimation in time
+/// transform, which is one fewer pass over the points, while a natural output takes one permutation either way.
+/// Decimation in frequency always takes the radix-2 rounds.
+pub fn srs_to_lagrange_with_variant<E, F>(
+    points: &[Affine<E>],
+    ordering: PointOrdering,
+    variant: FftVariant,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if variant == FftVariant::DecimationInTime {
+        return srs_to_lagrange_with_ordering(points, ordering);
+    }
     let domain =
-        Radix2EvaluationDomain::<F>::new(point_size).ok_or(InterpolationError::SizeError)?;
-    let gen = domain.group_gen_inv();
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    enter_span!(DEBUG, "srs_to_lagrange", size = points.len());
+    let mut result = try_copied(points)?;
+    group_fft_dif(&mut result, domain.group_gen_inv(), &VecProvider)?;
+    reorder_points(&mut result, PointOrdering::BitReversed, ordering)?;
+
+    enter_span!(TRACE, "rescale");
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(result).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(result)
+}
 
-    // Then we perform the FFT style operation.
-    for i in 1..=log_point_size {
-        // In each round we take the point_size >> i th root of unity
-        let prim_root = gen.pow(&[(point_size >> i) as u64]);
-        if i != 1 {
-            fft_round::<E, F, false>(&mut ordered_points, prim_root, i)?;
-        } else {
-            fft_round::<E, F, true>(&mut ordered_points, prim_root, i)?;
-        }
+/// This function returns the single commitment `[L_index(tau)]` of the Lagrange basis over the arkworks domain of
+/// size `points.len()`, without converting the rest of the SRS. It errors if the number of points is not a power of
+/// two or `index` is outside the domain.
+///
+/// Writing `w` for the generator of the domain, `L_i(X) = (1 / n) * sum_j (w^-i X)^j`, so `[L_i(tau)]` is one MSM
+/// of the coefficients `w^(-ij) / n` against the monomial points. That takes `O(n / log n)` group operations
+/// rather than the `O(n log n)` of the full transform, so it is the cheaper way to get a handful of points such as
+/// `[L_0(tau)]`.
+pub fn srs_to_lagrange_point<E, F>(
+    points: &[Affine<E>],
+    index: usize,
+) -> Result<Affine<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    if index >= points.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Index {} is outside the domain of {} points",
+            index,
+            points.len()
+        )));
+    }
+    let root = domain.group_gen_inv().pow([index as u64]);
+    let mut coeff = domain_size_inv::<F>(points.len())?;
+    let coeffs = (0..points.len())
+        .map(|_| {
+            let current = coeff;
+            coeff *= root;
+            current
+        })
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::msm_unchecked(points, &coeffs).into_affine())
+}
+
+/// This function returns the Lagrange points `[L_i(tau)]` for each `i` in `indices`, in the same order, over the
+/// arkworks domain of size `points.len()`. It errors if the number of points is not a power of two or an index is
+/// outside the domain.
+///
+/// For a few indices each point is computed by its own MSM with [`srs_to_lagrange_point`], and for more than
+/// [`SUBSET_MSMS_PER_LOG_SIZE`] times the log of the domain size the whole basis is converted instead.
+pub fn srs_to_lagrange_subset<E, F>(
+    points: &[Affine<E>],
+    indices: &[usize],
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
     }
+    if let Some(index) = indices.iter().find(|index| **index >= points.len()) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Index {} is outside the domain of {} points",
+            index,
+            points.len()
+        )));
+    }
+    if indices.len() <= SUBSET_MSMS_PER_LOG_SIZE * points.len().ilog2() as usize {
+        indices
+            .iter()
+            .map(|index| srs_to_lagrange_point(points, *index))
+            .collect()
+    } else {
+        let lagrange_points = srs_to_lagrange(points)?;
+        Ok(indices
+            .iter()
+            .map(|index| lagrange_points[*index])
+            .collect())
+    }
+}
+
+/// This function is [`srs_to_lagrange_subset`] for the consecutive indices in `range`.
+pub fn srs_to_lagrange_range<E, F>(
+    points: &[Affine<E>],
+    range: Range<usize>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    // Checking the range first keeps a range far past the domain from being collected.
+    if !range.is_empty() && range.end > points.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Index {} is outside the domain of {} points",
+            range.end - 1,
+            points.len()
+        )));
+    }
+    srs_to_lagrange_subset(points, &range.collect::<Vec<_>>())
+}
+
+/// This function is the same as [`srs_to_lagrange`] but calls `progress` with the number of rounds done and the
+/// total after each round of the FFT style operation, so long conversions can report how far they have got. It
+/// always takes the radix-2 rounds, whatever the size.
+pub fn srs_to_lagrange_with_progress<E, F>(
+    points: &[Affine<E>],
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    enter_span!(DEBUG, "srs_to_lagrange_with_progress", size = points.len());
+    hooks::srs_to_lagrange_with_hook(points, |state| progress(state.round, state.rounds))
+}
+
+/// This function permutes points stored in the order `from` so that they are stored in the order `to`.
+pub(crate) fn reorder_points<T>(
+    points: &mut [T],
+    from: PointOrdering,
+    to: PointOrdering,
+) -> Result<(), InterpolationError> {
+    if from != to && points.len() > 1 {
+        bit_reverse_permutation(points, points.len().ilog2() as usize)?;
+    }
+    Ok(())
+}
+
+/// This function is the same as [`srs_to_lagrange`] but the Lagrange basis is taken with respect to
+/// the domain generated by `gen`, which must be a primitive `points.len()`th root of unity.
+/// Other libraries do not always pick the same root of unity as arkworks, so this lets us match their ordering.
+/// It errors if the number of points is not a power of two or `gen` is not a primitive root of that order.
+pub fn srs_to_lagrange_with_generator<E, F>(
+    points: &[Affine<E>],
+    gen: F,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    check_generator(gen, points.len())?;
+    enter_span!(DEBUG, "srs_to_lagrange", size = points.len());
+    let mut ordered_points = try_copied(points)?;
+    srs_to_lagrange_unscaled(&mut ordered_points, gen)?;
 
     // Finally we rescale all the points by the size of the domain
-    let domain_size_inv =
-        domain
-            .size_as_field_element
-            .inverse()
-            .ok_or(InterpolationError::FieldError(
-                "Could not invert domain size".to_string(),
-            ))?;
-    Ok(cfg_iter!(ordered_points)
-        .map(|point| (*point * domain_size_inv).into_affine())
-        .collect())
+    enter_span!(TRACE, "rescale");
+    // The result goes back into the buffer that was already allocated, so the conversion needs no second one.
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(ordered_points)
+        .for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(ordered_points)
+}
+
+/// This function checks that `gen` generates a domain of `size` points.
+fn check_generator<F: PrimeField>(gen: F, size: usize) -> Result<(), InterpolationError> {
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    if !is_primitive_root(gen, size) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The generator is not a primitive root of unity of order {}",
+            size
+        )));
+    }
+    Ok(())
+}
+
+/// This function converts the SRS to `n` times the Lagrange basis in place, leaving the caller to divide
+/// each point by the domain size `n`. This lets callers that stream the output avoid a second vector.
+pub(crate) fn srs_to_lagrange_unscaled<E, F>(
+    points: &mut [Affine<E>],
+    gen: F,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    // We need the inverse of the |2^log_point_size|th root of unity in the field.
+    let gen = gen.inverse().ok_or(InterpolationError::FieldError(
+        "Could not invert domain generator".to_string(),
+    ))?;
+    group_fft_in_place(points, gen, &VecProvider)
+}
+
+/// This function returns the inverse of the domain size as a field element.
+pub(crate) fn domain_size_inv<F: PrimeField>(point_size: usize) -> Result<F, InterpolationError> {
+    F::from(point_size as u64)
+        .inverse()
+        .ok_or(InterpolationError::FieldError(
+            "Could not invert domain size".to_string(),
+        ))
 }
 
 /// This function takes a list of commitments to the Lagrange basis and converts them to the SRS.
-/// It errors if the number of points is not a power of two.
+/// It errors if the number of points is not a power of two, which includes an empty list. The one and two point
+/// cases are the inverses of those described for [`srs_to_lagrange`].
 pub fn lagrange_to_srs<E, F>(points: &[Affine<E>]) -> Result<Vec<Affine<E>>, InterpolationError>
 where
     E: SWCurveConfig<ScalarField = F>,
     F: PrimeField,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    lagrange_to_srs_with_generator(points, domain.group_gen())
+}
+
+/// This function is the inverse of [`srs_to_lagrange_with_generator`], `gen` must be a primitive `points.len()`th root of unity.
+pub fn lagrange_to_srs_with_generator<E, F>(
+    points: &[Affine<E>],
+    gen: F,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    check_generator(gen, points.len())?;
+    enter_span!(DEBUG, "lagrange_to_srs", size = points.len());
+    let mut ordered_points = try_copied(points)?;
+    group_fft_in_place(&mut ordered_points, gen, &VecProvider)?;
+    Ok(ordered_points)
+}
+
+/// This function performs the FFT style operation on `points` in place, where `gen` is the root of unity
+/// whose powers the points are multiplied by. It errors if the number of points is not a power of two.
+/// Domains of at least [`four_step::FOUR_STEP_THRESHOLD`] points use the cache friendlier four-step decomposition.
+/// Scratch space is taken from `provider`.
+pub(crate) fn group_fft_in_place<E, F, P>(
+    points: &mut [Affine<E>],
+    gen: F,
+    provider: &P,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
 {
     // First we check that the number of points is a power of two.
-    let log_point_size = points.len().ilog2() as usize;
-    let point_size = 1usize << log_point_size;
-    if points.len() != point_size {
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    if points.len() >= four_step::FOUR_STEP_THRESHOLD {
+        return four_step::group_fft_four_step(points, gen, provider);
+    }
+    group_fft_radix2(points, gen, provider)
+}
+
+/// This function is the radix-2 form of [`group_fft_in_place`], which takes every round over the whole of
+/// `points`. The number of points must be a power of two. One scratch buffer from `provider` is shared by all the
+/// rounds.
+pub(crate) fn group_fft_radix2<E, F, P>(
+    points: &mut [Affine<E>],
+    gen: F,
+    provider: &P,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    group_fft_radix2_with(points, gen, provider, |_, _, _| Ok(()))
+}
+
+/// This function is the same as [`group_fft_radix2`] but calls `after_round` with the points, the number of rounds
+/// done and the total after each round, stopping with its error if it returns one. This lets callers report progress,
+/// cancel and record the intermediate points between rounds.
+pub(crate) fn group_fft_radix2_with<E, F, P>(
+    points: &mut [Affine<E>],
+    gen: F,
+    provider: &P,
+    mut after_round: impl FnMut(&[Affine<E>], usize, usize) -> Result<(), InterpolationError>,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    let point_size = points.len();
+    if !point_size.is_power_of_two() {
         return Err(InterpolationError::SizeError);
     }
+    let log_point_size = point_size.ilog2() as usize;
 
     if points.len() == 1 {
-        return Ok(points.to_vec());
+        return Ok(());
     }
 
     // First we order the points so that it is convenient to perform the FFT style operation.
-    let mut ordered_points = cfg_into_iter!(0..points.len())
-        .map(|i| Ok(points[bit_reverse(i, log_point_size)?]))
-        .collect::<Result<Vec<Affine<E>>, InterpolationError>>()?;
-
-    // We need the |2^log_point_size|th root of unity in the field.
-    let domain =
-        Radix2EvaluationDomain::<F>::new(point_size).ok_or(InterpolationError::SizeError)?;
-    let gen = domain.group_gen();
+    {
+        enter_span!(TRACE, "reorder");
+        bit_reverse_permutation(points, log_point_size)?;
+    }
 
     // Then we perform the FFT style operation.
+    let mut scratch = buffer::scratch::<E, _>(provider, utils::scratch_len::<E>(point_size))?;
     for i in 1..=log_point_size {
+        enter_span!(DEBUG, "round", round = i);
         // In each round we take the point_size >> i th root of unity
-        let prim_root = gen.pow(&[(point_size >> i) as u64]);
+        let prim_root = gen.pow([(point_size >> i) as u64]);
         if i != 1 {
-            fft_round::<E, F, false>(&mut ordered_points, prim_root, i)?;
+            fft_round_with_scratch::<E, F, false>(points, prim_root, i, &mut scratch)?;
         } else {
-            fft_round::<E, F, true>(&mut ordered_points, prim_root, i)?;
+            fft_round_with_scratch::<E, F, true>(points, prim_root, i, &mut scratch)?;
         }
+        after_round(points, i, log_point_size)?;
     }
 
-    Ok(ordered_points)
+    Ok(())
+}
+
+/// This function is the decimation in frequency form of [`group_fft_radix2`], which takes `points` in natural
+/// order and leaves the result in bit-reversed order. Each round runs the butterflies before the twiddles, from the
+/// largest blocks to the smallest. The number of points must be a power of two.
+pub(crate) fn group_fft_dif<E, F, P>(
+    points: &mut [Affine<E>],
+    gen: F,
+    provider: &P,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    P: BufferProvider,
+{
+    let point_size = points.len();
+    if !point_size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let log_point_size = point_size.ilog2() as usize;
+    let mut scratch = buffer::scratch::<E, _>(provider, utils::scratch_len::<E>(point_size))?;
+    for i in (1..=log_point_size).rev() {
+        enter_span!(DEBUG, "round", round = i);
+        let prim_root = gen.pow([(point_size >> i) as u64]);
+        // The butterflies come first, so they run without the twiddles of the decimation in time rounds.
+        fft_round_with_scratch::<E, F, true>(points, prim_root, i, &mut scratch)?;
+        if i != 1 {
+            twiddle_round(points, prim_root, i)?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_bn254::{g1::Config as BnConfig, Fr};
-    use ark_ec::{
-        scalar_mul::fixed_base::FixedBase, short_weierstrass::Projective, CurveGroup,
-        VariableBaseMSM,
-    };
+    use ark_bn254::{g1::Config as BnConfig, Fq, Fr, G1Affine};
+    use ark_ec::{short_weierstrass::Projective, AffineRepr, CurveGroup, VariableBaseMSM};
+    use ark_ff::{Field, One, Zero};
 
-    use ark_std::{rand::RngCore, One, UniformRand};
+    use ark_std::rand::RngCore;
 
     /// We only generate the G1 points for testing purposes.
     pub(crate) fn gen_srs_for_testing<E: SWCurveConfig, R: RngCore>(
         rng: &mut R,
         max_degree: usize,
     ) -> Result<Vec<Affine<E>>, InterpolationError> {
-        let beta = E::ScalarField::rand(rng);
-        let g = Projective::<E>::rand(rng);
-
-        let mut powers_of_beta = vec![E::ScalarField::one()];
-
-        let mut cur = beta;
-        for _ in 0..max_degree {
-            powers_of_beta.push(cur);
-            cur *= &beta;
-        }
-
-        let window_size = FixedBase::get_mul_window_size(max_degree + 1);
-
-        let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
-
-        let g_table = FixedBase::get_window_table(scalar_bits, window_size, g);
-        let powers_of_g =
-            FixedBase::msm::<Projective<E>>(scalar_bits, window_size, &g_table, &powers_of_beta);
-
-        let powers_of_g = Projective::<E>::normalize_batch(&powers_of_g);
-
-        Ok(powers_of_g)
+        Ok(setups::insecure::insecure_monomial_srs(rng, max_degree + 1).into_points())
     }
 
     fn test_fft_ifft_helper<E, F>()
@@ -176,6 +573,224 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_degenerate_sizes() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 1).unwrap();
+        let (one, tau) = (srs[0].into_group(), srs[1].into_group());
+        let half = Fr::from(2u64).inverse().unwrap();
+
+        // A single point is its own Lagrange form.
+        assert_eq!(srs_to_lagrange(&srs[..1]).unwrap(), srs[..1]);
+        assert_eq!(lagrange_to_srs(&srs[..1]).unwrap(), srs[..1]);
+        assert_eq!(
+            srs_to_lagrange_with_ordering(&srs[..1], PointOrdering::BitReversed).unwrap(),
+            srs[..1]
+        );
+
+        // Two points give the commitments to (1 + X) / 2 and (1 - X) / 2.
+        let expected = [
+            ((one + tau) * half).into_affine(),
+            ((one - tau) * half).into_affine(),
+        ];
+        let lagrange = srs_to_lagrange(&srs).unwrap();
+        assert_eq!(lagrange, expected);
+        assert_eq!(lagrange_to_srs(&lagrange).unwrap(), srs);
+        // Bit reversing two indices swaps nothing.
+        assert_eq!(
+            srs_to_lagrange_with_ordering(&srs, PointOrdering::BitReversed).unwrap(),
+            expected
+        );
+
+        // No points is not a domain at all.
+        assert!(srs_to_lagrange::<BnConfig, Fr>(&[]).is_err());
+        assert!(lagrange_to_srs::<BnConfig, Fr>(&[]).is_err());
+        assert!(srs_to_lagrange_into::<BnConfig, Fr>(&[], &mut []).is_err());
+    }
+
+    #[test]
+    fn test_adversarial_inputs() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let g = G1Affine::generator();
+        let off_curve = G1Affine::new_unchecked(Fq::one(), Fq::one());
+        let corpora = [
+            vec![G1Affine::identity(); 16],
+            vec![g; 16],
+            (0..16).map(|i| if i % 2 == 0 { g } else { -g }).collect(),
+            vec![off_curve; 16],
+            srs,
+        ];
+        let algorithms = [
+            dispatch::Algorithm::DirectMsm,
+            dispatch::Algorithm::Projective,
+            dispatch::Algorithm::Sequential,
+            dispatch::Algorithm::Radix2(FftVariant::DecimationInTime),
+            dispatch::Algorithm::Radix2(FftVariant::DecimationInFrequency),
+            dispatch::Algorithm::FourStep,
+        ];
+
+        // Every conversion returns, with an error or with points, rather than panicking.
+        for points in corpora.iter() {
+            for size in [0, 1, 2, 3, 6, 8, 16] {
+                let points = &points[..size];
+                let _ = lagrange_to_srs(points);
+                for ordering in [PointOrdering::Natural, PointOrdering::BitReversed] {
+                    for variant in [
+                        FftVariant::DecimationInTime,
+                        FftVariant::DecimationInFrequency,
+                    ] {
+                        let _ = srs_to_lagrange_with_variant(points, ordering, variant);
+                    }
+                    for algorithm in algorithms {
+                        let _ =
+                            dispatch::srs_to_lagrange_with_algorithm(points, ordering, algorithm);
+                    }
+                }
+                for gen in [Fr::zero(), Fr::one(), -Fr::one(), Fr::from(5u64)] {
+                    let _ = srs_to_lagrange_with_generator(points, gen);
+                    let _ = lagrange_to_srs_with_generator(points, gen);
+                }
+                for index in [size, usize::MAX] {
+                    assert!(srs_to_lagrange_point(points, index).is_err());
+                    assert!(srs_to_lagrange_subset(points, &[index]).is_err());
+                }
+                assert!(srs_to_lagrange_range(points, 0..usize::MAX).is_err());
+                for threads in [0, 3, 100] {
+                    let _ = threads::srs_to_lagrange_threads(points, threads);
+                }
+                let _ = report::srs_to_lagrange_with_report(points);
+            }
+        }
+
+        // Rounds and tiles outside what a domain can hold are errors too.
+        let mut points = vec![g; 4];
+        let mut scratch = vec![Fq::zero(); 4];
+        assert!(utils::twiddle_round(&mut points, Fr::one(), usize::MAX).is_err());
+        assert!(utils::twiddle_round(&mut points, Fr::one(), 64).is_err());
+        assert!(utils::fft_round_with_scratch::<_, _, false>(
+            &mut points,
+            Fr::one(),
+            0,
+            &mut scratch
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_point() {
+        let rng = &mut ark_std::test_rng();
+        for size in [1, 2, 64] {
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            let lagrange = srs_to_lagrange(&srs).unwrap();
+            for index in [0, size / 2, size - 1] {
+                assert_eq!(srs_to_lagrange_point(&srs, index).unwrap(), lagrange[index]);
+            }
+            assert!(srs_to_lagrange_point(&srs, size).is_err());
+        }
+        assert!(srs_to_lagrange_point::<BnConfig, Fr>(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_subset() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        let lagrange = srs_to_lagrange(&srs).unwrap();
+
+        // Few enough indices for MSMs, and enough to take the full transform.
+        let few = [5, 0, 63, 5];
+        let subset = srs_to_lagrange_subset(&srs, &few).unwrap();
+        assert_eq!(subset, few.map(|index| lagrange[index]));
+        let range = srs_to_lagrange_range(&srs, 8..40).unwrap();
+        assert_eq!(range, lagrange[8..40]);
+        assert!(srs_to_lagrange_range(&srs, 0..0).unwrap().is_empty());
+
+        assert!(srs_to_lagrange_subset(&srs, &[64]).is_err());
+        assert!(srs_to_lagrange_range(&srs, 60..70).is_err());
+        assert!(srs_to_lagrange_subset(&srs[..3], &[0]).is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_with_progress() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap();
+        let mut reports = Vec::new();
+        let lagrange =
+            srs_to_lagrange_with_progress(&srs, |done, rounds| reports.push((done, rounds)))
+                .unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&srs).unwrap());
+        assert_eq!(reports, (1..=5).map(|done| (done, 5)).collect::<Vec<_>>());
+
+        let monomial = srs::MonomialSrs::new(srs.clone());
+        assert_eq!(
+            monomial
+                .to_lagrange_with_progress(PointOrdering::BitReversed, |_, _| ())
+                .unwrap(),
+            monomial
+                .to_lagrange_with_ordering(PointOrdering::BitReversed)
+                .unwrap()
+        );
+        assert!(srs_to_lagrange_with_progress(&srs[..3], |_, _| ()).is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_into() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap();
+        let mut out = vec![Affine::<BnConfig>::identity(); 32];
+        srs_to_lagrange_into(&srs, &mut out).unwrap();
+        assert_eq!(out, srs_to_lagrange(&srs).unwrap());
+        assert!(srs_to_lagrange_into(&srs, &mut out[..16]).is_err());
+        assert!(srs_to_lagrange_into(&srs[..3], &mut out[..3]).is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_with_variant() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        for size in [1, 2, 4, 64] {
+            for ordering in [PointOrdering::Natural, PointOrdering::BitReversed] {
+                let expected = srs_to_lagrange_with_ordering(&srs[..size], ordering).unwrap();
+                for variant in [
+                    FftVariant::DecimationInTime,
+                    FftVariant::DecimationInFrequency,
+                ] {
+                    let points = srs_to_lagrange_with_variant(&srs[..size], ordering, variant);
+                    assert_eq!(points.unwrap(), expected);
+                }
+            }
+        }
+        assert!(srs_to_lagrange_with_variant(
+            &srs[..3],
+            PointOrdering::BitReversed,
+            FftVariant::DecimationInFrequency
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_with_generator() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let natural = srs_to_lagrange(&srs).unwrap();
+        let gen = Radix2EvaluationDomain::<Fr>::new(16).unwrap().group_gen();
+
+        // The ith point over the domain generated by w^3 is the Lagrange point at (w^3)^i.
+        let cubed = srs_to_lagrange_with_generator(&srs, gen.pow([3])).unwrap();
+        for (i, point) in cubed.iter().enumerate() {
+            assert_eq!(*point, natural[3 * i % 16]);
+        }
+        assert_eq!(
+            lagrange_to_srs_with_generator(&cubed, gen.pow([3])).unwrap(),
+            srs
+        );
+
+        // w^2 only generates eight points.
+        assert!(srs_to_lagrange_with_generator(&srs, gen.square()).is_err());
+        assert!(lagrange_to_srs_with_generator(&natural, gen.square()).is_err());
+        assert!(srs_to_lagrange_with_generator(&srs[..3], gen).is_err());
+    }
+
     fn test_srs_interpolation_helper<E, F>()
     where
         E: SWCurveConfig<ScalarField = F>,
@@ -210,5 +825,100 @@ mod tests {
 
             assert_eq!(coeff_commitment, lagrange_commitment);
         }
+
+        // An empty SRS is rejected rather than treated as a domain of one point.
+        assert!(srs_to_lagrange::<E, F>(&[]).is_err());
+        assert!(lagrange_to_srs::<E, F>(&[]).is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_feature() {
+        use ark_std::cfg_into_iter;
+        use std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::{Duration, Instant},
+        };
+
+        // Each item waits for the other to start, which only happens if the macro splits them over the pool.
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let started = AtomicUsize::new(0);
+        let overlapped = pool.install(|| {
+            assert_eq!(rayon::current_num_threads(), 2);
+            cfg_into_iter!(0..2)
+                .map(|_| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    let deadline = Instant::now() + Duration::from_secs(10);
+                    while started.load(Ordering::SeqCst) < 2 && Instant::now() < deadline {
+                        std::hint::spin_loop();
+                    }
+                    started.load(Ordering::SeqCst) == 2
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(overlapped, [true, true]);
+
+        // The conversion gives the same points on a pool of one thread as on the pool of two.
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 1023).unwrap();
+        let single = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        assert_eq!(
+            pool.install(|| srs_to_lagrange(&srs)).unwrap(),
+            single.install(|| srs_to_lagrange(&srs)).unwrap()
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_conversion_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{span, subscriber::with_default, Event, Metadata, Subscriber};
+
+        /// Records the name of every span in the order they are created.
+        struct Recorder(Arc<Mutex<Vec<&'static str>>>);
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                span::Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+            fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+            fn event(&self, _event: &Event<'_>) {}
+            fn enter(&self, _span: &span::Id) {}
+            fn exit(&self, _span: &span::Id) {}
+        }
+
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 3).unwrap();
+        let names = Arc::new(Mutex::new(Vec::new()));
+        with_default(Recorder(names.clone()), || srs_to_lagrange(&srs).unwrap());
+        assert_eq!(
+            *names.lock().unwrap(),
+            [
+                "srs_to_lagrange",
+                "reorder",
+                "round",
+                "butterfly",
+                "invert",
+                "butterfly",
+                "round",
+                "twiddle",
+                "butterfly",
+                "invert",
+                "butterfly",
+                "rescale",
+            ]
+        );
     }
 }
@@ -0,0 +1,216 @@
+//! This module computes the G1 preprocessing of a cq lookup table from a monomial SRS.
+//!
+//! For a table `t` of size `N` over the arkworks domain `V` with generator `w`, write `T` for the polynomial of
+//! degree less than `N` with `T(w^i) = t_i` and `Z_V` for the vanishing polynomial of `V`. The prover of cq needs,
+//! for every `i`, the commitments to
+//! - the Lagrange polynomial `L_i`,
+//! - the quotient `Q_i` with `L_i(X) T(X) = t_i L_i(X) + Z_V(X) Q_i(X)`, and
+//! - the polynomial `(L_i(X) - L_i(0)) / X`.
+//!
+//! Since `L_i(X) = (w^i / N) Z_V(X) / (X - w^i)`, the quotient `Q_i` is `w^i / N` times the KZG opening quotient of
+//! `T` at `w^i`, so all of the quotients are computed at once with the Feist-Khovratovich algorithm in
+//! `O(N log N)` group operations. Its Toeplitz product pads the SRS with the point at infinity, which the affine
+//! group FFT of this crate cannot add, so that step runs the FFT of `ark-poly` over projective points.
+//!
+//! The G2 commitments to `T` and `Z_V` the verifier needs come from a G2 SRS, and `T` can be committed to with
+//! [`crate::plonk::preprocess`] against its Lagrange basis.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter, cfg_iter_mut, vec::Vec, Zero};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The G1 points a cq prover needs for a lookup table, each indexed by the position in the table.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct PreprocessedTable<E: SWCurveConfig> {
+    /// The Lagrange basis over the domain of the table, in natural order.
+    pub lagrange: LagrangeSrs<E>,
+    /// The commitments to the quotients `Q_i` of `L_i(X) (T(X) - t_i)` by `Z_V(X)`.
+    pub quotients: Vec<Affine<E>>,
+    /// The commitments to `(L_i(X) - L_i(0)) / X`.
+    pub lagrange_zero_quotients: Vec<Affine<E>>,
+}
+
+/// This function returns the commitments to the opening quotients `(T(X) - T(w^i)) / (X - w^i)` of the polynomial
+/// with monomial coefficients `coeffs` at every point `w^i` of the domain of size `coeffs.len()`.
+fn opening_quotients<E, F>(
+    points: &[Affine<E>],
+    coeffs: &[F],
+    domain: &Radix2EvaluationDomain<F>,
+) -> Result<Vec<Projective<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = coeffs.len();
+    let double_domain =
+        Radix2EvaluationDomain::<F>::new(2 * size).ok_or(InterpolationError::SizeError)?;
+
+    // The jth coefficient of the quotient's commitment as a polynomial in w^i is
+    // h_j = sum_{k > j} T_k [x^(k - j - 1)], which is the (size + j)th coefficient of the product of
+    // sum_m [x^m] Y^(size - 1 - m) and T(Y). The product has degree less than 2 * size, so a cyclic
+    // convolution of that size computes it.
+    let mut reversed_points = points[..size]
+        .iter()
+        .rev()
+        .map(|point| Projective::<E>::from(*point))
+        .collect::<Vec<_>>();
+    reversed_points.resize(2 * size, Projective::<E>::zero());
+    double_domain.fft_in_place(&mut reversed_points);
+    let coeff_evals = double_domain.fft(coeffs);
+    cfg_iter_mut!(reversed_points)
+        .zip(cfg_iter!(coeff_evals))
+        .for_each(|(point, eval)| *point *= eval);
+    double_domain.ifft_in_place(&mut reversed_points);
+
+    let mut quotients = reversed_points.split_off(size);
+    domain.fft_in_place(&mut quotients);
+    Ok(quotients)
+}
+
+/// This function preprocesses a cq lookup table against `srs`, which needs at least `table.len()` points. The
+/// size of the table must be a power of two and its `i`th entry is the evaluation of the table polynomial at the
+/// `i`th element of the arkworks domain of that size.
+pub fn preprocess_table<E, F>(
+    table: &[F],
+    srs: &MonomialSrs<E>,
+) -> Result<PreprocessedTable<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = table.len();
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    if srs.len() < size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but the table needs {}",
+            srs.len(),
+            size
+        )));
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    let size_inv = domain_size_inv::<F>(size)?;
+    let lagrange = MonomialSrs::new(srs.points()[..size].to_vec()).to_lagrange()?;
+
+    // Q_i is w^i / N times the opening quotient of T at w^i.
+    let coeffs = domain.ifft(table);
+    let mut quotients = opening_quotients(srs.points(), &coeffs, &domain)?;
+    let scales = domain.elements().map(|root| root * size_inv);
+    quotients
+        .iter_mut()
+        .zip(scales)
+        .for_each(|(quotient, scale)| *quotient *= scale);
+
+    Ok(PreprocessedTable {
+        lagrange_zero_quotients: lagrange_zero_quotients(srs.points(), &lagrange)?,
+        lagrange,
+        quotients: Projective::<E>::normalize_batch(&quotients),
+    })
+}
+
+/// This function returns the commitments to `(L_i(X) - L_i(0)) / X` for every Lagrange polynomial of `lagrange`,
+/// which must be in natural order over the arkworks domain of its size, given at least that many monomial `points`.
+pub(crate) fn lagrange_zero_quotients<E, F>(
+    points: &[Affine<E>],
+    lagrange: &LagrangeSrs<E>,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = lagrange.len();
+    if size == 0 || points.len() < size {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    let size_inv = domain_size_inv::<F>(size)?;
+
+    // sum_m w^(-im) [x^m] over m < N is N [L_i], so (L_i(X) - 1 / N) / X commits to
+    // w^(-i) [L_i] - [x^(N - 1)] / N.
+    let last_point = points[size - 1] * size_inv;
+    let quotients = cfg_iter!(lagrange.points())
+        .enumerate()
+        .map(|(i, point)| *point * domain.element((size - i) % size) - last_point)
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::normalize_batch(&quotients))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::VariableBaseMSM;
+    use ark_poly::{
+        univariate::{DenseOrSparsePolynomial, DensePolynomial},
+        DenseUVPolynomial,
+    };
+    use ark_std::{One, UniformRand};
+
+    fn commit(srs: &MonomialSrs<BnConfig>, polynomial: &DensePolynomial<Fr>) -> Affine<BnConfig> {
+        G1Projective::msm(&srs.points()[..polynomial.coeffs.len()], &polynomial.coeffs)
+            .unwrap()
+            .into_affine()
+    }
+
+    #[test]
+    fn test_preprocess_table() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+
+        // A table with repeated and zero entries, as range tables have.
+        let mut table = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        table[2] = Fr::zero();
+        table[5] = table[1];
+        let preprocessed = preprocess_table(&table, &srs).unwrap();
+        assert_eq!(
+            preprocessed.lagrange,
+            MonomialSrs::new(srs.points()[..8].to_vec())
+                .to_lagrange()
+                .unwrap()
+        );
+
+        let t = DensePolynomial::from_coefficients_vec(domain.ifft(&table));
+        let vanishing: DenseOrSparsePolynomial<Fr> = domain.vanishing_polynomial().into();
+        for i in 0..8 {
+            let mut unit = vec![Fr::zero(); 8];
+            unit[i] = Fr::one();
+            let lagrange = DensePolynomial::from_coefficients_vec(domain.ifft(&unit));
+
+            // L_i (T - t_i) is divisible by Z_V with quotient Q_i.
+            let shifted = &t - &DensePolynomial::from_coefficients_vec(vec![table[i]]);
+            let numerator: DenseOrSparsePolynomial<Fr> = (&lagrange * &shifted).into();
+            let (quotient, remainder) = numerator.divide_with_q_and_r(&vanishing).unwrap();
+            assert!(remainder.is_zero());
+            assert_eq!(preprocessed.quotients[i], commit(&srs, &quotient));
+
+            let zero_quotient = DensePolynomial::from_coefficients_slice(&lagrange.coeffs[1..]);
+            assert_eq!(
+                preprocessed.lagrange_zero_quotients[i],
+                commit(&srs, &zero_quotient)
+            );
+        }
+
+        assert!(preprocess_table(&table[..6], &srs).is_err());
+        assert!(preprocess_table(&vec![Fr::one(); 32], &srs).is_err());
+    }
+}
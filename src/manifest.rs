@@ -0,0 +1,162 @@
+//! This module computes digests of the points going into and coming out of a conversion so results can be
+//! published and cross-checked. Each point is hashed in its compressed arkworks serialization, and the points are
+//! hashed in the order they are stored, so two parties converting the same SRS get the same manifest.
+use crate::{error::InterpolationError, srs_to_lagrange};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{fmt, string::String, vec::Vec};
+use sha2::{Digest, Sha256};
+
+/// The SHA-256 and Blake3 digests of a list of points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointsDigest {
+    /// The number of points hashed.
+    pub len: u64,
+    /// The SHA-256 digest of the concatenated compressed points.
+    pub sha256: [u8; 32],
+    /// The Blake3 digest of the concatenated compressed points.
+    pub blake3: [u8; 32],
+}
+
+impl fmt::Display for PointsDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} points, sha256 ", self.len)?;
+        self.sha256
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))?;
+        write!(f, ", blake3 ")?;
+        self.blake3.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// The digests of the input and output of a conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionManifest {
+    /// The digest of the monomial points that were converted.
+    pub input: PointsDigest,
+    /// The digest of the Lagrange points that were produced.
+    pub output: PointsDigest,
+}
+
+/// An incremental hasher producing a [`PointsDigest`], for callers that see the points one at a time.
+#[derive(Clone, Default)]
+pub struct PointsHasher {
+    len: u64,
+    sha256: Sha256,
+    blake3: blake3::Hasher,
+    buffer: Vec<u8>,
+}
+
+impl PointsHasher {
+    /// Creates a hasher that has seen no points.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next point to the digest.
+    pub fn update<E: SWCurveConfig>(&mut self, point: &Affine<E>) {
+        self.buffer.clear();
+        point
+            .serialize_compressed(&mut self.buffer)
+            .expect("serializing into a vector is infallible");
+        self.sha256.update(&self.buffer);
+        self.blake3.update(&self.buffer);
+        self.len += 1;
+    }
+
+    /// Returns the digest of all the points seen so far.
+    pub fn finalize(self) -> PointsDigest {
+        PointsDigest {
+            len: self.len,
+            sha256: self.sha256.finalize().into(),
+            blake3: self.blake3.finalize().into(),
+        }
+    }
+}
+
+/// This function encodes `bytes` as lowercase hex.
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// This function decodes a hex string of exactly `N` bytes.
+pub(crate) fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], InterpolationError> {
+    let invalid = || InterpolationError::SerializationError(format!("Invalid hex string {}", hex));
+    if hex.len() != 2 * N {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = hex
+            .get(2 * i..2 * i + 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+            .ok_or_else(invalid)?;
+    }
+    Ok(bytes)
+}
+
+/// This function returns the digest of `points`.
+pub fn digest_points<E: SWCurveConfig>(points: &[Affine<E>]) -> PointsDigest {
+    let mut hasher = PointsHasher::new();
+    points.iter().for_each(|point| hasher.update(point));
+    hasher.finalize()
+}
+
+/// This function is the same as [`srs_to_lagrange`] but also returns the manifest of the conversion.
+pub fn srs_to_lagrange_with_manifest<E, F>(
+    points: &[Affine<E>],
+) -> Result<(Vec<Affine<E>>, ConversionManifest), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let lagrange_points = srs_to_lagrange(points)?;
+    let manifest = ConversionManifest {
+        input: digest_points(points),
+        output: digest_points(&lagrange_points),
+    };
+    Ok((lagrange_points, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::g1::Config as BnConfig;
+    use ark_ec::AffineRepr;
+
+    #[test]
+    fn test_manifest() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let (lagrange_points, manifest) = srs_to_lagrange_with_manifest(&points).unwrap();
+        assert_eq!(lagrange_points, srs_to_lagrange(&points).unwrap());
+
+        // The digests are of the concatenated compressed points.
+        let mut bytes = Vec::new();
+        points
+            .iter()
+            .for_each(|point| point.serialize_compressed(&mut bytes).unwrap());
+        assert_eq!(manifest.input.len, 16);
+        assert_eq!(
+            manifest.input.sha256,
+            <[u8; 32]>::from(Sha256::digest(&bytes))
+        );
+        assert_eq!(manifest.input.blake3, *blake3::hash(&bytes).as_bytes());
+        assert_eq!(manifest.output, digest_points(&lagrange_points));
+
+        // Changing a single point changes both digests.
+        let mut changed = points.clone();
+        changed[3] = Affine::<BnConfig>::generator();
+        let changed = digest_points(&changed);
+        assert_ne!(changed.sha256, manifest.input.sha256);
+        assert_ne!(changed.blake3, manifest.input.blake3);
+
+        let display = manifest.input.to_string();
+        assert!(display.starts_with("16 points, sha256 "));
+        assert!(display.len() > 2 * 64);
+    }
+}
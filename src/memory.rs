@@ -0,0 +1,140 @@
+//! This module estimates how much memory a conversion needs before it is run, so operators can size machines for
+//! large setups and pick the execution mode that fits.
+//!
+//! The estimates count the buffers of points and base field elements the conversion allocates, at their size in
+//! memory rather than on disk, and leave out allocations that do not grow with the SRS.
+use crate::{curves::CurveId, error::InterpolationError, four_step, utils};
+
+/// The expected peak memory of converting one SRS, in bytes, under each way of running the conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// The number of points in the SRS.
+    pub size: usize,
+    /// The bytes of one affine point in memory.
+    pub point_bytes: usize,
+    /// The bytes of the points of the SRS, which is also the size of the output.
+    pub points_bytes: usize,
+    /// The bytes of scratch space the rounds use on top of the points, including the second copy of the points
+    /// that domains large enough for the four-step decomposition need.
+    pub scratch_bytes: usize,
+    /// The peak of [`crate::srs_to_lagrange`], which keeps the input and allocates the output.
+    pub in_memory_bytes: usize,
+    /// The peak of [`crate::srs_to_lagrange_into`] into a caller's buffer, such as a memory-mapped file, counting
+    /// that buffer but not the input.
+    pub in_place_bytes: usize,
+    /// The peak of the conversions in [`crate::stream`], which hold a single vector of points.
+    pub streaming_bytes: usize,
+    /// Whether the domain is large enough for the conversion to use the four-step decomposition.
+    pub four_step: bool,
+}
+
+/// This function returns the number of bytes of an element of the base field of `curve` in memory.
+fn base_field_bytes(curve: CurveId) -> usize {
+    match curve {
+        CurveId::Bn254
+        | CurveId::Bandersnatch
+        | CurveId::Grumpkin
+        | CurveId::Pallas
+        | CurveId::Vesta => 32,
+        CurveId::Bls12_381 | CurveId::Bls12_377 => 48,
+        CurveId::Bw6_761 => 96,
+    }
+}
+
+/// This function estimates the memory needed to convert an SRS of `2^log_n` points on `curve`. It errors if that
+/// many points do not fit in a [`usize`] or their size in bytes would overflow one.
+///
+/// The scratch of the four-step decomposition is counted for every thread of the rayon pool when the `parallel`
+/// feature is on, since each thread runs a smaller operation at a time.
+pub fn memory_requirements(
+    log_n: u32,
+    curve: CurveId,
+) -> Result<MemoryEstimate, InterpolationError> {
+    let size = utils::size_from_log(log_n)?;
+    let field_bytes = base_field_bytes(curve);
+    // The two coordinates and the infinity flag, padded to the alignment of the limbs.
+    let point_bytes = 2 * field_bytes + 8;
+    let overflow = || {
+        InterpolationError::InvalidParameters(format!(
+            "The memory for 2^{} points does not fit in a usize",
+            log_n
+        ))
+    };
+    let points_bytes = size.checked_mul(point_bytes).ok_or_else(overflow)?;
+
+    let four_step = size >= four_step::FOUR_STEP_THRESHOLD;
+    let scratch_bytes = if four_step {
+        let side = 1usize << log_n.div_ceil(2);
+        #[cfg(feature = "parallel")]
+        let threads = rayon::current_num_threads();
+        #[cfg(not(feature = "parallel"))]
+        let threads = 1;
+        let round_scratch = threads * utils::scratch_len_for(side, field_bytes) * field_bytes;
+        points_bytes
+            .checked_add(round_scratch)
+            .ok_or_else(overflow)?
+    } else {
+        utils::scratch_len_for(size, field_bytes) * field_bytes
+    };
+
+    let streaming_bytes = points_bytes
+        .checked_add(scratch_bytes)
+        .ok_or_else(overflow)?;
+    Ok(MemoryEstimate {
+        size,
+        point_bytes,
+        points_bytes,
+        scratch_bytes,
+        in_memory_bytes: streaming_bytes
+            .checked_add(points_bytes)
+            .ok_or_else(overflow)?,
+        in_place_bytes: streaming_bytes,
+        streaming_bytes,
+        four_step,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{curves::SrsCurve, utils::scratch_len};
+    use ark_ec::short_weierstrass::Affine;
+    use ark_std::mem::size_of;
+
+    fn check_curve<E: SrsCurve>() {
+        let estimate = memory_requirements(10, E::CURVE_ID).unwrap();
+        assert_eq!(estimate.point_bytes, size_of::<Affine<E>>());
+        assert_eq!(base_field_bytes(E::CURVE_ID), size_of::<E::BaseField>());
+        assert_eq!(
+            estimate.scratch_bytes,
+            scratch_len::<E>(1 << 10) * size_of::<E::BaseField>()
+        );
+    }
+
+    #[test]
+    fn test_memory_requirements() {
+        check_curve::<ark_bn254::g1::Config>();
+        check_curve::<crate::grumpkin::GrumpkinConfig>();
+        #[cfg(feature = "bls12-381")]
+        check_curve::<ark_bls12_381::g1::Config>();
+        #[cfg(feature = "bw6-761")]
+        check_curve::<ark_bw6_761::g1::Config>();
+
+        let estimate = memory_requirements(20, CurveId::Bn254).unwrap();
+        assert_eq!(estimate.size, 1 << 20);
+        assert_eq!(estimate.points_bytes, 72 << 20);
+        assert_eq!(
+            estimate.in_memory_bytes,
+            2 * estimate.points_bytes + estimate.scratch_bytes
+        );
+        assert_eq!(estimate.in_place_bytes, estimate.streaming_bytes);
+
+        // The four-step decomposition adds a second copy of the points.
+        let large = memory_requirements(22, CurveId::Bn254).unwrap();
+        assert!(large.four_step && !estimate.four_step);
+        assert!(large.scratch_bytes > large.points_bytes);
+
+        assert!(memory_requirements(64, CurveId::Bn254).is_err());
+        assert!(memory_requirements(60, CurveId::Bw6_761).is_err());
+    }
+}
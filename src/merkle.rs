@@ -0,0 +1,238 @@
+//! This module builds a Merkle tree over the points of a Lagrange SRS, so light clients can fetch only the basis
+//! points they need along with a proof that each belongs to an SRS they know the root of.
+//!
+//! The leaves are the SHA-256 hashes of the compressed encodings of the points, in the order of the SRS, and each
+//! node is the SHA-256 hash of its two children. Leaves and nodes are hashed with different prefixes, so a node
+//! cannot be passed off as a leaf. The number of points must be a power of two, which the size of a Lagrange SRS
+//! always is.
+use crate::{error::InterpolationError, srs::LagrangeSrs};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{
+    io::{Read, Write},
+    vec::Vec,
+};
+use sha2::{Digest, Sha256};
+
+/// The prefix of the preimage of a leaf.
+const LEAF_PREFIX: u8 = 0;
+/// The prefix of the preimage of a node.
+const NODE_PREFIX: u8 = 1;
+
+/// This function returns the leaf of `point`.
+pub fn leaf_hash<E: SWCurveConfig>(point: &Affine<E>) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(point.compressed_size());
+    point
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a vector does not fail");
+    Sha256::new()
+        .chain_update([LEAF_PREFIX])
+        .chain_update(bytes)
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([NODE_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// A Merkle tree over the points of an SRS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// The levels of the tree, from the leaves to the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// This function builds the tree over `points`. It errors if the number of points is not a power of two.
+    pub fn new<E: SWCurveConfig>(points: &[Affine<E>]) -> Result<Self, InterpolationError> {
+        if !points.len().is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let mut levels = vec![points.iter().map(leaf_hash).collect::<Vec<_>>()];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks_exact(2)
+                .map(|pair| node_hash(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { levels })
+    }
+
+    /// This function builds the tree over the points of `srs`.
+    pub fn from_srs<E: SWCurveConfig>(srs: &LagrangeSrs<E>) -> Result<Self, InterpolationError> {
+        Self::new(srs.points())
+    }
+
+    /// The root, which commits to every point.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The number of points the tree is over.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Whether the tree is over no points, which [`MerkleTree::new`] does not allow.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// This function returns the proof that the point at `index` is in the tree. It errors if `index` is out of
+    /// range.
+    pub fn proof(&self, index: usize) -> Result<MerkleProof, InterpolationError> {
+        if index >= self.len() {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The tree is over {} points but the proof of point {} was requested",
+                self.len(),
+                index
+            )));
+        }
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect();
+        Ok(MerkleProof {
+            index: index as u64,
+            siblings,
+        })
+    }
+}
+
+/// The proof that a point is at an index of the SRS a Merkle tree is over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MerkleProof {
+    /// The index of the point.
+    pub index: u64,
+    /// The siblings of the nodes on the path from the leaf to the root, starting with the sibling of the leaf.
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// This function returns the root the proof leads to from `point`.
+    pub fn root<E: SWCurveConfig>(&self, point: &Affine<E>) -> [u8; 32] {
+        self.siblings.iter().enumerate().fold(
+            leaf_hash(point),
+            |node, (depth, sibling)| match (self.index >> depth) & 1 {
+                0 => node_hash(&node, sibling),
+                _ => node_hash(sibling, &node),
+            },
+        )
+    }
+
+    /// This function checks that `point` is at [`MerkleProof::index`] of an SRS of `size` points whose tree has the
+    /// root `root`.
+    pub fn verify<E: SWCurveConfig>(
+        &self,
+        root: &[u8; 32],
+        size: usize,
+        point: &Affine<E>,
+    ) -> Result<(), InterpolationError> {
+        if !size.is_power_of_two() || self.siblings.len() != size.ilog2() as usize {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "A proof with {} siblings is not for a tree over {} points",
+                self.siblings.len(),
+                size
+            )));
+        }
+        if self.index >= size as u64 {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Point {} is not in a tree over {} points",
+                self.index, size
+            )));
+        }
+        if self.root(point) != *root {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The point is not at index {} of the tree",
+                self.index
+            )));
+        }
+        Ok(())
+    }
+
+    /// Writes the proof as the little endian index, the number of siblings as a byte and the siblings.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        writer.write_all(&self.index.to_le_bytes())?;
+        writer.write_all(&[self.siblings.len() as u8])?;
+        for sibling in self.siblings.iter() {
+            writer.write_all(sibling)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a proof written by [`MerkleProof::write`].
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut index = [0u8; 8];
+        reader.read_exact(&mut index)?;
+        let mut depth = [0u8; 1];
+        reader.read_exact(&mut depth)?;
+        let siblings = (0..depth[0])
+            .map(|_| {
+                let mut sibling = [0u8; 32];
+                reader.read_exact(&mut sibling)?;
+                Ok(sibling)
+            })
+            .collect::<Result<Vec<_>, InterpolationError>>()?;
+        Ok(Self {
+            index: u64::from_le_bytes(index),
+            siblings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs::MonomialSrs, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_merkle_proofs() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange = srs.to_lagrange().unwrap();
+        let tree = MerkleTree::from_srs(&lagrange).unwrap();
+        assert_eq!(tree.len(), 16);
+        let root = tree.root();
+        for (i, point) in lagrange.points().iter().enumerate() {
+            let proof = tree.proof(i).unwrap();
+            assert_eq!(proof.siblings.len(), 4);
+            proof.verify(&root, 16, point).unwrap();
+
+            let mut bytes = Vec::new();
+            proof.write(&mut bytes).unwrap();
+            assert_eq!(MerkleProof::read(&mut bytes.as_slice()).unwrap(), proof);
+        }
+
+        // A point at another index, another index or a changed sibling fails.
+        let proof = tree.proof(3).unwrap();
+        assert!(proof.verify(&root, 16, &lagrange.points()[4]).is_err());
+        let mut moved = proof.clone();
+        moved.index = 2;
+        assert!(moved.verify(&root, 16, &lagrange.points()[3]).is_err());
+        let mut changed = proof.clone();
+        changed.siblings[2][0] ^= 1;
+        assert!(changed.verify(&root, 16, &lagrange.points()[3]).is_err());
+        assert!(proof.verify(&root, 32, &lagrange.points()[3]).is_err());
+        assert!(tree.proof(16).is_err());
+
+        // The tree over a single point is its leaf.
+        let single = MerkleTree::new(&lagrange.points()[..1]).unwrap();
+        assert_eq!(single.root(), leaf_hash(&lagrange.points()[0]));
+        single
+            .proof(0)
+            .unwrap()
+            .verify(&single.root(), 1, &lagrange.points()[0])
+            .unwrap();
+        assert!(MerkleTree::new(&lagrange.points()[..3]).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! This module contains conversions fixed to the G1 groups of BN254 and BLS12-381, which almost every user of this
+//! crate converts. They are compiled once here, fully monomorphized, rather than in every downstream crate, and
+//! check the size of the SRS against the two-adicity of each scalar field up front.
+use crate::{error::InterpolationError, lagrange_to_srs, srs_to_lagrange};
+use ark_ff::FftField;
+use ark_std::vec::Vec;
+
+/// This function returns an error naming `curve` if a domain of `size` points does not exist in a scalar field of
+/// two-adicity `two_adicity`.
+fn check_size(curve: &str, size: usize, two_adicity: u32) -> Result<(), InterpolationError> {
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    if size.ilog2() > two_adicity {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "{} has no domain of {} points, the largest is 2^{}",
+            curve, size, two_adicity
+        )));
+    }
+    Ok(())
+}
+
+/// This function is [`crate::srs_to_lagrange`] for BN254, which has domains of up to `2^28` points.
+#[cfg(feature = "fast-bn254")]
+pub fn srs_to_lagrange_bn254(
+    points: &[ark_bn254::G1Affine],
+) -> Result<Vec<ark_bn254::G1Affine>, InterpolationError> {
+    check_size("BN254", points.len(), ark_bn254::Fr::TWO_ADICITY)?;
+    srs_to_lagrange::<ark_bn254::g1::Config, ark_bn254::Fr>(points)
+}
+
+/// This function is [`crate::lagrange_to_srs`] for BN254.
+#[cfg(feature = "fast-bn254")]
+pub fn lagrange_to_srs_bn254(
+    points: &[ark_bn254::G1Affine],
+) -> Result<Vec<ark_bn254::G1Affine>, InterpolationError> {
+    check_size("BN254", points.len(), ark_bn254::Fr::TWO_ADICITY)?;
+    lagrange_to_srs::<ark_bn254::g1::Config, ark_bn254::Fr>(points)
+}
+
+/// This function is [`crate::srs_to_lagrange`] for BLS12-381, which has domains of up to `2^32` points.
+#[cfg(feature = "fast-bls12-381")]
+pub fn srs_to_lagrange_bls12_381(
+    points: &[ark_bls12_381::G1Affine],
+) -> Result<Vec<ark_bls12_381::G1Affine>, InterpolationError> {
+    check_size("BLS12-381", points.len(), ark_bls12_381::Fr::TWO_ADICITY)?;
+    srs_to_lagrange::<ark_bls12_381::g1::Config, ark_bls12_381::Fr>(points)
+}
+
+/// This function is [`crate::lagrange_to_srs`] for BLS12-381.
+#[cfg(feature = "fast-bls12-381")]
+pub fn lagrange_to_srs_bls12_381(
+    points: &[ark_bls12_381::G1Affine],
+) -> Result<Vec<ark_bls12_381::G1Affine>, InterpolationError> {
+    check_size("BLS12-381", points.len(), ark_bls12_381::Fr::TWO_ADICITY)?;
+    lagrange_to_srs::<ark_bls12_381::g1::Config, ark_bls12_381::Fr>(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+
+    #[test]
+    fn test_check_size() {
+        assert!(check_size("BN254", 1 << 28, 28).is_ok());
+        assert!(check_size("BN254", 1, 28).is_ok());
+        assert!(check_size("BN254", 1 << 29, 28).is_err());
+        assert!(check_size("BN254", 12, 28).is_err());
+        assert!(check_size("BN254", 0, 28).is_err());
+    }
+
+    #[cfg(feature = "fast-bn254")]
+    #[test]
+    fn test_bn254() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<ark_bn254::g1::Config, _>(rng, 15).unwrap();
+        let lagrange = srs_to_lagrange_bn254(&srs).unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&srs).unwrap());
+        assert_eq!(lagrange_to_srs_bn254(&lagrange).unwrap(), srs);
+        assert!(srs_to_lagrange_bn254(&srs[..3]).is_err());
+    }
+
+    #[cfg(feature = "fast-bls12-381")]
+    #[test]
+    fn test_bls12_381() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<ark_bls12_381::g1::Config, _>(rng, 15).unwrap();
+        let lagrange = srs_to_lagrange_bls12_381(&srs).unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&srs).unwrap());
+        assert_eq!(lagrange_to_srs_bls12_381(&lagrange).unwrap(), srs);
+    }
+}
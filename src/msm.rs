@@ -0,0 +1,238 @@
+//! This module contains precomputed tables that speed up repeated MSMs over the same points, such as the
+//! commitments made with a [`crate::srs::LagrangeSrs`] over the lifetime of a prover.
+//!
+//! Pippenger's algorithm splits each scalar into windows of `c` bits, sorts the points into buckets by their digit
+//! in each window, and doubles `c` times between windows. With every point multiplied by `2^(c j)` for each window
+//! `j` ahead of time, all the windows can share one set of buckets and the doublings disappear, so an MSM over `n`
+//! points costs about `n * b / c + 2^c` additions for `b`-bit scalars instead of `b / c * (n + 2^c)`. That makes
+//! windows of around `log2(n) + 1` bits pay off where plain Pippenger would use far smaller ones. The buckets are
+//! also added to in affine form with the batched inversions of [`crate::batch`], which is cheaper per addition.
+//! The price is storing `b / c + 1` points for each point of the SRS.
+use crate::{batch::batch_add_at, error::InterpolationError};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, Group,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{cfg_chunks, cfg_iter, mem::size_of, vec::Vec, Zero};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The largest window [`MsmTable::new`] accepts. Every thread keeps `2^window_bits` buckets while committing.
+pub const MAX_WINDOW_BITS: usize = 20;
+/// The most bucket additions [`MsmTable::msm`] batches under one inversion.
+const MAX_BATCH_SIZE: usize = 1024;
+
+/// The multiples `2^(c j) P` of every point `P` of a list, for a window of `c` bits and each window `j` of a scalar.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct MsmTable<E: SWCurveConfig> {
+    /// The multiples of the `i`th point are at `windows * i..windows * (i + 1)`.
+    bases: Vec<Affine<E>>,
+    window_bits: usize,
+    windows: usize,
+}
+
+impl<E: SWCurveConfig> MsmTable<E> {
+    /// This function builds the table for `points` with windows of `window_bits` bits, which must be between one
+    /// and [`MAX_WINDOW_BITS`]. Larger windows make each MSM faster for large lists, at the cost of more memory.
+    pub fn new(points: &[Affine<E>], window_bits: usize) -> Result<Self, InterpolationError> {
+        if window_bits == 0 || window_bits > MAX_WINDOW_BITS {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The window must be between 1 and {} bits, not {}",
+                MAX_WINDOW_BITS, window_bits
+            )));
+        }
+        // Signed digits can carry one bit past the top of the scalar.
+        let windows = E::ScalarField::MODULUS_BIT_SIZE as usize / window_bits + 1;
+        let multiples = cfg_iter!(points)
+            .flat_map(|point| {
+                let mut multiple = Projective::<E>::from(*point);
+                (0..windows)
+                    .map(|_| {
+                        let current = multiple;
+                        (0..window_bits).for_each(|_| {
+                            multiple.double_in_place();
+                        });
+                        current
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Ok(Self {
+            bases: Projective::<E>::normalize_batch(&multiples),
+            window_bits,
+            windows,
+        })
+    }
+
+    /// The number of points the table was built for.
+    pub fn len(&self) -> usize {
+        self.bases.len() / self.windows
+    }
+
+    /// Whether the table was built for no points.
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+
+    /// The number of bits in each window.
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// The number of bytes the stored points take up.
+    pub fn size_in_bytes(&self) -> usize {
+        self.bases.len() * size_of::<Affine<E>>()
+    }
+
+    /// This function returns the sum of `scalars[i]` times the `i`th point. There must be one scalar per point.
+    pub fn msm(&self, scalars: &[E::ScalarField]) -> Result<Projective<E>, InterpolationError> {
+        if scalars.len() != self.len() {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "The table has {} points but {} scalars were given",
+                self.len(),
+                scalars.len()
+            )));
+        }
+        if scalars.is_empty() {
+            return Ok(Projective::<E>::zero());
+        }
+        let scalars = cfg_iter!(scalars)
+            .map(|scalar| scalar.into_bigint())
+            .collect::<Vec<_>>();
+
+        // Each thread sorts its share of the points into its own buckets.
+        let chunk = scalars.len().div_ceil(thread_count());
+        Ok(cfg_chunks!(self.bases, chunk * self.windows)
+            .zip(cfg_chunks!(scalars, chunk))
+            .map(|(bases, scalars)| self.bucket_sum(bases, scalars))
+            .sum())
+    }
+
+    fn bucket_sum(
+        &self,
+        bases: &[Affine<E>],
+        scalars: &[<E::ScalarField as PrimeField>::BigInt],
+    ) -> Projective<E> {
+        // Digits are taken between -2^(c - 1) and 2^(c - 1), so a negative digit adds the negated point to the
+        // bucket of its absolute value and only half as many buckets are needed.
+        let half = 1i64 << (self.window_bits - 1);
+        let batch_size = (half as usize).min(MAX_BATCH_SIZE);
+
+        // The buckets are kept in affine form and added to in batches that share one inversion. A point for a
+        // bucket already in the current batch goes to a projective spill bucket instead.
+        let mut buckets = vec![Affine::<E>::identity(); half as usize];
+        let mut spill = vec![Projective::<E>::zero(); half as usize];
+        let mut busy = vec![false; half as usize];
+        let mut batch = Vec::with_capacity(batch_size);
+        for (multiples, scalar) in bases.chunks(self.windows).zip(scalars) {
+            let mut carry = 0;
+            for (j, multiple) in multiples.iter().enumerate() {
+                let mut digit =
+                    window(scalar, j * self.window_bits, self.window_bits) as i64 + carry;
+                carry = 0;
+                if digit > half {
+                    digit -= 2 * half;
+                    carry = 1;
+                }
+                let (index, point) = match digit {
+                    0 => continue,
+                    d if d > 0 => (d as usize - 1, *multiple),
+                    d => ((-d) as usize - 1, -*multiple),
+                };
+                if busy[index] {
+                    spill[index] += point;
+                    continue;
+                }
+                busy[index] = true;
+                batch.push((index, point));
+                if batch.len() == batch_size {
+                    batch_add_at(&mut buckets, &batch);
+                    batch.drain(..).for_each(|(index, _)| busy[index] = false);
+                }
+            }
+        }
+        batch_add_at(&mut buckets, &batch);
+
+        // The running sum from the top bucket down adds the dth bucket d times.
+        let mut running = Projective::<E>::zero();
+        let mut total = Projective::<E>::zero();
+        for (bucket, spilled) in buckets.into_iter().zip(spill).rev() {
+            running += spilled + bucket;
+            total += running;
+        }
+        total
+    }
+}
+
+/// This function returns the `bits` bits of `scalar` starting at bit `start`.
+fn window<B: BigInteger>(scalar: &B, start: usize, bits: usize) -> usize {
+    let limbs = scalar.as_ref();
+    let (limb, offset) = (start / 64, start % 64);
+    let mut value = limbs[limb] >> offset;
+    if limb >= limbs.len() {
+        return 0;
+    }
+    if offset + bits > 64 && limb + 1 < limbs.len() {
+        value |= limbs[limb + 1] << (64 - offset);
+    }
+    (value & ((1 << bits) - 1)) as usize
+}
+
+fn thread_count() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::VariableBaseMSM;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_msm_table() {
+        let rng = &mut ark_std::test_rng();
+        let points = (0..20)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let mut scalars = (0..20).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        scalars[0] = Fr::zero();
+        scalars[1] = -Fr::from(1u64);
+        let expected = G1Projective::msm(&points, &scalars).unwrap();
+
+        // Windows that do and do not divide the 254 bits of the scalar, and ones that straddle limbs.
+        for window_bits in [1, 7, 13, 16] {
+            let table = MsmTable::new(&points, window_bits).unwrap();
+            assert_eq!(table.len(), 20);
+            assert_eq!(table.window_bits(), window_bits);
+            assert_eq!(table.msm(&scalars).unwrap(), expected);
+        }
+
+        let table = MsmTable::new(&points, 8).unwrap();
+        assert_eq!(
+            table.size_in_bytes(),
+            20 * 32 * size_of::<Affine<ark_bn254::g1::Config>>()
+        );
+        assert!(table.msm(&scalars[..19]).is_err());
+        assert!(MsmTable::new(&points, 0).is_err());
+        assert!(MsmTable::new(&points, MAX_WINDOW_BITS + 1).is_err());
+        assert_eq!(
+            MsmTable::<ark_bn254::g1::Config>::new(&[], 4)
+                .unwrap()
+                .msm(&[])
+                .unwrap(),
+            G1Projective::zero()
+        );
+    }
+}
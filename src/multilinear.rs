@@ -0,0 +1,98 @@
+//! This module converts product structured multilinear SRSs, as PST and HyperKZG style schemes use, from the
+//! multilinear monomial basis to the Lagrange basis over the boolean hypercube.
+//!
+//! For `v` variables the SRS has `2^v` points and the point at index `b` is `[prod_k tau_k^(b_k)]`, where `b_k` is
+//! bit `k` of `b`. The Lagrange polynomial of the hypercube at `b` is `eq(b, X) = prod_k (b_k X_k + (1 - b_k)
+//! (1 - X_k))`, so every variable splits a pair of points `[m_0], [m_0 tau_k]` into `[m_0 (1 - tau_k)]` and
+//! `[m_0 tau_k]`. This is the butterfly structure of the group FFT with no twiddles: each round subtracts the point
+//! with bit `k` set from its partner, in `v 2^(v - 1)` group operations.
+use crate::error::InterpolationError;
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_std::{cfg_chunks_mut, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// This function runs a round over the variable whose pairs are `half` apart, replacing each pair `(a, c)` by
+/// `(a - c, c)` or, when `inverse` is set, by `(a + c, c)`.
+fn hypercube_round<E: SWCurveConfig>(points: &mut [Projective<E>], half: usize, inverse: bool) {
+    cfg_chunks_mut!(points, 2 * half).for_each(|chunk| {
+        let (low, high) = chunk.split_at_mut(half);
+        for (a, c) in low.iter_mut().zip(high.iter()) {
+            match inverse {
+                true => *a += c,
+                false => *a -= c,
+            }
+        }
+    });
+}
+
+fn hypercube_transform<E: SWCurveConfig>(
+    points: &[Affine<E>],
+    inverse: bool,
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let mut result = points
+        .iter()
+        .map(|point| Projective::<E>::from(*point))
+        .collect::<Vec<_>>();
+    let mut half = 1;
+    while half < result.len() {
+        hypercube_round(&mut result, half, inverse);
+        half *= 2;
+    }
+    Ok(Projective::<E>::normalize_batch(&result))
+}
+
+/// This function converts the points `[prod_k tau_k^(b_k)]` of a multilinear SRS to the commitments
+/// `[eq(b, tau)]` to the Lagrange basis over the boolean hypercube, both indexed by `b`. It errors if the number of
+/// points is not a power of two.
+pub fn multilinear_to_lagrange<E: SWCurveConfig>(
+    points: &[Affine<E>],
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    hypercube_transform(points, false)
+}
+
+/// This function is the inverse of [`multilinear_to_lagrange`].
+pub fn lagrange_to_multilinear<E: SWCurveConfig>(
+    points: &[Affine<E>],
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    hypercube_transform(points, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ec::AffineRepr;
+    use ark_std::{One, UniformRand};
+
+    #[test]
+    fn test_multilinear_to_lagrange() {
+        let rng = &mut ark_std::test_rng();
+        let taus = (0..3).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let g = G1Affine::generator();
+        let product = |b: usize, factor: &dyn Fn(bool, Fr) -> Fr| {
+            taus.iter().enumerate().fold(Fr::one(), |acc, (k, tau)| {
+                acc * factor(b >> k & 1 == 1, *tau)
+            })
+        };
+        let points = (0..8)
+            .map(|b| (g * product(b, &|bit, tau| if bit { tau } else { Fr::one() })).into_affine())
+            .collect::<Vec<_>>();
+
+        let lagrange = multilinear_to_lagrange(&points).unwrap();
+        for (b, point) in lagrange.iter().enumerate() {
+            let eq = product(b, &|bit, tau| if bit { tau } else { Fr::one() - tau });
+            assert_eq!(*point, (g * eq).into_affine());
+        }
+        assert_eq!(lagrange_to_multilinear(&lagrange).unwrap(), points);
+
+        assert_eq!(multilinear_to_lagrange(&points[..1]).unwrap(), points[..1]);
+        assert!(multilinear_to_lagrange(&points[..6]).is_err());
+    }
+}
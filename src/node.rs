@@ -0,0 +1,179 @@
+//! This module exposes the conversion and the Lagrange check to Node.js through `napi-rs`, taking and returning
+//! files in this crate's binary format as byte arrays.
+//!
+//! Both functions copy their input and run on the libuv worker pool, so a large setup never blocks the event loop,
+//! and return a promise that resolves with the result or rejects with the error message. The conversion itself
+//! still uses every core through rayon when the `parallel` feature is on.
+use crate::{
+    curves::{CurveId, SrsCurve},
+    error::InterpolationError,
+    formats::binary::{read_lagrange, read_monomial, write_lagrange, BinaryHeader},
+    srs::PointOrdering,
+    verify::check_lagrange_matches_monomial,
+};
+use ark_std::vec::Vec;
+use napi::{
+    bindgen_prelude::{AsyncTask, Buffer, Uint8Array},
+    Env, Error, Status, Task,
+};
+use napi_derive::napi;
+
+fn read_header(bytes: &[u8]) -> Result<BinaryHeader, InterpolationError> {
+    BinaryHeader::read(&mut &bytes[..])
+}
+
+fn unsupported(curve: CurveId) -> InterpolationError {
+    InterpolationError::InvalidParameters(format!("The curve {} is not supported", curve.name()))
+}
+
+fn convert_curve<E: SrsCurve>(
+    monomial: &[u8],
+    ordering: PointOrdering,
+) -> Result<Vec<u8>, InterpolationError> {
+    let header = read_header(monomial)?;
+    let lagrange = read_monomial::<_, E>(monomial)?.to_lagrange_with_ordering(ordering)?;
+    write_lagrange(Vec::new(), &lagrange, header.encoding, header.endianness)
+}
+
+/// This function converts a monomial SRS file to a Lagrange SRS file with the same encoding and endianness.
+fn convert_file(monomial: &[u8], ordering: PointOrdering) -> Result<Vec<u8>, InterpolationError> {
+    match read_header(monomial)?.curve {
+        CurveId::Bn254 => convert_curve::<ark_bn254::g1::Config>(monomial, ordering),
+        CurveId::Bls12_381 => convert_curve::<ark_bls12_381::g1::Config>(monomial, ordering),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+fn verify_curve<E: SrsCurve>(monomial: &[u8], lagrange: &[u8]) -> Result<bool, InterpolationError> {
+    check_lagrange_matches_monomial(
+        &read_monomial::<_, E>(monomial)?,
+        &read_lagrange::<_, E>(lagrange)?,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// This function checks that a Lagrange SRS file is the conversion of a monomial SRS file.
+fn verify_files(monomial: &[u8], lagrange: &[u8]) -> Result<bool, InterpolationError> {
+    let curve = read_header(monomial)?.curve;
+    if read_header(lagrange)?.curve != curve {
+        return Err(InterpolationError::InvalidParameters(
+            "The two SRSs are on different curves".to_string(),
+        ));
+    }
+    match curve {
+        CurveId::Bn254 => verify_curve::<ark_bn254::g1::Config>(monomial, lagrange),
+        CurveId::Bls12_381 => verify_curve::<ark_bls12_381::g1::Config>(monomial, lagrange),
+        curve => Err(unsupported(curve)),
+    }
+}
+
+fn to_napi_error(e: InterpolationError) -> Error {
+    Error::new(Status::GenericFailure, e.to_string())
+}
+
+/// The conversion of a monomial SRS file, run off the main thread.
+pub struct ConvertTask {
+    monomial: Vec<u8>,
+    ordering: PointOrdering,
+}
+
+impl Task for ConvertTask {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        convert_file(&self.monomial, self.ordering).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// The check of a Lagrange SRS file against a monomial SRS file, run off the main thread.
+pub struct VerifyTask {
+    monomial: Vec<u8>,
+    lagrange: Vec<u8>,
+}
+
+impl Task for VerifyTask {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        verify_files(&self.monomial, &self.lagrange).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// This function converts a monomial SRS file to a Lagrange SRS file, with its points in bit-reversed order if
+/// `bit_reversed` is set. It is `convertToLagrange` in JavaScript and returns a `Promise<Buffer>`.
+#[napi(ts_return_type = "Promise<Buffer>")]
+pub fn convert_to_lagrange(monomial: Uint8Array, bit_reversed: bool) -> AsyncTask<ConvertTask> {
+    let ordering = if bit_reversed {
+        PointOrdering::BitReversed
+    } else {
+        PointOrdering::Natural
+    };
+    AsyncTask::new(ConvertTask {
+        monomial: monomial.to_vec(),
+        ordering,
+    })
+}
+
+/// This function checks that a Lagrange SRS file is the conversion of a monomial SRS file. It is `verifyLagrange`
+/// in JavaScript and returns a `Promise<boolean>`.
+#[napi(ts_return_type = "Promise<boolean>")]
+pub fn verify_lagrange(monomial: Uint8Array, lagrange: Uint8Array) -> AsyncTask<VerifyTask> {
+    AsyncTask::new(VerifyTask {
+        monomial: monomial.to_vec(),
+        lagrange: lagrange.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{write_monomial, Endianness, PointEncoding},
+        srs::MonomialSrs,
+        tests::gen_srs_for_testing,
+    };
+    use ark_bls12_381::g1::Config as BlsConfig;
+
+    #[test]
+    fn test_convert_and_verify_files() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BlsConfig, _>(rng, 15).unwrap());
+        let monomial = write_monomial(
+            Vec::new(),
+            &srs,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        let lagrange = convert_file(&monomial, PointOrdering::BitReversed).unwrap();
+        assert_eq!(
+            read_lagrange::<_, BlsConfig>(lagrange.as_slice()).unwrap(),
+            srs.to_lagrange_with_ordering(PointOrdering::BitReversed)
+                .unwrap()
+        );
+        assert!(verify_files(&monomial, &lagrange).unwrap());
+
+        // SRSs of different sizes and files in the wrong basis are rejected.
+        let short = write_monomial(
+            Vec::new(),
+            &MonomialSrs::new(srs.points()[..8].to_vec()),
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+        let short_lagrange = convert_file(&short, PointOrdering::Natural).unwrap();
+        assert!(verify_files(&monomial, &short_lagrange).is_err());
+        assert!(convert_file(&lagrange, PointOrdering::Natural).is_err());
+    }
+}
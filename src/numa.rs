@@ -0,0 +1,246 @@
+//! This module runs the conversion with the points split between the NUMA nodes of a machine, for dual-socket
+//! prover boxes where traffic between sockets dominates on large point arrays. It is only available on Linux.
+//!
+//! After the bit-reversal permutation the first `log2(n / nodes)` rounds only ever combine points within the same
+//! block of `n / nodes`, so each node copies one block into memory it touches first, which Linux then places on
+//! that node, and runs those rounds with a thread pool pinned to its own CPUs. The remaining rounds combine points
+//! from different blocks and run on the global rayon pool as usual. Pinning is best effort: a CPU the process may
+//! not run on is skipped rather than reported.
+use crate::{
+    buffer::try_copied,
+    domain_size_inv,
+    error::InterpolationError,
+    utils::{bit_reverse_permutation, fft_round},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, vec::Vec};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use std::{fs, mem, path::Path, thread};
+
+/// The directory Linux lists the NUMA nodes in.
+const NODE_DIR: &str = "/sys/devices/system/node";
+
+/// A NUMA node and the CPUs on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaNode {
+    /// The number Linux gives the node.
+    pub id: usize,
+    /// The CPUs on the node.
+    pub cpus: Vec<usize>,
+}
+
+/// The NUMA nodes of a machine that have CPUs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaTopology {
+    nodes: Vec<NumaNode>,
+}
+
+impl NumaTopology {
+    /// This function reads the nodes of this machine from sysfs. A machine without NUMA support is treated as one
+    /// node holding every CPU.
+    pub fn detect() -> Result<Self, InterpolationError> {
+        let mut nodes = Vec::new();
+        if let Ok(entries) = fs::read_dir(NODE_DIR) {
+            for entry in entries {
+                let name = entry?.file_name();
+                let Some(id) = name
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("node"))
+                    .and_then(|id| id.parse::<usize>().ok())
+                else {
+                    continue;
+                };
+                let cpulist = fs::read_to_string(Path::new(NODE_DIR).join(&name).join("cpulist"))?;
+                let cpus = parse_cpulist(&cpulist)?;
+                if !cpus.is_empty() {
+                    nodes.push(NumaNode { id, cpus });
+                }
+            }
+        }
+        if nodes.is_empty() {
+            let cpus = thread::available_parallelism().map_or(1, usize::from);
+            nodes.push(NumaNode {
+                id: 0,
+                cpus: (0..cpus).collect(),
+            });
+        }
+        nodes.sort_by_key(|node| node.id);
+        Ok(Self { nodes })
+    }
+
+    /// This function builds a topology from the CPUs of each node, for machines whose sysfs is not to be trusted
+    /// or to use only some of the nodes. Every node must have at least one CPU.
+    pub fn from_cpus(nodes: Vec<Vec<usize>>) -> Result<Self, InterpolationError> {
+        if nodes.is_empty() || nodes.iter().any(|cpus| cpus.is_empty()) {
+            return Err(InterpolationError::InvalidParameters(
+                "Every NUMA node needs at least one CPU".to_string(),
+            ));
+        }
+        Ok(Self {
+            nodes: nodes
+                .into_iter()
+                .enumerate()
+                .map(|(id, cpus)| NumaNode { id, cpus })
+                .collect(),
+        })
+    }
+
+    /// The nodes, in order of their number.
+    pub fn nodes(&self) -> &[NumaNode] {
+        &self.nodes
+    }
+}
+
+/// This function parses a Linux CPU list such as `0-3,8-11`.
+fn parse_cpulist(cpulist: &str) -> Result<Vec<usize>, InterpolationError> {
+    let invalid = || InterpolationError::InvalidParameters(format!("Invalid CPU list {}", cpulist));
+    let mut cpus = Vec::new();
+    for range in cpulist.trim().split(',').filter(|range| !range.is_empty()) {
+        let (first, last) = range.split_once('-').unwrap_or((range, range));
+        let first = first.parse::<usize>().map_err(|_| invalid())?;
+        let last = last.parse::<usize>().map_err(|_| invalid())?;
+        if first > last {
+            return Err(invalid());
+        }
+        cpus.extend(first..=last);
+    }
+    Ok(cpus)
+}
+
+/// This function restricts the calling thread to `cpus`, skipping any the process may not run on.
+fn pin_to(cpus: &[usize]) {
+    // Safety: the set is zeroed before use and only CPUs below CPU_SETSIZE are added to it.
+    unsafe {
+        let mut set: libc::cpu_set_t = mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        cpus.iter()
+            .filter(|cpu| **cpu < libc::CPU_SETSIZE as usize)
+            .for_each(|cpu| libc::CPU_SET(*cpu, &mut set));
+        libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+}
+
+/// This function builds a thread pool with one thread per CPU of `node`, each pinned to the node.
+fn node_pool(node: &NumaNode) -> Result<ThreadPool, InterpolationError> {
+    let cpus = node.cpus.clone();
+    ThreadPoolBuilder::new()
+        .num_threads(node.cpus.len())
+        .start_handler(move |_| pin_to(&cpus))
+        .build()
+        .map_err(|e| InterpolationError::TaskError(e.to_string()))
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] but splits the points between the nodes of `topology`
+/// for the rounds that stay within a block. Only a power of two number of nodes is used, the largest that fits
+/// both the topology and the number of points.
+pub fn srs_to_lagrange_numa<E, F>(
+    points: &[Affine<E>],
+    topology: &NumaTopology,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = points.len();
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    let gen = domain.group_gen_inv();
+    let log_size = size.ilog2() as usize;
+    let nodes = 1usize << topology.nodes.len().min(size).ilog2();
+    let block_size = size / nodes;
+    let log_block_size = block_size.ilog2() as usize;
+    let round = |points: &mut [Affine<E>], i: usize| {
+        let prim_root = gen.pow([(size >> i) as u64]);
+        if i == 1 {
+            fft_round::<E, F, true>(points, prim_root, i)
+        } else {
+            fft_round::<E, F, false>(points, prim_root, i)
+        }
+    };
+
+    let mut ordered_points = try_copied(points)?;
+    bit_reverse_permutation(&mut ordered_points, log_size)?;
+
+    // Each node copies its block into memory it touches first and runs the rounds within the block there.
+    let pools = topology.nodes[..nodes]
+        .iter()
+        .map(node_pool)
+        .collect::<Result<Vec<_>, _>>()?;
+    thread::scope(|scope| {
+        let handles = ordered_points
+            .chunks_mut(block_size)
+            .zip(&pools)
+            .map(|(block, pool)| {
+                scope.spawn(move || {
+                    pool.install(|| {
+                        let mut local = try_copied(block)?;
+                        for i in 1..=log_block_size {
+                            round(&mut local, i)?;
+                        }
+                        block.copy_from_slice(&local);
+                        Result::<(), InterpolationError>::Ok(())
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().try_for_each(|handle| {
+            handle
+                .join()
+                .map_err(|_| InterpolationError::TaskError("A NUMA worker panicked".to_string()))?
+        })
+    })?;
+
+    // The rounds that combine blocks run over the whole array.
+    for i in log_block_size + 1..=log_size {
+        round(&mut ordered_points, i)?;
+    }
+
+    let domain_size_inv = domain_size_inv::<F>(size)?;
+    cfg_iter_mut!(ordered_points)
+        .for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    Ok(ordered_points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_parse_cpulist() {
+        assert_eq!(parse_cpulist("0-3,8-9\n").unwrap(), [0, 1, 2, 3, 8, 9]);
+        assert_eq!(parse_cpulist("5").unwrap(), [5]);
+        assert!(parse_cpulist("").unwrap().is_empty());
+        assert!(parse_cpulist("3-1").is_err());
+        assert!(parse_cpulist("a").is_err());
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_numa() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let expected = srs_to_lagrange(&srs).unwrap();
+
+        let topology = NumaTopology::detect().unwrap();
+        assert!(!topology.nodes().is_empty());
+        assert_eq!(srs_to_lagrange_numa(&srs, &topology).unwrap(), expected);
+
+        // Three nodes on the same CPU use two blocks of eight points, and a two point SRS uses two blocks of one.
+        let topology = NumaTopology::from_cpus(vec![vec![0], vec![0], vec![0]]).unwrap();
+        assert_eq!(srs_to_lagrange_numa(&srs, &topology).unwrap(), expected);
+        assert_eq!(
+            srs_to_lagrange_numa(&srs[..2], &topology).unwrap(),
+            srs_to_lagrange(&srs[..2]).unwrap()
+        );
+        assert!(srs_to_lagrange_numa(&srs[..3], &topology).is_err());
+        assert!(NumaTopology::from_cpus(vec![vec![0], vec![]]).is_err());
+    }
+}
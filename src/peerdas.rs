@@ -0,0 +1,386 @@
+//! This module derives the Lagrange bases PeerDAS (EIP-7594) needs from a monomial SRS: the basis over the domain
+//! of twice the size of the SRS, against which the extended blob polynomial is committed, and the bases over the
+//! cosets holding each cell.
+//!
+//! An SRS of size `n` only supports polynomials of degree less than `n`, so the extended basis is the one that
+//! commits correctly to those polynomials from their `2n` evaluations. Writing `w` for the generator of the extended
+//! domain, its even points are half the Lagrange basis of the SRS and its odd points are half the Lagrange basis of
+//! the SRS shifted by `w^-1`, that is of `[1], [tau / w], [(tau / w)^2], ...`.
+//!
+//! The proof of a cell is the commitment to the quotient of the blob polynomial by `X^l - z`, where `l` is the size
+//! of a cell and `z` the `l`th power of the shift of its coset. [`CellProofTables`] holds the part of the
+//! Feist-Khovratovich computation of every such quotient that only depends on the SRS, as
+//! [`crate::lookup::preprocess_table`] does for the openings at single points, so [`compute_cell_proofs`] takes
+//! `O(n log n)` group operations per blob of `n` coefficients.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+    srs_to_lagrange_unscaled,
+    utils::{bit_reverse, distribute_powers},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_into_iter, cfg_iter_mut, vec::Vec, Zero};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The number of field elements in an extended blob.
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = 8192;
+/// The number of field elements in a cell.
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+/// The number of cells in an extended blob.
+pub const CELLS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL;
+
+/// This function returns `scale` times the Lagrange basis over the coset `shift * H`, where `H` is the arkworks
+/// domain of size `points.len()`. The `j`th point is the commitment to the Lagrange polynomial that is one at
+/// `shift * generator^j`.
+fn scaled_coset_lagrange<E, F>(
+    points: &[Affine<E>],
+    shift: F,
+    scale: F,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let shift_inv = shift.inverse().ok_or(InterpolationError::FieldError(
+        "Could not invert coset shift".to_string(),
+    ))?;
+
+    // L_j(X) over the coset is L_j(X / shift) over H, so we shift the SRS before converting it.
+    let mut shifted = points.to_vec();
+    distribute_powers(&mut shifted, shift_inv);
+    srs_to_lagrange_unscaled(&mut shifted, domain.group_gen())?;
+    let scale = scale * domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(shifted).for_each(|point| *point = (*point * scale).into_affine());
+    Ok(shifted)
+}
+
+/// This function returns the Lagrange basis over the coset `shift * H`, where `H` is the arkworks domain of size
+/// `points.len()` and `points` are the first monomial points of an SRS. The points are in natural order.
+pub fn coset_lagrange<E, F>(
+    points: &[Affine<E>],
+    shift: F,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    scaled_coset_lagrange(points, shift, F::one())
+}
+
+/// This function returns the basis over the arkworks domain of size `2 * srs.len()` that commits to polynomials of
+/// degree less than `srs.len()` from their evaluations, in natural order. Use [`LagrangeSrs::with_ordering`] for the
+/// bit-reversed order the DAS spec uses.
+pub fn extended_lagrange_srs<E, F>(
+    srs: &MonomialSrs<E>,
+) -> Result<LagrangeSrs<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let extended_size = srs
+        .len()
+        .checked_mul(2)
+        .ok_or(InterpolationError::SizeError)?;
+    let domain =
+        Radix2EvaluationDomain::<F>::new(extended_size).ok_or(InterpolationError::SizeError)?;
+    let half = F::from(2u64)
+        .inverse()
+        .ok_or(InterpolationError::FieldError(
+            "Could not invert two".to_string(),
+        ))?;
+
+    let even = scaled_coset_lagrange(srs.points(), F::one(), half)?;
+    let odd = scaled_coset_lagrange(srs.points(), domain.group_gen(), half)?;
+    let points = even
+        .into_iter()
+        .zip(odd)
+        .flat_map(|(even, odd)| [even, odd])
+        .collect();
+    Ok(LagrangeSrs::from_parts(
+        points,
+        domain.group_gen(),
+        PointOrdering::Natural,
+    ))
+}
+
+/// This function returns, for each cell of an extended blob over the arkworks domain of size `extended_size`, the
+/// Lagrange basis over the coset holding the cell. As in the DAS spec, cell `i` holds the evaluations at the
+/// bit-reversed roots of unity with indices `cell_size * i` to `cell_size * (i + 1)`, and the points of each basis
+/// are given in that order. The basis of a cell commits to the interpolation polynomial of its evaluations.
+pub fn cell_coset_bases<E, F>(
+    srs: &MonomialSrs<E>,
+    extended_size: usize,
+    cell_size: usize,
+) -> Result<Vec<Vec<Affine<E>>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !extended_size.is_power_of_two() || !cell_size.is_power_of_two() || cell_size > extended_size
+    {
+        return Err(InterpolationError::SizeError);
+    }
+    if srs.len() < cell_size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but a cell needs {}",
+            srs.len(),
+            cell_size
+        )));
+    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(extended_size).ok_or(InterpolationError::SizeError)?;
+    let num_cells = extended_size / cell_size;
+    let log_cells = num_cells.ilog2() as usize;
+    let log_cell_size = cell_size.ilog2() as usize;
+
+    // With n = extended_size, the bit-reversed index cell_size * i + j is the root w^(brp(j) * num_cells + brp(i)),
+    // so cell i is the coset w^brp(i) * H_cell_size with its points in bit-reversed order.
+    (0..num_cells)
+        .map(|i| {
+            let shift = domain.group_gen().pow([bit_reverse(i, log_cells)? as u64]);
+            let basis = coset_lagrange(&srs.points()[..cell_size], shift)?;
+            (0..cell_size)
+                .map(|j| Ok(basis[bit_reverse(j, log_cell_size)?]))
+                .collect()
+        })
+        .collect()
+}
+
+/// The precomputed points from which [`compute_cell_proofs`] computes the proofs of every cell of a blob.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct CellProofTables<E: SWCurveConfig> {
+    /// The number of coefficients of a blob.
+    blob_size: usize,
+    /// The number of evaluations in a cell.
+    cell_size: usize,
+    /// For each of the `2 * blob_size / cell_size` evaluation points of the Toeplitz products, the evaluation of
+    /// the SRS column of each offset in a cell.
+    tables: Vec<Vec<Affine<E>>>,
+}
+
+impl<E: SWCurveConfig> CellProofTables<E> {
+    /// The number of coefficients of a blob.
+    pub fn blob_size(&self) -> usize {
+        self.blob_size
+    }
+
+    /// The number of evaluations in a cell.
+    pub fn cell_size(&self) -> usize {
+        self.cell_size
+    }
+
+    /// The number of cells of an extended blob.
+    pub fn num_cells(&self) -> usize {
+        2 * self.blob_size / self.cell_size
+    }
+}
+
+/// This function precomputes the tables for the proofs of the cells of blobs of `blob_size` coefficients, extended
+/// to twice that many evaluations in cells of `cell_size`. For EIP-7594 these are
+/// `FIELD_ELEMENTS_PER_EXT_BLOB / 2` and [`FIELD_ELEMENTS_PER_CELL`]. The SRS needs at least `blob_size` points.
+pub fn cell_proof_tables<E, F>(
+    srs: &MonomialSrs<E>,
+    blob_size: usize,
+    cell_size: usize,
+) -> Result<CellProofTables<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !blob_size.is_power_of_two() || !cell_size.is_power_of_two() || cell_size > blob_size {
+        return Err(InterpolationError::SizeError);
+    }
+    if srs.len() < blob_size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but a blob needs {}",
+            srs.len(),
+            blob_size
+        )));
+    }
+    let rows = blob_size / cell_size;
+    let domain = Radix2EvaluationDomain::<F>::new(2 * rows).ok_or(InterpolationError::SizeError)?;
+
+    // Splitting the blob polynomial into rows of cell_size coefficients, the quotient by X^l - z commits to
+    // sum_b z^b h_b with h_b = sum_r sum_{t > b} c_(l t + r) [x^(l (t - b - 1) + r)]. For each offset r this is the
+    // Toeplitz product of opening_quotients in crate::lookup, with the column [x^(l u + r)] as the SRS.
+    let columns = cfg_into_iter!(0..cell_size)
+        .map(|r| {
+            let mut column = (0..rows)
+                .rev()
+                .map(|u| Projective::<E>::from(srs.points()[cell_size * u + r]))
+                .collect::<Vec<_>>();
+            column.resize(2 * rows, Projective::<E>::zero());
+            domain.fft_in_place(&mut column);
+            Projective::<E>::normalize_batch(&column)
+        })
+        .collect::<Vec<_>>();
+    let tables = (0..2 * rows)
+        .map(|k| columns.iter().map(|column| column[k]).collect())
+        .collect();
+    Ok(CellProofTables {
+        blob_size,
+        cell_size,
+        tables,
+    })
+}
+
+/// This function returns the proofs of every cell of the blob with monomial coefficients `coeffs`, in the order of
+/// the cells of [`cell_coset_bases`]. The proof of a cell is the commitment to the quotient of the blob polynomial
+/// by the vanishing polynomial of the coset holding the cell.
+pub fn compute_cell_proofs<E, F>(
+    tables: &CellProofTables<E>,
+    coeffs: &[F],
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if coeffs.len() != tables.blob_size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The tables are for blobs of {} coefficients but the blob has {}",
+            tables.blob_size,
+            coeffs.len()
+        )));
+    }
+    let cell_size = tables.cell_size;
+    let rows = tables.blob_size / cell_size;
+    let num_cells = tables.num_cells();
+    let domain =
+        Radix2EvaluationDomain::<F>::new(num_cells).ok_or(InterpolationError::SizeError)?;
+
+    let row_evals = (0..cell_size)
+        .map(|r| {
+            let row = (0..rows)
+                .map(|t| coeffs[cell_size * t + r])
+                .collect::<Vec<_>>();
+            domain.fft(&row)
+        })
+        .collect::<Vec<_>>();
+    let mut products = cfg_into_iter!(0..num_cells)
+        .map(|k| {
+            let scalars = row_evals.iter().map(|evals| evals[k]).collect::<Vec<_>>();
+            Projective::<E>::msm_unchecked(&tables.tables[k], &scalars)
+        })
+        .collect::<Vec<_>>();
+    domain.ifft_in_place(&mut products);
+
+    // The quotient of the coset with z = g^k, for g the generator of the domain of num_cells, is the evaluation of
+    // sum_b h_b Y^b at g^k, and cell i is the coset with k = brp(i).
+    let mut proofs = products.split_off(rows);
+    proofs.resize(num_cells, Projective::<E>::zero());
+    domain.fft_in_place(&mut proofs);
+    let proofs = Projective::<E>::normalize_batch(&proofs);
+    let log_cells = num_cells.ilog2() as usize;
+    (0..num_cells)
+        .map(|i| Ok(proofs[bit_reverse(i, log_cells)?]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::VariableBaseMSM;
+    use ark_poly::{
+        univariate::{DenseOrSparsePolynomial, DensePolynomial},
+        DenseUVPolynomial, Polynomial,
+    };
+
+    fn commit(bases: &[Affine<BnConfig>], scalars: &[Fr]) -> Affine<BnConfig> {
+        G1Projective::msm(bases, scalars).unwrap().into_affine()
+    }
+
+    #[test]
+    fn test_extended_lagrange_srs() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let extended = extended_lagrange_srs(&srs).unwrap();
+        assert_eq!(extended.len(), 32);
+
+        // Committing to the 32 evaluations of a polynomial of degree less than 16 gives its commitment.
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+        let domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        let evaluations = domain.fft(&polynomial.coeffs);
+        let expected = commit(srs.points(), &polynomial.coeffs);
+        assert_eq!(commit(extended.points(), &evaluations), expected);
+
+        // The same holds in the bit-reversed order with the evaluations bit reversed.
+        let reversed = extended.with_ordering(PointOrdering::BitReversed).unwrap();
+        let reversed_evaluations = (0..32)
+            .map(|i| evaluations[bit_reverse(i, 5).unwrap()])
+            .collect::<Vec<_>>();
+        assert_eq!(commit(reversed.points(), &reversed_evaluations), expected);
+    }
+
+    #[test]
+    fn test_cell_coset_bases() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let bases = cell_coset_bases(&srs, 32, 4).unwrap();
+        assert_eq!(bases.len(), 8);
+
+        let domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        for (i, basis) in bases.iter().enumerate() {
+            // Each cell basis commits to the interpolation polynomial of the cell's evaluations.
+            let interpolation = DensePolynomial::<Fr>::rand(3, rng);
+            let evaluations = (0..4)
+                .map(|j| {
+                    let root = domain.element(bit_reverse(4 * i + j, 5).unwrap());
+                    interpolation.evaluate(&root)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                commit(basis, &evaluations),
+                commit(&srs.points()[..4], &interpolation.coeffs)
+            );
+        }
+
+        assert!(cell_coset_bases(&srs, 32, 64).is_err());
+        assert!(cell_coset_bases(&srs, 24, 4).is_err());
+    }
+
+    #[test]
+    fn test_compute_cell_proofs() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let tables = cell_proof_tables(&srs, 16, 4).unwrap();
+        assert_eq!(tables.num_cells(), 8);
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+        let proofs = compute_cell_proofs(&tables, &polynomial.coeffs).unwrap();
+
+        let domain = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+        for (i, proof) in proofs.iter().enumerate() {
+            // The proof commits to the quotient by X^4 - z for the shift h of the coset of cell i and z = h^4.
+            let z = ark_ff::Field::pow(&domain.element(bit_reverse(i, 3).unwrap()), [4]);
+            let mut vanishing = vec![Fr::zero(); 5];
+            vanishing[0] = -z;
+            vanishing[4] = Fr::from(1u64);
+            let (quotient, _) = DenseOrSparsePolynomial::from(&polynomial)
+                .divide_with_q_and_r(&DensePolynomial::from_coefficients_vec(vanishing).into())
+                .unwrap();
+            let bases = &srs.points()[..quotient.coeffs.len()];
+            assert_eq!(*proof, commit(bases, &quotient.coeffs));
+        }
+
+        assert!(compute_cell_proofs(&tables, &polynomial.coeffs[..8]).is_err());
+        assert!(cell_proof_tables(&srs, 32, 4).is_err());
+        assert!(cell_proof_tables(&srs, 16, 32).is_err());
+    }
+}
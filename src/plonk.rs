@@ -0,0 +1,194 @@
+//! This module commits to the preprocessed polynomials of a PLONK circuit, which circuit frontends produce as
+//! evaluation tables over the circuit's domain, directly against a Lagrange SRS, and derives the fixed commitments
+//! verifier keys need straight from a monomial SRS.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    srs::{commit_evals, LagrangeSrs, MonomialSrs},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::PrimeField;
+use ark_std::{cfg_iter, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// This function returns the commitments to the polynomials whose evaluations over the domain of `srs` are given by
+/// each entry of `evaluations`, such as the selector and permutation polynomials of a circuit. The evaluations must
+/// be stored in the same order as the points of `srs`, and each table must have one evaluation per point.
+pub fn preprocess<E, T>(
+    srs: &LagrangeSrs<E>,
+    evaluations: &[T],
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig,
+    T: AsRef<[E::ScalarField]> + Sync,
+{
+    cfg_iter!(evaluations)
+        .map(|table| commit_evals(table.as_ref(), srs))
+        .collect()
+}
+
+/// The commitments to the polynomials built from the vanishing polynomial `Z_H(X) = X^n - 1` of a domain `H` of
+/// size `n` that verifier keys commonly hold.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct FixedCommitments<E: SWCurveConfig> {
+    /// The size `n` of the domain.
+    pub domain_size: usize,
+    /// `[Z_H(tau)] = [tau^n] - [1]`.
+    pub vanishing: Affine<E>,
+    /// `[tau Z_H(tau)] = [tau^(n + 1)] - [tau]`.
+    pub tau_vanishing: Affine<E>,
+    /// `[Z_H(tau)^k]` for `k` from one to the number of powers asked for.
+    pub vanishing_powers: Vec<Affine<E>>,
+    /// The commitment `[L_0(tau)]` to the first Lagrange polynomial of `H`, which is one at `1`.
+    pub first_lagrange: Affine<E>,
+}
+
+/// This function computes the [`FixedCommitments`] of the arkworks domain of size `domain_size` from `srs`, with
+/// the powers of `[Z_H(tau)]` up to `[Z_H(tau)^max_power]`. The SRS needs more than `domain_size * max_power` points,
+/// and at least `domain_size + 2`.
+pub fn fixed_commitments<E, F>(
+    srs: &MonomialSrs<E>,
+    domain_size: usize,
+    max_power: usize,
+) -> Result<FixedCommitments<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !domain_size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let needed = domain_size
+        .checked_mul(max_power)
+        .and_then(|highest| highest.max(domain_size + 1).checked_add(1))
+        .ok_or(InterpolationError::SizeError)?;
+    if srs.len() < needed {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but the commitments need {}",
+            srs.len(),
+            needed
+        )));
+    }
+    let points = srs.points();
+    let vanishing = (points[domain_size] - points[0]).into_affine();
+    let tau_vanishing = (points[domain_size + 1] - points[1]).into_affine();
+
+    // Z_H^k = sum_j (k choose j) (-1)^(k - j) X^(n j), so it only touches every nth point.
+    let vanishing_powers = (1..=max_power)
+        .map(|k| {
+            let mut binomial = F::one();
+            let mut scalars = Vec::with_capacity(k + 1);
+            for j in 0..=k {
+                scalars.push(match (k - j) % 2 {
+                    0 => binomial,
+                    _ => -binomial,
+                });
+                let denominator =
+                    F::from((j + 1) as u64)
+                        .inverse()
+                        .ok_or(InterpolationError::FieldError(
+                            "Could not invert a binomial denominator".to_string(),
+                        ))?;
+                binomial *= F::from((k - j) as u64) * denominator;
+            }
+            let bases = (0..=k).map(|j| points[domain_size * j]).collect::<Vec<_>>();
+            Ok(Projective::<E>::msm_unchecked(&bases, &scalars))
+        })
+        .collect::<Result<Vec<_>, InterpolationError>>()?;
+
+    // L_0(X) = (1 + X + ... + X^(n - 1)) / n.
+    let first_lagrange = points[..domain_size]
+        .iter()
+        .fold(Projective::<E>::default(), |sum, point| sum + point)
+        * domain_size_inv::<F>(domain_size)?;
+    Ok(FixedCommitments {
+        domain_size,
+        vanishing,
+        tau_vanishing,
+        vanishing_powers: Projective::<E>::normalize_batch(&vanishing_powers),
+        first_lagrange: first_lagrange.into_affine(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs::MonomialSrs, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    };
+    use ark_std::{One, Zero};
+
+    #[test]
+    fn test_preprocess() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange = srs.to_lagrange().unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+
+        let polynomials = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(15, rng))
+            .collect::<Vec<_>>();
+        let tables = polynomials
+            .iter()
+            .map(|polynomial| domain.fft(&polynomial.coeffs))
+            .collect::<Vec<_>>();
+        let commitments = preprocess(&lagrange, &tables).unwrap();
+        for (polynomial, commitment) in polynomials.iter().zip(commitments) {
+            let expected = G1Projective::msm(srs.points(), &polynomial.coeffs).unwrap();
+            assert_eq!(commitment, expected.into_affine());
+        }
+
+        assert!(preprocess::<BnConfig, Vec<Fr>>(&lagrange, &[])
+            .unwrap()
+            .is_empty());
+        assert!(preprocess(&lagrange, &[&tables[0][1..]]).is_err());
+    }
+
+    #[test]
+    fn test_fixed_commitments() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let commit = |polynomial: &DensePolynomial<Fr>| {
+            G1Projective::msm(&srs.points()[..polynomial.coeffs.len()], &polynomial.coeffs)
+                .unwrap()
+                .into_affine()
+        };
+        let fixed = fixed_commitments(&srs, 4, 3).unwrap();
+
+        let vanishing: DensePolynomial<Fr> = Radix2EvaluationDomain::<Fr>::new(4)
+            .unwrap()
+            .vanishing_polynomial()
+            .into();
+        assert_eq!(fixed.vanishing, commit(&vanishing));
+        let x = DensePolynomial::from_coefficients_vec(vec![Fr::zero(), Fr::one()]);
+        assert_eq!(fixed.tau_vanishing, commit(&(&x * &vanishing)));
+        let mut power = vanishing.clone();
+        for commitment in fixed.vanishing_powers.iter() {
+            assert_eq!(*commitment, commit(&power));
+            power = &power * &vanishing;
+        }
+        assert_eq!(fixed.vanishing_powers.len(), 3);
+        let lagrange = MonomialSrs::new(srs.points()[..4].to_vec())
+            .to_lagrange()
+            .unwrap();
+        assert_eq!(fixed.first_lagrange, lagrange.points()[0]);
+
+        assert!(fixed_commitments(&srs, 4, 4).is_err());
+        assert!(fixed_commitments(&srs, 16, 0).is_err());
+        assert!(fixed_commitments(&srs, 6, 1).is_err());
+    }
+}
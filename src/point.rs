@@ -0,0 +1,175 @@
+//! This module contains [`AffinePoint`], the conversion between the affine points of another library and arkworks,
+//! so that libraries with their own point types can hand their points to the conversions without first encoding
+//! them in one of the arkworks formats.
+//!
+//! The rounds only run on arkworks points. A backend implements the coordinate accessors for its type and names the
+//! arkworks configuration of its curve, and [`srs_to_lagrange_points`] and [`lagrange_to_srs_points`] convert every
+//! point to arkworks before the rounds and back after them. That costs two passes over the points and two more
+//! copies of them in memory than the arkworks conversions. For backends such as `blst`, whose coordinates have the
+//! same Montgomery limbs as arkworks, each pass is a copy of the limbs rather than an encoding and decoding of every
+//! point.
+use crate::{error::InterpolationError, lagrange_to_srs, srs_to_lagrange};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveConfig,
+};
+use ark_ff::PrimeField;
+use ark_std::{cfg_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The base field of the curve of an [`AffinePoint`].
+pub type BaseField<P> = <<P as AffinePoint>::Config as CurveConfig>::BaseField;
+
+/// An affine point on a short Weierstrass curve, in the representation of some backend, that converts to and from
+/// the arkworks point the rounds run on.
+pub trait AffinePoint: Copy + Send + Sync {
+    /// The arkworks configuration holding the constants of the curve.
+    type Config: SWCurveConfig;
+
+    /// The point at infinity.
+    fn identity() -> Self;
+
+    /// Whether this is the point at infinity.
+    fn is_identity(&self) -> bool;
+
+    /// The coordinates of a point other than the point at infinity, erroring if the backend holds a coordinate
+    /// that is not a field element.
+    fn xy(&self) -> Result<(BaseField<Self>, BaseField<Self>), InterpolationError>;
+
+    /// The point with the given coordinates, which are not checked to be on the curve.
+    fn from_xy_unchecked(x: BaseField<Self>, y: BaseField<Self>) -> Self;
+
+    /// This function converts the point to arkworks.
+    fn to_arkworks(&self) -> Result<Affine<Self::Config>, InterpolationError> {
+        if self.is_identity() {
+            return Ok(Affine::identity());
+        }
+        let (x, y) = self.xy()?;
+        Ok(Affine::new_unchecked(x, y))
+    }
+
+    /// This function converts a point from arkworks.
+    fn from_arkworks(point: &Affine<Self::Config>) -> Self {
+        match point.infinity {
+            true => Self::identity(),
+            false => Self::from_xy_unchecked(point.x, point.y),
+        }
+    }
+}
+
+impl<E: SWCurveConfig> AffinePoint for Affine<E> {
+    type Config = E;
+
+    fn identity() -> Self {
+        AffineRepr::zero()
+    }
+
+    fn is_identity(&self) -> bool {
+        self.infinity
+    }
+
+    fn xy(&self) -> Result<(E::BaseField, E::BaseField), InterpolationError> {
+        Ok((self.x, self.y))
+    }
+
+    fn from_xy_unchecked(x: E::BaseField, y: E::BaseField) -> Self {
+        Affine::new_unchecked(x, y)
+    }
+}
+
+/// This function converts `points` of any backend to arkworks.
+fn to_arkworks<P: AffinePoint>(points: &[P]) -> Result<Vec<Affine<P::Config>>, InterpolationError> {
+    cfg_iter!(points).map(P::to_arkworks).collect()
+}
+
+/// This function converts arkworks `points` to a backend.
+fn from_arkworks<P: AffinePoint>(points: &[Affine<P::Config>]) -> Vec<P> {
+    cfg_iter!(points).map(P::from_arkworks).collect()
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] for the points of any backend, which it converts to
+/// arkworks and back around the rounds.
+pub fn srs_to_lagrange_points<P, F>(points: &[P]) -> Result<Vec<P>, InterpolationError>
+where
+    P: AffinePoint,
+    P::Config: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    Ok(from_arkworks(&srs_to_lagrange(&to_arkworks(points)?)?))
+}
+
+/// This function is the same as [`crate::lagrange_to_srs`] for the points of any backend, which it converts to
+/// arkworks and back around the rounds.
+pub fn lagrange_to_srs_points<P, F>(points: &[P]) -> Result<Vec<P>, InterpolationError>
+where
+    P: AffinePoint,
+    P::Config: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    Ok(from_arkworks(&lagrange_to_srs(&to_arkworks(points)?)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fq, G1Affine};
+
+    /// A point type of another library, which keeps the identity as the all zero point.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct ForeignPoint {
+        x: Fq,
+        y: Fq,
+    }
+
+    impl AffinePoint for ForeignPoint {
+        type Config = BnConfig;
+
+        fn identity() -> Self {
+            Self {
+                x: Fq::from(0u64),
+                y: Fq::from(0u64),
+            }
+        }
+
+        fn is_identity(&self) -> bool {
+            *self == Self::identity()
+        }
+
+        fn xy(&self) -> Result<(Fq, Fq), InterpolationError> {
+            Ok((self.x, self.y))
+        }
+
+        fn from_xy_unchecked(x: Fq, y: Fq) -> Self {
+            Self { x, y }
+        }
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_points() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let foreign = srs
+            .iter()
+            .map(ForeignPoint::from_arkworks)
+            .collect::<Vec<_>>();
+        let lagrange = srs_to_lagrange_points(&foreign).unwrap();
+        assert_eq!(
+            to_arkworks(&lagrange).unwrap(),
+            srs_to_lagrange(&srs).unwrap()
+        );
+        assert_eq!(lagrange_to_srs_points(&lagrange).unwrap(), foreign);
+        assert_eq!(
+            srs_to_lagrange_points(&srs).unwrap(),
+            srs_to_lagrange(&srs).unwrap()
+        );
+
+        assert_eq!(
+            ForeignPoint::from_arkworks(&G1Affine::identity()),
+            ForeignPoint::identity()
+        );
+        assert!(ForeignPoint::identity().to_arkworks().unwrap().is_zero());
+        assert!(srs_to_lagrange_points(&foreign[..3]).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! This module contains a slow but independent conversion of a monomial SRS to the Lagrange basis, for
+//! differential testing of the fast path and for cross-checking small setups without trusting it.
+//!
+//! Each `L_i(X) = (1 / n) * sum_j (w^-i X)^j` for the generator `w` of the domain, so `[L_i(tau)]` is the MSM of
+//! those coefficients against the monomial points. That is `n` MSMs of `n` points, so it takes quadratic time and
+//! is only practical for a few thousand points.
+use crate::{domain_size_inv, error::InterpolationError};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_into_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// This function computes the same result as [`crate::srs_to_lagrange`] by committing to the coefficients of each
+/// Lagrange polynomial directly. It errors if the number of points is not a power of two.
+pub fn srs_to_lagrange_reference<E, F>(
+    points: &[Affine<E>],
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = points.len();
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    let size_inv = domain_size_inv::<F>(size)?;
+    let gen_inv = domain.group_gen_inv();
+
+    let lagrange_points = cfg_into_iter!(0..size)
+        .map(|i| {
+            let root = gen_inv.pow([i as u64]);
+            let mut coeff = size_inv;
+            let coeffs = (0..size)
+                .map(|_| {
+                    let current = coeff;
+                    coeff *= root;
+                    current
+                })
+                .collect::<Vec<_>>();
+            Projective::<E>::msm_unchecked(points, &coeffs)
+        })
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::normalize_batch(&lagrange_points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_srs_to_lagrange_reference() {
+        let rng = &mut ark_std::test_rng();
+        for size in [1, 2, 4, 64] {
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            assert_eq!(
+                srs_to_lagrange_reference(&srs).unwrap(),
+                srs_to_lagrange(&srs).unwrap()
+            );
+        }
+        assert!(srs_to_lagrange_reference::<BnConfig, _>(&[]).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! This module runs the conversion while recording how long each part takes and how much work it does, so runs on
+//! different machines and configurations can be compared without an external profiler.
+//!
+//! The operation counts follow from the size of the SRS rather than being counted as the conversion runs, so they
+//! cost nothing to collect. Field multiplications done inside scalar multiplications are not included. The timings
+//! use [`std::time::Instant`], which is not available on `wasm32-unknown-unknown`.
+use crate::{
+    buffer::try_copied,
+    domain_size_inv,
+    error::InterpolationError,
+    utils::{bit_reverse_permutation, fft_round, twiddle_round},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter, mem::size_of, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+/// The timings and operation counts of one round of the FFT style operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundReport {
+    /// The number of the round, starting from one.
+    pub round: usize,
+    /// The time spent multiplying points by powers of the root of unity, which runs on every thread.
+    pub twiddle: Duration,
+    /// The time spent on the batched affine additions and subtractions, which runs on one thread.
+    pub butterflies: Duration,
+    /// The number of points multiplied by a power of the root of unity.
+    pub scalar_multiplications: usize,
+    /// The number of base field multiplications and squarings.
+    pub field_multiplications: usize,
+    /// The number of base field inversions, one for the batched additions and one per multiplied point.
+    pub field_inversions: usize,
+}
+
+/// The timings and operation counts of a whole conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// The number of points converted.
+    pub size: usize,
+    /// The number of threads the parallel steps could use.
+    pub threads: usize,
+    /// The time spent putting the points in bit-reversed order.
+    pub reorder: Duration,
+    /// The report of each round, in order.
+    pub rounds: Vec<RoundReport>,
+    /// The time spent dividing every point by the size of the domain, which runs on every thread.
+    pub rescale: Duration,
+    /// The time the whole conversion took.
+    pub total: Duration,
+    /// The largest amount of scratch space a round allocates on top of the points, in bytes.
+    pub peak_scratch_bytes: usize,
+}
+
+impl ConversionReport {
+    /// The number of points multiplied by a scalar, including the final rescaling.
+    pub fn scalar_multiplications(&self) -> usize {
+        self.rounds
+            .iter()
+            .map(|round| round.scalar_multiplications)
+            .sum::<usize>()
+            + self.size
+    }
+
+    /// The number of base field multiplications and squarings outside of scalar multiplications.
+    pub fn field_multiplications(&self) -> usize {
+        self.rounds
+            .iter()
+            .map(|round| round.field_multiplications)
+            .sum()
+    }
+
+    /// The number of base field inversions, including one per rescaled point.
+    pub fn field_inversions(&self) -> usize {
+        self.rounds
+            .iter()
+            .map(|round| round.field_inversions)
+            .sum::<usize>()
+            + self.size
+    }
+
+    /// The time spent in the steps that run on every thread.
+    pub fn parallel_time(&self) -> Duration {
+        self.rounds
+            .iter()
+            .map(|round| round.twiddle)
+            .sum::<Duration>()
+            + self.rescale
+    }
+
+    /// An upper bound on the fraction of the available thread time the conversion used, taking the parallel steps
+    /// to keep every thread busy and the rest to keep one thread busy.
+    pub fn thread_utilization(&self) -> f64 {
+        let total = self.total.as_secs_f64();
+        if total == 0.0 {
+            return 1.0;
+        }
+        let parallel = self.parallel_time().as_secs_f64().min(total);
+        let threads = self.threads as f64;
+        (parallel * threads + (total - parallel)) / (total * threads)
+    }
+}
+
+fn thread_count() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] but also returns a report of the conversion.
+pub fn srs_to_lagrange_with_report<E, F>(
+    points: &[Affine<E>],
+) -> Result<(Vec<Affine<E>>, ConversionReport), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let start = Instant::now();
+    let size = points.len();
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    let gen = domain.group_gen_inv();
+    let log_size = size.ilog2() as usize;
+
+    let mut ordered_points = try_copied(points)?;
+    let reorder_start = Instant::now();
+    bit_reverse_permutation(&mut ordered_points, log_size)?;
+    let reorder = reorder_start.elapsed();
+
+    let rounds = (1..=log_size)
+        .map(|i| {
+            let prim_root = gen.pow([(size >> i) as u64]);
+            let twiddle_start = Instant::now();
+            if i != 1 {
+                twiddle_round(&mut ordered_points, prim_root, i)?;
+            }
+            let twiddle = twiddle_start.elapsed();
+
+            // The points have already been multiplied, so the round runs as a first round would.
+            let butterflies_start = Instant::now();
+            fft_round::<E, F, true>(&mut ordered_points, prim_root, i)?;
+            let butterflies = butterflies_start.elapsed();
+
+            // Each of the size / 2 pairs costs ten multiplications, and each multiplied point also costs a
+            // multiplication to step the power and an inversion to return to affine form.
+            let multiplied = (size >> i) * ((1usize << (i - 1)) - 1);
+            Ok(RoundReport {
+                round: i,
+                twiddle,
+                butterflies,
+                scalar_multiplications: multiplied,
+                field_multiplications: 5 * size + multiplied,
+                field_inversions: 1 + multiplied,
+            })
+        })
+        .collect::<Result<Vec<_>, InterpolationError>>()?;
+
+    let rescale_start = Instant::now();
+    let domain_size_inv = domain_size_inv::<F>(size)?;
+    let lagrange_points = cfg_iter!(ordered_points)
+        .map(|point| (*point * domain_size_inv).into_affine())
+        .collect::<Vec<_>>();
+    let rescale = rescale_start.elapsed();
+
+    let report = ConversionReport {
+        size,
+        threads: thread_count(),
+        reorder,
+        rounds,
+        rescale,
+        total: start.elapsed(),
+        peak_scratch_bytes: if size > 1 {
+            size * size_of::<E::BaseField>()
+        } else {
+            0
+        },
+    };
+    Ok((lagrange_points, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fq};
+
+    #[test]
+    fn test_srs_to_lagrange_with_report() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap();
+        let (points, report) = srs_to_lagrange_with_report(&srs).unwrap();
+        assert_eq!(points, srs_to_lagrange(&srs).unwrap());
+
+        // With 8 points the second round multiplies one point in each of two blocks and the third round three
+        // points in one block.
+        assert_eq!(report.size, 8);
+        assert_eq!(
+            report
+                .rounds
+                .iter()
+                .map(|round| round.scalar_multiplications)
+                .collect::<Vec<_>>(),
+            [0, 2, 3]
+        );
+        assert_eq!(report.scalar_multiplications(), 13);
+        assert_eq!(report.field_multiplications(), 3 * 40 + 5);
+        assert_eq!(report.field_inversions(), 3 + 5 + 8);
+        assert_eq!(report.peak_scratch_bytes, 8 * size_of::<Fq>());
+        assert!(report.total >= report.parallel_time());
+        let utilization = report.thread_utilization();
+        assert!(utilization > 0.0 && utilization <= 1.0);
+
+        assert!(srs_to_lagrange_with_report(&srs[..6]).is_err());
+    }
+}
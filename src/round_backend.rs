@@ -0,0 +1,225 @@
+//! This module runs the radix-2 rounds of a conversion on a pluggable [`RoundBackend`], keeping the orchestration
+//! and the verification of the result in this crate.
+//!
+//! A backend holds the points between calls and runs two primitives: a round of butterflies with a table of
+//! twiddles, and the multiplication of every point by a scalar. Those are the primitives an NTT over curve points is
+//! built from, so a port to an accelerator library such as ICICLE only has to provide them. The crate permutes the
+//! points, computes the twiddles of every round, drives the rounds in order and finally checks the result against
+//! the monomial SRS with [`check_lagrange_matches_monomial`], so a faulty backend cannot hand back a wrong basis.
+//!
+//! No accelerator backend ships with the crate, since those bindings need the CUDA toolkit to build and live with
+//! the prover that links them. [`CpuRoundBackend`] runs the primitives on the CPU, as a reference to check a port
+//! against.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+    utils::bit_reverse_permutation,
+    verify::check_lagrange_matches_monomial,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_chunks_mut, cfg_iter_mut, rand::RngCore, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A backend that runs the rounds of a conversion over points it holds.
+pub trait RoundBackend<E: SWCurveConfig> {
+    /// This function replaces the points held by the backend with `points`.
+    fn upload(&mut self, points: &[Affine<E>]) -> Result<(), InterpolationError>;
+
+    /// This function runs one round of butterflies over the points held by the backend. The round combines the
+    /// points at `i` and `i + twiddles.len()` for every `i` in the first half of each block of `2 * twiddles.len()`
+    /// points, replacing `a` and `b` by `a + t b` and `a - t b` for the twiddle `t = twiddles[i mod twiddles.len()]`.
+    fn butterfly_round(&mut self, twiddles: &[E::ScalarField]) -> Result<(), InterpolationError>;
+
+    /// This function multiplies every point held by the backend by `scalar`.
+    fn scale(&mut self, scalar: E::ScalarField) -> Result<(), InterpolationError>;
+
+    /// This function returns the points held by the backend.
+    fn download(&mut self) -> Result<Vec<Projective<E>>, InterpolationError>;
+}
+
+/// A backend that runs the rounds on the CPU in projective form.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    Default(bound = "E: SWCurveConfig")
+)]
+pub struct CpuRoundBackend<E: SWCurveConfig> {
+    points: Vec<Projective<E>>,
+}
+
+impl<E: SWCurveConfig> CpuRoundBackend<E> {
+    /// This function returns a backend holding no points.
+    pub fn new() -> Self {
+        Self { points: Vec::new() }
+    }
+}
+
+impl<E: SWCurveConfig> RoundBackend<E> for CpuRoundBackend<E> {
+    fn upload(&mut self, points: &[Affine<E>]) -> Result<(), InterpolationError> {
+        self.points = points.iter().map(|point| (*point).into()).collect();
+        Ok(())
+    }
+
+    fn butterfly_round(&mut self, twiddles: &[E::ScalarField]) -> Result<(), InterpolationError> {
+        let half = twiddles.len();
+        if half == 0 || !self.points.len().is_multiple_of(2 * half) {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "A round of {} twiddles does not divide {} points",
+                half,
+                self.points.len()
+            )));
+        }
+        cfg_chunks_mut!(self.points, 2 * half).for_each(|block| {
+            let (top, bottom) = block.split_at_mut(half);
+            top.iter_mut()
+                .zip(bottom.iter_mut())
+                .zip(twiddles.iter())
+                .for_each(|((a, b), twiddle)| {
+                    let twiddled = *b * twiddle;
+                    *b = *a - twiddled;
+                    *a += twiddled;
+                });
+        });
+        Ok(())
+    }
+
+    fn scale(&mut self, scalar: E::ScalarField) -> Result<(), InterpolationError> {
+        cfg_iter_mut!(self.points).for_each(|point| *point *= scalar);
+        Ok(())
+    }
+
+    fn download(&mut self) -> Result<Vec<Projective<E>>, InterpolationError> {
+        Ok(self.points.clone())
+    }
+}
+
+/// This function converts `srs` to the Lagrange basis in natural order with the rounds running on `backend`, then
+/// checks the result against `srs` with randomness from `rng`. It errors if the size of the SRS is not a power of
+/// two, if the backend fails, or with [`InterpolationError::TaskError`] if the backend returns a wrong basis.
+pub fn srs_to_lagrange_with_backend<E, F, B, R>(
+    srs: &MonomialSrs<E>,
+    backend: &mut B,
+    rng: &mut R,
+) -> Result<LagrangeSrs<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    B: RoundBackend<E>,
+    R: RngCore,
+{
+    let size = srs.len();
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+    if domain.size() != size {
+        return Err(InterpolationError::SizeError);
+    }
+    let log_size = size.ilog2() as usize;
+
+    let mut permuted = srs.points().to_vec();
+    bit_reverse_permutation(&mut permuted, log_size)?;
+    backend.upload(&permuted)?;
+    let gen = domain.group_gen_inv();
+    for round in 0..log_size {
+        let half = 1 << round;
+        let root = gen.pow([(size >> (round + 1)) as u64]);
+        let twiddles = ark_std::iter::successors(Some(F::one()), |twiddle| Some(*twiddle * root))
+            .take(half)
+            .collect::<Vec<_>>();
+        backend.butterfly_round(&twiddles)?;
+    }
+    backend.scale(domain_size_inv(size)?)?;
+
+    let points = backend.download()?;
+    if points.len() != size {
+        return Err(InterpolationError::TaskError(format!(
+            "The backend returned {} points for an SRS of {}",
+            points.len(),
+            size
+        )));
+    }
+    let lagrange = LagrangeSrs::from_parts(
+        Projective::normalize_batch(&points),
+        domain.group_gen(),
+        PointOrdering::Natural,
+    );
+    if !check_lagrange_matches_monomial(srs, &lagrange, rng)? {
+        return Err(InterpolationError::TaskError(
+            "The backend returned points that are not the Lagrange basis of the SRS".to_string(),
+        ));
+    }
+    Ok(lagrange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+    use ark_ec::Group;
+    use ark_std::UniformRand;
+
+    /// A backend whose last round corrupts the first point.
+    struct FaultyBackend {
+        inner: CpuRoundBackend<BnConfig>,
+    }
+
+    impl RoundBackend<BnConfig> for FaultyBackend {
+        fn upload(&mut self, points: &[Affine<BnConfig>]) -> Result<(), InterpolationError> {
+            self.inner.upload(points)
+        }
+
+        fn butterfly_round(
+            &mut self,
+            twiddles: &[ark_bn254::Fr],
+        ) -> Result<(), InterpolationError> {
+            self.inner.butterfly_round(twiddles)?;
+            if 2 * twiddles.len() == self.inner.points.len() {
+                self.inner.points[0].double_in_place();
+            }
+            Ok(())
+        }
+
+        fn scale(&mut self, scalar: ark_bn254::Fr) -> Result<(), InterpolationError> {
+            self.inner.scale(scalar)
+        }
+
+        fn download(&mut self) -> Result<Vec<Projective<BnConfig>>, InterpolationError> {
+            self.inner.download()
+        }
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_with_backend() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap();
+        let srs = MonomialSrs::new(points.clone());
+        let lagrange =
+            srs_to_lagrange_with_backend(&srs, &mut CpuRoundBackend::new(), rng).unwrap();
+        assert_eq!(lagrange.points(), srs_to_lagrange(&points).unwrap());
+        assert_eq!(lagrange.ordering(), PointOrdering::Natural);
+
+        let mut faulty = FaultyBackend {
+            inner: CpuRoundBackend::new(),
+        };
+        assert!(matches!(
+            srs_to_lagrange_with_backend(&srs, &mut faulty, rng),
+            Err(InterpolationError::TaskError(_))
+        ));
+
+        let mut backend = CpuRoundBackend::<BnConfig>::new();
+        backend.upload(&points[..6]).unwrap();
+        assert!(backend
+            .butterfly_round(&[ark_bn254::Fr::rand(rng); 4])
+            .is_err());
+        let srs = MonomialSrs::new(points[..6].to_vec());
+        assert!(srs_to_lagrange_with_backend(&srs, &mut CpuRoundBackend::new(), rng).is_err());
+    }
+}
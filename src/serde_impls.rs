@@ -0,0 +1,129 @@
+//! This module implements `serde` support for the SRS types. Points and field elements are encoded as hex strings
+//! of their compressed arkworks serialization, so the types can pass through human readable formats such as JSON.
+use crate::srs::{LagrangeSrs, MonomialSrs, PointOrdering};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::vec::Vec;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The serde representation of a [`MonomialSrs`].
+#[derive(Serialize, Deserialize)]
+struct MonomialSrsRepr {
+    points: Vec<String>,
+}
+
+/// The serde representation of a [`LagrangeSrs`].
+#[derive(Serialize, Deserialize)]
+struct LagrangeSrsRepr {
+    generator: String,
+    ordering: PointOrdering,
+    points: Vec<String>,
+}
+
+/// This function encodes the compressed serialization of `value` as a hex string.
+pub(crate) fn to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a vector is infallible");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// This function decodes a hex string produced by [`to_hex`], validating the decoded value.
+pub(crate) fn from_hex<T: CanonicalDeserialize, E: Error>(hex: &str) -> Result<T, E> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return Err(E::custom("hex string has an odd number of digits"));
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| E::custom("invalid hex digit"))
+        })
+        .collect::<Result<Vec<u8>, E>>()?;
+    T::deserialize_compressed(bytes.as_slice()).map_err(E::custom)
+}
+
+impl<E: SWCurveConfig> Serialize for MonomialSrs<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MonomialSrsRepr {
+            points: self.points().iter().map(to_hex).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, E: SWCurveConfig> Deserialize<'de> for MonomialSrs<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MonomialSrsRepr::deserialize(deserializer)?;
+        let points = repr
+            .points
+            .iter()
+            .map(|point| from_hex(point))
+            .collect::<Result<Vec<Affine<E>>, D::Error>>()?;
+        Ok(MonomialSrs::new(points))
+    }
+}
+
+impl<E: SWCurveConfig> Serialize for LagrangeSrs<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LagrangeSrsRepr {
+            generator: to_hex(&self.generator()),
+            ordering: self.ordering(),
+            points: self.points().iter().map(to_hex).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, E: SWCurveConfig> Deserialize<'de> for LagrangeSrs<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = LagrangeSrsRepr::deserialize(deserializer)?;
+        let points = repr
+            .points
+            .iter()
+            .map(|point| from_hex(point))
+            .collect::<Result<Vec<Affine<E>>, D::Error>>()?;
+        let srs = LagrangeSrs::from_parts(points, from_hex(&repr.generator)?, repr.ordering);
+        srs.check().map_err(D::Error::custom)?;
+        Ok(srs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_serde_json() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 7).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+
+        let json = serde_json::to_string(&srs).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MonomialSrs<BnConfig>>(&json).unwrap(),
+            srs
+        );
+
+        let json = serde_json::to_string(&lagrange_srs).unwrap();
+        assert!(json.contains("\"ordering\":\"natural\""));
+        assert_eq!(
+            serde_json::from_str::<LagrangeSrs<BnConfig>>(&json).unwrap(),
+            lagrange_srs
+        );
+
+        // Corrupting a point is caught when deserializing.
+        let value = serde_json::to_value(&srs).unwrap();
+        let mut corrupted = value.clone();
+        corrupted["points"][0] = serde_json::Value::String("ff".repeat(32));
+        assert!(serde_json::from_value::<MonomialSrs<BnConfig>>(corrupted).is_err());
+        let mut corrupted = value;
+        corrupted["points"][0] = serde_json::Value::String("0xzz".to_string());
+        assert!(serde_json::from_value::<MonomialSrs<BnConfig>>(corrupted).is_err());
+    }
+}
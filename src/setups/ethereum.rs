@@ -0,0 +1,108 @@
+//! This module embeds the BLS12-381 setup from the Ethereum KZG ceremony used for EIP-4844 blob commitments.
+//! The 4096 monomial and Lagrange G1 points and the 65 monomial G2 points are taken from the `trusted_setup.txt`
+//! file shipped with c-kzg-4844 and stored in their compressed form. Converting 4096 points takes several seconds,
+//! so we embed the published Lagrange points rather than computing them on first use.
+use crate::srs::{LagrangeSrs, MonomialSrs, PointOrdering};
+use ark_bls12_381::{g1::Config as G1Config, Fr, G1Affine, G2Affine};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::vec::Vec;
+use std::sync::OnceLock;
+
+/// The number of G1 points in the setup.
+pub const ETHEREUM_KZG_G1_SIZE: usize = 4096;
+/// The number of G2 points in the setup.
+pub const ETHEREUM_KZG_G2_SIZE: usize = 65;
+
+const G1_MONOMIAL_BYTES: &[u8] = include_bytes!("data/ethereum_g1_monomial.bin");
+const G1_LAGRANGE_BYTES: &[u8] = include_bytes!("data/ethereum_g1_lagrange.bin");
+const G2_MONOMIAL_BYTES: &[u8] = include_bytes!("data/ethereum_g2_monomial.bin");
+
+/// This function decodes concatenated compressed points. The embedded points come from the ceremony output,
+/// whose points were checked when it was published, and the tests of this module check them again, so we skip
+/// the subgroup checks here.
+fn decode_points<P: CanonicalDeserialize>(bytes: &[u8], size: usize) -> Vec<P> {
+    let mut reader = bytes;
+    let points = (0..size)
+        .map(|_| {
+            P::deserialize_compressed_unchecked(&mut reader)
+                .expect("the embedded setup is well formed")
+        })
+        .collect();
+    assert!(reader.is_empty());
+    points
+}
+
+/// The monomial G1 points `[1], [tau], ..., [tau^4095]` of the Ethereum KZG setup.
+pub fn ethereum_kzg_monomial() -> &'static MonomialSrs<G1Config> {
+    static SRS: OnceLock<MonomialSrs<G1Config>> = OnceLock::new();
+    SRS.get_or_init(|| {
+        MonomialSrs::new(decode_points::<G1Affine>(
+            G1_MONOMIAL_BYTES,
+            ETHEREUM_KZG_G1_SIZE,
+        ))
+    })
+}
+
+/// The Lagrange G1 points of the Ethereum KZG setup over the arkworks domain of size 4096, which is the domain
+/// EIP-4844 uses. The points are stored in natural order; c-kzg-4844 and the consensus specs use them bit reversed,
+/// which [`LagrangeSrs::with_ordering`] gives.
+pub fn ethereum_kzg_lagrange() -> &'static LagrangeSrs<G1Config> {
+    static SRS: OnceLock<LagrangeSrs<G1Config>> = OnceLock::new();
+    SRS.get_or_init(|| {
+        let domain = Radix2EvaluationDomain::<Fr>::new(ETHEREUM_KZG_G1_SIZE)
+            .expect("the setup size is a power of two");
+        LagrangeSrs::from_parts(
+            decode_points(G1_LAGRANGE_BYTES, ETHEREUM_KZG_G1_SIZE),
+            domain.group_gen(),
+            PointOrdering::Natural,
+        )
+    })
+}
+
+/// The monomial G2 points `[1], [tau], ..., [tau^64]` of the Ethereum KZG setup.
+pub fn ethereum_kzg_g2() -> &'static [G2Affine] {
+    static POINTS: OnceLock<Vec<G2Affine>> = OnceLock::new();
+    POINTS.get_or_init(|| decode_points(G2_MONOMIAL_BYTES, ETHEREUM_KZG_G2_SIZE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, G1Projective};
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::Field;
+    use ark_serialize::Valid;
+
+    #[test]
+    fn test_ethereum_setup() {
+        let monomial = ethereum_kzg_monomial();
+        let lagrange = ethereum_kzg_lagrange();
+        let g2 = ethereum_kzg_g2();
+        assert_eq!(monomial.len(), ETHEREUM_KZG_G1_SIZE);
+        assert_eq!(lagrange.len(), ETHEREUM_KZG_G1_SIZE);
+        assert_eq!(g2.len(), ETHEREUM_KZG_G2_SIZE);
+        assert!(monomial.check().is_ok());
+        assert!(lagrange.check().is_ok());
+        assert!(g2.iter().all(|point| point.check().is_ok()));
+        assert_eq!(monomial.points()[0], G1Affine::generator());
+        assert_eq!(g2[0], G2Affine::generator());
+
+        // The G1 and G2 points use the same tau.
+        assert_eq!(
+            Bls12_381::pairing(monomial.points()[1], g2[0]),
+            Bls12_381::pairing(monomial.points()[0], g2[1])
+        );
+
+        // The Lagrange polynomials sum to one, and L_0 is the sum of the powers of X divided by the domain size,
+        // so both relations tie the Lagrange points to the monomial points without a full conversion.
+        let lagrange_sum: G1Projective = lagrange.points().iter().map(|p| p.into_group()).sum();
+        assert_eq!(lagrange_sum.into_affine(), G1Affine::generator());
+        let monomial_sum: G1Projective = monomial.points().iter().map(|p| p.into_group()).sum();
+        let size_inv = Fr::from(ETHEREUM_KZG_G1_SIZE as u64).inverse().unwrap();
+        assert_eq!(
+            (monomial_sum * size_inv).into_affine(),
+            lagrange.points()[0]
+        );
+    }
+}
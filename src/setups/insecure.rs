@@ -0,0 +1,91 @@
+//! This module generates SRSs from a known random `tau` for tests and local development. Anyone who sees `tau` can
+//! forge proofs against such an SRS, so it must never be used in production.
+//!
+//! With the `zeroize` feature, `tau` and its powers are wiped from memory once the points have been computed, so
+//! the secret does not outlive the call in integration environments that generate setups on the fly.
+use crate::srs::MonomialSrs;
+use ark_ec::{
+    scalar_mul::fixed_base::FixedBase,
+    short_weierstrass::{Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{Field, PrimeField};
+use ark_std::{rand::RngCore, vec::Vec, One, UniformRand};
+
+/// This function overwrites `secrets` with zeros if the `zeroize` feature is on, and otherwise does nothing.
+fn wipe<F: Field>(secrets: &mut [F]) {
+    #[cfg(feature = "zeroize")]
+    secrets.iter_mut().for_each(zeroize::Zeroize::zeroize);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = secrets;
+}
+
+/// This function returns the monomial SRS `[1], [tau], ..., [tau^(size - 1)]` for a random `tau` and a random
+/// generator, both drawn from `rng`. It is insecure and only meant for testing.
+pub fn insecure_monomial_srs<E: SWCurveConfig, R: RngCore>(
+    rng: &mut R,
+    size: usize,
+) -> MonomialSrs<E> {
+    let mut tau = [E::ScalarField::rand(rng)];
+    let g = Projective::<E>::rand(rng);
+
+    let mut powers_of_tau = Vec::with_capacity(size);
+    let mut cur = [E::ScalarField::one()];
+    for _ in 0..size {
+        powers_of_tau.push(cur[0]);
+        cur[0] *= &tau[0];
+    }
+    wipe(&mut tau);
+    wipe(&mut cur);
+
+    let window_size = FixedBase::get_mul_window_size(size);
+    let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
+    let g_table = FixedBase::get_window_table(scalar_bits, window_size, g);
+    let powers_of_g =
+        FixedBase::msm::<Projective<E>>(scalar_bits, window_size, &g_table, &powers_of_tau);
+    wipe(&mut powers_of_tau);
+
+    MonomialSrs::new(Projective::<E>::normalize_batch(&powers_of_g))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{g1::Config as BnConfig, Fr};
+    use ark_ec::{AffineRepr, Group};
+    use ark_std::Zero;
+
+    #[test]
+    fn test_insecure_monomial_srs() {
+        let rng = &mut ark_std::test_rng();
+        let srs = insecure_monomial_srs::<BnConfig, _>(rng, 8);
+        assert_eq!(srs.len(), 8);
+
+        // The points are successive multiples of the first by the same tau, so [tau] * tau^i = [tau^(i + 1)] can
+        // be checked against a tau recovered from a fresh rng with the same seed.
+        let rng = &mut ark_std::test_rng();
+        let tau = Fr::rand(rng);
+        for i in 1..8 {
+            assert_eq!(
+                srs.points()[i].into_group(),
+                srs.points()[i - 1].into_group() * tau
+            );
+        }
+        assert_ne!(srs.points()[0].into_group(), Projective::<BnConfig>::zero());
+        assert_ne!(
+            srs.points()[0].into_group(),
+            Projective::<BnConfig>::generator()
+        );
+        assert!(insecure_monomial_srs::<BnConfig, _>(rng, 0).is_empty());
+    }
+
+    #[test]
+    fn test_wipe() {
+        let mut secrets = [Fr::from(3u64), Fr::from(5u64)];
+        wipe(&mut secrets);
+        #[cfg(feature = "zeroize")]
+        assert!(secrets.iter().all(|s| s.is_zero()));
+        #[cfg(not(feature = "zeroize"))]
+        assert_eq!(secrets, [Fr::from(3u64), Fr::from(5u64)]);
+    }
+}
@@ -0,0 +1,7 @@
+//! This module contains well-known public setups that can be used without handling any files, and an insecure
+//! generator for tests.
+#[cfg(feature = "ethereum-setup")]
+pub mod ethereum;
+pub mod insecure;
+#[cfg(feature = "download")]
+pub mod registry;
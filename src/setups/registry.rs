@@ -0,0 +1,240 @@
+//! This module knows where to download well-known public setups and converts them in one call.
+//!
+//! Every download is checked against a SHA-256 digest, either the one this crate pins for the file or one the caller
+//! passes, and a setup with neither is refused rather than converted unchecked. The Ethereum KZG setup is pinned to
+//! the c-kzg-4844 v2.1.1 file. snarkjs publishes Blake2b digests for the Hermez powers of tau files, so the SHA-256
+//! pins here are of copies of the files themselves, which so far covers only
+//! `powersOfTau28_hez_final_08.ptau`. The Aztec Ignition transcripts are not listed because this crate does not read
+//! their format.
+use crate::{
+    curves::CurveId,
+    error::InterpolationError,
+    formats::ptau::read_tau_g1,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_bn254::g1::Config as BnConfig;
+use ark_std::io::{self, Read};
+use sha2::{Digest, Sha256};
+
+/// The smallest power for which a Hermez powers of tau file is published.
+pub const HERMEZ_MIN_POWER: u32 = 8;
+/// The largest power for which a Hermez powers of tau file is published.
+pub const HERMEZ_MAX_POWER: u32 = 28;
+
+/// The SHA-256 digest of `powersOfTau28_hez_final_08.ptau`, taken from the copy of the file published in the
+/// `ppot-rs` 0.1.1 crate: a power 8 file of the power 28 ceremony, with the 54 contributions and the beacon and the
+/// Lagrange sections snarkjs adds.
+const HERMEZ_08_SHA256: [u8; 32] = [
+    0xf7, 0x41, 0xf2, 0xdd, 0xee, 0x28, 0x75, 0x91, 0x5c, 0x24, 0xdb, 0x8a, 0xae, 0x90, 0xd0, 0x21,
+    0xf5, 0x11, 0x81, 0x53, 0x3f, 0x1e, 0xe3, 0xb5, 0x8b, 0xaf, 0x64, 0xb0, 0x42, 0xe9, 0x16, 0x54,
+];
+
+/// The SHA-256 digest of c-kzg-4844's `trusted_setup.txt` at v2.1.1.
+const ETHEREUM_KZG_SHA256: [u8; 32] = [
+    0xd3, 0x9b, 0x9f, 0x2d, 0x04, 0x7c, 0xc9, 0xdc, 0xa2, 0xde, 0x58, 0xf2, 0x64, 0xb6, 0xa0, 0x94,
+    0x48, 0xcc, 0xd3, 0x4d, 0xb9, 0x67, 0x88, 0x1a, 0x67, 0x13, 0xea, 0xca, 0xcf, 0x0f, 0x26, 0xb7,
+];
+
+/// A public setup this crate knows how to download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KnownSetup {
+    /// The Hermez `powersOfTau28_hez_final_{power}.ptau` file, a BN254 setup for domains up to `2^power`.
+    HermezPtau {
+        /// The power of the file, between [`HERMEZ_MIN_POWER`] and [`HERMEZ_MAX_POWER`].
+        power: u32,
+    },
+    /// The BLS12-381 setup from the Ethereum KZG ceremony, as shipped with c-kzg-4844.
+    EthereumKzg,
+}
+
+impl KnownSetup {
+    /// This function returns the smallest Hermez powers of tau file holding a domain of size `size`.
+    pub fn hermez_for_size(size: usize) -> Result<Self, InterpolationError> {
+        if !size.is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let power = size.ilog2().max(HERMEZ_MIN_POWER);
+        if power > HERMEZ_MAX_POWER {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "No Hermez powers of tau file holds 2^{} points",
+                power
+            )));
+        }
+        Ok(KnownSetup::HermezPtau { power })
+    }
+
+    /// The curve of the setup.
+    pub fn curve(&self) -> CurveId {
+        match self {
+            KnownSetup::HermezPtau { .. } => CurveId::Bn254,
+            KnownSetup::EthereumKzg => CurveId::Bls12_381,
+        }
+    }
+
+    /// The URL the setup is downloaded from.
+    pub fn url(&self) -> String {
+        match self {
+            KnownSetup::HermezPtau { power } => format!(
+                "https://storage.googleapis.com/zkevm/ptau/powersOfTau28_hez_final_{:02}.ptau",
+                power
+            ),
+            KnownSetup::EthereumKzg => {
+                "https://raw.githubusercontent.com/ethereum/c-kzg-4844/v2.1.1/src/trusted_setup.txt"
+                    .to_string()
+            }
+        }
+    }
+
+    /// The SHA-256 digest of the file pinned by this crate, if there is one.
+    pub fn sha256(&self) -> Option<[u8; 32]> {
+        match self {
+            KnownSetup::HermezPtau { power: 8 } => Some(HERMEZ_08_SHA256),
+            KnownSetup::HermezPtau { .. } => None,
+            KnownSetup::EthereumKzg => Some(ETHEREUM_KZG_SHA256),
+        }
+    }
+}
+
+/// A reader that computes the SHA-256 digest of everything read through it.
+struct HashingReader<R: Read> {
+    reader: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Reads the rest of the input and checks its digest against `expected`.
+    fn finish(mut self, expected: [u8; 32]) -> Result<(), InterpolationError> {
+        io::copy(&mut self, &mut io::sink())?;
+        let digest: [u8; 32] = self.hasher.finalize().into();
+        if digest != expected {
+            return Err(InterpolationError::DownloadError(
+                "The downloaded setup does not match its expected digest".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// This function starts downloading `setup` and returns a reader over the response body.
+pub fn download(setup: KnownSetup) -> Result<impl Read + Send, InterpolationError> {
+    let response = ureq::get(&setup.url())
+        .call()
+        .map_err(|e| InterpolationError::DownloadError(e.to_string()))?;
+    Ok(response.into_reader())
+}
+
+/// This function reads the first `size` points of a ptau file from `reader` and converts them to the Lagrange
+/// basis, checking the digest of the whole file against `expected_sha256`.
+pub(crate) fn lagrange_from_ptau<R: Read>(
+    reader: R,
+    size: usize,
+    expected_sha256: [u8; 32],
+) -> Result<LagrangeSrs<BnConfig>, InterpolationError> {
+    let mut reader = HashingReader::new(reader);
+    let (_, points) = read_tau_g1(&mut reader, size)?;
+    reader.finish(expected_sha256)?;
+    MonomialSrs::new(points).to_lagrange()
+}
+
+/// This function downloads the smallest Hermez powers of tau file holding a domain of size `size` and returns
+/// the BN254 Lagrange SRS of that size. The download is checked against `expected_sha256` if given and against the
+/// digest this crate pins for the file otherwise. It errors without downloading anything if there is neither.
+pub fn fetch_bn254_lagrange(
+    size: usize,
+    expected_sha256: Option<[u8; 32]>,
+) -> Result<LagrangeSrs<BnConfig>, InterpolationError> {
+    let setup = KnownSetup::hermez_for_size(size)?;
+    let expected_sha256 = expected_sha256.or(setup.sha256()).ok_or_else(|| {
+        InterpolationError::DownloadError(format!(
+            "No SHA-256 digest is pinned for {}, so one has to be passed",
+            setup.url()
+        ))
+    })?;
+    lagrange_from_ptau(download(setup)?, size, expected_sha256)
+}
+
+/// This function downloads the Ethereum KZG setup and checks it against its pinned digest.
+#[cfg(feature = "bls12-381")]
+pub fn fetch_ethereum_kzg() -> Result<crate::formats::ckzg::CkzgTrustedSetup, InterpolationError> {
+    let setup = KnownSetup::EthereumKzg;
+    let mut reader = HashingReader::new(download(setup)?);
+    let trusted_setup = crate::formats::ckzg::CkzgTrustedSetup::read(&mut reader)?;
+    reader.finish(ETHEREUM_KZG_SHA256)?;
+    Ok(trusted_setup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srs_to_lagrange;
+
+    const PTAU_FIXTURE: &str = include_str!("../formats/testdata/bn254_power_2.ptau.hex");
+    const HERMEZ_08: &[u8] = include_bytes!("../formats/testdata/powersOfTau28_hez_final_08.ptau");
+
+    fn decode_hex(hex: &str) -> Vec<u8> {
+        let hex = hex.split_whitespace().collect::<String>();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_known_setups() {
+        assert_eq!(
+            KnownSetup::hermez_for_size(1 << 20).unwrap(),
+            KnownSetup::HermezPtau { power: 20 }
+        );
+        assert_eq!(
+            KnownSetup::hermez_for_size(16).unwrap(),
+            KnownSetup::HermezPtau { power: 8 }
+        );
+        assert!(KnownSetup::hermez_for_size(1 << 29).is_err());
+        assert!(KnownSetup::hermez_for_size(12).is_err());
+        assert!(KnownSetup::HermezPtau { power: 8 }
+            .url()
+            .ends_with("powersOfTau28_hez_final_08.ptau"));
+        assert_eq!(KnownSetup::EthereumKzg.curve(), CurveId::Bls12_381);
+    }
+
+    #[test]
+    fn test_lagrange_from_ptau() {
+        let bytes = decode_hex(PTAU_FIXTURE);
+        let digest: [u8; 32] = Sha256::digest(&bytes).into();
+        let srs = lagrange_from_ptau(bytes.as_slice(), 4, digest).unwrap();
+        let (_, points) = read_tau_g1(&mut bytes.as_slice(), 4).unwrap();
+        assert_eq!(srs.points(), srs_to_lagrange(&points).unwrap().as_slice());
+
+        // The digest covers the whole file, not just the points we read.
+        let mut changed = bytes.clone();
+        *changed.last_mut().unwrap() ^= 1;
+        assert!(lagrange_from_ptau(changed.as_slice(), 4, digest).is_err());
+
+        // The pinned digest is that of the Hermez file.
+        let hermez = KnownSetup::HermezPtau { power: 8 };
+        let srs = lagrange_from_ptau(HERMEZ_08, 1 << 8, hermez.sha256().unwrap()).unwrap();
+        let (_, points) = read_tau_g1(&mut &HERMEZ_08[..], 1 << 8).unwrap();
+        assert_eq!(srs.points(), srs_to_lagrange(&points).unwrap().as_slice());
+        assert!(KnownSetup::HermezPtau { power: 20 }.sha256().is_none());
+        assert!(matches!(
+            fetch_bn254_lagrange(1 << 20, None),
+            Err(InterpolationError::DownloadError(_))
+        ));
+    }
+}
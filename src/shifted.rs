@@ -0,0 +1,246 @@
+//! This module converts runs of consecutive powers that do not start at `[1]`, such as the shifted powers Marlin
+//! and Sonic use to enforce degree bounds or the reversed sequences `[tau^d], [tau^(d - 1)], ...` some universal
+//! setups publish, to the Lagrange basis.
+//!
+//! The group FFT is linear, so converting `[tau^s], [tau^(s + 1)], ..., [tau^(s + n - 1)]` gives the points
+//! `[tau^s L_i(tau)]`, and committing to the evaluations of `f` with them gives the shifted commitment
+//! `[tau^s f(tau)]`. The only bookkeeping is finding the shift `s` and putting the points in ascending order.
+//!
+//! [`lagrange_with_x_lagrange`] gives the commitments `[tau L_i(tau)]` to `X L_i(X)` that permutation arguments
+//! use, along with the Lagrange basis itself, from one [`Converter`].
+use crate::{
+    converter::Converter,
+    error::InterpolationError,
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
+use derivative::Derivative;
+
+/// The order of the exponents in a run of consecutive powers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerOrder {
+    /// The exponents increase by one from each point to the next.
+    Ascending,
+    /// The exponents decrease by one from each point to the next.
+    Descending,
+}
+
+/// A Lagrange SRS multiplied by a power of tau, whose points are `[tau^shift L_i(tau)]`.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct ShiftedLagrangeSrs<E: SWCurveConfig> {
+    srs: LagrangeSrs<E>,
+    shift: usize,
+}
+
+impl<E: SWCurveConfig> ShiftedLagrangeSrs<E> {
+    /// The shifted points as a Lagrange SRS, so that [`crate::srs::commit_evals`] gives `[tau^shift f(tau)]`.
+    pub fn srs(&self) -> &LagrangeSrs<E> {
+        &self.srs
+    }
+
+    /// Consumes the SRS and returns the shifted points as a Lagrange SRS.
+    pub fn into_srs(self) -> LagrangeSrs<E> {
+        self.srs
+    }
+
+    /// The power of tau the Lagrange basis is multiplied by.
+    pub fn shift(&self) -> usize {
+        self.shift
+    }
+}
+
+/// This function converts a run of consecutive powers of tau to the Lagrange basis. `first_exponent` is the
+/// exponent of the first point and `order` says whether the following exponents go up or down from it.
+pub fn shifted_to_lagrange<E, F>(
+    points: &[Affine<E>],
+    first_exponent: usize,
+    order: PowerOrder,
+) -> Result<ShiftedLagrangeSrs<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let (ascending, shift) = match order {
+        PowerOrder::Ascending => (points.to_vec(), first_exponent),
+        PowerOrder::Descending => {
+            let shift = (first_exponent + 1).checked_sub(points.len()).ok_or(
+                InterpolationError::InvalidParameters(format!(
+                    "{} points cannot descend from the exponent {}",
+                    points.len(),
+                    first_exponent
+                )),
+            )?;
+            (points.iter().rev().copied().collect(), shift)
+        }
+    };
+    Ok(ShiftedLagrangeSrs {
+        srs: MonomialSrs::new(ascending).to_lagrange()?,
+        shift,
+    })
+}
+
+/// This function returns the shifted Lagrange basis of size `size` Marlin and Sonic use for polynomials with the
+/// degree bound `degree_bound`, that is the basis multiplied by `tau^(d - degree_bound)` where `d` is the degree of
+/// `srs`. The run of powers this needs must fit in `srs`, so usually `size` is `degree_bound + 1`.
+pub fn degree_bound_lagrange<E, F>(
+    srs: &MonomialSrs<E>,
+    degree_bound: usize,
+    size: usize,
+) -> Result<ShiftedLagrangeSrs<E>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let shift =
+        srs.len()
+            .checked_sub(degree_bound + 1)
+            .ok_or(InterpolationError::InvalidParameters(format!(
+                "The SRS has {} points so it cannot enforce the degree bound {}",
+                srs.len(),
+                degree_bound
+            )))?;
+    if shift + size > srs.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "A basis of size {} shifted by {} needs {} points but the SRS has {}",
+            size,
+            shift,
+            shift + size,
+            srs.len()
+        )));
+    }
+    shifted_to_lagrange(
+        &srs.points()[shift..shift + size],
+        shift,
+        PowerOrder::Ascending,
+    )
+}
+
+/// This function returns the Lagrange basis over the domain of `converter` and the commitments `[tau L_i(tau)]`,
+/// which are the conversion of the run of powers shifted by one. The last point of that run is `[tau^n]`, not the
+/// `[1]` a cyclic shift would wrap around to, since `X L_i(X)` has degree `n`, so the SRS needs at least `n + 1`
+/// points. Both conversions share the setup of `converter`.
+pub fn lagrange_with_x_lagrange<E, F>(
+    converter: &Converter<E>,
+    srs: &MonomialSrs<E>,
+) -> Result<(LagrangeSrs<E>, ShiftedLagrangeSrs<E>), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = converter.size();
+    if srs.len() <= size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but [tau L_i(tau)] over a domain of size {} needs {}",
+            srs.len(),
+            size,
+            size + 1
+        )));
+    }
+    let basis = |points: &[Affine<E>]| -> Result<LagrangeSrs<E>, InterpolationError> {
+        Ok(LagrangeSrs::from_parts(
+            converter.convert(points)?,
+            converter.generator(),
+            PointOrdering::Natural,
+        ))
+    };
+    let lagrange = basis(&srs.points()[..size])?;
+    let shifted = ShiftedLagrangeSrs {
+        srs: basis(&srs.points()[1..=size])?,
+        shift: 1,
+    };
+    Ok((lagrange, shifted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs::commit_evals, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+    };
+
+    #[test]
+    fn test_shifted_to_lagrange() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap());
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+        let evals = domain.fft(&polynomial.coeffs);
+
+        // Committing to the evaluations gives [tau^8 f(tau)].
+        let expected = G1Projective::msm(&srs.points()[8..24], &polynomial.coeffs)
+            .unwrap()
+            .into_affine();
+        let ascending =
+            shifted_to_lagrange(&srs.points()[8..24], 8, PowerOrder::Ascending).unwrap();
+        assert_eq!(ascending.shift(), 8);
+        assert_eq!(commit_evals(&evals, ascending.srs()).unwrap(), expected);
+
+        // [tau^23], [tau^22], ..., [tau^8] is the same run in reverse.
+        let reversed = srs.points()[8..24]
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        let descending = shifted_to_lagrange(&reversed, 23, PowerOrder::Descending).unwrap();
+        assert_eq!(descending, ascending);
+        assert!(shifted_to_lagrange(&reversed, 14, PowerOrder::Descending).is_err());
+    }
+
+    #[test]
+    fn test_degree_bound_lagrange() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap());
+
+        // With 32 powers the degree bound 15 shifts by 31 - 15.
+        let shifted = degree_bound_lagrange(&srs, 15, 16).unwrap();
+        assert_eq!(shifted.shift(), 16);
+        assert_eq!(
+            shifted.into_srs(),
+            MonomialSrs::new(srs.points()[16..].to_vec())
+                .to_lagrange()
+                .unwrap()
+        );
+        assert!(degree_bound_lagrange(&srs, 7, 16).is_err());
+        assert!(degree_bound_lagrange(&srs, 32, 16).is_err());
+    }
+
+    #[test]
+    fn test_lagrange_with_x_lagrange() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 16).unwrap());
+        let converter = Converter::new(16).unwrap();
+        let (lagrange, x_lagrange) = lagrange_with_x_lagrange(&converter, &srs).unwrap();
+        assert_eq!(
+            lagrange,
+            MonomialSrs::new(srs.points()[..16].to_vec())
+                .to_lagrange()
+                .unwrap()
+        );
+        assert_eq!(x_lagrange.shift(), 1);
+
+        // The ith point commits to X L_i(X), whose coefficients are those of L_i moved up by one.
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        for i in [0, 5, 15] {
+            let mut unit = vec![Fr::from(0u64); 16];
+            unit[i] = Fr::from(1u64);
+            let coeffs = domain.ifft(&unit);
+            let expected = G1Projective::msm(&srs.points()[1..17], &coeffs)
+                .unwrap()
+                .into_affine();
+            assert_eq!(x_lagrange.srs().points()[i], expected);
+        }
+
+        let short = MonomialSrs::new(srs.points()[..16].to_vec());
+        assert!(lagrange_with_x_lagrange(&converter, &short).is_err());
+    }
+}
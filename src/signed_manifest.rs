@@ -0,0 +1,283 @@
+//! This module signs the manifest of a conversion with ed25519, so ceremony coordinators can attest to the
+//! artifacts they publish and anyone holding their public key can check the attestation.
+//!
+//! A [`ManifestStatement`] records the digests of the input and output, the version of this crate, the parameters
+//! and when the conversion ran. The statement is signed in its text form, which is also how a [`SignedManifest`] is
+//! stored, followed by the public key and the signature:
+//!
+//! ```text
+//! srs-interpolation signed manifest 1
+//! crate-version <the version of this crate>
+//! curve <curve name>
+//! ordering <natural or bit-reversed>
+//! started-at <unix seconds>
+//! finished-at <unix seconds>
+//! input <number of points> <sha256 in hex> <blake3 in hex>
+//! output <number of points> <sha256 in hex> <blake3 in hex>
+//! public-key <ed25519 public key in hex>
+//! signature <ed25519 signature in hex>
+//! ```
+use crate::{
+    curves::SrsCurve,
+    error::InterpolationError,
+    manifest::{decode_hex, digest_points, encode_hex, PointsDigest},
+    srs::{LagrangeSrs, MonomialSrs, PointOrdering},
+};
+use ark_std::{
+    io::{Read, Write},
+    string::String,
+    vec::Vec,
+};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The first line of every signed manifest.
+const MANIFEST_VERSION: &str = "srs-interpolation signed manifest 1";
+
+/// What a signed manifest attests to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestStatement {
+    /// The version of this crate that ran the conversion.
+    pub crate_version: String,
+    /// The name of the curve, as given by [`crate::curves::CurveId::name`].
+    pub curve: String,
+    /// The order of the output points.
+    pub ordering: PointOrdering,
+    /// When the conversion started, in seconds since the Unix epoch.
+    pub started_at: u64,
+    /// When the conversion finished, in seconds since the Unix epoch.
+    pub finished_at: u64,
+    /// The digest of the monomial points.
+    pub input: PointsDigest,
+    /// The digest of the Lagrange points.
+    pub output: PointsDigest,
+}
+
+impl ManifestStatement {
+    /// This function returns the statement of the conversion of `srs` to `lagrange` by this version of the crate,
+    /// which ran between the Unix times `started_at` and `finished_at`.
+    pub fn new<E: SrsCurve>(
+        srs: &MonomialSrs<E>,
+        lagrange: &LagrangeSrs<E>,
+        started_at: u64,
+        finished_at: u64,
+    ) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            curve: E::CURVE_ID.name().to_string(),
+            ordering: lagrange.ordering(),
+            started_at,
+            finished_at,
+            input: digest_points(srs.points()),
+            output: digest_points(lagrange.points()),
+        }
+    }
+
+    /// This function returns the text form of the statement, which is what is signed.
+    pub fn to_text(&self) -> String {
+        let digest = |digest: &PointsDigest| {
+            format!(
+                "{} {} {}",
+                digest.len,
+                encode_hex(&digest.sha256),
+                encode_hex(&digest.blake3)
+            )
+        };
+        format!(
+            "{}\ncrate-version {}\ncurve {}\nordering {}\nstarted-at {}\nfinished-at {}\ninput {}\noutput {}\n",
+            MANIFEST_VERSION,
+            self.crate_version,
+            self.curve,
+            ordering_name(self.ordering),
+            self.started_at,
+            self.finished_at,
+            digest(&self.input),
+            digest(&self.output),
+        )
+    }
+}
+
+/// A statement with the ed25519 signature of its text form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedManifest {
+    /// The statement that was signed.
+    pub statement: ManifestStatement,
+    /// The public key of the signer.
+    pub public_key: [u8; 32],
+    /// The signature of the text form of the statement.
+    pub signature: [u8; 64],
+}
+
+fn ordering_name(ordering: PointOrdering) -> &'static str {
+    match ordering {
+        PointOrdering::Natural => "natural",
+        PointOrdering::BitReversed => "bit-reversed",
+    }
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+impl SignedManifest {
+    /// Writes the manifest.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), InterpolationError> {
+        write!(writer, "{}", self.statement.to_text())?;
+        writeln!(writer, "public-key {}", encode_hex(&self.public_key))?;
+        writeln!(writer, "signature {}", encode_hex(&self.signature))?;
+        Ok(())
+    }
+
+    /// Reads a manifest. The signature is not checked, which [`verify_manifest`] does.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, InterpolationError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(MANIFEST_VERSION) {
+            return Err(InterpolationError::SerializationError(
+                "Missing signed manifest version".to_string(),
+            ));
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|line| line.strip_prefix(' '))
+                .ok_or_else(|| {
+                    InterpolationError::SerializationError(format!(
+                        "Missing {} in signed manifest",
+                        name
+                    ))
+                })
+        };
+        let invalid = |name: &str| {
+            InterpolationError::SerializationError(format!("Invalid {} in signed manifest", name))
+        };
+        let crate_version = field("crate-version")?.to_string();
+        let curve = field("curve")?.to_string();
+        let ordering = match field("ordering")? {
+            "natural" => PointOrdering::Natural,
+            "bit-reversed" => PointOrdering::BitReversed,
+            _ => return Err(invalid("ordering")),
+        };
+        let started_at = field("started-at")?
+            .parse()
+            .map_err(|_| invalid("started-at"))?;
+        let finished_at = field("finished-at")?
+            .parse()
+            .map_err(|_| invalid("finished-at"))?;
+        let mut digest = |name: &str| -> Result<PointsDigest, InterpolationError> {
+            match field(name)?.split(' ').collect::<Vec<_>>().as_slice() {
+                [len, sha256, blake3] => Ok(PointsDigest {
+                    len: len.parse().map_err(|_| invalid(name))?,
+                    sha256: decode_hex(sha256)?,
+                    blake3: decode_hex(blake3)?,
+                }),
+                _ => Err(invalid(name)),
+            }
+        };
+        let input = digest("input")?;
+        let output = digest("output")?;
+        let public_key = decode_hex(field("public-key")?)?;
+        let signature = decode_hex(field("signature")?)?;
+        Ok(Self {
+            statement: ManifestStatement {
+                crate_version,
+                curve,
+                ordering,
+                started_at,
+                finished_at,
+                input,
+                output,
+            },
+            public_key,
+            signature,
+        })
+    }
+}
+
+/// This function signs `statement` with `key`.
+pub fn sign_manifest(statement: ManifestStatement, key: &SigningKey) -> SignedManifest {
+    let signature = key.sign(statement.to_text().as_bytes());
+    SignedManifest {
+        statement,
+        public_key: key.verifying_key().to_bytes(),
+        signature: signature.to_bytes(),
+    }
+}
+
+/// This function checks that `manifest` was signed by the holder of `key`. It errors if the manifest is signed by
+/// another key or its statement was changed after it was signed.
+pub fn verify_manifest(
+    manifest: &SignedManifest,
+    key: &VerifyingKey,
+) -> Result<(), InterpolationError> {
+    if manifest.public_key != key.to_bytes() {
+        return Err(InterpolationError::InvalidParameters(
+            "The manifest is signed by another key".to_string(),
+        ));
+    }
+    key.verify_strict(
+        manifest.statement.to_text().as_bytes(),
+        &Signature::from_bytes(&manifest.signature),
+    )
+    .map_err(|_| {
+        InterpolationError::InvalidParameters("The manifest signature is invalid".to_string())
+    })
+}
+
+/// This function converts `srs` to the Lagrange basis in the order `ordering` and signs the manifest of the
+/// conversion with `key`.
+pub fn srs_to_lagrange_with_signed_manifest<E: SrsCurve>(
+    srs: &MonomialSrs<E>,
+    ordering: PointOrdering,
+    key: &SigningKey,
+) -> Result<(LagrangeSrs<E>, SignedManifest), InterpolationError> {
+    let started_at = unix_seconds();
+    let lagrange = srs.to_lagrange_with_ordering(ordering)?;
+    let finished_at = unix_seconds();
+    let statement = ManifestStatement::new(srs, &lagrange, started_at, finished_at);
+    Ok((lagrange, sign_manifest(statement, key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_signed_manifest() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let (lagrange, manifest) =
+            srs_to_lagrange_with_signed_manifest(&srs, PointOrdering::Natural, &key).unwrap();
+        assert_eq!(lagrange.points(), srs_to_lagrange(srs.points()).unwrap());
+        assert_eq!(manifest.statement.output, digest_points(lagrange.points()));
+        assert_eq!(manifest.statement.curve, "bn254");
+        verify_manifest(&manifest, &key.verifying_key()).unwrap();
+
+        let mut bytes = Vec::new();
+        manifest.write(&mut bytes).unwrap();
+        let read = SignedManifest::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read, manifest);
+        verify_manifest(&read, &key.verifying_key()).unwrap();
+
+        // Changing the statement or checking against another key fails.
+        let mut changed = manifest.clone();
+        changed.statement.output.sha256[0] ^= 1;
+        assert!(verify_manifest(&changed, &key.verifying_key()).is_err());
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        assert!(verify_manifest(&manifest, &other.verifying_key()).is_err());
+        let mut resigned = manifest.clone();
+        resigned.public_key = other.verifying_key().to_bytes();
+        assert!(verify_manifest(&resigned, &other.verifying_key()).is_err());
+
+        let text = String::from_utf8(bytes).unwrap();
+        let truncated = text.lines().take(8).collect::<Vec<_>>().join("\n");
+        assert!(SignedManifest::read(&mut truncated.as_bytes()).is_err());
+    }
+}
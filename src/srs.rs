@@ -0,0 +1,794 @@
+//! This module contains wrapper types for an SRS in the monomial basis and in the Lagrange basis.
+use crate::{
+    domain_size_inv,
+    error::InterpolationError,
+    lagrange_to_srs_with_generator,
+    msm::MsmTable,
+    reorder_points, srs_to_lagrange_point, srs_to_lagrange_with_generator,
+    srs_to_lagrange_with_progress, srs_to_lagrange_with_variant,
+    utils::{distribute_powers, is_primitive_root},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{cfg_iter, vec::Vec, Zero};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The version of the header written before the points when serializing an SRS.
+const SRS_FORMAT_VERSION: u8 = 1;
+/// The header tag of a [`MonomialSrs`].
+const MONOMIAL_TAG: u8 = 0;
+/// The header tag of a [`LagrangeSrs`].
+const LAGRANGE_TAG: u8 = 1;
+/// [`commit_sparse_evals`] switches to the dense path once at least one in this many evaluations is nonzero.
+const SPARSE_CROSSOVER: usize = 2;
+
+/// The order in which the points of a [`LagrangeSrs`] are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PointOrdering {
+    /// The `i`th point is the commitment to the `i`th Lagrange polynomial.
+    #[default]
+    Natural,
+    /// The `i`th point is the commitment to the Lagrange polynomial whose index is `i` with its bits reversed.
+    BitReversed,
+}
+
+/// The order in which the rounds of the group FFT run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FftVariant {
+    /// Decimation in time, which permutes the input into bit-reversed order and returns the points in natural
+    /// order.
+    #[default]
+    DecimationInTime,
+    /// Decimation in frequency, which takes the input in natural order and returns the points in bit-reversed
+    /// order, so a bit-reversed output needs no permutation at all.
+    DecimationInFrequency,
+}
+
+impl PointOrdering {
+    fn to_byte(self) -> u8 {
+        match self {
+            PointOrdering::Natural => 0,
+            PointOrdering::BitReversed => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, SerializationError> {
+        match byte {
+            0 => Ok(PointOrdering::Natural),
+            1 => Ok(PointOrdering::BitReversed),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+/// An SRS in the monomial basis, that is `[1], [tau], [tau^2], ..., [tau^(n-1)]`.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct MonomialSrs<E: SWCurveConfig> {
+    points: Vec<Affine<E>>,
+}
+
+impl<E: SWCurveConfig> MonomialSrs<E> {
+    /// Wraps a list of points given in ascending order of the powers of tau.
+    pub fn new(points: Vec<Affine<E>>) -> Self {
+        Self { points }
+    }
+
+    /// The points of the SRS.
+    pub fn points(&self) -> &[Affine<E>] {
+        &self.points
+    }
+
+    /// Consumes the SRS and returns its points.
+    pub fn into_points(self) -> Vec<Affine<E>> {
+        self.points
+    }
+
+    /// The number of points in the SRS.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the SRS has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// This function converts the SRS to the Lagrange basis over the arkworks radix-2 domain of the same size.
+    /// It errors if the number of points is not a power of two.
+    pub fn to_lagrange(&self) -> Result<LagrangeSrs<E>, InterpolationError> {
+        self.to_lagrange_with_ordering(PointOrdering::Natural)
+    }
+
+    /// This function is the same as [`MonomialSrs::to_lagrange`] but stores the points in the given `ordering`.
+    pub fn to_lagrange_with_ordering(
+        &self,
+        ordering: PointOrdering,
+    ) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(self.len())
+            .ok_or(InterpolationError::SizeError)?;
+        let mut points = srs_to_lagrange_with_generator(&self.points, domain.group_gen())?;
+        reorder_points(&mut points, PointOrdering::Natural, ordering)?;
+        Ok(LagrangeSrs::from_parts(
+            points,
+            domain.group_gen(),
+            ordering,
+        ))
+    }
+
+    /// This function is the same as [`MonomialSrs::to_lagrange_with_ordering`] but runs the group FFT as `variant`,
+    /// see [`crate::srs_to_lagrange_with_variant`].
+    pub fn to_lagrange_with_variant(
+        &self,
+        ordering: PointOrdering,
+        variant: FftVariant,
+    ) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(self.len())
+            .ok_or(InterpolationError::SizeError)?;
+        let points = srs_to_lagrange_with_variant(&self.points, ordering, variant)?;
+        Ok(LagrangeSrs::from_parts(
+            points,
+            domain.group_gen(),
+            ordering,
+        ))
+    }
+
+    /// This function is the same as [`MonomialSrs::to_lagrange_with_ordering`] but takes the Lagrange basis over the
+    /// domain generated by `generator`, for matching implementations that fix a different root of unity than
+    /// arkworks. It errors if `generator` is not a primitive root of unity of order the size of the SRS.
+    pub fn to_lagrange_with_generator(
+        &self,
+        generator: E::ScalarField,
+        ordering: PointOrdering,
+    ) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let mut points = srs_to_lagrange_with_generator(&self.points, generator)?;
+        reorder_points(&mut points, PointOrdering::Natural, ordering)?;
+        Ok(LagrangeSrs::from_parts(points, generator, ordering))
+    }
+
+    /// This function is the same as [`MonomialSrs::to_lagrange_with_ordering`] but reports its progress to
+    /// `progress`, see [`crate::srs_to_lagrange_with_progress`].
+    pub fn to_lagrange_with_progress(
+        &self,
+        ordering: PointOrdering,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let mut points = srs_to_lagrange_with_progress(&self.points, progress)?;
+        reorder_points(&mut points, PointOrdering::Natural, ordering)?;
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(self.len())
+            .ok_or(InterpolationError::SizeError)?;
+        Ok(LagrangeSrs::from_parts(
+            points,
+            domain.group_gen(),
+            ordering,
+        ))
+    }
+
+    /// This function returns the single Lagrange point `[L_index(tau)]` over the arkworks domain of the same size,
+    /// see [`crate::srs_to_lagrange_point`].
+    pub fn lagrange_point(&self, index: usize) -> Result<Affine<E>, InterpolationError> {
+        srs_to_lagrange_point(&self.points, index)
+    }
+}
+
+/// An SRS in the Lagrange basis, that is `[L_0(tau)], [L_1(tau)], ..., [L_(n-1)(tau)]` where `L_i` is
+/// the Lagrange polynomial that is one at `generator^i` and zero on the rest of the domain.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct LagrangeSrs<E: SWCurveConfig> {
+    points: Vec<Affine<E>>,
+    generator: E::ScalarField,
+    ordering: PointOrdering,
+    /// The table built by [`LagrangeSrs::precompute`], which is not part of the SRS itself.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    table: Option<MsmTable<E>>,
+}
+
+impl<E: SWCurveConfig> LagrangeSrs<E> {
+    /// Wraps a list of Lagrange points without checking they are consistent with `generator` and `ordering`.
+    pub(crate) fn from_parts(
+        points: Vec<Affine<E>>,
+        generator: E::ScalarField,
+        ordering: PointOrdering,
+    ) -> Self {
+        Self {
+            points,
+            generator,
+            ordering,
+            table: None,
+        }
+    }
+
+    /// The points of the SRS, stored in the order given by [`LagrangeSrs::ordering`].
+    pub fn points(&self) -> &[Affine<E>] {
+        &self.points
+    }
+
+    /// Consumes the SRS and returns its points.
+    pub fn into_points(self) -> Vec<Affine<E>> {
+        self.points
+    }
+
+    /// The number of points in the SRS, which is also the size of the domain.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the SRS has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The primitive root of unity generating the evaluation domain.
+    pub fn generator(&self) -> E::ScalarField {
+        self.generator
+    }
+
+    /// The order the points are stored in.
+    pub fn ordering(&self) -> PointOrdering {
+        self.ordering
+    }
+
+    /// This function stores the points of the SRS in the given `ordering`, permuting them if needed. Permuting the
+    /// points drops any table built by [`LagrangeSrs::precompute`].
+    pub fn with_ordering(mut self, ordering: PointOrdering) -> Result<Self, InterpolationError> {
+        if ordering != self.ordering {
+            reorder_points(&mut self.points, self.ordering, ordering)?;
+            self.ordering = ordering;
+            self.table = None;
+        }
+        Ok(self)
+    }
+
+    /// This function builds an [`MsmTable`] with windows of `window_bits` bits for the points of the SRS, which
+    /// [`LagrangeSrs::commit`] uses from then on. The table stores about `256 / window_bits` points per point of
+    /// the SRS, so it is worth building when the SRS is used for many commitments.
+    pub fn precompute(&mut self, window_bits: usize) -> Result<(), InterpolationError> {
+        self.table = Some(MsmTable::new(&self.points, window_bits)?);
+        Ok(())
+    }
+
+    /// The table built by [`LagrangeSrs::precompute`], if there is one.
+    pub fn table(&self) -> Option<&MsmTable<E>> {
+        self.table.as_ref()
+    }
+
+    /// This function returns the commitment to the polynomial whose evaluations over the domain are `evals`, in
+    /// the order of the points. It uses the table built by [`LagrangeSrs::precompute`] if there is one and is
+    /// otherwise the same as [`commit_evals`].
+    pub fn commit(&self, evals: &[E::ScalarField]) -> Result<Affine<E>, InterpolationError> {
+        match &self.table {
+            Some(table) => Ok(table.msm(evals)?.into_affine()),
+            None => commit_evals(evals, self),
+        }
+    }
+
+    /// This function returns the Lagrange basis over the arkworks domain of twice the size, given the monomial SRS
+    /// `monomial` of that size whose first half this SRS was converted from. The SRS must be over the arkworks domain
+    /// of its own size, and the result is stored in the same order.
+    ///
+    /// Writing `w` for the generator of the larger domain, the even points of the result are
+    /// `([L_k(tau)] + [tau^n L_k(tau)]) / 2`, which reuses this SRS and needs only the conversion of the upper half of
+    /// `monomial`. The odd points are the Lagrange basis of the coset `w H` times `(1 - tau^n) / 2`, which is the
+    /// conversion of `w^-j ([tau^j] - [tau^(n + j)])`. That is still two conversions of size `n`, the same as the
+    /// two halves of a conversion of size `2n`, so this saves the first round of the larger conversion and not more.
+    pub fn extend(&self, monomial: &MonomialSrs<E>) -> Result<LagrangeSrs<E>, InterpolationError> {
+        let size = self.len();
+        if monomial.len() != 2 * size {
+            return Err(InterpolationError::InvalidParameters(format!(
+                "Extending {} Lagrange points needs {} monomial points but there are {}",
+                size,
+                2 * size,
+                monomial.len()
+            )));
+        }
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(2 * size)
+            .ok_or(InterpolationError::SizeError)?;
+        let gen = domain.group_gen();
+        if self.generator != gen.square() {
+            return Err(InterpolationError::InvalidParameters(
+                "Only an SRS over the arkworks domain of its size can be extended".to_string(),
+            ));
+        }
+        let mut lower = self.points.clone();
+        reorder_points(&mut lower, self.ordering, PointOrdering::Natural)?;
+        let (monomial_lower, monomial_upper) = monomial.points().split_at(size);
+
+        let upper = srs_to_lagrange_with_generator(monomial_upper, self.generator)?;
+        let differences = cfg_iter!(monomial_lower)
+            .zip(cfg_iter!(monomial_upper))
+            .map(|(p, q)| *p - q)
+            .collect::<Vec<_>>();
+        let mut differences = Projective::<E>::normalize_batch(&differences);
+        distribute_powers(&mut differences, domain.group_gen_inv());
+        let coset = srs_to_lagrange_with_generator(&differences, self.generator)?;
+
+        let half = domain_size_inv::<E::ScalarField>(2)?;
+        let evens = cfg_iter!(lower)
+            .zip(cfg_iter!(upper))
+            .map(|(l, u)| (*l + u) * half)
+            .collect::<Vec<_>>();
+        let odds = cfg_iter!(coset).map(|c| *c * half).collect::<Vec<_>>();
+        let points = evens
+            .into_iter()
+            .zip(odds)
+            .flat_map(|(even, odd)| [even, odd])
+            .collect::<Vec<_>>();
+        let mut points = Projective::<E>::normalize_batch(&points);
+        reorder_points(&mut points, PointOrdering::Natural, self.ordering)?;
+        Ok(LagrangeSrs::from_parts(points, gen, self.ordering))
+    }
+
+    /// This function converts the SRS back to the monomial basis.
+    pub fn to_monomial(&self) -> Result<MonomialSrs<E>, InterpolationError> {
+        let points = match self.ordering {
+            PointOrdering::Natural => lagrange_to_srs_with_generator(&self.points, self.generator)?,
+            PointOrdering::BitReversed => {
+                let mut natural = self.points.clone();
+                reorder_points(&mut natural, self.ordering, PointOrdering::Natural)?;
+                lagrange_to_srs_with_generator(&natural, self.generator)?
+            }
+        };
+        Ok(MonomialSrs::new(points))
+    }
+}
+
+/// This function returns the commitment to the polynomial whose evaluations over the domain of `srs` are `evals`,
+/// stored in the same order as the points of `srs`. There must be one evaluation per point.
+pub fn commit_evals<E: SWCurveConfig>(
+    evals: &[E::ScalarField],
+    srs: &LagrangeSrs<E>,
+) -> Result<Affine<E>, InterpolationError> {
+    if evals.len() != srs.len() {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} evaluations were given",
+            srs.len(),
+            evals.len()
+        )));
+    }
+    let scalars = cfg_iter!(evals)
+        .map(|eval| eval.into_bigint())
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::msm_bigint(srs.points(), &scalars).into_affine())
+}
+
+/// This function is the same as [`commit_evals`] for a polynomial whose only nonzero evaluations are given as
+/// `(index, value)` pairs, where `index` is the position of the matching point of `srs`. Repeated indices add up.
+/// Only the points with nonzero evaluations are used unless there are enough of them for the dense path to be
+/// cheaper.
+pub fn commit_sparse_evals<E: SWCurveConfig>(
+    evals: &[(usize, E::ScalarField)],
+    srs: &LagrangeSrs<E>,
+) -> Result<Affine<E>, InterpolationError> {
+    if let Some((index, _)) = evals.iter().find(|(index, _)| *index >= srs.len()) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but an evaluation is at index {}",
+            srs.len(),
+            index
+        )));
+    }
+
+    if evals.len() * SPARSE_CROSSOVER >= srs.len() {
+        let mut dense = vec![E::ScalarField::zero(); srs.len()];
+        evals
+            .iter()
+            .for_each(|(index, value)| dense[*index] += value);
+        return commit_evals(&dense, srs);
+    }
+    let (bases, scalars): (Vec<_>, Vec<_>) = evals
+        .iter()
+        .map(|(index, value)| (srs.points()[*index], value.into_bigint()))
+        .unzip();
+    Ok(Projective::<E>::msm_bigint(&bases, &scalars).into_affine())
+}
+
+/// A monomial SRS together with its conversion to the Lagrange basis, as returned by [`trim`].
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "E: SWCurveConfig"),
+    Debug(bound = "E: SWCurveConfig"),
+    PartialEq(bound = "E: SWCurveConfig"),
+    Eq(bound = "E: SWCurveConfig")
+)]
+pub struct SrsPair<E: SWCurveConfig> {
+    monomial: MonomialSrs<E>,
+    lagrange: LagrangeSrs<E>,
+}
+
+impl<E: SWCurveConfig> SrsPair<E> {
+    /// The SRS in the monomial basis.
+    pub fn monomial(&self) -> &MonomialSrs<E> {
+        &self.monomial
+    }
+
+    /// The SRS in the Lagrange basis, with its points in natural order.
+    pub fn lagrange(&self) -> &LagrangeSrs<E> {
+        &self.lagrange
+    }
+
+    /// The number of points in each basis, which is also the size of the domain.
+    pub fn len(&self) -> usize {
+        self.monomial.len()
+    }
+
+    /// Whether the SRS has no points.
+    pub fn is_empty(&self) -> bool {
+        self.monomial.is_empty()
+    }
+
+    /// Consumes the pair and returns the monomial and Lagrange SRS.
+    pub fn into_parts(self) -> (MonomialSrs<E>, LagrangeSrs<E>) {
+        (self.monomial, self.lagrange)
+    }
+}
+
+/// This function trims `srs` to the smallest power of two domain supporting polynomials of degree `target_degree`
+/// and returns the trimmed SRS in both bases, so a circuit of `2^k` gates takes a `target_degree` of `2^k - 1`. It
+/// errors if `srs` has too few points.
+pub fn trim<E: SWCurveConfig>(
+    srs: &MonomialSrs<E>,
+    target_degree: usize,
+) -> Result<SrsPair<E>, InterpolationError> {
+    let size = target_degree
+        .checked_add(1)
+        .and_then(usize::checked_next_power_of_two)
+        .ok_or(InterpolationError::SizeError)?;
+    if srs.len() < size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but {} are needed for degree {}",
+            srs.len(),
+            size,
+            target_degree
+        )));
+    }
+    let monomial = MonomialSrs::new(srs.points()[..size].to_vec());
+    let lagrange = monomial.to_lagrange()?;
+    Ok(SrsPair { monomial, lagrange })
+}
+
+/// This function reads the version and tag at the start of a serialized SRS.
+fn read_header<R: Read>(mut reader: R, expected_tag: u8) -> Result<(), SerializationError> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    if header != [SRS_FORMAT_VERSION, expected_tag] {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(())
+}
+
+impl<E: SWCurveConfig> Valid for MonomialSrs<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.points.check()
+    }
+}
+
+impl<E: SWCurveConfig> CanonicalSerialize for MonomialSrs<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(&[SRS_FORMAT_VERSION, MONOMIAL_TAG])?;
+        self.points.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        2 + self.points.serialized_size(compress)
+    }
+}
+
+impl<E: SWCurveConfig> CanonicalDeserialize for MonomialSrs<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        read_header(&mut reader, MONOMIAL_TAG)?;
+        let points = Vec::<Affine<E>>::deserialize_with_mode(reader, compress, validate)?;
+        Ok(Self { points })
+    }
+}
+
+impl<E: SWCurveConfig> Valid for LagrangeSrs<E> {
+    /// Checks the points are valid and that the generator is a primitive root of unity of the right order.
+    fn check(&self) -> Result<(), SerializationError> {
+        if !is_primitive_root(self.generator, self.len()) {
+            return Err(SerializationError::InvalidData);
+        }
+        self.points.check()
+    }
+}
+
+impl<E: SWCurveConfig> CanonicalSerialize for LagrangeSrs<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(&[SRS_FORMAT_VERSION, LAGRANGE_TAG, self.ordering.to_byte()])?;
+        self.generator.serialize_with_mode(&mut writer, compress)?;
+        self.points.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        3 + self.generator.serialized_size(compress) + self.points.serialized_size(compress)
+    }
+}
+
+impl<E: SWCurveConfig> CanonicalDeserialize for LagrangeSrs<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        read_header(&mut reader, LAGRANGE_TAG)?;
+        let mut ordering = [0u8; 1];
+        reader.read_exact(&mut ordering)?;
+        let ordering = PointOrdering::from_byte(ordering[0])?;
+        let generator = E::ScalarField::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let points = Vec::<Affine<E>>::deserialize_with_mode(reader, compress, Validate::No)?;
+        let srs = Self::from_parts(points, generator, ordering);
+        if let Validate::Yes = validate {
+            srs.check()?;
+        }
+        Ok(srs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use crate::utils::bit_reverse;
+    use ark_bn254::{g1::Config as BnConfig, Fr, G1Projective};
+    use ark_ff::Field;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_extend() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap());
+        for size in [1, 2, 32] {
+            let large = MonomialSrs::new(srs.points()[..2 * size].to_vec());
+            let small = MonomialSrs::new(srs.points()[..size].to_vec());
+            for ordering in [PointOrdering::Natural, PointOrdering::BitReversed] {
+                let lagrange = small.to_lagrange_with_ordering(ordering).unwrap();
+                assert_eq!(
+                    lagrange.extend(&large).unwrap(),
+                    large.to_lagrange_with_ordering(ordering).unwrap()
+                );
+            }
+        }
+
+        let lagrange = MonomialSrs::new(srs.points()[..8].to_vec())
+            .to_lagrange()
+            .unwrap();
+        assert!(lagrange.extend(&srs).is_err());
+        let other_domain = LagrangeSrs::from_parts(
+            lagrange.points().to_vec(),
+            lagrange.generator().inverse().unwrap(),
+            PointOrdering::Natural,
+        );
+        assert!(other_domain
+            .extend(&MonomialSrs::new(srs.points()[..16].to_vec()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_srs_conversion() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+        assert_eq!(lagrange_srs.len(), 32);
+        assert_eq!(lagrange_srs.ordering(), PointOrdering::Natural);
+        assert_eq!(lagrange_srs.to_monomial().unwrap(), srs);
+
+        // Storing the points bit reversed does not change the monomial SRS we recover.
+        let reversed_srs = srs
+            .to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert_eq!(reversed_srs.ordering(), PointOrdering::BitReversed);
+        for i in 0..32 {
+            assert_eq!(
+                reversed_srs.points()[bit_reverse(i, 5).unwrap()],
+                lagrange_srs.points()[i]
+            );
+        }
+        assert_eq!(reversed_srs.to_monomial().unwrap(), srs);
+        assert_eq!(
+            reversed_srs.with_ordering(PointOrdering::Natural).unwrap(),
+            lagrange_srs
+        );
+    }
+
+    #[test]
+    fn test_srs_serialization() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+
+        for compress in [Compress::Yes, Compress::No] {
+            let mut bytes = Vec::new();
+            srs.serialize_with_mode(&mut bytes, compress).unwrap();
+            assert_eq!(bytes.len(), srs.serialized_size(compress));
+            let read_srs = MonomialSrs::<BnConfig>::deserialize_with_mode(
+                bytes.as_slice(),
+                compress,
+                Validate::Yes,
+            )
+            .unwrap();
+            assert_eq!(read_srs, srs);
+
+            let mut bytes = Vec::new();
+            lagrange_srs
+                .serialize_with_mode(&mut bytes, compress)
+                .unwrap();
+            assert_eq!(bytes.len(), lagrange_srs.serialized_size(compress));
+            let read_srs = LagrangeSrs::<BnConfig>::deserialize_with_mode(
+                bytes.as_slice(),
+                compress,
+                Validate::Yes,
+            )
+            .unwrap();
+            assert_eq!(read_srs, lagrange_srs);
+
+            // A monomial SRS can't be read as a Lagrange SRS.
+            let mut bytes = Vec::new();
+            srs.serialize_with_mode(&mut bytes, compress).unwrap();
+            assert!(LagrangeSrs::<BnConfig>::deserialize_with_mode(
+                bytes.as_slice(),
+                compress,
+                Validate::Yes
+            )
+            .is_err());
+        }
+
+        // A generator that does not match the size of the domain is rejected.
+        let bad_srs = LagrangeSrs::from_parts(
+            lagrange_srs.points().to_vec(),
+            lagrange_srs.generator().square(),
+            PointOrdering::Natural,
+        );
+        let mut bytes = Vec::new();
+        bad_srs.serialize_compressed(&mut bytes).unwrap();
+        assert!(LagrangeSrs::<BnConfig>::deserialize_compressed(bytes.as_slice()).is_err());
+        assert!(
+            LagrangeSrs::<BnConfig>::deserialize_compressed_unchecked(bytes.as_slice()).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_commit_evals() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+
+        // Committing to the evaluations matches committing to the coefficients with the monomial SRS.
+        let polynomial = DensePolynomial::<Fr>::rand(15, rng);
+        let mut evals = domain.fft(&polynomial.coeffs);
+        let expected = G1Projective::msm(srs.points(), &polynomial.coeffs)
+            .unwrap()
+            .into_affine();
+        assert_eq!(commit_evals(&evals, &lagrange_srs).unwrap(), expected);
+
+        // Bit-reversed points take bit-reversed evaluations.
+        let reversed_srs = lagrange_srs
+            .clone()
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        reorder_points(
+            &mut evals,
+            PointOrdering::Natural,
+            PointOrdering::BitReversed,
+        )
+        .unwrap();
+        assert_eq!(commit_evals(&evals, &reversed_srs).unwrap(), expected);
+        assert!(commit_evals(&evals[1..], &lagrange_srs).is_err());
+    }
+
+    #[test]
+    fn test_trim() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap());
+
+        // Degrees 8 through 15 all need the domain of 16 points.
+        for degree in [8, 15] {
+            let pair = trim(&srs, degree).unwrap();
+            assert_eq!(pair.len(), 16);
+            assert_eq!(pair.monomial().points(), &srs.points()[..16]);
+            assert_eq!(pair.lagrange(), &pair.monomial().to_lagrange().unwrap());
+        }
+        let (monomial, lagrange) = trim(&srs, 0).unwrap().into_parts();
+        assert_eq!(monomial.points(), &srs.points()[..1]);
+        assert_eq!(lagrange.points(), monomial.points());
+
+        assert_eq!(trim(&srs, 31).unwrap().monomial(), &srs);
+        assert!(trim(&srs, 32).is_err());
+        assert!(trim(&srs, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_precomputed_commit() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let mut lagrange_srs = srs.to_lagrange().unwrap();
+        let evals = (0..16).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let expected = commit_evals(&evals, &lagrange_srs).unwrap();
+        assert_eq!(lagrange_srs.commit(&evals).unwrap(), expected);
+
+        // The table gives the same commitment and does not change what the SRS compares equal to.
+        let plain_srs = lagrange_srs.clone();
+        lagrange_srs.precompute(10).unwrap();
+        assert_eq!(lagrange_srs.table().unwrap().len(), 16);
+        assert_eq!(lagrange_srs.commit(&evals).unwrap(), expected);
+        assert!(lagrange_srs.commit(&evals[1..]).is_err());
+        assert_eq!(lagrange_srs, plain_srs);
+        assert!(lagrange_srs.precompute(0).is_err());
+
+        // Reordering the points drops the table.
+        let reversed_srs = lagrange_srs
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert!(reversed_srs.table().is_none());
+    }
+
+    #[test]
+    fn test_commit_sparse_evals() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange_srs = srs.to_lagrange().unwrap();
+
+        // Both the sparse and the dense path match the dense commitment, with repeated indices adding up.
+        for sparse in [
+            vec![],
+            vec![
+                (3, Fr::from(5u64)),
+                (9, Fr::from(7u64)),
+                (3, Fr::from(2u64)),
+            ],
+            (0..12).map(|i| (i, Fr::from(i as u64))).collect(),
+        ] {
+            let mut dense = vec![Fr::zero(); 16];
+            sparse
+                .iter()
+                .for_each(|(index, value)| dense[*index] += value);
+            assert_eq!(
+                commit_sparse_evals(&sparse, &lagrange_srs).unwrap(),
+                commit_evals(&dense, &lagrange_srs).unwrap()
+            );
+        }
+        assert!(commit_sparse_evals(&[(16, Fr::from(1u64))], &lagrange_srs).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! This module contains conversions that read points incrementally and write the converted points incrementally,
+//! so the only large allocation is the single vector of points the FFT style operation works on.
+use crate::{
+    buffer::try_with_capacity,
+    curves::SrsCurve,
+    domain_size_inv,
+    error::InterpolationError,
+    formats::binary::{Basis, BinaryHeader, Endianness, PointEncoding, SrsReader, SrsWriter},
+    srs::PointOrdering,
+    srs_to_lagrange_unscaled,
+    utils::size_from_u64,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{
+    cfg_iter_mut,
+    io::{Read, Write},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// This function collects the monomial points yielded by `points`, converts them to the Lagrange basis over the
+/// arkworks domain and passes each Lagrange point to `sink` in order.
+/// It errors if the number of points is not a power of two or if reading a point or the sink errors.
+pub fn srs_to_lagrange_streaming<E, F, I, S>(
+    points: I,
+    mut sink: S,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    I: IntoIterator<Item = Result<Affine<E>, InterpolationError>>,
+    S: FnMut(&Affine<E>) -> Result<(), InterpolationError>,
+{
+    let points = points.into_iter();
+    let mut buffer = try_with_capacity(points.size_hint().0)?;
+    for point in points {
+        buffer.push(point?);
+    }
+
+    let domain =
+        Radix2EvaluationDomain::<F>::new(buffer.len()).ok_or(InterpolationError::SizeError)?;
+    srs_to_lagrange_unscaled(&mut buffer, domain.group_gen())?;
+
+    // We rescale in place rather than collecting into a second vector.
+    let domain_size_inv = domain_size_inv::<F>(buffer.len())?;
+    cfg_iter_mut!(buffer).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    buffer.iter().try_for_each(&mut sink)
+}
+
+/// This function reads a monomial SRS in this crate's binary format from `reader` and writes the Lagrange SRS
+/// over the arkworks domain to `writer` in the same format, using the given encoding and endianness.
+pub fn convert_binary<R, W, E>(
+    reader: R,
+    writer: W,
+    encoding: PointEncoding,
+    endianness: Endianness,
+) -> Result<W, InterpolationError>
+where
+    R: Read,
+    W: Write,
+    E: SrsCurve,
+{
+    let reader = SrsReader::<R, E>::new(reader)?;
+    let input_header = *reader.header();
+    if input_header.basis != Basis::Monomial {
+        return Err(InterpolationError::InvalidParameters(
+            "Expected a monomial SRS".to_string(),
+        ));
+    }
+    let size = size_from_u64(input_header.size)?;
+    let domain =
+        Radix2EvaluationDomain::<E::ScalarField>::new(size).ok_or(InterpolationError::SizeError)?;
+
+    let header = BinaryHeader {
+        basis: Basis::Lagrange,
+        ordering: PointOrdering::Natural,
+        encoding,
+        endianness,
+        ..input_header
+    };
+    let mut writer = SrsWriter::<W, E>::new(writer, header, Some(domain.group_gen()))?;
+    srs_to_lagrange_streaming(reader, |point| writer.write_point(point))?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{read_lagrange, write_lagrange, write_monomial},
+        srs::MonomialSrs,
+        srs_to_lagrange,
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_streaming_conversion() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 31).unwrap();
+        let expected = srs_to_lagrange(&points).unwrap();
+
+        let mut streamed = Vec::new();
+        srs_to_lagrange_streaming(points.iter().copied().map(Ok), |point| {
+            streamed.push(*point);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(streamed, expected);
+
+        // An error from the input is passed through.
+        let failing = points.iter().copied().map(Ok).chain(Some(Err(
+            InterpolationError::SerializationError("failed".to_string()),
+        )));
+        assert!(srs_to_lagrange_streaming(failing, |_| Ok(())).is_err());
+        assert!(
+            srs_to_lagrange_streaming(points[..3].iter().copied().map(Ok), |_| Ok(())).is_err()
+        );
+    }
+
+    #[test]
+    fn test_binary_conversion() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let input = write_monomial(
+            Vec::new(),
+            &srs,
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        let output = convert_binary::<_, _, BnConfig>(
+            input.as_slice(),
+            Vec::new(),
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        let expected = write_lagrange(
+            Vec::new(),
+            &srs.to_lagrange().unwrap(),
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(
+            read_lagrange::<_, BnConfig>(output.as_slice()).unwrap(),
+            srs.to_lagrange().unwrap()
+        );
+
+        // A Lagrange SRS can't be converted again.
+        assert!(convert_binary::<_, _, BnConfig>(
+            output.as_slice(),
+            Vec::new(),
+            PointEncoding::Compressed,
+            Endianness::Little,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! This module contains a parallel conversion on threads from [`std::thread::scope`] with an explicit thread
+//! count, for integrators that cannot use rayon's global pool. Build without the `parallel` feature to leave
+//! rayon out of the crate entirely.
+//!
+//! Each round is split the same way the rayon path splits it. While a round has at least as many chunks as
+//! threads, every thread takes a run of whole chunks. In the last rounds, with fewer chunks than threads, each chunk
+//! is split further into runs of pairs so that every thread still has work.
+use crate::{
+    buffer::try_copied,
+    domain_size_inv,
+    error::InterpolationError,
+    utils::{
+        bit_reverse_permutation, distribute_powers, fft_round_segment, fft_round_with_scratch,
+        scratch_len,
+    },
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::vec::Vec;
+use std::thread;
+
+/// This function is the same as [`crate::srs_to_lagrange`] but runs on `threads` scoped threads. It errors if the
+/// number of points is not a power of two or `threads` is zero.
+pub fn srs_to_lagrange_threads<E, F>(
+    points: &[Affine<E>],
+    threads: usize,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if threads == 0 {
+        return Err(InterpolationError::InvalidParameters(
+            "Cannot convert on zero threads".to_string(),
+        ));
+    }
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let size = points.len();
+    let log_size = size.ilog2() as usize;
+    let gen_inv = domain.group_gen_inv();
+
+    let mut result = try_copied(points)?;
+    bit_reverse_permutation(&mut result, log_size)?;
+    for round in 1..=log_size {
+        let root = gen_inv.pow([(size >> round) as u64]);
+        run_round(&mut result, root, round, threads)?;
+    }
+
+    let size_inv = domain_size_inv::<F>(size)?;
+    let per_thread = size.div_ceil(threads);
+    thread::scope(|scope| {
+        for chunk in result.chunks_mut(per_thread) {
+            scope.spawn(move || {
+                chunk
+                    .iter_mut()
+                    .for_each(|point| *point = (*point * size_inv).into_affine())
+            });
+        }
+    });
+    Ok(result)
+}
+
+/// This function runs round `round` of the FFT style operation on `threads` threads.
+fn run_round<E, F>(
+    points: &mut [Affine<E>],
+    root: F,
+    round: usize,
+    threads: usize,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let k = 1usize << round;
+    let half = k / 2;
+    let chunks = points.len() / k;
+    let scratch_per_thread = scratch_len::<E>(points.len().div_ceil(threads)).max(2);
+    let spawn_error = || InterpolationError::TaskError("A conversion thread panicked".to_string());
+
+    if chunks >= threads {
+        let per_thread = chunks.div_ceil(threads) * k;
+        return thread::scope(|scope| {
+            let handles = points
+                .chunks_mut(per_thread)
+                .map(|group| {
+                    scope.spawn(move || {
+                        // The twiddles of the round are applied here, as the round's own would share them out over
+                        // the rayon pool.
+                        if round > 1 {
+                            group
+                                .chunks_exact_mut(k)
+                                .for_each(|chunk| distribute_powers(&mut chunk[half..], root));
+                        }
+                        let mut scratch = vec![E::BaseField::ZERO; scratch_per_thread];
+                        fft_round_with_scratch::<E, F, true>(group, root, round, &mut scratch)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().map_err(|_| spawn_error())?)
+        });
+    }
+
+    let per_part = half.div_ceil(threads.div_ceil(chunks));
+    thread::scope(|scope| {
+        let mut handles = Vec::with_capacity(threads);
+        for chunk in points.chunks_exact_mut(k) {
+            let (first, second) = chunk.split_at_mut(half);
+            for (part, (first, second)) in first
+                .chunks_mut(per_part)
+                .zip(second.chunks_mut(per_part))
+                .enumerate()
+            {
+                handles.push(scope.spawn(move || {
+                    let mut scratch = vec![E::BaseField::ZERO; scratch_per_thread];
+                    fft_round_segment(first, second, root, part * per_part, true, &mut scratch)
+                }));
+            }
+        }
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().map_err(|_| spawn_error())?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_srs_to_lagrange_threads() {
+        let rng = &mut ark_std::test_rng();
+        for size in [1, 2, 64] {
+            let srs = gen_srs_for_testing::<BnConfig, _>(rng, size - 1).unwrap();
+            let expected = srs_to_lagrange(&srs).unwrap();
+            // Thread counts below, at and above the number of chunks of every round, including odd ones.
+            for threads in [1, 3, 4, 64, 100] {
+                assert_eq!(srs_to_lagrange_threads(&srs, threads).unwrap(), expected);
+            }
+        }
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 3).unwrap();
+        assert!(srs_to_lagrange_threads(&srs, 0).is_err());
+        assert!(srs_to_lagrange_threads(&srs[..3], 2).is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_threads_leave_rayon_alone() {
+        use std::{
+            sync::{
+                atomic::{AtomicBool, AtomicUsize, Ordering},
+                mpsc, Arc,
+            },
+            time::Duration,
+        };
+
+        // With every thread of the global rayon pool held up, a conversion that touched the pool would hang.
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 63).unwrap();
+        let expected = srs_to_lagrange(&srs).unwrap();
+        let (blocked, release) = (
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let pool_threads = rayon::current_num_threads();
+        for _ in 0..pool_threads {
+            let (blocked, release) = (blocked.clone(), release.clone());
+            rayon::spawn(move || {
+                blocked.fetch_add(1, Ordering::SeqCst);
+                while !release.load(Ordering::SeqCst) {
+                    std::thread::yield_now();
+                }
+            });
+        }
+        while blocked.load(Ordering::SeqCst) < pool_threads {
+            std::thread::yield_now();
+        }
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || sender.send(srs_to_lagrange_threads(&srs, 4)));
+        let result = receiver.recv_timeout(Duration::from_secs(60));
+        release.store(true, Ordering::SeqCst);
+        assert_eq!(result.unwrap().unwrap(), expected);
+    }
+}
@@ -0,0 +1,282 @@
+//! This module records a tamper evident transcript of a conversion, so independent parties can confirm that their
+//! conversions of the same input agree by comparing a few hundred bytes instead of the points.
+//!
+//! The transcript holds the [`PointsDigest`] of the input, of the points after every round of the FFT style
+//! operation and of the output, each chained to the ones before it with SHA-256. The last link commits to every
+//! phase, so two parties only need to compare it, and if it differs the entries show the first phase where the
+//! conversions diverged. The rounds are always the radix-2 ones, since the intermediate points of other algorithms
+//! differ, so every party records the same phases whichever way they would convert by default.
+use crate::{
+    buffer::VecProvider,
+    domain_size_inv,
+    error::InterpolationError,
+    group_fft_radix2_with,
+    manifest::{digest_points, PointsDigest},
+};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{cfg_iter_mut, fmt, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+/// The domain separator of the first link, which also commits to the transcript version.
+const TRANSCRIPT_TAG: &[u8] = b"srs-interpolation transcript v1";
+
+/// A phase of a conversion whose points a transcript records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Phase {
+    /// The monomial points before the conversion.
+    Input,
+    /// The points after the given round, counting from one.
+    Round(u32),
+    /// The Lagrange points the conversion produced.
+    Output,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Input => write!(f, "input"),
+            Phase::Round(round) => write!(f, "round {}", round),
+            Phase::Output => write!(f, "output"),
+        }
+    }
+}
+
+/// The digest of the points of one phase and the link chaining it to the phases before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TranscriptEntry {
+    /// The phase the points are from.
+    pub phase: Phase,
+    /// The digest of the points.
+    pub digest: PointsDigest,
+    /// The SHA-256 link over the previous link, the phase and the digest.
+    pub link: [u8; 32],
+}
+
+/// The transcript of a conversion.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionTranscript {
+    /// The number of points converted.
+    pub size: u64,
+    /// The entries, from the input to the output.
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// This function returns the link before the first entry of the transcript of a conversion of `size` points.
+fn first_link(size: u64) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(TRANSCRIPT_TAG)
+        .chain_update(size.to_le_bytes())
+        .finalize()
+        .into()
+}
+
+/// This function returns the link of an entry for `phase` with `digest` following `previous`.
+fn link(previous: &[u8; 32], phase: Phase, digest: &PointsDigest) -> [u8; 32] {
+    let (tag, round) = match phase {
+        Phase::Input => (0u8, 0u32),
+        Phase::Round(round) => (1, round),
+        Phase::Output => (2, 0),
+    };
+    Sha256::new()
+        .chain_update(previous)
+        .chain_update([tag])
+        .chain_update(round.to_le_bytes())
+        .chain_update(digest.len.to_le_bytes())
+        .chain_update(digest.sha256)
+        .chain_update(digest.blake3)
+        .finalize()
+        .into()
+}
+
+impl ConversionTranscript {
+    fn new(size: u64) -> Self {
+        Self {
+            size,
+            entries: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, phase: Phase, digest: PointsDigest) {
+        let link = link(&self.head(), phase, &digest);
+        self.entries.push(TranscriptEntry {
+            phase,
+            digest,
+            link,
+        });
+    }
+
+    /// The last link, which commits to every entry, or the first link if there are none.
+    pub fn head(&self) -> [u8; 32] {
+        self.entries
+            .last()
+            .map_or_else(|| first_link(self.size), |entry| entry.link)
+    }
+
+    /// This function checks that the transcript is the input, every round and the output of a conversion of
+    /// [`ConversionTranscript::size`] points, each with the link that follows from the entries before it. A
+    /// transcript that passes has not been edited since its links were computed.
+    pub fn check(&self) -> Result<(), InterpolationError> {
+        if !self.size.is_power_of_two() {
+            return Err(InterpolationError::SizeError);
+        }
+        let rounds = self.size.ilog2();
+        let phases = [Phase::Input]
+            .into_iter()
+            .chain((1..=rounds).map(Phase::Round))
+            .chain([Phase::Output]);
+        let mut previous = first_link(self.size);
+        let mut entries = self.entries.iter();
+        for phase in phases {
+            let entry = entries
+                .next()
+                .ok_or(InterpolationError::InvalidParameters(format!(
+                    "The transcript has no {} entry",
+                    phase
+                )))?;
+            if entry.phase != phase {
+                return Err(InterpolationError::InvalidParameters(format!(
+                    "Expected the {} entry but found the {} entry",
+                    phase, entry.phase
+                )));
+            }
+            if entry.digest.len != self.size || entry.link != link(&previous, phase, &entry.digest)
+            {
+                return Err(InterpolationError::InvalidParameters(format!(
+                    "The {} entry does not match its link",
+                    phase
+                )));
+            }
+            previous = entry.link;
+        }
+        if entries.next().is_some() {
+            return Err(InterpolationError::InvalidParameters(
+                "The transcript has entries after the output".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// This function returns the first phase whose points differ between this transcript and `other`, or `None`
+    /// if the conversions agree.
+    pub fn first_difference(&self, other: &Self) -> Option<Phase> {
+        self.entries
+            .iter()
+            .zip(other.entries.iter())
+            .find(|(a, b)| a != b)
+            .map(|(a, _)| a.phase)
+            .or_else(|| {
+                // One transcript stops early, so the first phase it lacks is the difference.
+                let common = self.entries.len().min(other.entries.len());
+                let longer = match self.entries.len() > other.entries.len() {
+                    true => &self.entries,
+                    false => &other.entries,
+                };
+                longer.get(common).map(|entry| entry.phase)
+            })
+    }
+}
+
+impl fmt::Display for ConversionTranscript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.entries.iter() {
+            write!(f, "{}: link ", entry.phase)?;
+            entry.link.iter().try_for_each(|b| write!(f, "{:02x}", b))?;
+            writeln!(f, ", {}", entry.digest)?;
+        }
+        Ok(())
+    }
+}
+
+/// This function is the same as [`crate::srs_to_lagrange`] but also returns the transcript of the conversion. It
+/// always takes the radix-2 rounds, and hashing the points after every round makes it several times slower than a
+/// plain conversion.
+pub fn srs_to_lagrange_with_transcript<E, F>(
+    points: &[Affine<E>],
+) -> Result<(Vec<Affine<E>>, ConversionTranscript), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if !points.len().is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    let domain =
+        Radix2EvaluationDomain::<F>::new(points.len()).ok_or(InterpolationError::SizeError)?;
+    let mut transcript = ConversionTranscript::new(points.len() as u64);
+    transcript.push(Phase::Input, digest_points(points));
+    let mut result = points.to_vec();
+    group_fft_radix2_with(
+        &mut result,
+        domain.group_gen_inv(),
+        &VecProvider,
+        |points, rounds_done, _| {
+            transcript.push(Phase::Round(rounds_done as u32), digest_points(points));
+            Ok(())
+        },
+    )?;
+    let domain_size_inv = domain_size_inv::<F>(points.len())?;
+    cfg_iter_mut!(result).for_each(|point| *point = (*point * domain_size_inv).into_affine());
+    transcript.push(Phase::Output, digest_points(&result));
+    Ok((result, transcript))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{srs_to_lagrange, tests::gen_srs_for_testing};
+    use ark_bn254::g1::Config as BnConfig;
+    use ark_ec::AffineRepr;
+
+    #[test]
+    fn test_transcript() {
+        let rng = &mut ark_std::test_rng();
+        let points = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let (lagrange, transcript) = srs_to_lagrange_with_transcript(&points).unwrap();
+        assert_eq!(lagrange, srs_to_lagrange(&points).unwrap());
+        assert_eq!(transcript.entries.len(), 6);
+        assert_eq!(transcript.entries[5].digest, digest_points(&lagrange));
+        transcript.check().unwrap();
+
+        // A second conversion of the same input agrees on every link.
+        let (_, again) = srs_to_lagrange_with_transcript(&points).unwrap();
+        assert_eq!(again.head(), transcript.head());
+        assert_eq!(transcript.first_difference(&again), None);
+
+        // Editing an entry breaks its link.
+        let mut edited = transcript.clone();
+        edited.entries[2].digest.sha256[0] ^= 1;
+        assert!(edited.check().is_err());
+        assert_eq!(transcript.first_difference(&edited), Some(Phase::Round(2)));
+        let mut truncated = transcript.clone();
+        truncated.entries.pop();
+        assert!(truncated.check().is_err());
+        assert_eq!(transcript.first_difference(&truncated), Some(Phase::Output));
+
+        // A different input differs from the start.
+        let mut changed = points.clone();
+        changed[3] = Affine::generator();
+        let (_, other) = srs_to_lagrange_with_transcript(&changed).unwrap();
+        assert_ne!(other.head(), transcript.head());
+        assert_eq!(transcript.first_difference(&other), Some(Phase::Input));
+
+        // A single point has no rounds.
+        let (_, single) = srs_to_lagrange_with_transcript(&points[..1]).unwrap();
+        assert_eq!(single.entries.len(), 2);
+        single.check().unwrap();
+        assert!(transcript.to_string().starts_with("input: link "));
+    }
+}
@@ -0,0 +1,127 @@
+//! This module contains [`UniversalSrs`], which owns a monomial SRS and converts it to the other bases it is used
+//! in the first time each is asked for, so an application can pass one value around instead of tracking which
+//! basis it has already computed.
+use crate::{
+    error::InterpolationError,
+    lookup::lagrange_zero_quotients,
+    srs::{LagrangeSrs, MonomialSrs},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_std::vec::Vec;
+use derivative::Derivative;
+use std::sync::OnceLock;
+
+/// A monomial SRS together with its Lagrange basis over the arkworks domain of the same size and the commitments
+/// to `(L_i(X) - L_i(0)) / X`, each computed on first use and then kept.
+///
+/// It is [`Sync`], and when several threads ask for a basis that has not been computed yet only one of them
+/// computes it while the others wait. A conversion that fails is not retried, every later call returns the same
+/// error.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "E: SWCurveConfig"), Debug(bound = "E: SWCurveConfig"))]
+pub struct UniversalSrs<E: SWCurveConfig> {
+    monomial: MonomialSrs<E>,
+    lagrange: OnceLock<Result<LagrangeSrs<E>, InterpolationError>>,
+    zero_quotients: OnceLock<Result<Vec<Affine<E>>, InterpolationError>>,
+}
+
+impl<E: SWCurveConfig> UniversalSrs<E> {
+    /// Wraps a monomial SRS without converting it.
+    pub fn new(monomial: MonomialSrs<E>) -> Self {
+        Self {
+            monomial,
+            lagrange: OnceLock::new(),
+            zero_quotients: OnceLock::new(),
+        }
+    }
+
+    /// The monomial SRS.
+    pub fn monomial(&self) -> &MonomialSrs<E> {
+        &self.monomial
+    }
+
+    /// This function returns the Lagrange basis in natural order, converting the monomial SRS if this is the first
+    /// call. It errors if the number of points is not a power of two.
+    pub fn lagrange(&self) -> Result<&LagrangeSrs<E>, InterpolationError> {
+        self.lagrange
+            .get_or_init(|| self.monomial.to_lagrange())
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// This function returns the commitments to `(L_i(X) - L_i(0)) / X` that cq provers need, computing the
+    /// Lagrange basis first if that has not been computed yet.
+    pub fn lagrange_zero_quotients(&self) -> Result<&[Affine<E>], InterpolationError> {
+        self.zero_quotients
+            .get_or_init(|| lagrange_zero_quotients(self.monomial.points(), self.lagrange()?))
+            .as_deref()
+            .map_err(Clone::clone)
+    }
+
+    /// Whether the Lagrange basis has been computed.
+    pub fn has_lagrange(&self) -> bool {
+        matches!(self.lagrange.get(), Some(Ok(_)))
+    }
+
+    /// This function returns the monomial SRS and the Lagrange basis if it was computed successfully.
+    pub fn into_parts(self) -> (MonomialSrs<E>, Option<LagrangeSrs<E>>) {
+        (
+            self.monomial,
+            self.lagrange.into_inner().and_then(Result::ok),
+        )
+    }
+}
+
+impl<E: SWCurveConfig> From<MonomialSrs<E>> for UniversalSrs<E> {
+    fn from(monomial: MonomialSrs<E>) -> Self {
+        Self::new(monomial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lookup::preprocess_table, tests::gen_srs_for_testing};
+    use ark_bn254::{g1::Config as BnConfig, Fr};
+    use ark_std::One;
+
+    #[test]
+    fn test_universal_srs() {
+        let rng = &mut ark_std::test_rng();
+        let monomial = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let srs = UniversalSrs::from(monomial.clone());
+        assert!(!srs.has_lagrange());
+
+        // Every thread gets the same cached basis.
+        let lagrange = std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|| srs.lagrange().unwrap() as *const _ as usize))
+                .collect::<Vec<_>>();
+            let addresses = handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>();
+            assert!(addresses.iter().all(|address| *address == addresses[0]));
+            srs.lagrange().unwrap()
+        });
+        assert!(srs.has_lagrange());
+        assert_eq!(*lagrange, monomial.to_lagrange().unwrap());
+
+        let table = vec![Fr::one(); 16];
+        assert_eq!(
+            srs.lagrange_zero_quotients().unwrap(),
+            preprocess_table(&table, &monomial)
+                .unwrap()
+                .lagrange_zero_quotients
+        );
+
+        let (parts, lagrange) = srs.into_parts();
+        assert_eq!(parts, monomial);
+        assert!(lagrange.is_some());
+
+        let srs = UniversalSrs::new(MonomialSrs::new(monomial.points()[..3].to_vec()));
+        assert!(srs.lagrange().is_err());
+        assert!(srs.lagrange_zero_quotients().is_err());
+        assert!(srs.into_parts().1.is_none());
+    }
+}
@@ -1,12 +1,13 @@
 //! This module contains utility functions we repeatedly use in the library.
-use crate::error::InterpolationError;
+use crate::{buffer::try_filled, error::InterpolationError};
 use ark_ec::{
     short_weierstrass::{Affine, SWCurveConfig},
     CurveGroup,
 };
 use ark_ff::{Field, PrimeField};
-use ark_std::{cfg_chunks_mut, vec::Vec, One, Zero};
+use ark_std::{cfg_chunks_mut, mem::size_of, vec::Vec, One, Zero};
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 /// This table is used as a lookup for swapping 8 bits into their reverse order.
@@ -27,8 +28,16 @@ const BYTE_SWAP_TABLE: [u8; 256] = [
 ];
 
 /// This function reverses the order of the `log_n` bits of `index` and returns the result.
-/// The index is also assumed to be at most a [`u64`], although it will never be close to this large in practice.
+/// It errors if `log_n` is not less than the number of bits in a [`usize`], which is 32 on wasm32.
 pub(crate) fn bit_reverse(index: usize, log_n: usize) -> Result<usize, InterpolationError> {
+    if log_n >= usize::BITS as usize {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot reverse {} bits of a {} bit index",
+            log_n,
+            usize::BITS
+        )));
+    }
+
     // Find out how many bytes are in our representation (rounded down)
     let num_bytes = log_n / 8;
 
@@ -39,25 +48,121 @@ pub(crate) fn bit_reverse(index: usize, log_n: usize) -> Result<usize, Interpola
     let bytes = index.to_le_bytes();
 
     // Then we take the first `num_bytes` bytes and reverse them
-    let mut reversed_bytes = Vec::<u8>::with_capacity(8);
+    let mut reversed_bytes = Vec::<u8>::with_capacity(bytes.len());
     // We push the top (incomplete) byte first
     reversed_bytes.push(BYTE_SWAP_TABLE[bytes[num_bytes] as usize]);
 
     for i in 0..num_bytes {
         reversed_bytes.push(BYTE_SWAP_TABLE[bytes[num_bytes - 1 - i] as usize]);
     }
-    reversed_bytes.resize(8, 0);
+    reversed_bytes.resize(bytes.len(), 0);
 
     let result = usize::from_le_bytes(reversed_bytes.as_slice().try_into().map_err(|_| {
         InterpolationError::InvalidParameters(format!(
-            "Could not convert vector of length {} to array of length 8",
-            reversed_bytes.len()
+            "Could not convert vector of length {} to array of length {}",
+            reversed_bytes.len(),
+            bytes.len()
         ))
     })?);
 
     Ok(result >> (8 - leftover))
 }
 
+/// This function returns `2^log_size` as a [`usize`], erroring rather than overflowing if it does not fit, as
+/// happens for sizes of `2^32` and above on 32-bit targets such as wasm32.
+pub(crate) fn size_from_log(log_size: u32) -> Result<usize, InterpolationError> {
+    1usize.checked_shl(log_size).ok_or_else(|| {
+        InterpolationError::InvalidParameters(format!(
+            "A domain of size 2^{} does not fit in a {} bit usize",
+            log_size,
+            usize::BITS
+        ))
+    })
+}
+
+/// This function converts a size read from a file, which is always a [`u64`], to a [`usize`], erroring if it does
+/// not fit on this target.
+pub(crate) fn size_from_u64(size: u64) -> Result<usize, InterpolationError> {
+    usize::try_from(size).map_err(|_| {
+        InterpolationError::InvalidParameters(format!(
+            "A size of {} does not fit in a {} bit usize",
+            size,
+            usize::BITS
+        ))
+    })
+}
+
+/// This function reorders `values` in place so that the value at `i` moves to the index given by reversing the
+/// `log_n` bits of `i`. The permutation is its own inverse.
+pub(crate) fn bit_reverse_permutation<T>(
+    values: &mut [T],
+    log_n: usize,
+) -> Result<(), InterpolationError> {
+    for i in 0..values.len() {
+        let j = bit_reverse(i, log_n)?;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    Ok(())
+}
+
+/// This function returns `1 << round_number`, the number of points in each chunk of a round, erroring for round zero
+/// or a round whose chunks would not fit in a [`usize`].
+fn chunk_len(round_number: usize) -> Result<usize, InterpolationError> {
+    u32::try_from(round_number)
+        .ok()
+        .filter(|round_number| *round_number > 0)
+        .and_then(|round_number| 1usize.checked_shl(round_number))
+        .ok_or(InterpolationError::InvalidParameters(format!(
+            "There is no round {}",
+            round_number
+        )))
+}
+
+/// This function multiplies the second half of each block of `1 << round_number` points by the powers of `g`, which
+/// is the part of a round of the FFT style operation before the butterflies.
+pub(crate) fn twiddle_round<E, F>(
+    points: &mut [Affine<E>],
+    g: F,
+    round_number: usize,
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    enter_span!(TRACE, "twiddle");
+    let k = chunk_len(round_number)?;
+    let half = k >> 1;
+    cfg_chunks_mut!(points, k).try_for_each(|points: &mut [Affine<E>]| {
+        distribute_powers(&mut points[half..], g);
+        Result::<(), InterpolationError>::Ok(())
+    })
+}
+
+/// This function returns the `j`th value, without a bounds check if the `unsafe-perf` feature is on. Callers must
+/// keep `j` below the length, which debug builds still assert.
+#[inline(always)]
+fn at<T>(values: &[T], j: usize) -> &T {
+    debug_assert!(j < values.len());
+    // Safety: every caller indexes a chunk of exactly `half` values with `j < half`.
+    #[cfg(feature = "unsafe-perf")]
+    return unsafe { values.get_unchecked(j) };
+    #[cfg(not(feature = "unsafe-perf"))]
+    return &values[j];
+}
+
+/// This function is the mutable version of [`at`].
+#[inline(always)]
+fn at_mut<T>(values: &mut [T], j: usize) -> &mut T {
+    debug_assert!(j < values.len());
+    // Safety: every caller indexes a chunk of exactly `half` values with `j < half`.
+    #[cfg(feature = "unsafe-perf")]
+    return unsafe { values.get_unchecked_mut(j) };
+    #[cfg(not(feature = "unsafe-perf"))]
+    return &mut values[j];
+}
+
 /// This function takes as input a mutable reference to a slice of affine points and a generator `g` as well as a round number.
 /// It then mutates the slice in place to perform an FFT butterfly operation.
 pub(crate) fn fft_round<E, F, const IS_FIRST_ROUND: bool>(
@@ -69,79 +174,329 @@ where
     E: SWCurveConfig<ScalarField = F>,
     F: PrimeField,
 {
-    let k = 1usize << round_number;
+    let mut scratch = try_filled(scratch_len::<E>(points.len()).max(2), E::BaseField::zero())?;
+    fft_round_with_scratch::<E, F, IS_FIRST_ROUND>(points, g, round_number, &mut scratch)
+}
+
+/// This function is the same as [`fft_round`] but works in `scratch` instead of allocating. Any scratch of at least
+/// two elements will do, and rounds take one batch inversion per half of its length in pairs of points.
+pub(crate) fn fft_round_with_scratch<E, F, const IS_FIRST_ROUND: bool>(
+    points: &mut [Affine<E>],
+    g: F,
+    round_number: usize,
+    scratch: &mut [E::BaseField],
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let k = chunk_len(round_number)?;
     let half = k >> 1;
+    // The butterflies below index within exact chunks, which the unchecked accesses of `unsafe-perf` rely on.
+    if !points.len().is_multiple_of(k) || scratch.len() < 2 {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot run round {} on {} points with {} scratch elements",
+            round_number,
+            points.len(),
+            scratch.len()
+        )));
+    }
+    if points.is_empty() {
+        return Ok(());
+    }
     // If its the first round we don't have to rescale any points
     if !IS_FIRST_ROUND {
-        cfg_chunks_mut!(points, k).try_for_each(|points: &mut [Affine<E>]| {
-            distribute_powers(&mut points[half..], g);
-            Result::<(), InterpolationError>::Ok(())
-        })?;
+        twiddle_round(points, g, round_number)?;
     }
-    let len = points.len() / k;
 
-    let mut batch_inversion_accumulator = E::BaseField::one();
-    let mut scratch_x: Vec<E::BaseField> = vec![E::BaseField::zero(); len * half];
-    let mut scratch_y: Vec<E::BaseField> = vec![E::BaseField::zero(); len * half];
-    points
-        .chunks_mut(k)
-        .enumerate()
-        .for_each(|(i, points_chunk): (usize, &mut [Affine<E>])| {
-            for j in 0..half {
-                // We store the sum of the two x-coordinates in the scratch space
-                scratch_x[half * i + j] += points_chunk[j].x + points_chunk[j + half].x;
-                // Store y2 - y1 in the y scratch space
-                scratch_y[half * i + j] += points_chunk[j + half].y - points_chunk[j].y;
-                // Store x2 - x1 in the second points x-coordinate
-                points_chunk[j + half].x -= points_chunk[j].x;
-                // Store y2 + y1 in the second points y-coordinate
-                points_chunk[j + half].y += points_chunk[j].y;
-                // Multiply y2 + y1 by the product of the delta x's so far.
-                points_chunk[j + half].y *= -batch_inversion_accumulator;
-                // Multiply y2 - y1 by the product of the delta x's so far.
-                scratch_y[half * i + j] *= batch_inversion_accumulator;
-                // Update the accumulator with the denominator from this round.
-                batch_inversion_accumulator *= points_chunk[j + half].x;
+    // The pairs are taken a tile at a time, with one batch inversion per tile, so the scratch space never needs to
+    // hold more than a tile. A tile is either a run of pairs within one chunk or a run of whole chunks.
+    let tile = ((scratch.len() / 2).ilog2() as usize).min(points.len().ilog2() as usize - 1);
+    let tile = 1usize << tile;
+    let segment = tile.min(half);
+    let (scratch_x, scratch_y) = scratch[..2 * tile].split_at_mut(tile);
+    // The tiles are walked in place, as materializing the segments of a round would take memory in proportion to
+    // its points.
+    if segment == half {
+        for run in points.chunks_mut(k * (tile / half)) {
+            let mut blocks = Blocks::new(run, k, half, segment);
+            butterfly_tile(&mut blocks, scratch_x, scratch_y)?;
+        }
+    } else {
+        for points_chunk in points.chunks_exact_mut(k) {
+            for start in (0..half).step_by(segment) {
+                let run = &mut points_chunk[start..half + start + segment];
+                butterfly_tile(
+                    &mut Blocks::new(run, k, half, segment),
+                    scratch_x,
+                    scratch_y,
+                )?;
             }
-        });
+        }
+    }
+    Ok(())
+}
+
+/// This function runs the part of round `round_number` of the FFT style operation, including its twiddles, on the
+/// `pairs` pairs of points starting at pair `start`, where pairs are numbered chunk by chunk. The number of pairs
+/// must be a power of two that `start` is a multiple of, and `scratch` must hold at least twice as many elements, so
+/// that the pairs are either a run within one chunk or a run of whole chunks.
+pub(crate) fn fft_round_pairs<E, F>(
+    points: &mut [Affine<E>],
+    g: F,
+    round_number: usize,
+    start: usize,
+    pairs: usize,
+    scratch: &mut [E::BaseField],
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let k = chunk_len(round_number)?;
+    let half = k >> 1;
+    if !pairs.is_power_of_two()
+        || !start.is_multiple_of(pairs)
+        || start
+            .checked_add(pairs)
+            .and_then(|end| end.checked_mul(2))
+            .is_none_or(|end| end > points.len())
+        || !points.len().is_multiple_of(k)
+        || scratch.len() < 2 * pairs
+    {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot run {} pairs from {} of round {} on {} points with {} scratch elements",
+            pairs,
+            start,
+            round_number,
+            points.len(),
+            scratch.len()
+        )));
+    }
+    let segment = pairs.min(half);
+    let offset = start % half;
+    // The pairs are either whole chunks, from a chunk boundary, or a run within one chunk.
+    let run_start = start / half * k + offset;
+    let run_len = if pairs >= half {
+        pairs / half * k
+    } else {
+        half + segment
+    };
+    let mut blocks = Blocks::new(
+        &mut points[run_start..run_start + run_len],
+        k,
+        half,
+        segment,
+    );
+    if round_number > 1 {
+        let first_power = g.pow([offset as u64]);
+        for (_, second) in blocks.segments() {
+            let mut power = first_power;
+            for point in second.iter_mut() {
+                *point = (*point * power).into_affine();
+                power *= g;
+            }
+        }
+    }
+    let (scratch_x, scratch_y) = scratch[..2 * pairs].split_at_mut(pairs);
+    butterfly_tile(&mut blocks, scratch_x, scratch_y)
+}
+
+/// This function runs a round of the FFT style operation on the pairs `first[j]` and `second[j]` of one chunk,
+/// where `first` starts `offset` points into the first half of the chunk. If `twiddle` is set the second points
+/// are first multiplied by `g^(offset + j)`. The butterflies take one inversion per tile of half of `scratch`,
+/// which must hold at least two elements.
+pub(crate) fn fft_round_segment<E, F>(
+    first: &mut [Affine<E>],
+    second: &mut [Affine<E>],
+    g: F,
+    offset: usize,
+    twiddle: bool,
+    scratch: &mut [E::BaseField],
+) -> Result<(), InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if first.len() != second.len() || scratch.len() < 2 {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "Cannot pair {} points with {} using {} scratch elements",
+            first.len(),
+            second.len(),
+            scratch.len()
+        )));
+    }
+    if twiddle {
+        let mut power = g.pow([offset as u64]);
+        for point in second.iter_mut() {
+            *point = (*point * power).into_affine();
+            power *= g;
+        }
+    }
+    let tile = scratch.len() / 2;
+    let (scratch_x, scratch_y) = scratch[..2 * tile].split_at_mut(tile);
+    for (first, second) in first.chunks_mut(tile).zip(second.chunks_mut(tile)) {
+        let len = first.len();
+        butterfly_tile(
+            &mut (first, second),
+            &mut scratch_x[..len],
+            &mut scratch_y[..len],
+        )?;
+    }
+    Ok(())
+}
+
+/// The first and second points of a run of pairs the butterflies combine.
+type Segment<'a, E> = (&'a mut [Affine<E>], &'a mut [Affine<E>]);
+
+/// The segments of a tile of butterflies, which [`butterfly_tile`] walks forwards and then backwards.
+trait TileSegments<E: SWCurveConfig> {
+    /// The number of pairs in each segment.
+    fn segment_len(&self) -> usize;
+
+    /// This function returns the segments in order.
+    fn segments(&mut self) -> impl DoubleEndedIterator<Item = Segment<'_, E>> + ExactSizeIterator;
+}
+
+impl<E: SWCurveConfig> TileSegments<E> for Segment<'_, E> {
+    fn segment_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn segments(&mut self) -> impl DoubleEndedIterator<Item = Segment<'_, E>> + ExactSizeIterator {
+        ark_std::iter::once((&mut *self.0, &mut *self.1))
+    }
+}
+
+/// The segments of a run of points split into blocks of `stride`, each pairing the `segment` points at its start
+/// with the `segment` points `half` into it. Every block but the last holds `stride` points.
+struct Blocks<'a, E: SWCurveConfig> {
+    run: &'a mut [Affine<E>],
+    stride: usize,
+    half: usize,
+    segment: usize,
+}
+
+impl<'a, E: SWCurveConfig> Blocks<'a, E> {
+    fn new(run: &'a mut [Affine<E>], stride: usize, half: usize, segment: usize) -> Self {
+        debug_assert!(segment <= half && half + segment <= stride);
+        Self {
+            run,
+            stride,
+            half,
+            segment,
+        }
+    }
+}
+
+impl<E: SWCurveConfig> TileSegments<E> for Blocks<'_, E> {
+    fn segment_len(&self) -> usize {
+        self.segment
+    }
+
+    fn segments(&mut self) -> impl DoubleEndedIterator<Item = Segment<'_, E>> + ExactSizeIterator {
+        let (half, segment) = (self.half, self.segment);
+        self.run.chunks_mut(self.stride).map(move |block| {
+            let (first, second) = block.split_at_mut(half);
+            (&mut first[..segment], &mut second[..segment])
+        })
+    }
+}
+
+/// This function runs the butterflies on each pair of `first[j]` and `second[j]` in the segments of `tile`, which
+/// between them hold at most as many pairs as each scratch slice, sharing one inversion.
+fn butterfly_tile<E: SWCurveConfig, T: TileSegments<E>>(
+    tile: &mut T,
+    scratch_x: &mut [E::BaseField],
+    scratch_y: &mut [E::BaseField],
+) -> Result<(), InterpolationError> {
+    let segment = tile.segment_len();
+    if segment == 0 {
+        return Ok(());
+    }
+    let mut batch_inversion_accumulator = E::BaseField::one();
+    {
+        enter_span!(TRACE, "butterfly");
+        tile.segments()
+            .zip(scratch_x.chunks_exact_mut(segment))
+            .zip(scratch_y.chunks_exact_mut(segment))
+            .for_each(|(((first, second), scratch_x), scratch_y)| {
+                for j in 0..segment {
+                    let (p, q) = (at_mut(first, j), at_mut(second, j));
+                    let (sum_x, diff_y) = (at_mut(scratch_x, j), at_mut(scratch_y, j));
+                    // We store the sum of the two x-coordinates in the scratch space
+                    *sum_x = p.x + q.x;
+                    // Store y2 - y1 in the y scratch space
+                    *diff_y = q.y - p.y;
+                    // Store x2 - x1 in the second points x-coordinate
+                    q.x -= p.x;
+                    // Store y2 + y1 in the second points y-coordinate
+                    q.y += p.y;
+                    // Multiply y2 + y1 by the product of the delta x's so far.
+                    q.y *= -batch_inversion_accumulator;
+                    // Multiply y2 - y1 by the product of the delta x's so far.
+                    *diff_y *= batch_inversion_accumulator;
+                    // Update the accumulator with the denominator from this round.
+                    batch_inversion_accumulator *= q.x;
+                }
+            });
+    }
 
-    batch_inversion_accumulator =
+    batch_inversion_accumulator = {
+        enter_span!(TRACE, "invert");
         batch_inversion_accumulator
             .inverse()
             .ok_or(InterpolationError::FieldError(
                 "Could not invert batch inversion accumulator".to_string(),
-            ))?;
+            ))?
+    };
 
-    points.chunks_mut(k).enumerate().rev().for_each(
-        |(i, points_chunk): (usize, &mut [Affine<E>])| {
-            for j in (0..half).rev() {
+    enter_span!(TRACE, "butterfly");
+    tile.segments()
+        .zip(scratch_x.chunks_exact(segment))
+        .zip(scratch_y.chunks_exact_mut(segment))
+        .rev()
+        .for_each(|(((first, second), scratch_x), scratch_y)| {
+            for j in (0..segment).rev() {
+                let (p, q) = (at_mut(first, j), at_mut(second, j));
+                let (sum_x, diff_y) = (*at(scratch_x, j), at_mut(scratch_y, j));
                 // Store (y2 + y1) / (x2 - x1) in the y-coordinate of the second point
-                points_chunk[j + half].y *= batch_inversion_accumulator;
+                q.y *= batch_inversion_accumulator;
                 // Store (y2 - y1) / (x2 - x1) in the y scratch space
-                scratch_y[half * i + j] *= batch_inversion_accumulator;
+                *diff_y *= batch_inversion_accumulator;
                 // Update the inversion accumulator
-                batch_inversion_accumulator *= points_chunk[j + half].x;
+                batch_inversion_accumulator *= q.x;
 
                 // Calculate new x and y coordinates for P - Q.
-                points_chunk[j + half].x =
-                    points_chunk[j + half].y * points_chunk[j + half].y - scratch_x[half * i + j];
-                points_chunk[j + half].y = points_chunk[j + half].y
-                    * (points_chunk[j].x - points_chunk[j + half].x)
-                    - points_chunk[j].y;
+                q.x = q.y * q.y - sum_x;
+                q.y = q.y * (p.x - q.x) - p.y;
 
                 // Calculate new x and y coordinates for P + Q.
-                let x = points_chunk[j].x;
-                points_chunk[j].x =
-                    scratch_y[half * i + j] * scratch_y[half * i + j] - scratch_x[half * i + j];
-                points_chunk[j].y =
-                    scratch_y[half * i + j] * (x - points_chunk[j].x) - points_chunk[j].y;
+                let x = p.x;
+                p.x = *diff_y * *diff_y - sum_x;
+                p.y = *diff_y * (x - p.x) - p.y;
             }
-        },
-    );
+        });
 
     Ok(())
 }
 
+/// The most bytes of scratch space a round of the FFT style operation works in, whatever the size of the base
+/// field. Each tile of this much scratch costs one extra inversion, which is negligible at this size, while the
+/// scratch for a whole round of a large domain over a large base field such as BW6-761's would run to gigabytes.
+const MAX_SCRATCH_BYTES: usize = 1 << 24;
+
+/// This function returns the number of base field elements of scratch space a round over `size` points uses.
+pub(crate) fn scratch_len<E: SWCurveConfig>(size: usize) -> usize {
+    scratch_len_for(size, size_of::<E::BaseField>())
+}
+
+/// This function is [`scratch_len`] for a base field whose elements take `field_bytes` bytes.
+pub(crate) fn scratch_len_for(size: usize, field_bytes: usize) -> usize {
+    let max_len = (MAX_SCRATCH_BYTES / field_bytes).max(2);
+    size.min(1 << max_len.ilog2())
+}
+
 /// This function multiplies a list of affine points by the powers of `g` and stores the result in the same list.
 pub(crate) fn distribute_powers<E, F>(coeffs: &mut [Affine<E>], g: F)
 where
@@ -155,6 +510,14 @@ where
     })
 }
 
+/// This function checks that `size` is a power of two and that `gen` is a primitive `size`th root of unity.
+pub(crate) fn is_primitive_root<F: Field>(gen: F, size: usize) -> bool {
+    if size == 0 || !size.is_power_of_two() {
+        return false;
+    }
+    gen.pow([size as u64]).is_one() && (size == 1 || !gen.pow([(size >> 1) as u64]).is_one())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,10 +531,95 @@ mod tests {
             assert_eq!(i, bit_reverse(m, 10).unwrap());
         }
 
+        // Zero and one bit indices are their own reverse.
+        assert_eq!(bit_reverse(0, 0).unwrap(), 0);
+        assert_eq!(bit_reverse(0, 1).unwrap(), 0);
+        assert_eq!(bit_reverse(1, 1).unwrap(), 1);
+
         // Test against a known result
         let expected_result: [usize; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
-        for i in 0..8 {
-            assert_eq!(expected_result[i], bit_reverse(i, 3).unwrap());
+        for (i, expected) in expected_result.iter().enumerate() {
+            assert_eq!(*expected, bit_reverse(i, 3).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fft_round_sizes() {
+        use ark_bn254::{g1::Config as BnConfig, Fr};
+        use ark_ec::AffineRepr;
+        let mut points = vec![Affine::<BnConfig>::generator(); 6];
+        assert!(fft_round::<BnConfig, Fr, true>(&mut points, Fr::one(), 0).is_err());
+        assert!(fft_round::<BnConfig, Fr, true>(&mut points, Fr::one(), 2).is_err());
+        assert!(fft_round::<BnConfig, Fr, true>(&mut points[..4], Fr::one(), 3).is_err());
+    }
+
+    #[test]
+    fn test_fft_round_tiles() {
+        use crate::tests::gen_srs_for_testing;
+        use ark_bn254::{g1::Config as BnConfig, Fq, Fr};
+        use ark_ff::UniformRand;
+
+        // Tiles within a chunk in the late rounds and spanning chunks in the early ones give the same points.
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap();
+        let g = Fr::rand(rng);
+        for round in 1..=4 {
+            let mut expected = srs.clone();
+            fft_round_with_scratch::<BnConfig, Fr, false>(
+                &mut expected,
+                g,
+                round,
+                &mut [Fq::zero(); 16],
+            )
+            .unwrap();
+            for scratch_len in [2, 5, 8] {
+                let mut points = srs.clone();
+                let mut scratch = vec![Fq::zero(); scratch_len];
+                fft_round_with_scratch::<BnConfig, Fr, false>(&mut points, g, round, &mut scratch)
+                    .unwrap();
+                assert_eq!(points, expected);
+            }
         }
+        let mut points = srs;
+        assert!(
+            fft_round_with_scratch::<BnConfig, Fr, true>(&mut points, g, 1, &mut [Fq::zero()])
+                .is_err()
+        );
+        assert_eq!(scratch_len::<BnConfig>(16), 16);
+        assert_eq!(scratch_len::<BnConfig>(1 << 30), 1 << 19);
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(size_from_log(0).unwrap(), 1);
+        assert_eq!(size_from_log(20).unwrap(), 1 << 20);
+        assert_eq!(
+            size_from_log(usize::BITS - 1).unwrap(),
+            1 << (usize::BITS - 1)
+        );
+        assert!(size_from_log(usize::BITS).is_err());
+        assert!(size_from_log(u32::MAX).is_err());
+        assert_eq!(size_from_u64(12).unwrap(), 12);
+        assert!(bit_reverse(0, usize::BITS as usize).is_err());
+        assert_eq!(
+            bit_reverse(1, usize::BITS as usize - 1).unwrap(),
+            1 << (usize::BITS - 2)
+        );
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    fn test_sizes_32_bit() {
+        assert!(size_from_log(32).is_err());
+        assert!(size_from_u64(1 << 32).is_err());
+        assert_eq!(bit_reverse(1, 31).unwrap(), 1 << 30);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_sizes_64_bit() {
+        assert_eq!(size_from_log(32).unwrap(), 1 << 32);
+        assert_eq!(size_from_u64(1 << 40).unwrap(), 1 << 40);
+        assert!(size_from_u64(u64::MAX).is_ok());
     }
 }
@@ -0,0 +1,349 @@
+//! This module contains probabilistic checks that an SRS is well formed and that a Lagrange SRS is the conversion
+//! of a monomial SRS, so a converted setup can be checked without trusting whoever converted it.
+//!
+//! Both checks take a random linear combination of the points, so a bad SRS passes with probability at most
+//! `n / r`, where `r` is the order of the scalar field.
+//!
+//! [`check_lagrange_files_match`] checks a pair of files without loading either. Its combination is the polynomial
+//! `f(X) = sum_j c^j X^j` for a random `c`, whose evaluation at any root of unity `x` of order `n` is
+//! `(1 - c^n) / (1 - c x)`, so the coefficient of every point of either file is known on its own as it is read. A
+//! bad pair then passes with probability at most `2n / r`.
+use crate::{
+    curves::SrsCurve,
+    error::InterpolationError,
+    formats::binary::{Basis, SrsReader},
+    reorder_points,
+    srs::{commit_evals, LagrangeSrs, MonomialSrs, PointOrdering},
+    utils::{bit_reverse, is_primitive_root, size_from_u64},
+};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    VariableBaseMSM,
+};
+use ark_ff::{batch_inversion, Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{io::Read, rand::RngCore, vec::Vec, One, UniformRand, Zero};
+
+/// This function checks that `lagrange` is the Lagrange basis of `monomial` over its domain, in its ordering. It
+/// commits to a random polynomial with both SRSs and compares the commitments.
+pub fn check_lagrange_matches_monomial<E, F, R>(
+    monomial: &MonomialSrs<E>,
+    lagrange: &LagrangeSrs<E>,
+    rng: &mut R,
+) -> Result<bool, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+    R: RngCore,
+{
+    let size = monomial.len();
+    if lagrange.len() != size {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The monomial SRS has {} points but the Lagrange SRS has {}",
+            size,
+            lagrange.len()
+        )));
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(size).ok_or(InterpolationError::SizeError)?;
+
+    // The generator of the Lagrange SRS is w^k for the arkworks generator w, so its jth root is w^(kj mod n).
+    let k = domain
+        .elements()
+        .position(|root| root == lagrange.generator())
+        .ok_or(InterpolationError::InvalidParameters(
+            "The generator of the Lagrange SRS is not in the arkworks domain".to_string(),
+        ))?;
+    let coeffs = (0..size).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let arkworks_evals = domain.fft(&coeffs);
+    let mut evals = (0..size)
+        .map(|j| arkworks_evals[k.wrapping_mul(j) & (size - 1)])
+        .collect::<Vec<_>>();
+    reorder_points(&mut evals, PointOrdering::Natural, lagrange.ordering())?;
+
+    let expected = Projective::<E>::msm_unchecked(monomial.points(), &coeffs);
+    Ok(commit_evals(&evals, lagrange)? == expected)
+}
+
+/// This function checks that consecutive points of `srs` are consecutive powers of the tau committed to by the G2
+/// points `g2` and `tau_g2 = [tau] g2`, with a single pairing equation on a random linear combination of the points.
+pub fn check_monomial_powers<P, E, R>(
+    srs: &MonomialSrs<E>,
+    g2: P::G2Affine,
+    tau_g2: P::G2Affine,
+    rng: &mut R,
+) -> bool
+where
+    P: Pairing<G1Affine = Affine<E>, G1 = Projective<E>, ScalarField = E::ScalarField>,
+    E: SWCurveConfig,
+    R: RngCore,
+{
+    if srs.len() < 2 {
+        return true;
+    }
+    let r = E::ScalarField::rand(rng);
+    let powers = ark_std::iter::successors(Some(r), |power| Some(*power * r))
+        .take(srs.len() - 1)
+        .collect::<Vec<_>>();
+
+    // sum_i r^i [tau^i] paired with [tau] must match sum_i r^i [tau^(i + 1)] paired with [1].
+    let lower = Projective::<E>::msm_unchecked(&srs.points()[..srs.len() - 1], &powers);
+    let upper = Projective::<E>::msm_unchecked(&srs.points()[1..], &powers);
+    P::pairing(lower, tau_g2) == P::pairing(upper, g2)
+}
+
+/// This function checks that the Lagrange SRS read from `lagrange` is the Lagrange basis of the monomial SRS read
+/// from `monomial`, over the domain of the generator in its header and in its ordering, with both in this crate's
+/// binary format. The points are read `chunk_size` at a time, which bounds the memory used whatever the size of the
+/// files. It errors if either file is not in the expected basis, the sizes differ, the generator is not a primitive
+/// root of the size or reading a point fails.
+pub fn check_lagrange_files_match<M, L, E, R>(
+    monomial: M,
+    lagrange: L,
+    chunk_size: usize,
+    rng: &mut R,
+) -> Result<bool, InterpolationError>
+where
+    M: Read,
+    L: Read,
+    E: SrsCurve,
+    R: RngCore,
+{
+    if chunk_size == 0 {
+        return Err(InterpolationError::InvalidParameters(
+            "Cannot read the points in chunks of zero".to_string(),
+        ));
+    }
+    let mut monomial = SrsReader::<M, E>::new(monomial)?;
+    let mut lagrange = SrsReader::<L, E>::new(lagrange)?;
+    if monomial.header().basis != Basis::Monomial {
+        return Err(InterpolationError::InvalidParameters(
+            "Expected a monomial SRS".to_string(),
+        ));
+    }
+    let generator = match (lagrange.header().basis, lagrange.generator()) {
+        (Basis::Lagrange, Some(generator)) => generator,
+        _ => {
+            return Err(InterpolationError::InvalidParameters(
+                "Expected a Lagrange SRS".to_string(),
+            ))
+        }
+    };
+    let size = size_from_u64(monomial.header().size)?;
+    if lagrange.header().size != size as u64 {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The monomial SRS has {} points but the Lagrange SRS has {}",
+            size,
+            lagrange.header().size
+        )));
+    }
+    if !size.is_power_of_two() {
+        return Err(InterpolationError::SizeError);
+    }
+    if !is_primitive_root(generator, size) {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The generator of the Lagrange SRS is not a primitive root of order {}",
+            size
+        )));
+    }
+    let log_size = size.ilog2() as usize;
+    let bit_reversed = lagrange.header().ordering == PointOrdering::BitReversed;
+
+    // With c^n != 1 no denominator 1 - c x is zero, since (c x)^n = c^n.
+    let (c, numerator) = loop {
+        let c = E::ScalarField::rand(rng);
+        let numerator = E::ScalarField::one() - c.pow([size as u64]);
+        if !numerator.is_zero() {
+            break (c, numerator);
+        }
+    };
+
+    let mut monomial_sum = Projective::<E>::zero();
+    let mut lagrange_sum = Projective::<E>::zero();
+    let mut coeffs = Vec::with_capacity(chunk_size);
+    let mut power = E::ScalarField::one();
+    let mut start = 0;
+    while start < size {
+        let len = chunk_size.min(size - start);
+        let points = read_chunk(&mut monomial, len)?;
+        coeffs.clear();
+        for _ in 0..len {
+            coeffs.push(power);
+            power *= c;
+        }
+        monomial_sum += Projective::<E>::msm_unchecked(&points, &coeffs);
+
+        let points = read_chunk(&mut lagrange, len)?;
+        coeffs.clear();
+        for position in start..start + len {
+            let index = match bit_reversed {
+                true => bit_reverse(position, log_size)?,
+                false => position,
+            };
+            coeffs.push(E::ScalarField::one() - c * generator.pow([index as u64]));
+        }
+        batch_inversion(&mut coeffs);
+        coeffs.iter_mut().for_each(|coeff| *coeff *= numerator);
+        lagrange_sum += Projective::<E>::msm_unchecked(&points, &coeffs);
+        start += len;
+    }
+    Ok(monomial_sum == lagrange_sum)
+}
+
+/// This function reads the next `len` points of `reader`, erroring if the file ends first.
+fn read_chunk<R: Read, E: SrsCurve>(
+    reader: &mut SrsReader<R, E>,
+    len: usize,
+) -> Result<Vec<Affine<E>>, InterpolationError> {
+    let points = reader.read_points(len)?;
+    if points.len() < len {
+        return Err(InterpolationError::SerializationError(
+            "The file ended before the points in its header".to_string(),
+        ));
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{write_lagrange, write_monomial, Endianness, PointEncoding},
+        srs_to_lagrange_with_generator,
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::{g1::Config as BnConfig, Bn254, Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn test_check_lagrange_matches_monomial() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange = srs.to_lagrange().unwrap();
+        assert!(check_lagrange_matches_monomial(&srs, &lagrange, rng).unwrap());
+        let reversed = lagrange
+            .clone()
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert!(check_lagrange_matches_monomial(&srs, &reversed, rng).unwrap());
+
+        // A basis over another generator of the same domain is recognised.
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let generator = domain.group_gen().pow([3u64]);
+        let other = LagrangeSrs::from_parts(
+            srs_to_lagrange_with_generator(srs.points(), generator).unwrap(),
+            generator,
+            PointOrdering::Natural,
+        );
+        assert!(check_lagrange_matches_monomial(&srs, &other, rng).unwrap());
+
+        // Swapping two points is caught.
+        let mut points = lagrange.points().to_vec();
+        points.swap(2, 7);
+        let swapped = LagrangeSrs::from_parts(points, domain.group_gen(), PointOrdering::Natural);
+        assert!(!check_lagrange_matches_monomial(&srs, &swapped, rng).unwrap());
+        let short = MonomialSrs::new(srs.points()[..8].to_vec());
+        assert!(check_lagrange_matches_monomial(&short, &lagrange, rng).is_err());
+    }
+
+    #[test]
+    fn test_check_monomial_powers() {
+        let rng = &mut ark_std::test_rng();
+        let tau = Fr::from(12345u64);
+        let mut power = Fr::from(1u64);
+        let points = (0..8)
+            .map(|_| {
+                let point = (G1Affine::generator() * power).into_affine();
+                power *= tau;
+                point
+            })
+            .collect::<Vec<_>>();
+        let srs = MonomialSrs::new(points.clone());
+        let g2 = G2Affine::generator();
+        let tau_g2 = (g2 * tau).into_affine();
+        assert!(check_monomial_powers::<Bn254, _, _>(&srs, g2, tau_g2, rng));
+
+        let wrong_tau_g2 = (g2 * (tau + Fr::from(1u64))).into_affine();
+        assert!(!check_monomial_powers::<Bn254, _, _>(
+            &srs,
+            g2,
+            wrong_tau_g2,
+            rng
+        ));
+        let mut points = points;
+        points.swap(3, 4);
+        assert!(!check_monomial_powers::<Bn254, _, _>(
+            &MonomialSrs::new(points),
+            g2,
+            tau_g2,
+            rng
+        ));
+    }
+
+    #[test]
+    fn test_check_lagrange_files_match() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let lagrange = srs.to_lagrange().unwrap();
+        let write = |lagrange: &LagrangeSrs<BnConfig>| {
+            write_lagrange(
+                Vec::new(),
+                lagrange,
+                PointEncoding::Compressed,
+                Endianness::Little,
+            )
+            .unwrap()
+        };
+        let monomial_file = write_monomial(
+            Vec::new(),
+            &srs,
+            PointEncoding::Uncompressed,
+            Endianness::Big,
+        )
+        .unwrap();
+        let check = |lagrange_file: &[u8], chunk_size: usize, rng: &mut _| {
+            check_lagrange_files_match::<_, _, BnConfig, _>(
+                monomial_file.as_slice(),
+                lagrange_file,
+                chunk_size,
+                rng,
+            )
+        };
+        for chunk_size in [1, 3, 16, 100] {
+            assert!(check(&write(&lagrange), chunk_size, rng).unwrap());
+        }
+        let reversed = lagrange
+            .clone()
+            .with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+        assert!(check(&write(&reversed), 5, rng).unwrap());
+
+        // A basis over another generator of the same domain is checked against that generator.
+        let generator = lagrange.generator().pow([3u64]);
+        let other = LagrangeSrs::from_parts(
+            srs_to_lagrange_with_generator(srs.points(), generator).unwrap(),
+            generator,
+            PointOrdering::Natural,
+        );
+        assert!(check(&write(&other), 4, rng).unwrap());
+
+        // Swapping two points or mislabelling the ordering is caught.
+        let mut points = lagrange.points().to_vec();
+        points.swap(2, 7);
+        let swapped = LagrangeSrs::from_parts(points, lagrange.generator(), PointOrdering::Natural);
+        assert!(!check(&write(&swapped), 4, rng).unwrap());
+        let mislabelled = LagrangeSrs::from_parts(
+            lagrange.points().to_vec(),
+            lagrange.generator(),
+            PointOrdering::BitReversed,
+        );
+        assert!(!check(&write(&mislabelled), 4, rng).unwrap());
+
+        let short = MonomialSrs::new(srs.points()[..8].to_vec())
+            .to_lagrange()
+            .unwrap();
+        assert!(check(&write(&short), 4, rng).is_err());
+        assert!(check(&write(&lagrange), 0, rng).is_err());
+        assert!(check(&monomial_file, 4, rng).is_err());
+    }
+}
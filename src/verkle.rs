@@ -0,0 +1,300 @@
+//! This module contains the 256 point basis Verkle trees commit to vectors with, and the Lagrange bases over the
+//! integers that Bandersnatch setups with a monomial SRS need.
+//!
+//! The Verkle trees of Ethereum do not derive their basis from a trapdoor. go-ipa and rust-verkle hash the seed
+//! [`VERKLE_CRS_SEED`] with a counter onto Banderwagon, the prime order quotient of Bandersnatch in twisted Edwards
+//! form, and take the first 256 points that decode as the Lagrange basis over the integers `0, 1, ..., 255`
+//! directly. [`verkle_crs`] computes the same points, and [`banderwagon_to_bytes`] and [`banderwagon_from_bytes`]
+//! convert them to and from the 32 byte encoding those libraries use. Both need the `bandersnatch` feature.
+//!
+//! For a setup that does have a monomial SRS, the scalar field of Bandersnatch only has roots of unity of order up
+//! to 32, so its Lagrange basis of a Verkle width is taken over the integers too. The group FFT does not apply to
+//! that domain, so [`srs_to_lagrange_over_integers`] computes the basis with one multi-scalar multiplication per
+//! point instead, which is cheap at this size. Those points are in short Weierstrass form.
+use crate::{error::InterpolationError, srs::MonomialSrs, srs_to_lagrange};
+#[cfg(feature = "bandersnatch")]
+use ark_ec::twisted_edwards::TECurveConfig;
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+#[cfg(feature = "bandersnatch")]
+use ark_ed_on_bls12_381_bandersnatch::{BandersnatchConfig, EdwardsAffine, Fq};
+use ark_ff::{batch_inversion, PrimeField};
+#[cfg(feature = "bandersnatch")]
+use ark_ff::{BigInt, BigInteger, Field, One};
+use ark_std::{cfg_into_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "bandersnatch")]
+use sha2::{Digest, Sha256};
+
+/// The number of points in the Verkle basis, which is also the width of a Verkle tree node.
+pub const VERKLE_DOMAIN_SIZE: usize = 256;
+
+/// The seed go-ipa and rust-verkle hash onto Banderwagon to find the points of the Verkle basis.
+pub const VERKLE_CRS_SEED: &[u8] = b"eth_verkle_oct_2021";
+
+/// The domain a Lagrange basis is taken over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DomainConvention {
+    /// The arkworks multiplicative subgroup of the size of the basis, as in [`srs_to_lagrange`].
+    RootsOfUnity,
+    /// The integers `0, 1, ..., n - 1`, as in the Verkle spec.
+    #[default]
+    Integers,
+}
+
+/// This function takes a list of points in the SRS and converts them to the commitments to the Lagrange basis over
+/// the integers `0, 1, ..., points.len() - 1`, in that order.
+pub fn srs_to_lagrange_over_integers<E, F>(
+    points: &[Affine<E>],
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    let size = points.len();
+    if size == 0 {
+        return Err(InterpolationError::SizeError);
+    }
+
+    // The coefficients of the vanishing polynomial A(X) = (X - 0)(X - 1)...(X - (n - 1)), lowest first.
+    let mut vanishing = vec![F::one()];
+    for j in 0..size {
+        let root = F::from(j as u64);
+        vanishing.insert(0, F::zero());
+        for k in 0..vanishing.len() - 1 {
+            let shifted = vanishing[k + 1] * root;
+            vanishing[k] -= shifted;
+        }
+    }
+
+    // L_i(X) = A(X) / ((X - i) A'(i)) and A'(i) = i! (n - 1 - i)! (-1)^(n - 1 - i).
+    let mut factorials = vec![F::one(); size];
+    for i in 1..size {
+        factorials[i] = factorials[i - 1] * F::from(i as u64);
+    }
+    let mut denominators = (0..size)
+        .map(|i| {
+            let denominator = factorials[i] * factorials[size - 1 - i];
+            if (size - 1 - i).is_multiple_of(2) {
+                denominator
+            } else {
+                -denominator
+            }
+        })
+        .collect::<Vec<_>>();
+    batch_inversion(&mut denominators);
+
+    let lagrange = cfg_into_iter!(0..size)
+        .map(|i| {
+            // Synthetic division of A(X) by X - i, scaled by 1 / A'(i).
+            let root = F::from(i as u64);
+            let mut quotient = vec![F::zero(); size];
+            let mut carry = F::zero();
+            for k in (0..size).rev() {
+                carry = vanishing[k + 1] + root * carry;
+                quotient[k] = carry;
+            }
+            quotient
+                .iter_mut()
+                .for_each(|coeff| *coeff *= denominators[i]);
+            Projective::<E>::msm_unchecked(points, &quotient)
+        })
+        .collect::<Vec<_>>();
+    Ok(Projective::<E>::normalize_batch(&lagrange))
+}
+
+/// This function returns the Lagrange basis of the first [`VERKLE_DOMAIN_SIZE`] points of `srs`, taken over the
+/// domain given by `convention`.
+pub fn srs_to_lagrange_with_convention<E, F>(
+    srs: &MonomialSrs<E>,
+    convention: DomainConvention,
+) -> Result<Vec<Affine<E>>, InterpolationError>
+where
+    E: SWCurveConfig<ScalarField = F>,
+    F: PrimeField,
+{
+    if srs.len() < VERKLE_DOMAIN_SIZE {
+        return Err(InterpolationError::InvalidParameters(format!(
+            "The SRS has {} points but the basis needs {}",
+            srs.len(),
+            VERKLE_DOMAIN_SIZE
+        )));
+    }
+    let points = &srs.points()[..VERKLE_DOMAIN_SIZE];
+    match convention {
+        DomainConvention::RootsOfUnity => srs_to_lagrange(points),
+        DomainConvention::Integers => srs_to_lagrange_over_integers(points),
+    }
+}
+
+/// Whether `coordinate` is the larger of itself and its negation, which is how Banderwagon picks between the two
+/// points with the same `x` and between the two representatives of an element.
+#[cfg(feature = "bandersnatch")]
+fn is_positive(coordinate: Fq) -> bool {
+    coordinate > -coordinate
+}
+
+/// This function returns the Bandersnatch point with coordinate `x` and positive `y` if it is a Banderwagon element,
+/// which is when `1 - a x^2` is a square.
+#[cfg(feature = "bandersnatch")]
+fn banderwagon_from_x(x: Fq) -> Option<EdwardsAffine> {
+    let x_squared = x.square();
+    if (Fq::one() - <BandersnatchConfig as TECurveConfig>::COEFF_A * x_squared)
+        .legendre()
+        .is_qnr()
+    {
+        return None;
+    }
+    let y_squared = (<BandersnatchConfig as TECurveConfig>::COEFF_A * x_squared - Fq::one())
+        / (<BandersnatchConfig as TECurveConfig>::COEFF_D * x_squared - Fq::one());
+    let y = y_squared.sqrt()?;
+    let y = if is_positive(y) { y } else { -y };
+    Some(EdwardsAffine::new_unchecked(x, y))
+}
+
+/// This function encodes a Banderwagon element as go-ipa and rust-verkle do: the `x` coordinate of the
+/// representative with positive `y`, in big endian order.
+#[cfg(feature = "bandersnatch")]
+pub fn banderwagon_to_bytes(point: &EdwardsAffine) -> [u8; 32] {
+    let x = if is_positive(point.y) {
+        point.x
+    } else {
+        -point.x
+    };
+    x.into_bigint()
+        .to_bytes_be()
+        .try_into()
+        .expect("A Bandersnatch base field element is 32 bytes")
+}
+
+/// This function decodes a Banderwagon element from the encoding of [`banderwagon_to_bytes`], returning the
+/// representative with positive `y`. It errors if the bytes are not a reduced field element or not the encoding of
+/// an element.
+#[cfg(feature = "bandersnatch")]
+pub fn banderwagon_from_bytes(bytes: &[u8; 32]) -> Result<EdwardsAffine, InterpolationError> {
+    let x = Fq::from_bigint(BigInt::new(ark_std::array::from_fn(|i| {
+        u64::from_be_bytes(
+            bytes[24 - 8 * i..32 - 8 * i]
+                .try_into()
+                .expect("Limbs are eight bytes"),
+        )
+    })))
+    .ok_or(InterpolationError::SerializationError(
+        "Banderwagon coordinate is not reduced".to_string(),
+    ))?;
+    banderwagon_from_x(x).ok_or(InterpolationError::SerializationError(
+        "Bytes do not encode a Banderwagon element".to_string(),
+    ))
+}
+
+/// This function returns the [`VERKLE_DOMAIN_SIZE`] points of the Verkle basis. For each counter `i = 0, 1, ...`
+/// the SHA-256 digest of [`VERKLE_CRS_SEED`] and `i` as a big endian `u64` is reduced to a base field element, and it
+/// is kept as the `x` coordinate of the next point if it is that of a Banderwagon element.
+#[cfg(feature = "bandersnatch")]
+pub fn verkle_crs() -> Vec<EdwardsAffine> {
+    (0u64..)
+        .filter_map(|i| {
+            let digest = Sha256::new()
+                .chain_update(VERKLE_CRS_SEED)
+                .chain_update(i.to_be_bytes())
+                .finalize();
+            banderwagon_from_x(Fq::from_be_bytes_mod_order(&digest))
+        })
+        .take(VERKLE_DOMAIN_SIZE)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::gen_srs_for_testing;
+    use ark_bn254::{g1::Config as BnConfig, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+
+    fn check_integer_basis<E, F>(srs: &[Affine<E>], basis: &[Affine<E>])
+    where
+        E: SWCurveConfig<ScalarField = F>,
+        F: PrimeField,
+    {
+        // Committing to the evaluations at the integers matches committing to the polynomial.
+        let rng = &mut ark_std::test_rng();
+        let polynomial = DensePolynomial::<F>::rand(srs.len() - 1, rng);
+        let evaluations = (0..srs.len())
+            .map(|i| polynomial.evaluate(&F::from(i as u64)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Projective::<E>::msm(basis, &evaluations).unwrap(),
+            Projective::<E>::msm(srs, &polynomial.coeffs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_srs_to_lagrange_over_integers() {
+        let rng = &mut ark_std::test_rng();
+        let srs = gen_srs_for_testing::<BnConfig, _>(rng, 11).unwrap();
+        let basis = srs_to_lagrange_over_integers(&srs).unwrap();
+        check_integer_basis::<BnConfig, Fr>(&srs, &basis);
+
+        // Over a single point the basis is the constant polynomial.
+        assert_eq!(srs_to_lagrange_over_integers(&srs[..1]).unwrap(), srs[..1]);
+        assert!(srs_to_lagrange_over_integers::<BnConfig, Fr>(&[]).is_err());
+        assert!(srs_to_lagrange_with_convention(
+            &MonomialSrs::new(srs),
+            DomainConvention::Integers
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "bandersnatch")]
+    #[test]
+    fn test_bandersnatch_verkle_basis() {
+        use crate::curves::{CurveId, SrsCurve};
+        use ark_ed_on_bls12_381_bandersnatch::{Fr as BandersnatchFr, SWConfig};
+        assert_eq!(SWConfig::CURVE_ID, CurveId::Bandersnatch);
+
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<SWConfig, _>(rng, 255).unwrap());
+        let basis = srs_to_lagrange_with_convention(&srs, DomainConvention::Integers).unwrap();
+        assert_eq!(basis.len(), VERKLE_DOMAIN_SIZE);
+        check_integer_basis::<SWConfig, BandersnatchFr>(srs.points(), &basis);
+
+        // Bandersnatch has no subgroup of order 256 to take the basis over.
+        assert!(srs_to_lagrange_with_convention(&srs, DomainConvention::RootsOfUnity).is_err());
+    }
+
+    #[cfg(feature = "bandersnatch")]
+    #[test]
+    fn test_verkle_crs() {
+        fn hex(bytes: &[u8]) -> String {
+            bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+
+        // The vectors of the CRS consistency tests of go-ipa and rust-verkle.
+        let crs = verkle_crs();
+        assert_eq!(crs.len(), VERKLE_DOMAIN_SIZE);
+        assert_eq!(
+            hex(&banderwagon_to_bytes(&crs[0])),
+            "01587ad1336675eb912550ec2a28eb8923b824b490dd2ba82e48f14590a298a0"
+        );
+        let mut hasher = Sha256::new();
+        crs.iter()
+            .for_each(|point| hasher.update(banderwagon_to_bytes(point)));
+        assert_eq!(
+            hex(&hasher.finalize()),
+            "1fcaea10bf24f750200e06fa473c76ff0468007291fa548e2d99f09ba9256fdb"
+        );
+
+        for point in &crs {
+            assert!(point.is_on_curve());
+            let bytes = banderwagon_to_bytes(point);
+            assert_eq!(banderwagon_from_bytes(&bytes).unwrap(), *point);
+            // Both representatives of an element have the same encoding.
+            let other = EdwardsAffine::new_unchecked(-point.x, -point.y);
+            assert_eq!(banderwagon_to_bytes(&other), bytes);
+        }
+        assert!(banderwagon_from_bytes(&[0xff; 32]).is_err());
+    }
+}
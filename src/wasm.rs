@@ -0,0 +1,227 @@
+//! This module exposes the conversion to JavaScript through `wasm-bindgen`, taking and returning files in this
+//! crate's binary format as byte arrays.
+//!
+//! Build for the browser with `default-features = false, features = ["wasm"]` so that nothing depends on rayon,
+//! which needs threads the browser does not give a WebAssembly module by default. Large setups can be fed to a
+//! [`LagrangeConverter`] one chunk at a time as they are downloaded or read from disk, so the whole file never has
+//! to be held as bytes next to its decoded points.
+use crate::{
+    curves::{CurveId, SrsCurve},
+    error::InterpolationError,
+    formats::binary::{decode_point, write_lagrange, Basis, BinaryHeader, HEADER_SIZE},
+    srs::{MonomialSrs, PointOrdering},
+};
+use ark_ec::short_weierstrass::Affine;
+use ark_std::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+/// Decodes the points of a monomial SRS on a known curve as their bytes arrive.
+struct PointDecoder<E: SrsCurve> {
+    header: BinaryHeader,
+    points: Vec<Affine<E>>,
+}
+
+impl<E: SrsCurve> PointDecoder<E> {
+    fn new(header: BinaryHeader) -> Self {
+        Self {
+            header,
+            points: Vec::new(),
+        }
+    }
+
+    /// Decodes every whole point at the start of `bytes` and returns the number of bytes used.
+    fn decode(&mut self, bytes: &[u8]) -> Result<usize, InterpolationError> {
+        let point_size = self.header.point_size::<E>();
+        let mut used = 0;
+        for chunk in bytes.chunks_exact(point_size) {
+            if self.points.len() as u64 == self.header.size {
+                return Err(InterpolationError::SerializationError(
+                    "More points than the header declared".to_string(),
+                ));
+            }
+            self.points.push(decode_point(chunk, &self.header, true)?);
+            used += point_size;
+        }
+        Ok(used)
+    }
+
+    fn finish(self, ordering: PointOrdering) -> Result<Vec<u8>, InterpolationError> {
+        if self.points.len() as u64 != self.header.size {
+            return Err(InterpolationError::SerializationError(format!(
+                "Expected {} points but got {}",
+                self.header.size,
+                self.points.len()
+            )));
+        }
+        let encoding = self.header.encoding;
+        let endianness = self.header.endianness;
+        let lagrange = MonomialSrs::new(self.points).to_lagrange_with_ordering(ordering)?;
+        write_lagrange(Vec::new(), &lagrange, encoding, endianness)
+    }
+}
+
+/// The decoders for each curve the bindings support.
+enum CurveDecoder {
+    Bn254(PointDecoder<ark_bn254::g1::Config>),
+    #[cfg(feature = "bls12-381")]
+    Bls12_381(PointDecoder<ark_bls12_381::g1::Config>),
+}
+
+impl CurveDecoder {
+    fn new(header: BinaryHeader) -> Result<Self, InterpolationError> {
+        if header.basis != Basis::Monomial {
+            return Err(InterpolationError::InvalidParameters(
+                "Expected a monomial SRS".to_string(),
+            ));
+        }
+        match header.curve {
+            CurveId::Bn254 => Ok(CurveDecoder::Bn254(PointDecoder::new(header))),
+            #[cfg(feature = "bls12-381")]
+            CurveId::Bls12_381 => Ok(CurveDecoder::Bls12_381(PointDecoder::new(header))),
+            curve => Err(InterpolationError::InvalidParameters(format!(
+                "The curve {} is not supported",
+                curve.name()
+            ))),
+        }
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<usize, InterpolationError> {
+        match self {
+            CurveDecoder::Bn254(decoder) => decoder.decode(bytes),
+            #[cfg(feature = "bls12-381")]
+            CurveDecoder::Bls12_381(decoder) => decoder.decode(bytes),
+        }
+    }
+
+    fn finish(self, ordering: PointOrdering) -> Result<Vec<u8>, InterpolationError> {
+        match self {
+            CurveDecoder::Bn254(decoder) => decoder.finish(ordering),
+            #[cfg(feature = "bls12-381")]
+            CurveDecoder::Bls12_381(decoder) => decoder.finish(ordering),
+        }
+    }
+}
+
+/// The state of a conversion, kept separate from the exported type so it can be used without a JavaScript host.
+#[derive(Default)]
+struct ConverterState {
+    pending: Vec<u8>,
+    decoder: Option<CurveDecoder>,
+}
+
+impl ConverterState {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), InterpolationError> {
+        self.pending.extend_from_slice(chunk);
+        if self.decoder.is_none() {
+            if self.pending.len() < HEADER_SIZE {
+                return Ok(());
+            }
+            let header = BinaryHeader::read(&mut &self.pending[..HEADER_SIZE])?;
+            self.decoder = Some(CurveDecoder::new(header)?);
+            self.pending.drain(..HEADER_SIZE);
+        }
+        if let Some(decoder) = self.decoder.as_mut() {
+            let used = decoder.decode(&self.pending)?;
+            self.pending.drain(..used);
+        }
+        Ok(())
+    }
+
+    fn finish(self, ordering: PointOrdering) -> Result<Vec<u8>, InterpolationError> {
+        if !self.pending.is_empty() {
+            return Err(InterpolationError::SerializationError(
+                "The input ends part way through a point".to_string(),
+            ));
+        }
+        self.decoder
+            .ok_or(InterpolationError::SerializationError(
+                "The input ends before its header".to_string(),
+            ))?
+            .finish(ordering)
+    }
+}
+
+fn ordering(bit_reversed: bool) -> PointOrdering {
+    if bit_reversed {
+        PointOrdering::BitReversed
+    } else {
+        PointOrdering::Natural
+    }
+}
+
+fn to_js_error(e: InterpolationError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// A conversion fed with the bytes of a monomial SRS one chunk at a time.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct LagrangeConverter {
+    state: ConverterState,
+}
+
+#[wasm_bindgen]
+impl LagrangeConverter {
+    /// Creates a converter that has seen no bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next chunk of the monomial SRS file, decoding and checking the points it completes.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), JsError> {
+        self.state.push(chunk).map_err(to_js_error)
+    }
+
+    /// Converts the points and returns the Lagrange SRS file, with the encoding and endianness of the input.
+    pub fn finish(self, bit_reversed: bool) -> Result<Vec<u8>, JsError> {
+        self.state
+            .finish(ordering(bit_reversed))
+            .map_err(to_js_error)
+    }
+}
+
+/// This function converts a whole monomial SRS file to a Lagrange SRS file, with the encoding and endianness of the
+/// input and its points in bit-reversed order if `bit_reversed` is set.
+#[wasm_bindgen(js_name = convertToLagrange)]
+pub fn convert_to_lagrange(monomial: &[u8], bit_reversed: bool) -> Result<Vec<u8>, JsError> {
+    let mut state = ConverterState::default();
+    state.push(monomial).map_err(to_js_error)?;
+    state.finish(ordering(bit_reversed)).map_err(to_js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::binary::{read_lagrange, write_monomial, Endianness, PointEncoding},
+        tests::gen_srs_for_testing,
+    };
+    use ark_bn254::g1::Config as BnConfig;
+
+    #[test]
+    fn test_chunked_conversion() {
+        let rng = &mut ark_std::test_rng();
+        let srs = MonomialSrs::new(gen_srs_for_testing::<BnConfig, _>(rng, 15).unwrap());
+        let bytes =
+            write_monomial(Vec::new(), &srs, PointEncoding::Compressed, Endianness::Big).unwrap();
+        let expected = srs
+            .to_lagrange_with_ordering(PointOrdering::BitReversed)
+            .unwrap();
+
+        // Chunks that split the header and the points give the same file as one push.
+        let mut state = ConverterState::default();
+        bytes.chunks(7).for_each(|chunk| state.push(chunk).unwrap());
+        let output = state.finish(PointOrdering::BitReversed).unwrap();
+        assert_eq!(
+            read_lagrange::<_, BnConfig>(output.as_slice()).unwrap(),
+            expected
+        );
+
+        let mut truncated = ConverterState::default();
+        truncated.push(&bytes[..bytes.len() - 3]).unwrap();
+        assert!(truncated.finish(PointOrdering::Natural).is_err());
+        let mut lagrange = ConverterState::default();
+        assert!(lagrange.push(&output).is_err());
+    }
+}